@@ -0,0 +1,294 @@
+use std::collections::BTreeSet;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use chrono::{DateTime, Local, NaiveDate, Timelike};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::TimerType;
+use crate::history::{self, History, SessionRecord};
+use crate::theme::{self, Theme};
+
+// Work sessions started before this hour count for the early bird
+const EARLY_BIRD_HOUR: u32 = 7;
+const STREAK_DAYS: usize = 7;
+const TOTAL_POMODOROS: u32 = 500;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Reward {
+    Theme(&'static str), // Usable as achievements.theme
+    GoldenTomato,        // In the work completion animation
+}
+
+impl Reward {
+    fn describe(self) -> String {
+        match self {
+            Reward::Theme(name) => format!("the \"{name}\" theme"),
+            Reward::GoldenTomato => "a golden tomato in the animation".to_string(),
+        }
+    }
+}
+
+pub struct Achievement {
+    pub id: &'static str, // Stored in the unlock file; never rename
+    pub name: &'static str,
+    pub description: &'static str,
+    pub reward: Option<Reward>,
+}
+
+pub const ALL: [Achievement; 4] = [
+    Achievement {
+        id: "first_pomodoro",
+        name: "First Tomato",
+        description: "Finish your first work session",
+        reward: None,
+    },
+    Achievement {
+        id: "early_bird",
+        name: "Early Bird",
+        description: "Start a work session before 7 am",
+        reward: Some(Reward::Theme("sunrise")),
+    },
+    Achievement {
+        id: "streak_7",
+        name: "On a Roll",
+        description: "Work on 7 days in a row",
+        reward: Some(Reward::Theme("ember")),
+    },
+    Achievement {
+        id: "total_500",
+        name: "Tomato Farmer",
+        description: "Finish 500 work sessions",
+        reward: Some(Reward::GoldenTomato),
+    },
+];
+
+// Themes that achievements unlock, by name
+pub fn reward_theme(name: &str) -> Option<Theme> {
+    let (primary, highlight, progress) = match name {
+        "sunrise" => (
+            Color::Rgb(255, 170, 80),
+            Color::Rgb(255, 220, 120),
+            vec![Color::Rgb(255, 220, 120), Color::Rgb(255, 120, 80)],
+        ),
+        "ember" => (
+            Color::LightRed,
+            Color::Rgb(255, 160, 60),
+            vec![Color::Yellow, Color::Rgb(255, 120, 0), Color::Red],
+        ),
+        _ => return None,
+    };
+    Some(Theme {
+        primary,
+        highlight,
+        progress,
+        ..Theme::default()
+    })
+}
+
+fn local(secs: u64) -> Option<DateTime<Local>> {
+    DateTime::from_timestamp(secs as i64, 0).map(|time| time.with_timezone(&Local))
+}
+
+// Longest run of consecutive days with work on them
+fn longest_streak(days: &BTreeSet<NaiveDate>) -> usize {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for &day in days {
+        run = match previous {
+            Some(p) if p.succ_opt() == Some(day) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(day);
+    }
+    longest
+}
+
+// Ids of the achievements the history has earned. Archive summaries count for
+// all their sessions and keep the start of their earliest one
+pub fn earned(records: &[SessionRecord]) -> Vec<&'static str> {
+    let work: Vec<&SessionRecord> = records.iter().filter(|r| r.kind == TimerType::Work && !r.deleted).collect();
+    let total: u32 = work.iter().map(|r| r.sessions).sum();
+    let starts: Vec<DateTime<Local>> = work.iter().filter_map(|r| local(r.started_at)).collect();
+    let days: BTreeSet<NaiveDate> = starts.iter().map(|time| time.date_naive()).collect();
+
+    let mut earned = Vec::new();
+    if total > 0 {
+        earned.push("first_pomodoro");
+    }
+    if starts.iter().any(|time| time.hour() < EARLY_BIRD_HOUR) {
+        earned.push("early_bird");
+    }
+    if longest_streak(&days) >= STREAK_DAYS {
+        earned.push("streak_7");
+    }
+    if total >= TOTAL_POMODOROS {
+        earned.push("total_500");
+    }
+    earned
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Unlock {
+    id: String,
+    unlocked_at: u64,
+}
+
+// Unlocked achievements, appended to achievements/<machine>.jsonl in the
+// history directory so they sync with the sessions that earned them
+pub struct Trophies {
+    dir: PathBuf,
+    machine: String,
+    unlocked: Vec<Unlock>,
+}
+
+impl Trophies {
+    pub fn load(history: &History) -> Self {
+        let dir = history.dir().join("achievements");
+        let unlocked = fs::read_dir(&dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| fs::read_to_string(e.ok()?.path()).ok())
+                    .flat_map(|text| text.lines().filter_map(|line| serde_json::from_str(line).ok()).collect::<Vec<_>>())
+                    .collect()
+            })
+            .unwrap_or_default();
+        Trophies {
+            dir,
+            machine: history.machine().to_string(),
+            unlocked,
+        }
+    }
+
+    fn unlocked_at(&self, id: &str) -> Option<u64> {
+        // Another machine may have unlocked it too; the first one counts
+        self.unlocked.iter().filter(|u| u.id == id).map(|u| u.unlocked_at).min()
+    }
+
+    pub fn is_unlocked(&self, id: &str) -> bool {
+        self.unlocked_at(id).is_some()
+    }
+
+    pub fn has_reward(&self, reward: Reward) -> bool {
+        ALL.iter().any(|a| a.reward == Some(reward) && self.is_unlocked(a.id))
+    }
+
+    // The named reward theme, once it's been unlocked
+    pub fn theme(&self, name: &str) -> Option<Theme> {
+        let unlocked = ALL
+            .iter()
+            .any(|a| matches!(a.reward, Some(Reward::Theme(n)) if n == name) && self.is_unlocked(a.id));
+        if unlocked { reward_theme(name) } else { None }
+    }
+
+    // Records the achievements `records` have earned that weren't unlocked
+    // yet, and returns them
+    pub fn unlock(&mut self, records: &[SessionRecord]) -> io::Result<Vec<&'static Achievement>> {
+        let new: Vec<&'static Achievement> = earned(records)
+            .into_iter()
+            .filter(|id| !self.is_unlocked(id))
+            .filter_map(|id| ALL.iter().find(|a| a.id == id))
+            .collect();
+        if new.is_empty() {
+            return Ok(new);
+        }
+        fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(format!("{}.jsonl", self.machine));
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        for achievement in &new {
+            let unlock = Unlock {
+                id: achievement.id.to_string(),
+                unlocked_at: history::now_secs(),
+            };
+            writeln!(file, "{}", serde_json::to_string(&unlock)?)?;
+            self.unlocked.push(unlock);
+        }
+        Ok(new)
+    }
+}
+
+// The trophy screen: every achievement, unlocked ones with when and what they gave
+pub fn render(f: &mut Frame, area: Rect, trophies: &Trophies) {
+    let count = ALL.iter().filter(|a| trophies.is_unlocked(a.id)).count();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Achievements ({count}/{})", ALL.len()))
+        .title_alignment(Alignment::Center)
+        .border_style(Style::default().fg(theme::primary()));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    let locked = Style::default().fg(Color::DarkGray);
+    let mut lines = Vec::new();
+    for achievement in &ALL {
+        let unlocked = trophies.unlocked_at(achievement.id).and_then(local);
+        let (mark, style) = match unlocked {
+            Some(_) => ("★", Style::default().fg(theme::highlight()).add_modifier(Modifier::BOLD)),
+            None => ("☆", locked),
+        };
+        let mut title = vec![Span::styled(format!(" {mark} {}", achievement.name), style)];
+        if let Some(time) = unlocked {
+            title.push(Span::styled(format!("  {}", time.format("%Y-%m-%d")), locked));
+        }
+        lines.push(Line::from(title));
+        lines.push(Line::from(format!("   {}", achievement.description)));
+        if let Some(reward) = achievement.reward {
+            let text = match (reward, unlocked.is_some()) {
+                (Reward::Theme(name), true) => format!("   Unlocked {} - set achievements.theme = \"{name}\"", reward.describe()),
+                (_, true) => format!("   Unlocked {}", reward.describe()),
+                (_, false) => format!("   Unlocks {}", reward.describe()),
+            };
+            lines.push(Line::from(Span::styled(text, if unlocked.is_some() { Style::default() } else { locked })));
+        }
+        lines.push(Line::from(""));
+    }
+    f.render_widget(Paragraph::new(lines), chunks[0]);
+
+    let key = Style::default().fg(theme::primary()).add_modifier(Modifier::BOLD);
+    let help = Line::from(vec![Span::styled("Esc", key), Span::raw(" - Close")]);
+    f.render_widget(Paragraph::new(help).alignment(Alignment::Center), chunks[1]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_earned() {
+        let at = |day: u32, hour: u32, sessions: u32| SessionRecord {
+            id: format!("{day}-{hour}"),
+            started_at: Local.with_ymd_and_hms(2026, 10, day, hour, 0, 0).unwrap().timestamp() as u64,
+            duration_secs: 1500,
+            sessions,
+            ..SessionRecord::default()
+        };
+        assert!(earned(&[]).is_empty());
+        assert_eq!(earned(&[at(1, 10, 1)]), ["first_pomodoro"]);
+        assert_eq!(earned(&[at(1, 6, 1)]), ["first_pomodoro", "early_bird"]);
+
+        // Six days in a row, a gap, then seven
+        let mut records: Vec<SessionRecord> = (1..=6).chain(8..=14).map(|day| at(day, 10, 1)).collect();
+        assert_eq!(earned(&records[..12]), ["first_pomodoro"]);
+        assert_eq!(earned(&records), ["first_pomodoro", "streak_7"]);
+
+        // An archive summary counts for all its sessions
+        records.push(at(20, 9, 490));
+        assert_eq!(earned(&records), ["first_pomodoro", "streak_7", "total_500"]);
+    }
+}
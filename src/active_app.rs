@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, Instant};
+
+use crate::jobs;
+
+const SAMPLE_EVERY: Duration = Duration::from_secs(15);
+
+// Which application has focus, sampled through a work session. The key that
+// starts a session is pressed in the timer's own terminal, so a single look
+// at the start would mostly see that; the application seen most often is
+// what the session was spent in
+pub struct AppSampler {
+    seen: HashMap<String, u32>,
+    sampled_at: Option<Instant>,
+    pending: Option<Receiver<Option<String>>>,
+}
+
+impl AppSampler {
+    pub fn new() -> Self {
+        AppSampler {
+            seen: HashMap::new(),
+            sampled_at: None,
+            pending: None,
+        }
+    }
+
+    // Called every frame with whether a work session is running
+    pub fn tick(&mut self, working: bool) {
+        if let Some(receiver) = &self.pending
+            && let Ok(app) = receiver.try_recv()
+        {
+            if let Some(app) = app {
+                *self.seen.entry(app).or_default() += 1;
+            }
+            self.pending = None;
+        }
+        if !working || self.pending.is_some() || self.sampled_at.is_some_and(|at| at.elapsed() < SAMPLE_EVERY) {
+            return;
+        }
+        self.sampled_at = Some(Instant::now());
+        // The helpers take a few milliseconds to tens of them; keep them off the UI thread
+        self.pending = Some(jobs::run(focused_app));
+    }
+
+    // The application seen most since the last call, starting afresh
+    pub fn take(&mut self) -> Option<String> {
+        self.sampled_at = None;
+        let seen = std::mem::take(&mut self.seen);
+        seen.into_iter().max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0))).map(|(app, _)| app)
+    }
+}
+
+fn output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).stdin(Stdio::null()).stderr(Stdio::null()).output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !text.is_empty()).then_some(text)
+}
+
+#[cfg(target_os = "macos")]
+fn focused_app() -> Option<String> {
+    output(
+        "osascript",
+        &[
+            "-e",
+            "tell application \"System Events\" to get name of first application process whose frontmost is true",
+        ],
+    )
+}
+
+#[cfg(windows)]
+fn focused_app() -> Option<String> {
+    let script = "$k = Add-Type -Name Window -Namespace CyberTomato -PassThru -MemberDefinition \
+         '[DllImport(\"user32.dll\")] public static extern IntPtr GetForegroundWindow(); \
+         [DllImport(\"user32.dll\")] public static extern uint GetWindowThreadProcessId(IntPtr h, out uint p);'; \
+         $p = 0; [void]$k::GetWindowThreadProcessId($k::GetForegroundWindow(), [ref]$p); (Get-Process -Id $p).ProcessName";
+    output("powershell", &["-NoProfile", "-WindowStyle", "Hidden", "-Command", script])
+}
+
+// Wayland has no common way to ask, so the compositors that have one are
+// asked directly; otherwise X11 through xdotool
+#[cfg(not(any(target_os = "macos", windows)))]
+fn focused_app() -> Option<String> {
+    use std::env;
+
+    if env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+        let json: serde_json::Value = serde_json::from_str(&output("hyprctl", &["activewindow", "-j"])?).ok()?;
+        return json["class"].as_str().filter(|class| !class.is_empty()).map(str::to_string);
+    }
+    if env::var_os("SWAYSOCK").is_some() {
+        let tree: serde_json::Value = serde_json::from_str(&output("swaymsg", &["-t", "get_tree"])?).ok()?;
+        return sway_focused(&tree);
+    }
+    output("xdotool", &["getactivewindow", "getwindowclassname"])
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn sway_focused(node: &serde_json::Value) -> Option<String> {
+    if node["focused"].as_bool() == Some(true) {
+        // Native Wayland windows have an app_id, XWayland ones an X11 class
+        return node["app_id"].as_str().or(node["window_properties"]["class"].as_str()).map(str::to_string);
+    }
+    ["nodes", "floating_nodes"]
+        .iter()
+        .filter_map(|key| node[key].as_array())
+        .flatten()
+        .find_map(sway_focused)
+}
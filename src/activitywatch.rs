@@ -0,0 +1,60 @@
+use chrono::{TimeZone, Utc};
+use serde_json::json;
+
+use crate::config::ActivityWatchConfig;
+use crate::history::SessionRecord;
+use crate::jobs::Jobs;
+
+// Finished sessions are sent to a local ActivityWatch server, so pomodoros
+// show up next to its window and AFK tracking. Requests run off the UI
+// thread; failures come back through `errors` to be shown as toasts
+pub struct ActivityWatch {
+    config: ActivityWatchConfig,
+    jobs: Jobs,
+}
+
+impl ActivityWatch {
+    pub fn new(config: ActivityWatchConfig) -> Self {
+        ActivityWatch { config, jobs: Jobs::new() }
+    }
+
+    pub fn set_config(&mut self, config: ActivityWatchConfig) {
+        self.config = config;
+    }
+
+    pub fn push(&self, record: &SessionRecord) {
+        if !self.config.enabled {
+            return;
+        }
+        let config = self.config.clone();
+        let record = record.clone();
+        self.jobs.spawn(move || send(&config, &record).map_err(|e| format!("ActivityWatch: {e}")));
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = String> + '_ {
+        self.jobs.errors()
+    }
+}
+
+fn send(config: &ActivityWatchConfig, record: &SessionRecord) -> Result<(), ureq::Error> {
+    let bucket = config.bucket.clone().unwrap_or_else(|| format!("cyber-tomato_{}", record.machine));
+    let base = format!("{}/api/0/buckets/{bucket}", config.url.trim_end_matches('/'));
+    // Creating a bucket that already exists is answered with 304, so always ask
+    ureq::post(&base).send_json(json!({
+        "client": "cyber-tomato",
+        "type": "app.pomodoro",
+        "hostname": record.machine,
+    }))?;
+    let timestamp = Utc.timestamp_opt(record.started_at as i64, 0).single().unwrap_or_default();
+    let title = record.task.clone().unwrap_or_else(|| format!("{:?}", record.kind));
+    ureq::post(&format!("{base}/events")).send_json(json!([{
+        "timestamp": timestamp.to_rfc3339(),
+        "duration": record.duration_secs,
+        "data": {
+            "title": title,
+            "kind": record.kind,
+            "tags": record.tags,
+        },
+    }]))?;
+    Ok(())
+}
@@ -0,0 +1,347 @@
+use std::io;
+use std::path::PathBuf;
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Weekday};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Row, Table},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::migrate::{self, Schema};
+use crate::theme;
+
+// An alarm missed by more than this, while the machine slept, is skipped
+// rather than rung hours late
+const LATE_LIMIT: TimeDelta = TimeDelta::minutes(5);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Alarm {
+    pub time: String, // "10:00"
+    #[serde(default)]
+    pub repeat: String, // "daily", "weekdays", "weekends" or days like "mon,thu"; empty rings once
+    #[serde(default)]
+    pub label: String,
+}
+
+impl Alarm {
+    // "10:00 weekdays Stand-up": a time, optionally how it repeats, then the label
+    pub fn parse(text: &str) -> Result<Alarm, String> {
+        let mut words = text.split_whitespace();
+        let time = words.next().ok_or("Start with a time, like 10:00")?;
+        let time = NaiveTime::parse_from_str(time, "%H:%M").map_err(|_| format!("'{time}' is not a time like 10:00"))?;
+        let rest: Vec<&str> = words.collect();
+        let (repeat, label) = match rest.first() {
+            Some(word) if parse_days(word).is_ok() => (word.to_lowercase(), rest[1..].join(" ")),
+            _ => (String::new(), rest.join(" ")),
+        };
+        Ok(Alarm {
+            time: time.format("%H:%M").to_string(),
+            repeat: if repeat == "once" { String::new() } else { repeat },
+            label,
+        })
+    }
+
+    pub fn label(&self) -> &str {
+        if self.label.is_empty() { "Alarm" } else { &self.label }
+    }
+
+    pub fn is_once(&self) -> bool {
+        matches!(parse_days(&self.repeat), Ok(None))
+    }
+
+    fn rings_on(&self, date: NaiveDate) -> bool {
+        match parse_days(&self.repeat) {
+            Ok(None) => true,
+            Ok(Some(days)) => days.contains(&date.weekday()),
+            Err(_) => false,
+        }
+    }
+
+    // When it next goes off after `after`; None for an alarm edited into
+    // something unreadable
+    pub fn next(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let time = NaiveTime::parse_from_str(&self.time, "%H:%M").ok()?;
+        (0..=7)
+            .map(|days| after.date() + TimeDelta::days(days))
+            .filter(|&date| self.rings_on(date))
+            .map(|date| date.and_time(time))
+            .find(|&at| at > after)
+    }
+}
+
+// The weekdays "weekdays" or "mon,thu" stand for, or None for "once"
+pub fn parse_days(text: &str) -> Result<Option<Vec<Weekday>>, String> {
+    use Weekday::*;
+    match text.to_lowercase().as_str() {
+        "" | "once" => Ok(None),
+        "daily" => Ok(Some(vec![Mon, Tue, Wed, Thu, Fri, Sat, Sun])),
+        "weekdays" => Ok(Some(vec![Mon, Tue, Wed, Thu, Fri])),
+        "weekends" => Ok(Some(vec![Sat, Sun])),
+        days => days
+            .split(',')
+            .map(|day| day.parse().map_err(|_| format!("'{day}' is not a day")))
+            .collect::<Result<_, _>>()
+            .map(Some),
+    }
+}
+
+// alarms.json's format; see migrate
+const SCHEMA: Schema = Schema {
+    file: "alarms.json",
+    version: 1,
+    steps: &[|value| migrate::wrap_list(value, "alarms")],
+};
+
+// The alarms in alarms.json, checked every frame whatever the timer is doing
+pub struct Alarms {
+    path: PathBuf,
+    pub alarms: Vec<Alarm>,
+    checked: Option<NaiveDateTime>, // When due was last called
+    locked: Option<String>,         // Why saving would lose data
+}
+
+impl Alarms {
+    pub fn load(path: PathBuf) -> Self {
+        let list = migrate::read_list(&path, &SCHEMA, "alarms");
+        Alarms {
+            path,
+            alarms: list.items,
+            checked: None,
+            locked: list.locked,
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        if let Some(reason) = &self.locked {
+            return Err(io::Error::other(reason.clone()));
+        }
+        migrate::write_list(&self.path, &SCHEMA, "alarms", &self.alarms)
+    }
+
+    // Kept in order of the time of day
+    fn add(&mut self, alarm: Alarm) -> usize {
+        let index = self.alarms.partition_point(|a| a.time <= alarm.time);
+        self.alarms.insert(index, alarm);
+        index
+    }
+
+    // The alarms that went off since the last call. One-off alarms are taken
+    // off the list; saving it is up to the caller
+    pub fn due(&mut self, now: NaiveDateTime) -> Vec<Alarm> {
+        let Some(from) = self.checked.replace(now) else {
+            return Vec::new();
+        };
+        let from = from.max(now - LATE_LIMIT);
+        let due: Vec<Alarm> = self
+            .alarms
+            .iter()
+            .filter(|alarm| alarm.next(from).is_some_and(|at| at <= now))
+            .cloned()
+            .collect();
+        self.alarms.retain(|alarm| !(alarm.is_once() && due.contains(alarm)));
+        due
+    }
+}
+
+pub struct AlarmPane {
+    selected: usize,
+    adding: bool,
+    input: String,
+    message: Option<String>,
+}
+
+impl AlarmPane {
+    pub fn new() -> Self {
+        AlarmPane {
+            selected: 0,
+            adding: false,
+            input: String::new(),
+            message: None,
+        }
+    }
+
+    pub fn open(&mut self) {
+        self.adding = false;
+        self.message = None;
+    }
+
+    // Returns false when the pane should close
+    pub fn handle_key(&mut self, key: KeyEvent, alarms: &mut Alarms) -> bool {
+        self.message = None;
+        if self.adding {
+            match key.code {
+                KeyCode::Esc => self.adding = false,
+                KeyCode::Enter => match Alarm::parse(&self.input) {
+                    Ok(alarm) => {
+                        self.adding = false;
+                        self.selected = alarms.add(alarm);
+                        self.save(alarms);
+                    }
+                    Err(e) => self.message = Some(e),
+                },
+                KeyCode::Backspace => {
+                    self.input.pop();
+                }
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => self.input.push(c),
+                _ => {}
+            }
+            return true;
+        }
+
+        match key.code {
+            KeyCode::Esc => return false,
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.selected += 1,
+            KeyCode::Char('a') => {
+                self.adding = true;
+                self.input.clear();
+            }
+            KeyCode::Delete | KeyCode::Char('D') if self.selected < alarms.alarms.len() => {
+                alarms.alarms.remove(self.selected);
+                self.save(alarms);
+            }
+            _ => {}
+        }
+        self.selected = self.selected.min(alarms.alarms.len().saturating_sub(1));
+        true
+    }
+
+    fn save(&mut self, alarms: &Alarms) {
+        if let Err(e) = alarms.save() {
+            self.message = Some(format!("Could not save alarms: {e}"));
+        }
+    }
+}
+
+// "in 1h 05m" within a day, otherwise the day and time
+fn next_text(at: NaiveDateTime, now: NaiveDateTime) -> String {
+    let minutes = (at - now).num_minutes() + 1;
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("in {m}m"),
+        (h, m) if h < 24 => format!("in {h}h {m:02}m"),
+        _ => at.format("%a %H:%M").to_string(),
+    }
+}
+
+pub fn render(f: &mut Frame, area: Rect, alarms: &Alarms, pane: &AlarmPane, now: NaiveDateTime) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Alarms")
+        .title_alignment(Alignment::Center)
+        .border_style(Style::default().fg(theme::primary()));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(1), Constraint::Length(1)])
+        .split(inner);
+
+    if alarms.alarms.is_empty() {
+        f.render_widget(Paragraph::new("  No alarms yet - press a to set one"), chunks[0]);
+    } else {
+        let visible = chunks[0].height.saturating_sub(1) as usize;
+        let offset = (pane.selected + 1).saturating_sub(visible);
+        let rows: Vec<Row> = alarms
+            .alarms
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(visible)
+            .map(|(i, alarm)| {
+                let next = alarm.next(now).map(|at| next_text(at, now)).unwrap_or_else(|| "never".to_string());
+                let repeat = if alarm.repeat.is_empty() { "once" } else { &alarm.repeat };
+                let style = if i == pane.selected {
+                    Style::default().fg(Color::Black).bg(theme::primary())
+                } else {
+                    Style::default()
+                };
+                Row::new(vec![alarm.time.clone(), repeat.to_string(), alarm.label().to_string(), next]).style(style)
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            [Constraint::Length(6), Constraint::Length(16), Constraint::Min(10), Constraint::Length(12)],
+        )
+        .header(Row::new(vec!["Time", "Repeat", "Label", "Next"]).style(Style::default().fg(theme::highlight()).add_modifier(Modifier::BOLD)));
+        f.render_widget(table, chunks[0]);
+    }
+
+    let status_line = if pane.adding {
+        let mut spans = vec![
+            Span::raw("  New alarm (9:30 weekdays Stand-up): "),
+            Span::styled(pane.input.as_str(), Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+            Span::styled("█", Style::default().fg(theme::primary())), // Cursor
+        ];
+        if let Some(message) = &pane.message {
+            spans.push(Span::styled(format!("  {message}"), Style::default().fg(Color::Yellow)));
+        }
+        Line::from(spans)
+    } else {
+        Line::from(Span::styled(
+            format!("  {}", pane.message.as_deref().unwrap_or("")),
+            Style::default().fg(Color::Yellow),
+        ))
+    };
+    f.render_widget(Paragraph::new(status_line), chunks[1]);
+
+    let key = |k: &'static str| Span::styled(k, Style::default().fg(theme::primary()).add_modifier(Modifier::BOLD));
+    let help = if pane.adding {
+        Line::from(vec![key("↵"), Span::raw(" - Save | "), key("Esc"), Span::raw(" - Cancel")])
+    } else {
+        Line::from(vec![
+            key("a"),
+            Span::raw(" - Add | "),
+            key("D"),
+            Span::raw(" - Delete | "),
+            key("Esc"),
+            Span::raw(" - Close"),
+        ])
+    };
+    f.render_widget(Paragraph::new(help).alignment(Alignment::Center), chunks[2]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let alarm = Alarm::parse("9:30 weekdays Stand-up call").unwrap();
+        assert_eq!(alarm.time, "09:30");
+        assert_eq!(alarm.repeat, "weekdays");
+        assert_eq!(alarm.label, "Stand-up call");
+        let alarm = Alarm::parse("14:00 Mon,Thu").unwrap();
+        assert_eq!((alarm.repeat.as_str(), alarm.label()), ("mon,thu", "Alarm"));
+        assert!(Alarm::parse("17:45 Go home").unwrap().is_once());
+        assert!(Alarm::parse("25:00 Late").is_err());
+        assert!(Alarm::parse("").is_err());
+    }
+
+    #[test]
+    fn test_due() {
+        // 2026-10-16 is a Friday
+        let at = |day, h, m| NaiveDate::from_ymd_opt(2026, 10, day).unwrap().and_hms_opt(h, m, 0).unwrap();
+        let mut alarms = Alarms::load(PathBuf::new());
+        alarms.add(Alarm::parse("10:00 weekdays Stand-up").unwrap());
+        alarms.add(Alarm::parse("09:00 Coffee").unwrap());
+        assert_eq!(alarms.alarms[0].label, "Coffee");
+        assert_eq!(alarms.alarms[0].next(at(16, 9, 0)), Some(at(17, 9, 0)));
+        assert_eq!(alarms.alarms[1].next(at(16, 10, 0)), Some(at(19, 10, 0)));
+
+        // Nothing rings on the first check
+        assert!(alarms.due(at(16, 8, 59)).is_empty());
+        let due = alarms.due(at(16, 9, 0));
+        assert_eq!(due.len(), 1);
+        assert_eq!(alarms.alarms.len(), 1); // The one-off is used up
+        // Too late after a long sleep
+        assert!(alarms.due(at(16, 10, 6)).is_empty());
+        assert_eq!(alarms.due(at(19, 10, 3)).len(), 1);
+    }
+}
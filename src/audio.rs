@@ -1,23 +1,416 @@
-use rodio::{OutputStreamBuilder, Source};
-use std::f32::consts::PI;
-use std::time::Duration;
+use chrono::{Local, NaiveTime};
+use rodio::{OutputStream, OutputStreamBuilder, Sink, Source, buffer::SamplesBuffer, source::SineWave};
+use serde::Deserialize;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::hash::{BuildHasher, RandomState};
+use std::path::Path;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 
+use crate::chiptune::{Envelope, Song, Vibrato, Voice};
+use crate::config::{Ambient, SoundsConfig, Waveform};
+use crate::rtttl::{self, Melody};
+use crate::{midi, paths};
+
+const AMBIENT_FADE_IN: Duration = Duration::from_secs(4);
+const AMBIENT_FADE_OUT: Duration = Duration::from_millis(1500);
+// How far the ambient dips under a chime, and how quickly it moves
+const DUCK_LEVEL: f32 = 0.2;
+const DUCK_DOWN: Duration = Duration::from_millis(250);
+const DUCK_UP: Duration = Duration::from_millis(1500);
+
+// A gain moving in a straight line from one level to another
+#[derive(Clone, Copy)]
+struct Fade {
+    from: f32,
+    to: f32,
+    start: Instant,
+    length: Duration,
+}
+
+impl Fade {
+    fn hold(level: f32) -> Self {
+        Fade {
+            from: level,
+            to: level,
+            start: Instant::now(),
+            length: Duration::ZERO,
+        }
+    }
+
+    fn level(&self) -> f32 {
+        let t = self.start.elapsed().as_secs_f32() / self.length.as_secs_f32();
+        if t >= 1.0 || self.length.is_zero() {
+            return self.to;
+        }
+        self.from + (self.to - self.from) * t
+    }
+
+    fn done(&self) -> bool {
+        self.start.elapsed() >= self.length
+    }
+
+    // A new fade from wherever this one has got to
+    fn towards(&self, to: f32, length: Duration) -> Fade {
+        Fade {
+            from: self.level(),
+            to,
+            start: Instant::now(),
+            length,
+        }
+    }
+}
+
+// A stretch of the day without sounds, "22:00-07:00"; it may run past midnight
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QuietHours {
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl QuietHours {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let error = || format!("expected HH:MM-HH:MM, got '{text}'");
+        let (start, end) = text.split_once('-').ok_or_else(error)?;
+        let time = |s: &str| NaiveTime::parse_from_str(s.trim(), "%H:%M").map_err(|_| error());
+        Ok(QuietHours {
+            start: time(start)?,
+            end: time(end)?,
+        })
+    }
+
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+// The one output stream shared by chimes and the animations. It is opened on
+// first use, so runs that never make a sound never touch the device, and
+// opening is retried after a failure (e.g. headphones plugged in). The master
+// volume, mute and quiet hours apply to everything played through it
+pub struct AudioEngine {
+    stream: RefCell<Option<OutputStream>>,
+    volume: Cell<f32>,
+    muted: Cell<bool>,
+    quiet_hours: Cell<Option<QuietHours>>,
+    playing: RefCell<Vec<Sink>>, // Sounds still queued, kept alive until done
+    // Background loop under everything else. Its gain is the configured
+    // volume times a fade for starting and stopping and a dip for chimes
+    ambient: RefCell<Option<Sink>>,
+    ambient_volume: Cell<f32>,
+    fade: Cell<Fade>,
+    duck: Cell<Fade>,
+    ducked: RefCell<Vec<Sink>>, // Chimes held back until the ambient has dipped
+    clips: RefCell<HashMap<(&'static str, Waveform), SamplesBuffer>>,
+}
+
+impl AudioEngine {
+    pub fn new() -> Self {
+        AudioEngine {
+            stream: RefCell::new(None),
+            volume: Cell::new(1.0),
+            muted: Cell::new(false),
+            quiet_hours: Cell::new(None),
+            playing: RefCell::new(Vec::new()),
+            ambient: RefCell::new(None),
+            ambient_volume: Cell::new(0.0),
+            fade: Cell::new(Fade::hold(0.0)),
+            duck: Cell::new(Fade::hold(1.0)),
+            ducked: RefCell::new(Vec::new()),
+            clips: RefCell::new(HashMap::new()),
+        }
+    }
+
+    pub fn set_levels(&self, sounds: &SoundsConfig) {
+        self.volume.set(sounds.volume as f32 / 100.0);
+        self.muted.set(sounds.mute);
+        let quiet_hours = sounds.quiet_hours.as_deref().and_then(|hours| QuietHours::parse(hours).ok());
+        self.quiet_hours.set(quiet_hours);
+    }
+
+    // Muted, turned all the way down or in quiet hours
+    pub fn silenced(&self) -> bool {
+        let quiet = self.quiet_hours.get().is_some_and(|hours| hours.contains(Local::now().time()));
+        self.muted.get() || self.volume.get() == 0.0 || quiet
+    }
+
+    fn level(&self) -> f32 {
+        if self.silenced() { 0.0 } else { self.volume.get() }
+    }
+
+    // A new sink at the master volume, which stops when dropped. None while
+    // silenced, so nothing opens the device for sounds nobody would hear, and
+    // when no audio device could be opened
+    pub fn sink(&self) -> Option<Sink> {
+        if self.silenced() {
+            return None;
+        }
+        let sink = self.open()?;
+        sink.set_volume(self.volume.get());
+        Some(sink)
+    }
+
+    // A new sink on the shared stream, at full volume
+    fn open(&self) -> Option<Sink> {
+        let mut stream = self.stream.borrow_mut();
+        if stream.is_none()
+            && let Ok(builder) = OutputStreamBuilder::from_default_device()
+            && let Ok(mut opened) = builder.open_stream_or_fallback()
+        {
+            // Disable logging on drop to prevent stderr output
+            opened.log_on_drop(false);
+            *stream = Some(opened);
+        }
+        stream.as_ref().map(|stream| Sink::connect_new(stream.mixer()))
+    }
+
+    // An animation sound, synthesized the first time it's played and kept for
+    // every play after. Cheap to clone: the samples are shared
+    pub fn clip(&self, name: &'static str, waveform: Waveform, song: impl FnOnce() -> Song) -> SamplesBuffer {
+        self.clips.borrow_mut().entry((name, waveform)).or_insert_with(|| song().render()).clone()
+    }
+
+    // A sink for a chime. While the ambient is up it starts paused, and
+    // `play` holds it back until the ambient has dipped out of the way
+    fn chime_sink(&self) -> Option<Sink> {
+        let sink = self.sink()?;
+        let duck = self.duck.get();
+        if self.ambient.borrow().is_some() && !(duck.to < 1.0 && duck.done()) {
+            sink.pause();
+        }
+        Some(sink)
+    }
+
+    // Lets a filled sink play out in the background
+    fn play(&self, sink: Sink) {
+        if sink.is_paused() {
+            self.ducked.borrow_mut().push(sink);
+            return;
+        }
+        let mut playing = self.playing.borrow_mut();
+        playing.retain(|sink| !sink.empty());
+        playing.push(sink);
+    }
+
+    // Loops `source` in the background, fading it in
+    pub fn start_ambient(&self, source: impl Source + Send + 'static, volume: f32) {
+        // Opened even while silenced, since `tick` follows quiet hours ending
+        let Some(sink) = self.open() else {
+            return;
+        };
+        sink.set_volume(0.0);
+        sink.append(source);
+        // Straight back up if the last one was still fading out
+        let mut ambient = self.ambient.borrow_mut();
+        let fade = if ambient.is_some() { self.fade.get() } else { Fade::hold(0.0) };
+        self.fade.set(fade.towards(1.0, AMBIENT_FADE_IN));
+        self.ambient_volume.set(volume);
+        *ambient = Some(sink);
+    }
+
+    // Fades the ambient out; `tick` lets go of it once silent
+    pub fn stop_ambient(&self) {
+        let fade = self.fade.get();
+        if fade.to > 0.0 {
+            self.fade.set(fade.towards(0.0, AMBIENT_FADE_OUT));
+        }
+    }
+
+    // Moves the ambient's gain along and releases held chimes; call every frame
+    pub fn tick(&self) {
+        let mut ambient = self.ambient.borrow_mut();
+        let mut playing = self.playing.borrow_mut();
+        let mut ducked = self.ducked.borrow_mut();
+        playing.retain(|sink| !sink.empty());
+
+        let chimes = !playing.is_empty() || !ducked.is_empty();
+        let duck = self.duck.get();
+        if chimes && duck.to == 1.0 {
+            self.duck.set(duck.towards(DUCK_LEVEL, DUCK_DOWN));
+        } else if !chimes && duck.to < 1.0 {
+            self.duck.set(duck.towards(1.0, DUCK_UP));
+        }
+        if ambient.is_none() || self.duck.get().done() {
+            for sink in ducked.drain(..) {
+                sink.play();
+                playing.push(sink);
+            }
+        }
+
+        let Some(sink) = ambient.as_ref() else {
+            return;
+        };
+        let fade = self.fade.get();
+        sink.set_volume(self.level() * self.ambient_volume.get() * fade.level() * self.duck.get().level());
+        if fade.to == 0.0 && fade.done() {
+            *ambient = None;
+        }
+    }
+
+    // Sounds waiting or playing, the ambient loop aside
+    pub fn queued(&self) -> usize {
+        let playing = self.playing.borrow();
+        let ducked = self.ducked.borrow();
+        playing.iter().chain(ducked.iter()).map(Sink::len).sum()
+    }
+
+    // Blocks until every queued sound has finished, for callers about to exit
+    pub fn wait(&self) {
+        for sink in self.playing.borrow().iter() {
+            sink.sleep_until_end();
+        }
+    }
+}
+
+// The name that puts the built-in melody in a pool of custom ones
+const BUILTIN: &str = "builtin";
+
+// A sound in config.toml is one melody or a pool to pick from at random
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum TuneList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl TuneList {
+    pub fn tunes(&self) -> Vec<&str> {
+        match self {
+            TuneList::One(tune) => vec![tune.as_str()],
+            TuneList::Many(tunes) => tunes.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+// A custom melody from the config: a .mid file, or RTTTL inline or in a file.
+// One melody per voice; "builtin" stands for the usual melody and has none
+pub fn load_tune(tune: &str) -> Result<Vec<Melody>, String> {
+    if tune.trim() == BUILTIN {
+        return Ok(Vec::new());
+    }
+    let lower = tune.trim().to_ascii_lowercase();
+    if lower.ends_with(".mid") || lower.ends_with(".midi") {
+        midi::load(&paths::expand_home(Path::new(tune.trim())))
+    } else {
+        rtttl::load(tune).map(|melody| vec![melody])
+    }
+}
+
+// The melodies configured for one sound. An empty entry is the built-in one
+#[derive(Default)]
+struct Pool {
+    tunes: Vec<Vec<Melody>>,
+    avoid_repeats: bool,
+    last: Cell<Option<usize>>,
+}
+
+impl Pool {
+    fn new(list: &Option<TuneList>, avoid_repeats: bool) -> Self {
+        let tunes = list.iter().flat_map(TuneList::tunes).filter_map(|tune| load_tune(tune).ok()).collect();
+        Pool {
+            tunes,
+            avoid_repeats,
+            last: Cell::new(None),
+        }
+    }
+
+    // A random melody, or None for the built-in one
+    fn pick(&self) -> Option<&[Melody]> {
+        let len = self.tunes.len();
+        if len == 0 {
+            return None;
+        }
+        let random = RandomState::new().hash_one(()) as usize;
+        let index = match self.last.get() {
+            // Any but the last one: skip over it
+            Some(last) if self.avoid_repeats && len > 1 => {
+                let index = random % (len - 1);
+                if index >= last { index + 1 } else { index }
+            }
+            _ => random % len,
+        };
+        self.last.set(Some(index));
+        Some(&self.tunes[index]).filter(|tune| !tune.is_empty()).map(Vec::as_slice)
+    }
+}
+
+// Session chimes and cues. Playback doesn't block: sounds are queued on the
+// shared engine and the UI carries on
 pub struct AudioManager {
-    // No need to store sink anymore since we create fresh ones for each playback
+    engine: Rc<AudioEngine>,
+    sounds: SoundsConfig,
+    // Custom melodies from the config, checked when it was loaded
+    work_tunes: Pool,
+    break_tunes: Pool,
+    ambient: Cell<Ambient>, // What the engine is looping now
 }
 
 impl AudioManager {
-    pub fn play_work_complete_sound(&self) {
+    pub fn new(engine: Rc<AudioEngine>) -> Self {
+        AudioManager {
+            engine,
+            sounds: SoundsConfig::default(),
+            work_tunes: Pool::default(),
+            break_tunes: Pool::default(),
+            ambient: Cell::new(Ambient::Off),
+        }
+    }
+
+    pub fn set_config(&mut self, sounds: SoundsConfig) {
+        self.engine.set_levels(&sounds);
+        self.work_tunes = Pool::new(&sounds.work_complete_tune, sounds.avoid_repeats);
+        self.break_tunes = Pool::new(&sounds.break_complete_tune, sounds.avoid_repeats);
+        // A different ambient takes over on the next set_ambient
+        if (sounds.ambient, sounds.ambient_volume) != (self.sounds.ambient, self.sounds.ambient_volume) {
+            self.set_ambient(false);
+        }
+        self.sounds = sounds;
+    }
+
+    // Fades the configured ambient in or out; cheap to call every frame
+    pub fn set_ambient(&self, on: bool) {
+        let wanted = if on { self.sounds.ambient } else { Ambient::Off };
+        if self.ambient.replace(wanted) == wanted {
+            return;
+        }
+        let volume = self.sounds.ambient_volume as f32 / 100.0;
+        match wanted {
+            Ambient::Off => self.engine.stop_ambient(),
+            Ambient::Ticking => {
+                // Tick, tock
+                let ms = Duration::from_millis;
+                let tick = Voice::new(Waveform::Noise, Envelope::HIT, 0.5);
+                let song = Song::new().track(tick, &[(6000.0, ms(15)), (0.0, ms(985)), (4000.0, ms(15)), (0.0, ms(985))]);
+                self.engine.start_ambient(song.into_source().repeat_infinite(), volume);
+            }
+            Ambient::Noise => self.engine.start_ambient(BrownNoise::new(), volume),
+        }
+    }
+
+    // Each play_* returns false when no audio device could be opened
+    pub fn play_work_complete_sound(&self) -> bool {
         let tones = [
             (1760.0, Duration::from_millis(100)),
             (880.0, Duration::from_millis(100)),
             (440.0, Duration::from_millis(150)),
             (220.0, Duration::from_millis(200)),
         ];
-        self.play_audio(&tones);
+        if let Some(tune) = self.work_tunes.pick() {
+            return self.play_tune(self.sounds.work_complete, tune);
+        }
+        let lead = Voice::new(self.sounds.work_complete, Envelope::PLUCK, 0.3);
+        self.play(Song::new().track(lead, &tones))
     }
 
-    pub fn play_break_complete_music(&self) {
+    pub fn play_break_complete_music(&self) -> bool {
+        if let Some(tune) = self.break_tunes.pick() {
+            return self.play_tune(self.sounds.break_complete, tune);
+        }
         // Play notification + longer melody as one continuous sequence
         let complete_sequence = [
             // Initial notification tones
@@ -54,88 +447,118 @@ impl AudioManager {
             (1174.66, Duration::from_millis(200)), // D6
             (1318.51, Duration::from_millis(600)), // E6 - Final note
         ];
-        self.play_audio(&complete_sequence);
+        // Root notes under each half phrase, after the notification tones
+        let bass_line = [
+            (0.0, Duration::from_millis(1050)),
+            (130.81, Duration::from_millis(700)),  // C3
+            (174.61, Duration::from_millis(700)),  // F3
+            (98.00, Duration::from_millis(800)),   // G2
+            (130.81, Duration::from_millis(800)),  // C3
+            (87.31, Duration::from_millis(925)),   // F2
+            (98.00, Duration::from_millis(925)),   // G2
+            (130.81, Duration::from_millis(1700)), // C3
+        ];
+        let lead = Voice::new(self.sounds.break_complete, Envelope::HELD, 0.25).with_vibrato(Vibrato::GENTLE);
+        let bass = Voice::new(Waveform::Triangle, Envelope::HELD, 0.3);
+        self.play(Song::new().track(lead, &complete_sequence).track(bass, &bass_line))
     }
 
-    fn play_audio(&self, tones: &[(f32, Duration)]) {
-        // Create a new stream and sink for each audio playback
-        if let Ok(builder) = OutputStreamBuilder::from_default_device() {
-        if let Ok(mut stream) = builder.open_stream_or_fallback() {
-            // Disable logging on drop to prevent stderr output
-            stream.log_on_drop(false);
-            
-            let sink = rodio::Sink::connect_new(stream.mixer());
-            let sample_rate = 44100;
-
-            for (freq, dur) in tones {
-                if *freq == 0.0 {
-                    let silence = rodio::source::Zero::new(1, sample_rate).take_duration(*dur).buffered();
-                    sink.append(silence);
-                } else {
-                    let source = SquareWaveWithDecay::new(*freq, *dur, sample_rate);
-                    sink.append(source);
-                }
-            }
+    // Repeat of the work chime for nag mode
+    pub fn play_nag_chime(&self) {
+        self.play_work_complete_sound();
+    }
 
-            // Wait for the audio to finish playing
-            sink.sleep_until_end();
-        }
+    // Short tick for the final countdown
+    pub fn play_countdown_beep(&self) {
+        let voice = Voice::new(self.sounds.countdown, Envelope::PLUCK, 0.3);
+        self.play(Song::new().track(voice, &[(1318.51, Duration::from_millis(60))]));
+    }
+
+    // Three double beeps, unlike any session sound, for an alarm
+    pub fn play_alarm(&self) {
+        let ms = Duration::from_millis;
+        let voice = Voice::new(self.sounds.countdown, Envelope::PLUCK, 0.3);
+        let beeps: Vec<(f32, Duration)> = (0..3)
+            .flat_map(|_| [(1760.0, ms(90)), (0.0, ms(60)), (1760.0, ms(90)), (0.0, ms(400))])
+            .collect();
+        self.play(Song::new().track(voice, &beeps));
+    }
+
+    // Quiet sine cues for the breathing guide and eye rests
+    pub fn play_soft_tones(&self, freqs: &[f32]) {
+        let Some(sink) = self.engine.chime_sink() else {
+            return;
+        };
+        let duration = Duration::from_millis(700);
+        for &freq in freqs {
+            let tone = SineWave::new(freq)
+                .fade_out(duration)
+                .take_duration(duration)
+                .fade_in(Duration::from_millis(150))
+                .amplify(0.15);
+            sink.append(tone);
         }
+        self.engine.play(sink);
+    }
+
+    // Blocks until the queued sounds have played, for callers about to exit
+    pub fn wait(&self) {
+        self.engine.wait();
+    }
+
+    fn play_tune(&self, waveform: Waveform, voices: &[Melody]) -> bool {
+        // Chords get quieter per voice so they don't clip
+        let volume = 0.25 / (voices.len() as f32).sqrt();
+        let song = voices
+            .iter()
+            .fold(Song::new(), |song, melody| song.track(Voice::new(waveform, Envelope::HELD, volume), melody));
+        self.play(song)
+    }
+
+    fn play(&self, song: Song) -> bool {
+        let Some(sink) = self.engine.chime_sink() else {
+            // Silence that was asked for isn't a missing device
+            return self.engine.silenced();
+        };
+        sink.append(song.into_source());
+        self.engine.play(sink);
+        true
     }
 }
 
-struct SquareWaveWithDecay {
-    freq: f32,
-    duration: Duration,
-    sample_rate: u32,
-    sample_idx: usize,
-    total_samples: usize,
+// Endless brown noise, white noise with the highs rolled off: closer to rain
+// or a fan than to hiss
+struct BrownNoise {
+    state: u32, // xorshift
+    level: f32,
 }
 
-impl SquareWaveWithDecay {
-    fn new(freq: f32, duration: Duration, sample_rate: u32) -> Self {
-        let total_samples = (duration.as_secs_f32() * sample_rate as f32) as usize;
-        Self {
-            freq,
-            duration,
-            sample_rate,
-            sample_idx: 0,
-            total_samples,
+impl BrownNoise {
+    fn new() -> Self {
+        BrownNoise {
+            state: RandomState::new().hash_one(()) as u32 | 1,
+            level: 0.0,
         }
     }
 }
 
-impl Iterator for SquareWaveWithDecay {
+impl Iterator for BrownNoise {
     type Item = f32;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.sample_idx >= self.total_samples {
-            return None;
-        }
-
-        let t = self.sample_idx as f32 / self.sample_rate as f32;
-        let phase = 2.0 * PI * self.freq * t;
-
-        let wave = (1.0 * (phase).sin() + 1.0 / 3.0 * (3.0 * phase).sin() + 1.0 / 5.0 * (5.0 * phase).sin()) * (4.0 / PI);
-
-        let env = 0.3 * (-20.0 * t).exp();
-        let tail_start = self.duration.as_secs_f32() - 0.005;
-        let fade = if t >= tail_start {
-            let x = (self.duration.as_secs_f32() - t) / 0.005;
-            x.clamp(0.0, 1.0)
-        } else {
-            1.0
-        };
-
-        let sample = wave * env * fade;
-        self.sample_idx += 1;
-        Some(sample)
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        let white = self.state as f32 / u32::MAX as f32 * 2.0 - 1.0;
+        // A leaky running sum, so it wanders without drifting off
+        self.level = (self.level + 0.02 * white) / 1.02;
+        Some((self.level * 3.5).clamp(-1.0, 1.0))
     }
 }
 
-impl Source for SquareWaveWithDecay {
+impl Source for BrownNoise {
     fn current_span_len(&self) -> Option<usize> {
-        Some(self.total_samples - self.sample_idx)
+        None
     }
 
     fn channels(&self) -> u16 {
@@ -143,10 +566,61 @@ impl Source for SquareWaveWithDecay {
     }
 
     fn sample_rate(&self) -> u32 {
-        self.sample_rate
+        44100
     }
 
     fn total_duration(&self) -> Option<Duration> {
-        Some(self.duration)
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_avoids_repeats() {
+        let list = TuneList::Many(vec!["A:d=4:c".to_string(), "B:d=4:e".to_string()]);
+        let pool = Pool::new(&Some(list), true);
+        let picks: Vec<f32> = (0..10).map(|_| pool.pick().unwrap()[0][0].0).collect();
+        assert!(picks.windows(2).all(|pair| pair[0] != pair[1]));
+
+        // "builtin" leaves the choice to the usual melody
+        let pool = Pool::new(&Some(TuneList::One("builtin".to_string())), true);
+        assert!(pool.pick().is_none());
+    }
+
+    #[test]
+    fn test_quiet_hours() {
+        let at = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        let night = QuietHours::parse("22:00-07:00").unwrap();
+        assert!(night.contains(at(23, 30)));
+        assert!(night.contains(at(6, 59)));
+        assert!(!night.contains(at(7, 0)));
+        assert!(!night.contains(at(12, 0)));
+
+        let lunch = QuietHours::parse("12:00 - 13:00").unwrap();
+        assert!(lunch.contains(at(12, 30)));
+        assert!(!lunch.contains(at(23, 30)));
+
+        assert!(QuietHours::parse("22:00").is_err());
+        assert!(QuietHours::parse("25:00-07:00").is_err());
+    }
+
+    #[test]
+    fn test_fade() {
+        let halfway = Fade {
+            from: 1.0,
+            to: 0.2,
+            start: Instant::now() - Duration::from_secs(1),
+            length: Duration::from_secs(2),
+        };
+        assert!((halfway.level() - 0.6).abs() < 0.05);
+        assert!(!halfway.done());
+
+        // Turning back starts from the current level, not the old target
+        let back = halfway.towards(1.0, Duration::from_secs(1));
+        assert!((back.from - 0.6).abs() < 0.05);
+        assert_eq!(Fade::hold(0.3).level(), 0.3);
     }
 }
@@ -0,0 +1,103 @@
+use chrono::{Datelike, Local, NaiveDateTime, NaiveTime, TimeZone};
+
+use crate::alarms;
+use crate::config::AutoTagRule;
+use crate::history::SessionRecord;
+
+fn parse_time(text: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(text.trim(), "%H:%M").map_err(|_| format!("'{text}' is not a time like 12:00"))
+}
+
+// What config check reports about a rule
+pub fn check(rule: &AutoTagRule) -> Result<(), String> {
+    if rule.tag.trim().is_empty() {
+        return Err("tag is empty".to_string());
+    }
+    check_window(&rule.after, &rule.before, &rule.days)
+}
+
+// Hours ("HH:MM") and days as rules give them, also for [[notify]] rules
+pub fn check_window(after: &Option<String>, before: &Option<String>, days: &Option<String>) -> Result<(), String> {
+    for time in [after, before].into_iter().flatten() {
+        parse_time(time)?;
+    }
+    if let Some(days) = days
+        && !matches!(alarms::parse_days(days), Ok(Some(_)))
+    {
+        return Err(format!("'{days}' is not days like \"weekdays\" or \"mon,thu\""));
+    }
+    Ok(())
+}
+
+// Whether `at` falls within the hours and on the days; a window from 22:00
+// to 06:00 runs through midnight, and one that doesn't parse never holds
+pub fn in_window(after: &Option<String>, before: &Option<String>, days: &Option<String>, at: NaiveDateTime) -> bool {
+    let time = |text: &Option<String>| text.as_deref().map(parse_time).transpose();
+    let (Ok(after), Ok(before)) = (time(after), time(before)) else {
+        return false;
+    };
+    let t = at.time();
+    let in_hours = match (after, before) {
+        (Some(after), Some(before)) if before < after => t >= after || t < before,
+        (after, before) => after.is_none_or(|after| t >= after) && before.is_none_or(|before| t < before),
+    };
+    let on_day = match days.as_deref().map(alarms::parse_days) {
+        None => true,
+        Some(Ok(Some(days))) => days.contains(&at.weekday()),
+        Some(_) => false,
+    };
+    in_hours && on_day
+}
+
+fn matches(rule: &AutoTagRule, start: NaiveDateTime) -> bool {
+    in_window(&rule.after, &rule.before, &rule.days, start)
+}
+
+// Adds the tags of the rules that hold when the session started, after any
+// given by hand
+pub fn apply(rules: &[AutoTagRule], record: &mut SessionRecord) {
+    let Some(start) = Local.timestamp_opt(record.started_at as i64, 0).single() else {
+        return;
+    };
+    for rule in rules {
+        if matches(rule, start.naive_local()) && !record.tags.contains(&rule.tag) {
+            record.tags.push(rule.tag.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn rule(tag: &str, after: Option<&str>, before: Option<&str>, days: Option<&str>) -> AutoTagRule {
+        AutoTagRule {
+            tag: tag.to_string(),
+            after: after.map(str::to_string),
+            before: before.map(str::to_string),
+            days: days.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        // 2026-10-16 is a Friday
+        let at = |day, h, m| NaiveDate::from_ymd_opt(2026, 10, day).unwrap().and_hms_opt(h, m, 0).unwrap();
+        let morning = rule("morning", None, Some("12:00"), None);
+        assert!(matches(&morning, at(16, 11, 59)));
+        assert!(!matches(&morning, at(16, 12, 0)));
+        let night = rule("night", Some("22:00"), Some("06:00"), None);
+        assert!(matches(&night, at(16, 23, 0)));
+        assert!(matches(&night, at(17, 5, 0)));
+        assert!(!matches(&night, at(17, 12, 0)));
+        let personal = rule("personal", None, None, Some("weekends"));
+        assert!(!matches(&personal, at(16, 10, 0)));
+        assert!(matches(&personal, at(17, 10, 0)));
+
+        assert!(check(&morning).is_ok());
+        assert!(check(&rule("x", Some("noon"), None, None)).is_err());
+        assert!(check(&rule("x", None, None, Some("once"))).is_err());
+        assert!(check(&rule(" ", None, None, None)).is_err());
+    }
+}
@@ -0,0 +1,144 @@
+use crate::TimerType;
+use crate::clock;
+use crate::history;
+use crate::status::Status;
+
+// Whether to leave someone be, for personal chat bots that auto-reply
+// "In a pomodoro until 14:25". Busy only while work is counting down; a
+// break or a pause is fair game
+pub struct Away {
+    busy: bool,
+    state: &'static str, // As in the status file, or "none" with no timer
+    kind: Option<TimerType>,
+    until: Option<u64>, // Unix seconds the running session ends
+    ends: Option<String>,
+    task: Option<String>,
+    message: String, // templates.away while busy
+}
+
+impl Away {
+    pub fn new(status: Option<&Status>) -> Self {
+        let Some(status) = status else {
+            return Away {
+                busy: false,
+                state: "none",
+                kind: None,
+                until: None,
+                ends: None,
+                task: None,
+                message: String::new(),
+            };
+        };
+        let running = status.state == "running";
+        let busy = running && status.session == TimerType::Work;
+        Away {
+            busy,
+            state: status.state,
+            kind: Some(status.session.clone()),
+            until: running.then_some(history::now_secs() + status.remaining_secs),
+            ends: running.then(|| clock::ends_at(status.remaining_secs)),
+            task: status.task.clone(),
+            message: if busy { status.away.clone() } else { String::new() },
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self.kind {
+            Some(TimerType::Work) => "work",
+            Some(TimerType::Break) => "break",
+            None => "",
+        }
+    }
+
+    // One key=value per line, every key always there and empty when unknown
+    pub fn key_value(&self) -> String {
+        let line = |text: &str| text.replace(['\r', '\n'], " ");
+        [
+            format!("busy={}", if self.busy { "yes" } else { "no" }),
+            format!("state={}", self.state),
+            format!("kind={}", self.kind()),
+            format!("until={}", self.until.map(|secs| secs.to_string()).unwrap_or_default()),
+            format!("ends={}", self.ends.as_deref().unwrap_or_default()),
+            format!("task={}", line(self.task.as_deref().unwrap_or_default())),
+            format!("message={}", line(&self.message)),
+        ]
+        .map(|entry| entry + "\n")
+        .concat()
+    }
+
+    // A plist for `read`: (:busy t :state "running" ... :message "...")
+    pub fn emacs(&self) -> String {
+        let string = |text: Option<&str>| match text {
+            Some(text) => format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\"")),
+            None => "nil".to_string(),
+        };
+        format!(
+            "(:busy {} :state {} :kind {} :until {} :ends {} :task {} :message {})",
+            if self.busy { "t" } else { "nil" },
+            string(Some(self.state)),
+            string(self.kind.as_ref().map(|_| self.kind())),
+            self.until.map_or("nil".to_string(), |secs| secs.to_string()),
+            string(self.ends.as_deref()),
+            string(self.task.as_deref()),
+            string(Some(&self.message).filter(|message| !message.is_empty()).map(String::as_str)),
+        )
+    }
+
+    // A presence stanza a bot can send as is: do not disturb with the message
+    // while busy, plain available otherwise
+    pub fn xmpp(&self) -> String {
+        if !self.busy {
+            return "<presence/>".to_string();
+        }
+        let escaped = self
+            .message
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&apos;");
+        format!("<presence><show>dnd</show><status>{escaped}</status></presence>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ClockDisplay;
+
+    #[test]
+    fn test_away_formats() {
+        let mut status = Status {
+            session: TimerType::Work,
+            state: "running",
+            remaining_secs: 600,
+            duration_secs: 1500,
+            completed_sessions: 1,
+            today: 1,
+            daily_goal: 0,
+            task: Some("Say \"hi\"".to_string()),
+            tags: Vec::new(),
+            text: String::new(),
+            away: "In a pomodoro <until> 14:25".to_string(),
+            clock: ClockDisplay::Remaining,
+        };
+        let away = Away::new(Some(&status));
+        assert!(away.key_value().starts_with("busy=yes\nstate=running\nkind=work\nuntil="));
+        assert!(away.key_value().ends_with("task=Say \"hi\"\nmessage=In a pomodoro <until> 14:25\n"));
+        assert!(away.emacs().starts_with("(:busy t :state \"running\" :kind \"work\" :until "));
+        assert!(away.emacs().ends_with(":task \"Say \\\"hi\\\"\" :message \"In a pomodoro <until> 14:25\")"));
+        assert_eq!(
+            away.xmpp(),
+            "<presence><show>dnd</show><status>In a pomodoro &lt;until&gt; 14:25</status></presence>"
+        );
+
+        status.state = "paused";
+        let paused = Away::new(Some(&status));
+        assert!(paused.key_value().contains("busy=no\n") && paused.key_value().contains("\nuntil=\n"));
+        assert_eq!(paused.xmpp(), "<presence/>");
+        assert_eq!(
+            Away::new(None).emacs(),
+            "(:busy nil :state \"none\" :kind nil :until nil :ends nil :task nil :message nil)"
+        );
+    }
+}
@@ -0,0 +1,204 @@
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::Config;
+use crate::paths;
+
+// Archive layout: everything under the config dir is stored as `config/...`,
+// everything under the data dir as `data/...`, and a history dir configured
+// outside the data dir as `history/...`
+const CONFIG_PREFIX: &str = "config";
+const DATA_PREFIX: &str = "data";
+const HISTORY_PREFIX: &str = "history";
+
+pub fn default_backup_path() -> PathBuf {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    PathBuf::from(format!("cyber-tomato-backup-{secs}.tar"))
+}
+
+pub fn create_backup(output: &Path) -> Result<usize, Box<dyn std::error::Error>> {
+    let mut builder = tar::Builder::new(File::create(output)?);
+    let mut count = 0;
+
+    let data_dir = paths::data_dir();
+    let mut sources = vec![(CONFIG_PREFIX, paths::config_dir()), (DATA_PREFIX, data_dir.clone())];
+    // A config.toml that doesn't load is when a backup matters most, so
+    // history is taken from its default place instead
+    let history_dir = Config::load().map(|config| config.history_dir()).unwrap_or_else(|e| {
+        let history_dir = data_dir.join("history");
+        eprintln!("Warning: {e}\nBacking up history from {}", history_dir.display());
+        history_dir
+    });
+    if !history_dir.starts_with(&data_dir) {
+        sources.push((HISTORY_PREFIX, history_dir));
+    }
+
+    for (prefix, dir) in sources {
+        if dir.is_dir() {
+            count += append_dir(&mut builder, &dir, Path::new(prefix))?;
+        }
+    }
+
+    builder.finish()?;
+    Ok(count)
+}
+
+fn append_dir(builder: &mut tar::Builder<File>, dir: &Path, archive_dir: &Path) -> io::Result<usize> {
+    let mut count = 0;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let name = archive_dir.join(entry.file_name());
+        if path.is_dir() {
+            count += append_dir(builder, &path, &name)?;
+        } else if path.is_file() {
+            builder.append_path_with_name(&path, &name)?;
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+pub fn restore_backup(archive: &Path, force: bool) -> Result<usize, Box<dyn std::error::Error>> {
+    restore_into(archive, force, &paths::config_dir(), &paths::data_dir())
+}
+
+fn restore_into(archive: &Path, force: bool, config_dir: &Path, data_dir: &Path) -> Result<usize, Box<dyn std::error::Error>> {
+    // A first pass writes nothing: it checks every entry and finds where the
+    // archived config puts history, so a bad archive is refused up front
+    let mut history_dir = None;
+    let mut config_text = None;
+    for entry in tar::Archive::new(File::open(archive)?).entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        let kind = entry.header().entry_type();
+        // Links could point the entries after them outside the directories
+        if !kind.is_file() && !kind.is_dir() {
+            return Err(format!("unsupported entry in backup (only files and directories are restored): {}", path.display()).into());
+        }
+        if restore_target(&path, config_dir, data_dir, data_dir).is_none() {
+            return Err(format!("unexpected entry in backup: {}", path.display()).into());
+        }
+        if path == Path::new(CONFIG_PREFIX).join("config.toml") {
+            let mut text = String::new();
+            entry.read_to_string(&mut text)?;
+            config_text = Some(text);
+        }
+        if path.starts_with(HISTORY_PREFIX) {
+            history_dir = Some(data_dir.join("history"));
+        }
+    }
+    if history_dir.is_some()
+        && let Some(text) = config_text
+    {
+        let config: Config = toml::from_str(&text).map_err(|e| format!("config.toml in backup: {e}"))?;
+        history_dir = Some(config.history_dir());
+    }
+
+    let existing = [Some(config_dir), Some(data_dir), history_dir.as_deref()];
+    if !force && let Some(dir) = existing.into_iter().flatten().find(|dir| has_files(dir)) {
+        return Err(format!("existing data found in {} - rerun with --force to overwrite", dir.display()).into());
+    }
+
+    let history_dir = history_dir.unwrap_or_else(|| data_dir.join("history"));
+    let mut count = 0;
+    for entry in tar::Archive::new(File::open(archive)?).entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        let target = restore_target(&path, config_dir, data_dir, &history_dir).ok_or("backup changed while restoring")?;
+        if entry.header().entry_type().is_dir() {
+            fs::create_dir_all(&target)?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        entry.unpack(&target)?;
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+// Map an archive path onto the real directories, rejecting anything that
+// could escape them (absolute paths, `..`, unknown prefixes)
+fn restore_target(path: &Path, config_dir: &Path, data_dir: &Path, history_dir: &Path) -> Option<PathBuf> {
+    let mut components = path.components();
+    let base = match components.next()? {
+        Component::Normal(p) if p == CONFIG_PREFIX => config_dir,
+        Component::Normal(p) if p == DATA_PREFIX => data_dir,
+        Component::Normal(p) if p == HISTORY_PREFIX => history_dir,
+        _ => return None,
+    };
+
+    let rest = components.as_path();
+    if rest.as_os_str().is_empty() || !rest.components().all(|c| matches!(c, Component::Normal(_))) {
+        return None;
+    }
+    Some(base.join(rest))
+}
+
+fn has_files(dir: &Path) -> bool {
+    fs::read_dir(dir).map(|mut entries| entries.next().is_some()).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restore_target_rejects_escapes() {
+        let (config, data, history) = (Path::new("/cfg"), Path::new("/data"), Path::new("/sync"));
+        let target = |p: &str| restore_target(Path::new(p), config, data, history);
+        assert_eq!(target("config/config.toml"), Some(PathBuf::from("/cfg/config.toml")));
+        assert_eq!(target("data/history/a.jsonl"), Some(PathBuf::from("/data/history/a.jsonl")));
+        assert_eq!(target("history/laptop.jsonl"), Some(PathBuf::from("/sync/laptop.jsonl")));
+        assert_eq!(target("data/../../etc/passwd"), None);
+        assert_eq!(target("/etc/passwd"), None);
+        assert_eq!(target("other/file"), None);
+        assert_eq!(target("config"), None);
+    }
+
+    #[test]
+    fn test_restore_refuses_links_and_existing_history() {
+        let dir = std::env::temp_dir().join(format!("cyber-tomato-restore-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let (config, data, outside) = (dir.join("config"), dir.join("data"), dir.join("outside"));
+        fs::create_dir_all(&outside).unwrap();
+        let archive = dir.join("backup.tar");
+        let write_archive = |files: &[(&str, &str)], link: Option<(&str, &Path)>| {
+            let mut builder = tar::Builder::new(File::create(&archive).unwrap());
+            if let Some((name, target)) = link {
+                let mut header = tar::Header::new_gnu();
+                header.set_entry_type(tar::EntryType::Symlink);
+                header.set_size(0);
+                builder.append_link(&mut header, name, target).unwrap();
+            }
+            for (name, text) in files {
+                let mut header = tar::Header::new_gnu();
+                header.set_size(text.len() as u64);
+                header.set_mode(0o644);
+                builder.append_data(&mut header, name, text.as_bytes()).unwrap();
+            }
+            builder.finish().unwrap();
+        };
+
+        write_archive(&[("config/x/evil", "owned")], Some(("config/x", &outside)));
+        assert!(restore_into(&archive, true, &config, &data).is_err());
+        assert!(!outside.join("evil").exists() && !config.exists());
+
+        // History kept outside the data dir is checked before anything is written
+        let history = dir.join("sync");
+        fs::create_dir_all(&history).unwrap();
+        fs::write(history.join("laptop.jsonl"), "").unwrap();
+        let config_toml = format!("[history]\ndir = {:?}\n", history.display().to_string());
+        write_archive(&[("config/config.toml", &config_toml), ("history/laptop.jsonl", "{}\n")], None);
+        assert!(restore_into(&archive, false, &config, &data).is_err());
+        assert!(!config.exists());
+        assert_eq!(restore_into(&archive, true, &config, &data).unwrap(), 2);
+        assert_eq!(fs::read_to_string(history.join("laptop.jsonl")).unwrap(), "{}\n");
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,220 @@
+use std::fs;
+use std::rc::Rc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::TimerType;
+use crate::audio::AudioEngine;
+use crate::cli::say;
+use crate::config::{HistoryBackend, SoundsConfig, Waveform};
+use crate::fireworks::{self, Fireworks};
+use crate::history::{self, History, SessionStore};
+use crate::mario_animation::{self, MarioAnimation};
+use crate::session::PomodoroSession;
+
+// `cyber-tomato bench`: a soak test for timing changes. Runs thousands of
+// sessions through the session state machine at SCALE times real speed,
+// plays the animations on a simulated clock and writes a history to a
+// scratch folder, failing on the first thing that goes wrong
+
+const SCALE: f64 = 1_000_000.0;
+const WORK: Duration = Duration::from_secs(25 * 60);
+const BREAK: Duration = Duration::from_secs(5 * 60);
+// How often the bench looks at a running session, as the main loop would
+const POLL: Duration = Duration::from_micros(100);
+// Completion noticed later than this, in real time, is a stall
+const MAX_LATE: Duration = Duration::from_millis(50);
+// Allowance for float rounding in the scaled clock, per pause
+const ROUNDING: Duration = Duration::from_micros(1);
+// More than the busiest scene ever has alive at once
+const MAX_PARTICLES: usize = 1000;
+
+// xorshift, seeded from the clock; good enough for where pauses fall
+struct Random(u64);
+
+impl Random {
+    fn new() -> Self {
+        let seed = history::now_secs() ^ (u64::from(std::process::id()) << 32);
+        Random(seed | 1)
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % n
+    }
+}
+
+pub fn run(sessions: u32) -> Result<(), Box<dyn std::error::Error>> {
+    let mut random = Random::new();
+    clock(sessions, &mut random)?;
+    animations((sessions / 10).max(1), &mut random)?;
+    store(sessions)?;
+    Ok(())
+}
+
+// Paused sessions must bank exactly the time they ran, and a running one
+// must finish on time
+fn clock(sessions: u32, random: &mut Random) -> Result<(), String> {
+    let started = Instant::now();
+    let (mut pauses, mut latest) = (0, Duration::ZERO);
+    for n in 0..sessions {
+        let (kind, duration) = if n % 2 == 0 { (TimerType::Work, WORK) } else { (TimerType::Break, BREAK) };
+        let mut session = PomodoroSession::new(kind, duration);
+        // The time run lies between the inner and outer brackets of each stretch
+        let (mut least, mut most) = (Duration::ZERO, Duration::ZERO);
+        let stretches = random.below(4);
+        for _ in 0..stretches {
+            let before = Instant::now();
+            session.resume();
+            let resumed = Instant::now();
+            // Up to 50 simulated seconds a stretch, well short of a break
+            spin(Duration::from_micros(random.below(50)));
+            let pausing = Instant::now();
+            session.pause(SCALE);
+            let after = Instant::now();
+            least += pausing - resumed;
+            most += after - before;
+        }
+        pauses += stretches;
+        let banked = session.elapsed(SCALE);
+        let slack = ROUNDING * stretches as u32;
+        if banked + slack < least.mul_f64(SCALE) || banked > most.mul_f64(SCALE) + slack {
+            return Err(format!(
+                "session {n}: banked {banked:?} after {stretches} pause(s), ran {:?} to {:?}",
+                least.mul_f64(SCALE),
+                most.mul_f64(SCALE)
+            ));
+        }
+
+        session.resume();
+        let due = Instant::now() + duration.saturating_sub(banked).div_f64(SCALE);
+        let mut last = banked;
+        loop {
+            let elapsed = session.elapsed(SCALE);
+            if elapsed < last {
+                return Err(format!("session {n}: the clock went back from {last:?} to {elapsed:?}"));
+            }
+            last = elapsed;
+            if elapsed >= duration {
+                break;
+            }
+            thread::sleep(POLL);
+        }
+        session.finish(false);
+        let late = Instant::now().saturating_duration_since(due);
+        if late > MAX_LATE {
+            return Err(format!("session {n}: finished {late:?} late"));
+        }
+        latest = latest.max(late);
+        if session.elapsed(SCALE) != duration {
+            return Err(format!("session {n}: finished at {:?}, not {duration:?}", session.elapsed(SCALE)));
+        }
+    }
+    say!(
+        "Clock: {sessions} sessions at {SCALE}x with {pauses} pauses in {:.1}s; at most {:.2}ms late",
+        started.elapsed().as_secs_f64(),
+        latest.as_secs_f64() * 1000.0
+    );
+    Ok(())
+}
+
+fn spin(length: Duration) {
+    let until = Instant::now() + length;
+    while Instant::now() < until {}
+}
+
+// Plays each scene `runs` times at uneven frame rates, stalls included,
+// watching that the particles stay bounded
+fn animations(runs: u32, random: &mut Random) -> Result<(), String> {
+    let audio = Rc::new(AudioEngine::new());
+    audio.set_levels(&SoundsConfig {
+        mute: true,
+        ..SoundsConfig::default()
+    });
+    let mut most = 0;
+    for run in 0..runs {
+        let mut mario = MarioAnimation::new(audio.clone(), Waveform::default());
+        mario.set_golden(run % 2 == 1);
+        mario.start();
+        let mut fireworks = Fireworks::new(audio.clone(), Waveform::default(), String::new());
+        fireworks.start();
+
+        let mut shown = Duration::ZERO;
+        while shown < mario_animation::LENGTH.max(fireworks::LENGTH) {
+            // Mostly 60fps, now and then a slow frame or a stall
+            let frame = match random.below(100) {
+                0 => Duration::from_millis(500 + random.below(2000)),
+                1..=9 => Duration::from_millis(50 + random.below(150)),
+                _ => Duration::from_millis(16),
+            };
+            shown += frame;
+            if shown <= mario_animation::LENGTH {
+                mario.advance(frame, shown);
+            }
+            if shown <= fireworks::LENGTH {
+                fireworks.advance(frame, shown);
+            }
+            let particles = mario.particles().max(fireworks.particles());
+            if particles > MAX_PARTICLES {
+                return Err(format!("animation run {run}: {particles} particles alive at {shown:?}"));
+            }
+            most = most.max(particles);
+        }
+    }
+    say!("Animations: {runs} runs of each scene; at most {most} particles alive");
+    Ok(())
+}
+
+// Every session written must read back once, from a fresh open as well
+fn store(sessions: u32) -> Result<(), Box<dyn std::error::Error>> {
+    let backends = [
+        HistoryBackend::Jsonl,
+        #[cfg(feature = "sqlite")]
+        HistoryBackend::Sqlite,
+    ];
+    for backend in backends {
+        let dir = std::env::temp_dir().join(format!("cyber-tomato-bench-{}-{}", std::process::id(), backend.name()));
+        let _ = fs::remove_dir_all(&dir);
+        let result = write_history(&dir, backend, sessions);
+        let _ = fs::remove_dir_all(&dir);
+        let (secs, size) = result?;
+        say!(
+            "History ({}): {sessions} sessions written and read back in {secs:.1}s, {size} bytes",
+            backend.name()
+        );
+    }
+    Ok(())
+}
+
+fn write_history(dir: &std::path::Path, backend: HistoryBackend, sessions: u32) -> Result<(f64, u64), Box<dyn std::error::Error>> {
+    let started = Instant::now();
+    let mut history = History::open(dir.to_path_buf(), backend)?;
+    let first = history::now_secs() - sessions as u64 * 30 * 60;
+    let mut ids = Vec::new();
+    for n in 0..sessions as u64 {
+        let (kind, secs) = if n % 2 == 0 { (TimerType::Work, WORK) } else { (TimerType::Break, BREAK) };
+        let record = history.new_record(kind, first + n * 30 * 60, secs.as_secs());
+        ids.push(record.id.clone());
+        history.append(record)?;
+    }
+    for (label, history) in [("in memory", &history), ("reopened", &History::open(dir.to_path_buf(), backend)?)] {
+        let read: Vec<&str> = history.records().iter().map(|record| record.id.as_str()).collect();
+        if read != ids {
+            return Err(format!("{}: {} of {sessions} sessions read back {label}", backend.name(), read.len()).into());
+        }
+    }
+    let size = fs::read_dir(dir)?.filter_map(|entry| entry.ok()?.metadata().ok()).map(|meta| meta.len()).sum();
+    Ok((started.elapsed().as_secs_f64(), size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench_runs() {
+        assert!(run(10).is_ok());
+    }
+}
@@ -0,0 +1,190 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Color,
+    symbols::Marker,
+    widgets::canvas::{Canvas, Points},
+};
+
+// Glyphs are strokes through points on a grid DIGIT_WIDTH wide and
+// GLYPH_HEIGHT tall, y growing downwards, so they can be drawn at any size
+const DIGIT_WIDTH: f64 = 4.0;
+const COLON_WIDTH: f64 = 1.0;
+const GLYPH_HEIGHT: f64 = 6.0;
+const GAP: f64 = 1.5;
+// Stroke width in grid units
+const STROKE: f64 = 0.9;
+// Below this many dots per grid unit the strokes run together
+const MIN_SCALE: f64 = 1.5;
+
+type Stroke = &'static [(f64, f64)];
+
+fn strokes(c: char) -> &'static [Stroke] {
+    match c {
+        '0' => &[&[
+            (1.0, 0.0),
+            (3.0, 0.0),
+            (4.0, 1.0),
+            (4.0, 5.0),
+            (3.0, 6.0),
+            (1.0, 6.0),
+            (0.0, 5.0),
+            (0.0, 1.0),
+            (1.0, 0.0),
+        ]],
+        '1' => &[&[(0.8, 1.2), (2.2, 0.0), (2.2, 6.0)], &[(0.8, 6.0), (3.6, 6.0)]],
+        '2' => &[&[(0.0, 1.0), (1.0, 0.0), (3.0, 0.0), (4.0, 1.0), (4.0, 2.0), (0.0, 6.0), (4.0, 6.0)]],
+        '3' => &[
+            &[(0.0, 1.0), (1.0, 0.0), (3.0, 0.0), (4.0, 1.0), (4.0, 2.0), (3.0, 3.0), (1.5, 3.0)],
+            &[(3.0, 3.0), (4.0, 4.0), (4.0, 5.0), (3.0, 6.0), (1.0, 6.0), (0.0, 5.0)],
+        ],
+        '4' => &[&[(3.0, 6.0), (3.0, 0.0), (0.0, 4.0), (4.0, 4.0)]],
+        '5' => &[&[(4.0, 0.0), (0.0, 0.0), (0.0, 3.0), (3.0, 3.0), (4.0, 4.0), (4.0, 5.0), (3.0, 6.0), (0.0, 6.0)]],
+        '6' => &[&[
+            (3.5, 0.0),
+            (1.0, 0.0),
+            (0.0, 1.0),
+            (0.0, 5.0),
+            (1.0, 6.0),
+            (3.0, 6.0),
+            (4.0, 5.0),
+            (4.0, 4.0),
+            (3.0, 3.0),
+            (0.0, 3.0),
+        ]],
+        '7' => &[&[(0.0, 0.0), (4.0, 0.0), (4.0, 1.0), (1.5, 6.0)]],
+        '8' => &[
+            &[
+                (1.0, 3.0),
+                (0.0, 2.0),
+                (0.0, 1.0),
+                (1.0, 0.0),
+                (3.0, 0.0),
+                (4.0, 1.0),
+                (4.0, 2.0),
+                (3.0, 3.0),
+                (1.0, 3.0),
+            ],
+            &[(1.0, 3.0), (0.0, 4.0), (0.0, 5.0), (1.0, 6.0), (3.0, 6.0), (4.0, 5.0), (4.0, 4.0), (3.0, 3.0)],
+        ],
+        '9' => &[&[
+            (4.0, 3.0),
+            (1.0, 3.0),
+            (0.0, 2.0),
+            (0.0, 1.0),
+            (1.0, 0.0),
+            (3.0, 0.0),
+            (4.0, 1.0),
+            (4.0, 5.0),
+            (3.0, 6.0),
+            (0.5, 6.0),
+        ]],
+        // Single points, drawn as round dots
+        ':' => &[&[(0.5, 2.0)], &[(0.5, 4.0)]],
+        _ => &[],
+    }
+}
+
+// The blinking colon keeps its place while hidden
+fn advance(c: char) -> f64 {
+    match c {
+        ':' | ' ' => COLON_WIDTH,
+        _ => DIGIT_WIDTH,
+    }
+}
+
+fn text_width(text: &str) -> f64 {
+    let widths: f64 = text.chars().map(advance).sum();
+    widths + GAP * text.chars().count().saturating_sub(1) as f64
+}
+
+// Distance from p to the segment from a to b
+fn distance(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length = dx * dx + dy * dy;
+    let t = if length == 0.0 {
+        0.0
+    } else {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / length).clamp(0.0, 1.0)
+    };
+    (p.0 - a.0 - t * dx).hypot(p.1 - a.1 - t * dy)
+}
+
+// Lit dots for `text` centered in a grid `width` by `height` dots, top row
+// first, or None when it's too small to read
+pub fn dots(text: &str, width: u16, height: u16) -> Option<Vec<(u16, u16)>> {
+    // Half a stroke of room around the edges
+    let (text_width, text_height) = (text_width(text) + STROKE, GLYPH_HEIGHT + STROKE);
+    let scale = (width as f64 / text_width).min(height as f64 / text_height);
+    if scale < MIN_SCALE {
+        return None;
+    }
+    let left = (width as f64 - text_width * scale) / 2.0 + STROKE * scale / 2.0;
+    let top = (height as f64 - text_height * scale) / 2.0 + STROKE * scale / 2.0;
+
+    let mut lit = Vec::new();
+    let mut x = left;
+    for c in text.chars() {
+        let glyph = strokes(c);
+        let (from, to) = (x - STROKE * scale, x + (advance(c) + STROKE) * scale);
+        for column in (from.max(0.0) as u16)..(to.min(width as f64) as u16) {
+            for row in 0..height {
+                // Dot centers in grid units
+                let p = ((column as f64 + 0.5 - x) / scale, (row as f64 + 0.5 - top) / scale);
+                let hit = glyph.iter().any(|stroke| {
+                    let last = stroke.len() - 1;
+                    (0..stroke.len().max(2) - 1).any(|i| distance(p, stroke[i], stroke[(i + 1).min(last)]) <= STROKE / 2.0)
+                });
+                if hit {
+                    lit.push((column, row));
+                }
+            }
+        }
+        x += (advance(c) + GAP) * scale;
+    }
+    Some(lit)
+}
+
+// Draws the time as large as the area allows; false when it's too small, for
+// the caller to fall back to the block font
+pub fn render(f: &mut Frame, area: Rect, text: &str, color: Color) -> bool {
+    // Each braille cell holds 2x4 dots
+    let (width, height) = (area.width * 2, area.height * 4);
+    let Some(lit) = dots(text, width, height) else {
+        return false;
+    };
+    // The canvas counts y upwards from the bottom
+    let coords: Vec<(f64, f64)> = lit.iter().map(|&(x, y)| (x as f64, (height - 1 - y) as f64)).collect();
+    let canvas = Canvas::default()
+        .marker(Marker::Braille)
+        .x_bounds([0.0, (width - 1) as f64])
+        .y_bounds([0.0, (height - 1) as f64])
+        .paint(move |ctx| {
+            ctx.draw(&Points { coords: &coords, color });
+        });
+    f.render_widget(canvas, area);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dots_scale_with_area() {
+        // Two columns of dots per cell and four rows
+        assert!(dots("25:00", 2 * 10, 4 * 2).is_none());
+        let small = dots("25:00", 2 * 40, 4 * 5).unwrap();
+        let large = dots("25:00", 2 * 120, 4 * 15).unwrap();
+        assert!(large.len() > small.len() * 4);
+        assert!(large.iter().all(|&(x, y)| x < 240 && y < 60));
+    }
+
+    #[test]
+    fn test_blinking_colon_keeps_digits_in_place() {
+        let lit = dots("25:00", 80, 24).unwrap();
+        let blank = dots("25 00", 80, 24).unwrap();
+        assert!(blank.len() < lit.len());
+        assert!(blank.iter().all(|dot| lit.contains(dot)));
+    }
+}
@@ -0,0 +1,115 @@
+use std::f64::consts::PI;
+use std::time::Duration;
+
+use ratatui::{
+    Frame,
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    symbols::Marker,
+    widgets::{
+        Block, Paragraph,
+        canvas::{Canvas, Circle},
+    },
+};
+
+const INHALE: f64 = 4.0;
+const HOLD: f64 = 2.0;
+const EXHALE: f64 = 6.0;
+const CYCLE: f64 = INHALE + HOLD + EXHALE; // Five breaths a minute
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Phase {
+    Inhale,
+    Hold,
+    Exhale,
+}
+
+impl Phase {
+    fn text(self) -> &'static str {
+        match self {
+            Phase::Inhale => "Breathe in",
+            Phase::Hold => "Hold",
+            Phase::Exhale => "Breathe out",
+        }
+    }
+
+    // Soft cue played as the phase begins
+    pub fn tone(self) -> Option<f32> {
+        match self {
+            Phase::Inhale => Some(523.25), // C5
+            Phase::Hold => None,
+            Phase::Exhale => Some(392.0), // G4
+        }
+    }
+}
+
+// Phase at `elapsed` into the break, how full the lungs are (0.0 to 1.0) and
+// the seconds left in the phase
+pub fn phase_at(elapsed: Duration) -> (Phase, f64, f64) {
+    let ease = |x: f64| (1.0 - (PI * x).cos()) / 2.0;
+    let t = elapsed.as_secs_f64() % CYCLE;
+    if t < INHALE {
+        (Phase::Inhale, ease(t / INHALE), INHALE - t)
+    } else if t < INHALE + HOLD {
+        (Phase::Hold, 1.0, INHALE + HOLD - t)
+    } else {
+        let t = t - INHALE - HOLD;
+        (Phase::Exhale, 1.0 - ease(t / EXHALE), EXHALE - t)
+    }
+}
+
+// A circle that grows and shrinks with the breath, with a prompt underneath.
+// Driven by the break's elapsed time so it stops when the timer is paused
+pub fn render(f: &mut Frame, area: Rect, block: Block, elapsed: Duration, color: Color) {
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    if inner.height < 2 {
+        return;
+    }
+    let (phase, fullness, left) = phase_at(elapsed);
+
+    let circle_area = Rect {
+        height: inner.height - 1,
+        ..inner
+    };
+    // Cells are about twice as tall as wide, so one y unit is half a row
+    let (half_width, half_height) = (circle_area.width as f64 / 2.0, circle_area.height as f64);
+    let max_radius = half_width.min(half_height) * 0.9;
+    let radius = max_radius * (0.25 + 0.75 * fullness);
+    let canvas = Canvas::default()
+        .marker(Marker::Braille)
+        .x_bounds([-half_width, half_width])
+        .y_bounds([-half_height, half_height])
+        .paint(move |ctx| {
+            ctx.draw(&Circle { x: 0.0, y: 0.0, radius, color });
+            ctx.draw(&Circle {
+                x: 0.0,
+                y: 0.0,
+                radius: radius * 0.6,
+                color: Color::DarkGray,
+            });
+        });
+    f.render_widget(canvas, circle_area);
+
+    let prompt = Paragraph::new(format!("{}  {}", phase.text(), left.ceil()))
+        .style(Style::default().fg(color).add_modifier(Modifier::BOLD))
+        .alignment(Alignment::Center);
+    f.render_widget(prompt, Rect::new(inner.x, inner.bottom() - 1, inner.width, 1));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phase_cycle() {
+        let at = |secs: f64| phase_at(Duration::from_secs_f64(secs));
+        assert_eq!(at(0.0).0, Phase::Inhale);
+        assert_eq!(at(0.0).1, 0.0);
+        assert_eq!(at(4.5), (Phase::Hold, 1.0, 1.5));
+        assert_eq!(at(9.0).0, Phase::Exhale);
+        assert!((at(9.0).1 - 0.5).abs() < 1e-9);
+        // The next cycle starts from empty lungs again
+        assert_eq!(at(CYCLE + 1.0).0, Phase::Inhale);
+    }
+}
@@ -0,0 +1,292 @@
+use std::fs;
+use std::path::Path;
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, Instant};
+
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+
+use crate::config::CalendarConfig;
+use crate::{history, jobs, paths};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Event {
+    pub start: u64, // Unix seconds
+    pub end: u64,
+    pub summary: String,
+}
+
+impl Event {
+    // "Standup in 7 min", or "Standup now" once it has started
+    pub fn describe(&self, now: u64) -> String {
+        match self.start.checked_sub(now) {
+            Some(secs) if secs > 0 => format!("{} in {} min", self.summary, secs.div_ceil(60)),
+            _ => format!("{} now", self.summary),
+        }
+    }
+}
+
+// Upcoming events from a local .ics file or a calendar feed URL, reloaded in
+// the background every `refresh_minutes`. Only timed events count; all-day
+// events would otherwise block whole days
+pub struct Calendar {
+    config: CalendarConfig,
+    events: Vec<Event>,
+    loaded_at: Option<Instant>,
+    loading: Option<Receiver<Result<Vec<Event>, String>>>,
+    error: Option<String>, // The last load error, until it has been reported
+}
+
+impl Calendar {
+    pub fn new(config: CalendarConfig) -> Self {
+        Calendar {
+            config,
+            events: Vec::new(),
+            loaded_at: None,
+            loading: None,
+            error: None,
+        }
+    }
+
+    pub fn set_config(&mut self, config: CalendarConfig) {
+        if config.source != self.config.source {
+            self.events.clear();
+            self.loaded_at = None;
+        }
+        self.config = config;
+    }
+
+    pub fn blocks_auto_start(&self) -> bool {
+        self.config.block_auto_start
+    }
+
+    // Called every frame: picks up a finished load and starts the next one when due
+    pub fn tick(&mut self) {
+        if let Some(receiver) = &self.loading
+            && let Ok(result) = receiver.try_recv()
+        {
+            match result {
+                Ok(events) => self.events = events,
+                Err(e) => self.error = Some(format!("Calendar: {e}")),
+            }
+            self.loading = None;
+        }
+        let Some(source) = self.config.source.clone() else {
+            return;
+        };
+        let refresh = Duration::from_secs(self.config.refresh_minutes as u64 * 60);
+        if self.loading.is_some() || self.loaded_at.is_some_and(|at| at.elapsed() < refresh) {
+            return;
+        }
+        self.loaded_at = Some(Instant::now());
+        self.loading = Some(jobs::run(move || load(&source).map(|text| parse(&text, history::now_secs()))));
+    }
+
+    pub fn errors(&mut self) -> impl Iterator<Item = String> {
+        self.error.take().into_iter()
+    }
+
+    // The first event overlapping `from..until`
+    pub fn collision(&self, from: u64, until: u64) -> Option<&Event> {
+        self.events.iter().filter(|e| e.start < until && e.end > from).min_by_key(|e| e.start)
+    }
+
+    // The first event under way or starting within `warn_minutes`
+    pub fn upcoming(&self, now: u64) -> Option<&Event> {
+        self.collision(now, now + self.config.warn_minutes as u64 * 60)
+    }
+}
+
+fn load(source: &str) -> Result<String, String> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        ureq::get(source)
+            .call()
+            .and_then(|mut response| response.body_mut().read_to_string())
+            .map_err(|e| e.to_string())
+    } else {
+        fs::read_to_string(paths::expand_home(Path::new(source))).map_err(|e| format!("{source}: {e}"))
+    }
+}
+
+// Timed events from now until a day ahead, with daily and weekly repeats
+// expanded. Other recurrence rules only count their first occurrence
+fn parse(text: &str, now: u64) -> Vec<Event> {
+    let horizon = now + 24 * 60 * 60;
+    // Long lines are folded onto continuation lines starting with a space
+    let unfolded = text.replace("\r\n", "\n").replace("\n ", "").replace("\n\t", "");
+    let mut events = Vec::new();
+    let mut fields: Vec<(String, String)> = Vec::new();
+    let mut inside = false;
+    for line in unfolded.lines() {
+        match line {
+            "BEGIN:VEVENT" => {
+                inside = true;
+                fields.clear();
+            }
+            "END:VEVENT" => {
+                inside = false;
+                events.extend(occurrences(&fields, now, horizon));
+            }
+            _ if inside => {
+                if let Some((name, value)) = line.split_once(':') {
+                    fields.push((name.to_string(), value.to_string()));
+                }
+            }
+            _ => {}
+        }
+    }
+    events.sort_by_key(|e| e.start);
+    events
+}
+
+fn occurrences(fields: &[(String, String)], now: u64, horizon: u64) -> Vec<Event> {
+    // Property names carry parameters: DTSTART;TZID=Europe/Berlin
+    let field = |name: &str| {
+        fields
+            .iter()
+            .find(|(key, _)| key.split(';').next() == Some(name))
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    };
+    let Some(start) = field("DTSTART").and_then(|(key, value)| parse_time(key, value)) else {
+        return Vec::new();
+    };
+    let end = field("DTEND")
+        .and_then(|(key, value)| parse_time(key, value))
+        .unwrap_or(start + 60 * 60)
+        .max(start);
+    let summary = field("SUMMARY").map(|(_, value)| unescape(value)).unwrap_or_else(|| "Event".to_string());
+    let excluded: Vec<u64> = fields
+        .iter()
+        .filter(|(key, _)| key.split(';').next() == Some("EXDATE"))
+        .flat_map(|(key, value)| value.split(',').filter_map(|v| parse_time(key, v)))
+        .collect();
+
+    let rule = field("RRULE").map(|(_, value)| value).unwrap_or("");
+    let starts = repeat(start, rule, horizon);
+    starts
+        .into_iter()
+        .filter(|s| !excluded.contains(s))
+        .map(|s| Event {
+            start: s,
+            end: s + (end - start),
+            summary: summary.clone(),
+        })
+        .filter(|e| e.end > now && e.start < horizon)
+        .collect()
+}
+
+// Start times of a DAILY or WEEKLY rule up to `horizon`
+fn repeat(start: u64, rule: &str, horizon: u64) -> Vec<u64> {
+    let part = |name: &str| rule.split(';').find_map(|p| p.strip_prefix(name).and_then(|rest| rest.strip_prefix('=')));
+    let step_days = match part("FREQ") {
+        Some("DAILY") => 1,
+        Some("WEEKLY") => 7,
+        _ => return vec![start],
+    };
+    let interval: i64 = part("INTERVAL").and_then(|v| v.parse().ok()).unwrap_or(1).max(1);
+    let count: Option<usize> = part("COUNT").and_then(|v| v.parse().ok());
+    let until = part("UNTIL").and_then(|v| parse_time("UNTIL", v)).unwrap_or(u64::MAX);
+    let Some(first) = Local.timestamp_opt(start as i64, 0).single() else {
+        return vec![start];
+    };
+    // Weekly rules may name several days; the default is the first event's day
+    let mut days: Vec<Weekday> = match part("BYDAY") {
+        Some(list) if step_days == 7 => list.split(',').filter_map(weekday).collect(),
+        _ => Vec::new(),
+    };
+    if days.is_empty() {
+        days.push(first.weekday());
+    }
+    days.sort_by_key(|day| day.num_days_from_monday());
+    let week_start = first.date_naive() - chrono::Duration::days(first.weekday().num_days_from_monday() as i64);
+
+    let mut starts = Vec::new();
+    for period in 0.. {
+        let base = if step_days == 7 {
+            week_start + chrono::Duration::weeks(period * interval)
+        } else {
+            first.date_naive() + chrono::Duration::days(period * interval)
+        };
+        let dates: Vec<NaiveDate> = if step_days == 7 {
+            days.iter()
+                .map(|day| base + chrono::Duration::days(day.num_days_from_monday() as i64))
+                .collect()
+        } else {
+            vec![base]
+        };
+        for date in dates {
+            let Some(at) = Local.from_local_datetime(&date.and_time(first.time())).earliest() else {
+                continue;
+            };
+            let at = at.timestamp() as u64;
+            if at < start {
+                continue;
+            }
+            if at > until || at >= horizon || count.is_some_and(|c| starts.len() >= c) {
+                return starts;
+            }
+            starts.push(at);
+        }
+    }
+    starts
+}
+
+fn weekday(code: &str) -> Option<Weekday> {
+    // Monthly forms like 1MO keep their day code at the end
+    match code.get(code.len().saturating_sub(2)..)? {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+// 20250101T090000Z is UTC; times with a TZID or none at all are read as
+// local time. Dates without a time (all-day events) are skipped
+fn parse_time(key: &str, value: &str) -> Option<u64> {
+    if key.contains("VALUE=DATE") && !key.contains("VALUE=DATE-TIME") {
+        return None;
+    }
+    let (text, utc) = match value.strip_suffix('Z') {
+        Some(text) => (text, true),
+        None => (value, false),
+    };
+    let time = NaiveDateTime::parse_from_str(text, "%Y%m%dT%H%M%S").ok()?;
+    let at = if utc {
+        Utc.from_utc_datetime(&time).timestamp()
+    } else {
+        Local.from_local_datetime(&time).earliest()?.timestamp()
+    };
+    u64::try_from(at).ok()
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("\\n", " ").replace("\\,", ",").replace("\\;", ";").replace("\\\\", "\\")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_events() {
+        let at = |text: &str| parse_time("DTSTART", text).unwrap();
+        let now = at("20250106T080000Z");
+        let ics = "BEGIN:VCALENDAR\r\n\
+            BEGIN:VEVENT\r\nSUMMARY:Planning\\, Q1\r\nDTSTART:20250106T090000Z\r\nDTEND:20250106T100000Z\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nSUMMARY:Holiday\r\nDTSTART;VALUE=DATE:20250106\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nSUMMARY:Stand\r\n up\r\nDTSTART:20241230T094500Z\r\nDTEND:20241230T100000Z\r\n\
+            RRULE:FREQ=WEEKLY;BYDAY=MO,WE\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nSUMMARY:Old\r\nDTSTART:20250101T090000Z\r\nDTEND:20250101T100000Z\r\nEND:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+        let events = parse(ics, now);
+        let summaries: Vec<&str> = events.iter().map(|e| e.summary.as_str()).collect();
+        // The all-day and past events are left out; the weekly standup repeats into today
+        assert_eq!(summaries, ["Planning, Q1", "Standup"]);
+        assert_eq!(events[1].start, at("20250106T094500Z"));
+        assert_eq!(events[0].describe(now), "Planning, Q1 in 60 min");
+    }
+}
@@ -0,0 +1,291 @@
+use std::cell::Cell;
+use std::fs::{self, OpenOptions};
+use std::hash::{BuildHasher, RandomState};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, canvas::Canvas},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::mario_animation::{self, Coat, YELLOW_CAT};
+use crate::{history, theme};
+
+// Size of one cat in the collection screen, borders included
+const CELL_WIDTH: u16 = 20;
+const CELL_HEIGHT: u16 = 9;
+
+pub struct Breed {
+    pub id: &'static str, // Stored in cats.jsonl; never rename
+    pub name: &'static str,
+    pub coat: Coat,
+}
+
+const fn breed(id: &'static str, name: &'static str, fur: Color, legs: Color, tail: Color, eyes: Color) -> Breed {
+    Breed {
+        id,
+        name,
+        coat: Coat { fur, legs, tail, eyes },
+    }
+}
+
+pub const BREEDS: [Breed; 9] = [
+    Breed {
+        id: "lemon",
+        name: "Lemon",
+        coat: YELLOW_CAT,
+    },
+    breed(
+        "ginger",
+        "Ginger",
+        Color::Rgb(255, 165, 80),
+        Color::Rgb(255, 220, 180),
+        Color::Rgb(230, 130, 50),
+        Color::Black,
+    ),
+    breed(
+        "tabby",
+        "Tabby",
+        Color::Rgb(190, 150, 100),
+        Color::Rgb(220, 200, 170),
+        Color::Rgb(140, 100, 60),
+        Color::Black,
+    ),
+    breed("tuxedo", "Tuxedo", Color::DarkGray, Color::White, Color::DarkGray, Color::Yellow),
+    breed(
+        "midnight",
+        "Midnight",
+        Color::Rgb(90, 90, 110),
+        Color::Rgb(90, 90, 110),
+        Color::Rgb(70, 70, 90),
+        Color::Yellow,
+    ),
+    breed("snowball", "Snowball", Color::White, Color::White, Color::Rgb(230, 230, 230), Color::LightBlue),
+    breed(
+        "siamese",
+        "Siamese",
+        Color::Rgb(245, 230, 200),
+        Color::Rgb(120, 80, 60),
+        Color::Rgb(120, 80, 60),
+        Color::LightBlue,
+    ),
+    breed(
+        "blue",
+        "Russian Blue",
+        Color::Rgb(150, 160, 180),
+        Color::Rgb(150, 160, 180),
+        Color::Rgb(130, 140, 160),
+        Color::Green,
+    ),
+    breed("calico", "Calico", Color::Rgb(255, 165, 80), Color::White, Color::Rgb(60, 60, 60), Color::Black),
+];
+
+const NAMES: [&str; 16] = [
+    "Miso", "Pixel", "Biscuit", "Nori", "Mochi", "Byte", "Pepper", "Tofu", "Sushi", "Neon", "Ziggy", "Olive", "Luna", "Pickles", "Gizmo", "Waffles",
+];
+
+// One rescued cat, appended to cats.jsonl
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Cat {
+    pub breed: String,
+    pub name: String,
+    pub rescued_at: u64,
+}
+
+impl Cat {
+    pub fn breed(&self) -> &'static Breed {
+        // Breeds from a newer version fall back to the first
+        BREEDS.iter().find(|b| b.id == self.breed).unwrap_or(&BREEDS[0])
+    }
+}
+
+// The cats rescued so far: a finished work session has a chance of adding
+// one, preferring breeds not in the collection yet
+pub struct Cats {
+    path: PathBuf,
+    cats: Vec<Cat>,
+}
+
+impl Cats {
+    pub fn load(path: PathBuf) -> Self {
+        let cats = fs::read_to_string(&path)
+            .map(|text| text.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+            .unwrap_or_default();
+        Cats { path, cats }
+    }
+
+    pub fn cats(&self) -> &[Cat] {
+        &self.cats
+    }
+
+    fn breeds_collected(&self) -> usize {
+        BREEDS.iter().filter(|b| self.cats.iter().any(|c| c.breed == b.id)).count()
+    }
+
+    // The cat `random` rescues, if any
+    fn roll(&self, random: u64, chance_percent: u32) -> Option<Cat> {
+        if random % 100 >= chance_percent as u64 {
+            return None;
+        }
+        let random = random / 100;
+        let unseen: Vec<&Breed> = BREEDS.iter().filter(|b| !self.cats.iter().any(|c| c.breed == b.id)).collect();
+        let pool = if unseen.is_empty() { BREEDS.iter().collect() } else { unseen };
+        let breed = pool[(random % pool.len() as u64) as usize];
+        let name = NAMES[(random / pool.len() as u64 % NAMES.len() as u64) as usize];
+        Some(Cat {
+            breed: breed.id.to_string(),
+            name: name.to_string(),
+            rescued_at: history::now_secs(),
+        })
+    }
+
+    // Tries for a cat after a work session; the rescued one is saved
+    pub fn rescue(&mut self, chance_percent: u32) -> io::Result<Option<&Cat>> {
+        let random = RandomState::new().hash_one(self.cats.len());
+        let Some(cat) = self.roll(random, chance_percent) else {
+            return Ok(None);
+        };
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(&cat)?)?;
+        self.cats.push(cat);
+        Ok(self.cats.last())
+    }
+}
+
+pub struct CollectionScreen {
+    page: usize,
+    pages: Cell<usize>, // Set by render, which knows the size
+}
+
+impl CollectionScreen {
+    pub fn new() -> Self {
+        CollectionScreen { page: 0, pages: Cell::new(1) }
+    }
+
+    // Returns false when the screen should close
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Esc => return false,
+            KeyCode::Left | KeyCode::PageUp => self.page = self.page.saturating_sub(1),
+            KeyCode::Right | KeyCode::PageDown => self.page = (self.page + 1).min(self.pages.get().saturating_sub(1)),
+            _ => {}
+        }
+        true
+    }
+}
+
+pub fn render(f: &mut Frame, area: Rect, screen: &CollectionScreen, cats: &Cats) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Rescued Cats")
+        .title_alignment(Alignment::Center)
+        .border_style(Style::default().fg(theme::primary()));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(CELL_HEIGHT), Constraint::Length(1), Constraint::Length(1)])
+        .split(inner);
+
+    let columns = (chunks[0].width / CELL_WIDTH).max(1) as usize;
+    let rows = (chunks[0].height / CELL_HEIGHT).max(1) as usize;
+    let per_page = columns * rows;
+    let pages = cats.cats().len().div_ceil(per_page).max(1);
+    screen.pages.set(pages);
+    let page = screen.page.min(pages - 1);
+
+    if cats.cats().is_empty() {
+        let empty = Paragraph::new("No cats yet. Each finished work session might rescue one.")
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(Color::DarkGray));
+        f.render_widget(empty, chunks[0]);
+    }
+    for (i, cat) in cats.cats().iter().skip(page * per_page).take(per_page).enumerate() {
+        let cell = Rect {
+            x: chunks[0].x + (i % columns) as u16 * CELL_WIDTH,
+            y: chunks[0].y + (i / columns) as u16 * CELL_HEIGHT,
+            width: CELL_WIDTH,
+            height: CELL_HEIGHT,
+        };
+        render_cat(f, cell.intersection(chunks[0]), cat);
+    }
+
+    let summary = format!(
+        "{} cat(s) rescued, {} of {} breeds | page {} of {pages}",
+        cats.cats().len(),
+        cats.breeds_collected(),
+        BREEDS.len(),
+        page + 1
+    );
+    f.render_widget(Paragraph::new(summary).alignment(Alignment::Center), chunks[1]);
+
+    let key = Style::default().fg(theme::primary()).add_modifier(Modifier::BOLD);
+    let help = Line::from(vec![
+        Span::styled("←/→", key),
+        Span::raw(" - Page | "),
+        Span::styled("Esc", key),
+        Span::raw(" - Close"),
+    ]);
+    f.render_widget(Paragraph::new(help).alignment(Alignment::Center), chunks[2]);
+}
+
+fn render_cat(f: &mut Frame, area: Rect, cat: &Cat) {
+    let breed = cat.breed();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(cat.name.as_str())
+        .border_style(Style::default().fg(theme::primary()));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let parts = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+    let canvas = Canvas::default()
+        .x_bounds([-8.0, 8.0])
+        .y_bounds([-3.0, 15.0])
+        .paint(|ctx| mario_animation::draw_cat(ctx, 0.0, 0.0, &breed.coat, 0));
+    f.render_widget(canvas, parts[0]);
+    let label = Paragraph::new(breed.name)
+        .alignment(Alignment::Center)
+        .style(Style::default().fg(theme::highlight()));
+    f.render_widget(label, parts[1]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roll() {
+        let mut cats = Cats {
+            path: PathBuf::new(),
+            cats: Vec::new(),
+        };
+        // The last two digits decide whether a cat turns up at all
+        assert!(cats.roll(42, 0).is_none());
+        assert!(cats.roll(42, 42).is_none());
+        assert!(cats.roll(41, 42).is_some());
+        assert!(cats.roll(99, 100).is_some());
+
+        // Missing breeds come first
+        for random in 0..BREEDS.len() as u64 - 1 {
+            let cat = cats.roll(random * 100, 100).unwrap();
+            cats.cats.push(cat);
+        }
+        assert_eq!(cats.breeds_collected(), BREEDS.len() - 1);
+        let last = cats.roll(12345 * 100, 100).unwrap();
+        assert!(!cats.cats.iter().any(|c| c.breed == last.breed));
+    }
+}
@@ -0,0 +1,278 @@
+use rodio::Source;
+use rodio::buffer::SamplesBuffer;
+use std::f32::consts::PI;
+use std::time::Duration;
+
+use crate::config::Waveform;
+
+const SAMPLE_RATE: u32 = 44100;
+
+// Attack, decay and release in seconds; sustain is a level from 0 to 1. The
+// release happens inside the note, so notes in a track never overlap
+#[derive(Clone, Copy, Debug)]
+pub struct Envelope {
+    pub attack: f32,
+    pub decay: f32,
+    pub sustain: f32,
+    pub release: f32,
+}
+
+impl Envelope {
+    // Sharp start that dies away, like the old chimes
+    pub const PLUCK: Envelope = Envelope {
+        attack: 0.002,
+        decay: 0.12,
+        sustain: 0.25,
+        release: 0.01,
+    };
+    // Held notes for melodies and bass lines
+    pub const HELD: Envelope = Envelope {
+        attack: 0.005,
+        decay: 0.08,
+        sustain: 0.7,
+        release: 0.03,
+    };
+    // Short burst for percussion
+    pub const HIT: Envelope = Envelope {
+        attack: 0.001,
+        decay: 0.05,
+        sustain: 0.0,
+        release: 0.005,
+    };
+
+    fn level(&self, t: f32, length: f32) -> f32 {
+        let adsr = if t < self.attack {
+            t / self.attack
+        } else if t < self.attack + self.decay {
+            1.0 - (1.0 - self.sustain) * (t - self.attack) / self.decay
+        } else {
+            self.sustain
+        };
+        let release = if self.release > 0.0 {
+            ((length - t) / self.release).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        adsr * release
+    }
+}
+
+// Pitch wobble that sets in once a note has been held for `delay` seconds
+#[derive(Clone, Copy, Debug)]
+pub struct Vibrato {
+    pub rate: f32,      // Hz
+    pub semitones: f32, // Depth either way
+    pub delay: f32,
+}
+
+impl Vibrato {
+    pub const GENTLE: Vibrato = Vibrato {
+        rate: 5.5,
+        semitones: 0.15,
+        delay: 0.15,
+    };
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Voice {
+    pub waveform: Waveform,
+    pub envelope: Envelope,
+    pub volume: f32,
+    pub vibrato: Option<Vibrato>,
+}
+
+impl Voice {
+    pub fn new(waveform: Waveform, envelope: Envelope, volume: f32) -> Self {
+        Voice {
+            waveform,
+            envelope,
+            volume,
+            vibrato: None,
+        }
+    }
+
+    pub fn with_vibrato(self, vibrato: Vibrato) -> Self {
+        Voice {
+            vibrato: Some(vibrato),
+            ..self
+        }
+    }
+}
+
+// Notes are (frequency, length); a frequency of 0 is a rest
+struct Track {
+    voice: Voice,
+    notes: Vec<(f32, Duration)>,
+}
+
+// A few tracks played together, each with its own voice
+#[derive(Default)]
+pub struct Song {
+    tracks: Vec<Track>,
+}
+
+impl Song {
+    pub fn new() -> Self {
+        Song::default()
+    }
+
+    pub fn track(mut self, voice: Voice, notes: &[(f32, Duration)]) -> Self {
+        self.tracks.push(Track { voice, notes: notes.to_vec() });
+        self
+    }
+
+    pub fn into_source(self) -> ChipSource {
+        let total_samples = self
+            .tracks
+            .iter()
+            .map(|track| track.notes.iter().map(|(_, length)| samples(*length)).sum::<usize>())
+            .max()
+            .unwrap_or(0);
+        ChipSource {
+            channels: self.tracks.into_iter().map(Channel::new).collect(),
+            sample_idx: 0,
+            total_samples,
+        }
+    }
+
+    // The whole song synthesized up front, for sounds played again and again
+    pub fn render(self) -> SamplesBuffer {
+        SamplesBuffer::new(1, SAMPLE_RATE, self.into_source().collect::<Vec<f32>>())
+    }
+}
+
+fn samples(length: Duration) -> usize {
+    (length.as_secs_f32() * SAMPLE_RATE as f32) as usize
+}
+
+struct Channel {
+    track: Track,
+    note: usize,
+    note_sample: usize,
+    phase: f32, // 0..1 through the current cycle
+    noise: u16, // Shift register for the noise waveform
+    noise_out: f32,
+}
+
+impl Channel {
+    fn new(track: Track) -> Self {
+        Channel {
+            track,
+            note: 0,
+            note_sample: 0,
+            phase: 0.0,
+            noise: 1,
+            noise_out: 1.0,
+        }
+    }
+
+    fn next_sample(&mut self) -> f32 {
+        let Some(&(freq, length)) = self.track.notes.get(self.note) else {
+            return 0.0;
+        };
+        let note_samples = samples(length);
+        if self.note_sample >= note_samples {
+            self.note += 1;
+            self.note_sample = 0;
+            return self.next_sample();
+        }
+        let t = self.note_sample as f32 / SAMPLE_RATE as f32;
+        self.note_sample += 1;
+        if freq <= 0.0 {
+            return 0.0;
+        }
+
+        let voice = self.track.voice;
+        let freq = match voice.vibrato {
+            Some(vibrato) if t > vibrato.delay => {
+                let wobble = (2.0 * PI * vibrato.rate * (t - vibrato.delay)).sin() * vibrato.semitones;
+                freq * 2f32.powf(wobble / 12.0)
+            }
+            _ => freq,
+        };
+        self.phase += freq / SAMPLE_RATE as f32;
+        let wrapped = self.phase >= 1.0;
+        self.phase = self.phase.fract();
+
+        let wave = match voice.waveform {
+            Waveform::Square => {
+                if self.phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            Waveform::Triangle => 1.0 - 4.0 * (self.phase - 0.5).abs(),
+            Waveform::Sine => (2.0 * PI * self.phase).sin(),
+            // A new random level every cycle, so the pitch colours the hiss
+            Waveform::Noise => {
+                if wrapped {
+                    let bit = (self.noise ^ (self.noise >> 1)) & 1;
+                    self.noise = (self.noise >> 1) | (bit << 14);
+                    self.noise_out = if self.noise & 1 == 1 { 1.0 } else { -1.0 };
+                }
+                self.noise_out
+            }
+        };
+        wave * voice.envelope.level(t, length.as_secs_f32()) * voice.volume
+    }
+}
+
+// Mono mix of a song's tracks
+pub struct ChipSource {
+    channels: Vec<Channel>,
+    sample_idx: usize,
+    total_samples: usize,
+}
+
+impl Iterator for ChipSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.sample_idx >= self.total_samples {
+            return None;
+        }
+        self.sample_idx += 1;
+        let mix: f32 = self.channels.iter_mut().map(Channel::next_sample).sum();
+        Some(mix.clamp(-1.0, 1.0))
+    }
+}
+
+impl Source for ChipSource {
+    fn current_span_len(&self) -> Option<usize> {
+        Some(self.total_samples - self.sample_idx)
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f32(self.total_samples as f32 / SAMPLE_RATE as f32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_song_mixes_tracks() {
+        let ms = Duration::from_millis;
+        let lead = Voice::new(Waveform::Square, Envelope::HELD, 0.5);
+        let bass = Voice::new(Waveform::Triangle, Envelope::HELD, 0.5);
+        let song = Song::new().track(lead, &[(440.0, ms(100)), (0.0, ms(100))]).track(bass, &[(110.0, ms(300))]);
+        let samples: Vec<f32> = song.into_source().collect();
+        // As long as the longest track, and never louder than full scale
+        assert_eq!(samples.len(), 3 * 4410);
+        assert!(samples.iter().all(|s| s.abs() <= 1.0));
+        // Silent at the very start (attack) and end (release), sounding in between
+        assert_eq!(samples[0], 0.0);
+        assert!(samples[2000..2400].iter().any(|s| s.abs() > 0.3));
+        assert!(samples[samples.len() - 1].abs() < 0.01);
+    }
+}
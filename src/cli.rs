@@ -0,0 +1,571 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use chrono::Local;
+
+use crate::TimerType;
+use crate::auto_tags;
+use crate::backup;
+use crate::config::{Config, HistoryBackend};
+use crate::history::{self, History, SessionStore};
+use crate::hosts;
+use crate::remote::StatusFormat;
+use crate::technique::{self, Technique};
+use crate::{report, review};
+
+// Exit statuses wrapper scripts and cron jobs can rely on, listed in the
+// README; any other failure exits 1
+pub const EXIT_INTERRUPTED: i32 = 2;
+pub const EXIT_CONFIG: i32 = 3;
+
+// Errors that end the process with a status of their own
+#[derive(Debug)]
+pub enum Exit {
+    // A countdown or session stopped before its time was up
+    Interrupted,
+    // An invalid config file or command line
+    Config(String),
+}
+
+impl fmt::Display for Exit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Exit::Interrupted => write!(f, "interrupted"),
+            Exit::Config(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for Exit {}
+
+pub fn exit_code(error: &(dyn std::error::Error + 'static)) -> i32 {
+    match error.downcast_ref::<Exit>() {
+        Some(Exit::Interrupted) => EXIT_INTERRUPTED,
+        Some(Exit::Config(_)) => EXIT_CONFIG,
+        None => 1,
+    }
+}
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub fn quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+// println! that --quiet silences; errors go to stderr either way
+macro_rules! say {
+    ($($arg:tt)*) => {
+        if !$crate::cli::quiet() {
+            println!($($arg)*);
+        }
+    };
+}
+pub(crate) use say;
+
+pub struct Args {
+    pub command: Command,
+    // --quiet, which goes before any command
+    pub quiet: bool,
+}
+
+pub enum Command {
+    Run(RunOptions),
+    Once(OnceOptions),
+    Backup { output: Option<PathBuf> },
+    Restore { archive: PathBuf, force: bool },
+    Merge { file: PathBuf },
+    Log { duration: Option<Duration> },
+    Archive { months: Option<u32> },
+    Report { html: PathBuf, last_week: bool },
+    Migrate { to: HistoryBackend },
+    ConfigCheck { file: Option<PathBuf> },
+    Hosts { block: Vec<String> },
+    Remote { command: String, format: StatusFormat },
+    RoomServe { port: u16 },
+    // Hidden: the soak test for timing changes
+    Bench { sessions: u32 },
+    Help,
+}
+
+#[derive(Default)]
+pub struct RunOptions {
+    // Hidden debug flag: the clock runs this many times faster than real time
+    pub time_scale: Option<f64>,
+    // Mirror the running instance read-only instead of running a timer
+    pub watch: bool,
+    // [profiles.NAME] whose startup actions to run
+    pub profile: Option<String>,
+    // Lengths from a technique preset instead of [timer]
+    pub technique: Option<&'static Technique>,
+}
+
+pub struct OnceOptions {
+    pub duration: Duration,
+    // --quiet: no output and no chime
+    pub quiet: bool,
+    // Take commands on stdin and answer with JSON lines
+    pub stdin: bool,
+    // Print every state change, including each second, as JSON lines
+    pub json_events: bool,
+}
+
+const DEFAULT_ROOM_PORT: u16 = 7625;
+const DEFAULT_BENCH_SESSIONS: u32 = 2000;
+
+const USAGE: &str = "Usage: cyber-tomato [--quiet] [COMMAND]
+
+Commands:
+  (none)                      Start the pomodoro timer
+  --profile NAME              Start it and run the startup actions of [profiles.NAME]
+  --technique NAME            Start it with a technique's lengths: classic (25/5, 15 after
+                              every fourth), desktime (52/17), ultradian (90/20) or 45-15
+  watch                       Mirror the running timer read-only, e.g. on a second monitor
+  once DURATION [--stdin] [--json-events]
+                              Count down once without the TUI (25m, 90s, 1h), then chime and exit;
+                              --stdin takes pause/resume/add 5m/status/stop lines and prints JSON,
+                              --json-events prints a JSON line for every second and state change
+  remote COMMAND              Query or control the running timer, e.g. from an editor:
+                              status, left, start_work, start_break, pause, resume,
+                              toggle_pause, snooze, next_quote, add_session, remove_session;
+                              status --emacs or --xmpp answers for chat bot auto-replies
+  backup [FILE]               Archive config and data into a .tar file
+  restore FILE [--force]      Restore config and data from a backup archive
+  merge FILE                  Import sessions from another machine's history file
+  log [DURATION]              Record a work session done away from the timer, ending now
+                              (defaults to timer.work_minutes)
+  archive [--months N]        Compact history older than N months into daily summaries
+  report --html FILE [--last] Write this week's report (last week's with --last) as a
+                              standalone HTML page with charts, for sharing
+  migrate --to sqlite|jsonl   Copy the history into the other storage backend, then set
+                              history.backend to use it
+  config check [FILE]         Validate config.toml, exiting non-zero on problems
+  hosts block DOMAIN...       Block sites in the hosts file (used through sudo by the timer)
+  hosts unblock               Remove the sites blocked by cyber-tomato from the hosts file
+  room serve [--port N]       Host study-with-me rooms for [room] url (port 7625 by default)
+  help                        Show this message
+
+Options:
+  -q, --quiet                 Print nothing but errors; once doesn't chime either
+
+Exit status: 0 done, 1 failed, 2 interrupted (Ctrl-C, or a timer quit mid-session),
+3 config error (an invalid config.toml or command line)";
+
+pub fn parse_args(args: &[String]) -> Result<Args, String> {
+    // Only ahead of the command, where it can't be some option's value
+    let flags = args.iter().take_while(|arg| matches!(arg.as_str(), "--quiet" | "-q")).count();
+    let quiet = flags > 0;
+    let command = parse_command(args[flags..].iter().map(String::as_str), quiet)?;
+    Ok(Args { command, quiet })
+}
+
+fn parse_command<'a>(mut args: impl Iterator<Item = &'a str>, quiet: bool) -> Result<Command, String> {
+    match args.next() {
+        None => Ok(Command::Run(RunOptions::default())),
+        Some("watch") => {
+            expect_end(args)?;
+            Ok(Command::Run(RunOptions {
+                watch: true,
+                ..RunOptions::default()
+            }))
+        }
+        Some("once") => {
+            let mut duration = None;
+            let mut stdin = false;
+            let mut json_events = false;
+            for arg in args {
+                match arg {
+                    "--stdin" => stdin = true,
+                    "--json-events" => json_events = true,
+                    _ if arg.starts_with('-') => return Err(format!("Unknown option: {arg}")),
+                    _ if duration.is_none() => duration = Some(parse_duration(arg)?),
+                    _ => return Err(format!("Unexpected argument: {arg}")),
+                }
+            }
+            let duration = duration.ok_or("once needs a DURATION such as 25m")?;
+            Ok(Command::Once(OnceOptions {
+                duration,
+                quiet,
+                stdin,
+                json_events,
+            }))
+        }
+        Some("remote") => {
+            let command = args.next().ok_or("remote needs a COMMAND such as status")?.to_string();
+            let format = match args.next() {
+                Some("--emacs") if command == "status" => StatusFormat::Emacs,
+                Some("--xmpp") if command == "status" => StatusFormat::Xmpp,
+                Some(arg) => return Err(format!("Unexpected argument: {arg}")),
+                None => StatusFormat::Json,
+            };
+            expect_end(args)?;
+            Ok(Command::Remote { command, format })
+        }
+        Some("backup") => {
+            let output = args.next().map(PathBuf::from);
+            expect_end(args)?;
+            Ok(Command::Backup { output })
+        }
+        Some("restore") => {
+            let mut archive = None;
+            let mut force = false;
+            for arg in args {
+                match arg {
+                    "--force" | "-f" => force = true,
+                    _ if arg.starts_with('-') => return Err(format!("Unknown option: {arg}")),
+                    _ if archive.is_none() => archive = Some(PathBuf::from(arg)),
+                    _ => return Err(format!("Unexpected argument: {arg}")),
+                }
+            }
+            let archive = archive.ok_or("restore needs a backup FILE")?;
+            Ok(Command::Restore { archive, force })
+        }
+        Some("merge") => {
+            let file = args.next().map(PathBuf::from).ok_or("merge needs a history FILE")?;
+            expect_end(args)?;
+            Ok(Command::Merge { file })
+        }
+        Some("log") => {
+            let duration = args.next().map(parse_duration).transpose()?;
+            expect_end(args)?;
+            Ok(Command::Log { duration })
+        }
+        Some("archive") => {
+            let months = match args.next() {
+                Some("--months") => {
+                    let value = args.next().ok_or("--months needs a number")?;
+                    Some(value.parse().map_err(|_| format!("Invalid number of months: {value}"))?)
+                }
+                Some(arg) => return Err(format!("Unexpected argument: {arg}")),
+                None => None,
+            };
+            expect_end(args)?;
+            Ok(Command::Archive { months })
+        }
+        Some("report") => {
+            let mut html = None;
+            let mut last_week = false;
+            while let Some(arg) = args.next() {
+                match arg {
+                    "--html" => html = Some(PathBuf::from(args.next().ok_or("--html needs a FILE")?)),
+                    "--last" => last_week = true,
+                    _ => return Err(format!("Unexpected argument: {arg}")),
+                }
+            }
+            let html = html.ok_or("report needs --html FILE")?;
+            Ok(Command::Report { html, last_week })
+        }
+        Some("migrate") => {
+            let to = match (args.next(), args.next()) {
+                (Some("--to"), Some("sqlite")) => HistoryBackend::Sqlite,
+                (Some("--to"), Some("jsonl")) => HistoryBackend::Jsonl,
+                (Some("--to"), Some(other)) => return Err(format!("Unknown backend: {other} (sqlite or jsonl)")),
+                _ => return Err("migrate needs --to sqlite or --to jsonl".to_string()),
+            };
+            expect_end(args)?;
+            Ok(Command::Migrate { to })
+        }
+        Some("config") => match args.next() {
+            Some("check") => {
+                let file = args.next().map(PathBuf::from);
+                expect_end(args)?;
+                Ok(Command::ConfigCheck { file })
+            }
+            Some(other) => Err(format!("Unknown config command: {other}")),
+            None => Err("config needs a command: check".to_string()),
+        },
+        Some("hosts") => match args.next() {
+            Some("block") => {
+                let block: Vec<String> = args.map(str::to_string).collect();
+                if block.is_empty() {
+                    return Err("hosts block needs at least one DOMAIN".to_string());
+                }
+                for domain in &block {
+                    hosts::check_domain(domain)?;
+                }
+                Ok(Command::Hosts { block })
+            }
+            Some("unblock") => {
+                expect_end(args)?;
+                Ok(Command::Hosts { block: Vec::new() })
+            }
+            Some(other) => Err(format!("Unknown hosts command: {other}")),
+            None => Err("hosts needs a command: block or unblock".to_string()),
+        },
+        Some("room") => match args.next() {
+            Some("serve") => {
+                let port = match args.next() {
+                    Some("--port") => {
+                        let value = args.next().ok_or("--port needs a number")?;
+                        value.parse().map_err(|_| format!("Invalid port: {value}"))?
+                    }
+                    Some(arg) => return Err(format!("Unexpected argument: {arg}")),
+                    None => DEFAULT_ROOM_PORT,
+                };
+                expect_end(args)?;
+                Ok(Command::RoomServe { port })
+            }
+            Some(other) => Err(format!("Unknown room command: {other}")),
+            None => Err("room needs a command: serve".to_string()),
+        },
+        Some("bench") => {
+            let sessions = match args.next() {
+                Some("--sessions") => {
+                    let value = args.next().ok_or("--sessions needs a number")?;
+                    value
+                        .parse()
+                        .ok()
+                        .filter(|&n| n > 0)
+                        .ok_or_else(|| format!("Invalid number of sessions: {value}"))?
+                }
+                Some(arg) => return Err(format!("Unexpected argument: {arg}")),
+                None => DEFAULT_BENCH_SESSIONS,
+            };
+            expect_end(args)?;
+            Ok(Command::Bench { sessions })
+        }
+        Some("help" | "--help" | "-h") => Ok(Command::Help),
+        Some(first) if first.starts_with("--") => parse_run_options(std::iter::once(first).chain(args)).map(Command::Run),
+        Some(other) => Err(format!("Unknown command: {other}")),
+    }
+}
+
+fn parse_run_options<'a>(mut args: impl Iterator<Item = &'a str>) -> Result<RunOptions, String> {
+    let mut options = RunOptions::default();
+    while let Some(arg) = args.next() {
+        match arg {
+            "--time-scale" => {
+                let value = args.next().ok_or("--time-scale needs a factor")?;
+                let scale: f64 = value.parse().map_err(|_| format!("Invalid time scale: {value}"))?;
+                if !scale.is_finite() || scale <= 0.0 {
+                    return Err(format!("Invalid time scale: {value}"));
+                }
+                options.time_scale = Some(scale);
+            }
+            "--profile" => options.profile = Some(args.next().ok_or("--profile needs a NAME")?.to_string()),
+            "--technique" => {
+                let name = args.next().ok_or("--technique needs a NAME")?;
+                let found = technique::find(name).ok_or_else(|| format!("Unknown technique: {name} (try {})", technique::ids()))?;
+                options.technique = Some(found);
+            }
+            _ => return Err(format!("Unknown option: {arg}")),
+        }
+    }
+    Ok(options)
+}
+
+// "90s", "25m" or "1h", with fractions ("0.5", "1.5h"); a bare number is
+// minutes, and "25:30" minutes and seconds. Rounded to whole seconds
+pub fn parse_duration(text: &str) -> Result<Duration, String> {
+    let invalid = || format!("Invalid duration: {text}");
+    if let Some((mins, secs)) = text.split_once(':') {
+        let mins: u64 = mins.parse().map_err(|_| invalid())?;
+        let secs: u64 = secs.parse().ok().filter(|&s| s < 60).ok_or_else(invalid)?;
+        let total = mins.checked_mul(60).and_then(|m| m.checked_add(secs)).filter(|&t| t > 0);
+        return total.map(Duration::from_secs).ok_or_else(invalid);
+    }
+    let (number, unit) = match text.find(|c: char| !c.is_ascii_digit() && c != '.') {
+        Some(i) => text.split_at(i),
+        None => (text, "m"),
+    };
+    let secs = match unit {
+        "s" => 1.0,
+        "m" => 60.0,
+        "h" => 60.0 * 60.0,
+        _ => return Err(invalid()),
+    };
+    let number: f64 = number.parse().map_err(|_| invalid())?;
+    match Duration::try_from_secs_f64((number * secs).round()) {
+        Ok(duration) if !duration.is_zero() => Ok(duration),
+        _ => Err(invalid()),
+    }
+}
+
+fn expect_end<'a>(mut args: impl Iterator<Item = &'a str>) -> Result<(), String> {
+    match args.next() {
+        Some(arg) => Err(format!("Unexpected argument: {arg}")),
+        None => Ok(()),
+    }
+}
+
+pub fn load_config() -> Result<Config, Exit> {
+    Config::load().map_err(Exit::Config)
+}
+
+pub fn print_usage() {
+    println!("{USAGE}");
+}
+
+pub fn run_backup(output: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    let output = output.unwrap_or_else(backup::default_backup_path);
+    let count = backup::create_backup(&output)?;
+    say!("Backed up {count} file(s) to {}", output.display());
+    Ok(())
+}
+
+pub fn run_restore(archive: PathBuf, force: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let count = backup::restore_backup(&archive, force)?;
+    say!("Restored {count} file(s) from {}", archive.display());
+    Ok(())
+}
+
+pub fn run_merge(file: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+    let config = load_config()?;
+    let mut history = History::open(config.history_dir(), config.history.backend)?;
+    let imported = history.import(&file)?;
+    say!("Imported {imported} new session(s) into {}", history.dir().display());
+    Ok(())
+}
+
+pub fn run_log(duration: Option<Duration>) -> Result<(), Box<dyn std::error::Error>> {
+    let config = load_config()?;
+    let secs = duration.map_or(config.timer.work_minutes as u64 * 60, |d| d.as_secs());
+    let mut history = History::open(config.history_dir(), config.history.backend)?;
+    let mut record = history.new_record(TimerType::Work, history::now_secs().saturating_sub(secs), secs);
+    record.manual = true;
+    auto_tags::apply(&config.auto_tags, &mut record);
+    history.append(record)?;
+    say!("Logged a {} min work session ({} today)", secs / 60, history.work_today().count());
+    Ok(())
+}
+
+pub fn run_archive(months: Option<u32>) -> Result<(), Box<dyn std::error::Error>> {
+    let config = load_config()?;
+    let months = months
+        .or(config.history.archive_after_months)
+        .ok_or("pass --months N or set history.archive_after_months in config.toml")?;
+    let mut history = History::open(config.history_dir(), config.history.backend)?;
+    let archived = history.archive(history::archive_cutoff(months))?;
+    say!("Archived {archived} session(s) older than {months} month(s)");
+    Ok(())
+}
+
+pub fn run_report(html: PathBuf, last_week: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let config = load_config()?;
+    let history = History::open(config.history_dir(), config.history.backend)?;
+    let week = report::week_start(Local::now().date_naive(), last_week as u32);
+    fs::write(&html, report::html(history.records(), week, config.goals.weekly_pomodoros))?;
+    say!("Wrote the report for {} to {}", review::week_id(week), html.display());
+    Ok(())
+}
+
+// Copies every stored version, tombstones included, so the target merges to
+// exactly the same sessions. The archive of raw lines stays where it is
+pub fn run_migrate(to: HistoryBackend) -> Result<(), Box<dyn std::error::Error>> {
+    let config = load_config()?;
+    let dir = config.history_dir();
+    let from = match to {
+        HistoryBackend::Jsonl => HistoryBackend::Sqlite,
+        HistoryBackend::Sqlite => HistoryBackend::Jsonl,
+    };
+    let records = history::open_store(&dir, from)?.load()?;
+    let mut target = history::open_store(&dir, to)?;
+    if !target.load()?.is_empty() {
+        return Err(format!("{} already has {} history in it; nothing was copied", dir.display(), to.name()).into());
+    }
+    target.append_all(&records)?;
+    say!("Copied {} record(s) from {} to {}", records.len(), from.name(), to.name());
+    if config.history.backend != to {
+        say!("Set history.backend = \"{}\" in config.toml to use it", to.name());
+    }
+    Ok(())
+}
+
+pub fn run_hosts(block: Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
+    hosts::apply(&block)?;
+    if block.is_empty() {
+        say!("Unblocked all sites");
+    } else {
+        say!("Blocked {} site(s)", block.len());
+    }
+    Ok(())
+}
+
+pub fn run_config_check(file: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    let path = file.clone().unwrap_or_else(Config::path);
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        // No config at the default location just means defaults are used
+        Err(e) if e.kind() == io::ErrorKind::NotFound && file.is_none() => {
+            say!("{}: not found, using defaults", path.display());
+            return Ok(());
+        }
+        Err(e) => return Err(Exit::Config(format!("{}: {e}", path.display())).into()),
+    };
+
+    let problems = Config::check(&text);
+    for problem in &problems {
+        match problem.line {
+            Some(line) => eprintln!("{}:{line}: {}", path.display(), problem.message),
+            None => eprintln!("{}: {}", path.display(), problem.message),
+        }
+    }
+    if problems.is_empty() {
+        say!("{}: OK", path.display());
+        Ok(())
+    } else {
+        Err(Exit::Config(format!("{} problem(s) found", problems.len())).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("25m"), Ok(Duration::from_secs(25 * 60)));
+        assert_eq!(parse_duration("90s"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("1h"), Ok(Duration::from_secs(3600)));
+        assert_eq!(parse_duration("20"), Ok(Duration::from_secs(20 * 60)));
+        assert!(parse_duration("0m").is_err());
+        assert!(parse_duration("m").is_err());
+        assert!(parse_duration("5x").is_err());
+        assert!(parse_duration("1h30m").is_err());
+
+        // Under a minute, and to the second
+        assert_eq!(parse_duration("0.5"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("1.5h"), Ok(Duration::from_secs(5400)));
+        assert_eq!(parse_duration("25:30"), Ok(Duration::from_secs(25 * 60 + 30)));
+        assert_eq!(parse_duration("0:45"), Ok(Duration::from_secs(45)));
+        assert!(parse_duration("0.001").is_err());
+        assert!(parse_duration("1.2.3").is_err());
+        assert!(parse_duration("25:60").is_err());
+        assert!(parse_duration("0:00").is_err());
+    }
+
+    #[test]
+    fn test_quiet_and_exit_codes() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<String>>();
+        let parsed = parse_args(&args(&["-q", "once", "25m"])).unwrap();
+        assert!(parsed.quiet);
+        assert!(matches!(parsed.command, Command::Once(OnceOptions { quiet: true, .. })));
+        assert!(!parse_args(&args(&["backup"])).unwrap().quiet);
+        // Past the command it's an argument like any other
+        assert!(parse_args(&args(&["once", "-q", "25m"])).is_err());
+
+        let config: Box<dyn std::error::Error> = Exit::Config("bad".to_string()).into();
+        assert_eq!(exit_code(config.as_ref()), EXIT_CONFIG);
+        assert_eq!(exit_code(Box::<dyn std::error::Error>::from(Exit::Interrupted).as_ref()), EXIT_INTERRUPTED);
+        assert_eq!(exit_code(Box::<dyn std::error::Error>::from("disk full").as_ref()), 1);
+    }
+
+    #[test]
+    fn test_remote_usage_matches_actions() {
+        let start = USAGE.find("e.g. from an editor:").unwrap() + "e.g. from an editor:".len();
+        let end = start + USAGE[start..].find("status --emacs").unwrap();
+        // Everything listed but the two queries is sent to the timer
+        let actions: Vec<&str> = USAGE[start..end]
+            .split([',', ';', ' ', '\n'])
+            .filter(|word| !word.is_empty() && !matches!(*word, "status" | "left"))
+            .collect();
+        assert_eq!(actions, crate::remote::ACTIONS);
+    }
+}
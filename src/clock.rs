@@ -0,0 +1,75 @@
+use chrono::{Local, TimeDelta};
+use serde::Deserialize;
+
+// What the big digits count: the time left, the time spent, or the wall
+// clock time the session ends at. The terminal title's {clock} follows it
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClockDisplay {
+    #[default]
+    Remaining,
+    Elapsed,
+    Ends,
+}
+
+impl ClockDisplay {
+    pub fn next(self) -> Self {
+        match self {
+            ClockDisplay::Remaining => ClockDisplay::Elapsed,
+            ClockDisplay::Elapsed => ClockDisplay::Ends,
+            ClockDisplay::Ends => ClockDisplay::Remaining,
+        }
+    }
+
+    // Shown under the digits, which say nothing about what they count
+    pub fn label(self) -> Option<&'static str> {
+        match self {
+            ClockDisplay::Remaining => None,
+            ClockDisplay::Elapsed => Some("elapsed"),
+            ClockDisplay::Ends => Some("ends at"),
+        }
+    }
+
+    // The digits, with `separator` between minutes and seconds (or hours and
+    // minutes) so the caller can blink it
+    pub fn digits(self, elapsed_secs: u64, remaining_secs: u64, separator: char) -> String {
+        match self {
+            ClockDisplay::Remaining => minutes(remaining_secs, separator),
+            ClockDisplay::Elapsed => minutes(elapsed_secs, separator),
+            ClockDisplay::Ends => ends_at(remaining_secs).replace(':', &separator.to_string()),
+        }
+    }
+
+    // For the title and other text: "04:05", "20:55 elapsed" or "ends 14:37"
+    pub fn text(self, elapsed_secs: u64, remaining_secs: u64) -> String {
+        match self {
+            ClockDisplay::Remaining => minutes(remaining_secs, ':'),
+            ClockDisplay::Elapsed => format!("{} elapsed", minutes(elapsed_secs, ':')),
+            ClockDisplay::Ends => format!("ends {}", ends_at(remaining_secs)),
+        }
+    }
+}
+
+pub fn minutes(secs: u64, separator: char) -> String {
+    format!("{:02}{separator}{:02}", secs / 60, secs % 60)
+}
+
+// Local time once `remaining_secs` have passed, as 14:37. A paused session's
+// end moves on with the clock until it's resumed
+pub fn ends_at(remaining_secs: u64) -> String {
+    (Local::now() + TimeDelta::seconds(remaining_secs as i64)).format("%H:%M").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clock_display() {
+        assert_eq!(ClockDisplay::Remaining.digits(1255, 245, ' '), "04 05");
+        assert_eq!(ClockDisplay::Elapsed.text(1255, 245), "20:55 elapsed");
+        let ends = ClockDisplay::Ends.digits(0, 3600, ' ');
+        assert!(ends.len() == 5 && ends.contains(' '));
+        assert_eq!(ClockDisplay::Ends.next(), ClockDisplay::Remaining);
+    }
+}
@@ -0,0 +1,1049 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Deserialize;
+
+use crate::TimerType;
+use crate::achievements;
+use crate::audio::{self, TuneList};
+use crate::auto_tags;
+use crate::clock::ClockDisplay;
+use crate::graphics::GraphicsProtocol;
+use crate::history;
+use crate::hosts;
+use crate::icons::IconSet;
+use crate::keymap::{KeyList, Keymap};
+use crate::migrate::{self, Schema};
+use crate::panels::Panel;
+use crate::paths;
+use crate::routing::{self, Channel, NotifyEvent};
+use crate::scripts;
+use crate::startup::{self, StartupAction};
+use crate::theme::Theme;
+
+// config.toml's format; see migrate. A config without `version` is at 1
+const SCHEMA: Schema = Schema {
+    file: "config.toml",
+    version: 1,
+    steps: &[],
+};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub version: Option<u32>,
+    pub timer: TimerConfig,
+    pub theme: Theme,
+    // Extra themes by name, for the `theme NAME` startup action
+    pub themes: BTreeMap<String, Theme>,
+    pub keys: BTreeMap<String, KeyList>,
+    pub history: HistoryConfig,
+    pub stats: StatsConfig,
+    pub quotes: QuotesConfig,
+    pub notifications: NotificationsConfig,
+    pub speech: SpeechConfig,
+    pub countdown: CountdownConfig,
+    pub milestones: MilestonesConfig,
+    pub goals: GoalsConfig,
+    pub achievements: AchievementsConfig,
+    pub cats: CatsConfig,
+    pub sounds: SoundsConfig,
+    pub layout: LayoutConfig,
+    pub breaks: BreaksConfig,
+    pub pauses: PausesConfig,
+    pub eye_rest: EyeRestConfig,
+    pub focus: FocusConfig,
+    pub activitywatch: ActivityWatchConfig,
+    pub time_tracking: TimeTrackingConfig,
+    pub calendar: CalendarConfig,
+    pub room: RoomConfig,
+    pub status_file: StatusFileConfig,
+    pub obs: ObsConfig,
+    pub auto_tags: Vec<AutoTagRule>,
+    pub notify: Vec<NotifyRule>,
+    pub startup: StartupConfig,
+    // Startup actions picked with `--profile NAME`, run after [startup]'s
+    pub profiles: BTreeMap<String, StartupConfig>,
+    pub scripts: ScriptsConfig,
+    pub templates: TemplatesConfig,
+    pub icons: IconsConfig,
+    pub graphics: GraphicsConfig,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct TimerConfig {
+    pub work_minutes: u32,
+    pub break_minutes: u32,
+    // A longer break after every `long_break_every` pomodoros; 0 never takes one
+    pub long_break_minutes: u32,
+    pub long_break_every: u32,
+    // How much the snooze key adds to a work session that just finished
+    pub snooze_minutes: u32,
+    // What auto mode starts by itself; turn one off to confirm that switch with a key
+    pub auto_start_break: bool,
+    pub auto_start_work: bool,
+    // Whether a session auto mode starts waits for the completion animation to
+    // end or be dismissed, rather than running underneath it
+    pub hold_for_animation: bool,
+    // Whether time spent suspended with Ctrl-Z counts toward the session
+    pub count_suspended: bool,
+    // Longest session the lengths above and custom timers may ask for
+    pub max_session_minutes: u32,
+    // Starting over with w or b asks for a second press once the session has
+    // run this long; 0 never asks
+    pub confirm_restart_seconds: u32,
+    // Lengthening the break after overtime (snoozes, and time a finished work
+    // session rang in nag mode) so work and rest keep their ratio
+    pub extend_break: ExtendBreak,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExtendBreak {
+    #[default]
+    Off,
+    Ask,
+    Auto,
+}
+
+impl Default for TimerConfig {
+    fn default() -> Self {
+        TimerConfig {
+            work_minutes: 25,
+            break_minutes: 5,
+            long_break_minutes: 15,
+            long_break_every: 0,
+            snooze_minutes: 3,
+            auto_start_break: true,
+            auto_start_work: true,
+            hold_for_animation: true,
+            count_suspended: false,
+            max_session_minutes: 240,
+            confirm_restart_seconds: 60,
+            extend_break: ExtendBreak::Off,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct HistoryConfig {
+    // Where session history lives, with tasks and alarms when set; point it at
+    // a synced or git-managed folder to share them between machines. Defaults
+    // to <data dir>/history
+    pub dir: Option<PathBuf>,
+    // Compact sessions older than this many months into daily summaries on
+    // startup. Unset keeps everything
+    pub archive_after_months: Option<u32>,
+    // Record the application in focus for most of each work session
+    pub capture_app: bool,
+    // How sessions are stored in the directory; `cyber-tomato migrate` converts
+    pub backend: HistoryBackend,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryBackend {
+    // One JSONL file per machine, safe to sync
+    #[default]
+    Jsonl,
+    // One database for years of sessions on a single machine; needs the
+    // `sqlite` feature
+    Sqlite,
+}
+
+impl HistoryBackend {
+    pub fn name(self) -> &'static str {
+        match self {
+            HistoryBackend::Jsonl => "jsonl",
+            HistoryBackend::Sqlite => "sqlite",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct StatsConfig {
+    // How many days the stats screen looks back by default
+    pub days: u32,
+}
+
+impl Default for StatsConfig {
+    fn default() -> Self {
+        StatsConfig { days: 14 }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct QuotesConfig {
+    pub enabled: bool,
+    // Messages to rotate through; the built-in quotes are used when this and
+    // `file` are both empty
+    pub messages: Vec<String>,
+    // Text file with one message per line
+    pub file: Option<PathBuf>,
+}
+
+impl Default for QuotesConfig {
+    fn default() -> Self {
+        QuotesConfig {
+            enabled: true,
+            messages: Vec::new(),
+            file: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct NotificationsConfig {
+    // Desktop notification when a session completes
+    pub desktop: bool,
+    // When a finished session leaves the timer stopped, repeat the chime and
+    // flash the screen this often until a key is pressed; 0 turns it off
+    pub nag_seconds: u32,
+    // Skip the desktop notification while the terminal has focus; needs a
+    // terminal that reports focus (tmux: set focus-events on)
+    pub suppress_when_focused: bool,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        NotificationsConfig {
+            desktop: true,
+            nag_seconds: 0,
+            suppress_when_focused: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SpeechConfig {
+    // Announce finished sessions out loud
+    pub enabled: bool,
+    // Voice name as the platform knows it; the default voice otherwise
+    pub voice: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct CountdownConfig {
+    // Length of the final countdown; 0 turns it off
+    pub seconds: u32,
+    // Flash the digits every half second
+    pub pulse: bool,
+    // Short beep on each of the last seconds
+    pub beep: bool,
+}
+
+impl Default for CountdownConfig {
+    fn default() -> Self {
+        CountdownConfig {
+            seconds: 10,
+            pulse: true,
+            beep: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct GoalsConfig {
+    // Pomodoros to aim for each day, celebrated with fireworks; 0 sets no goal
+    pub daily_pomodoros: u32,
+    // Pomodoros to aim for each Monday-to-Sunday week; 0 sets no goal
+    pub weekly_pomodoros: u32,
+    // Show last week's review the first time the timer runs in a new week
+    pub weekly_review: bool,
+}
+
+impl Default for GoalsConfig {
+    fn default() -> Self {
+        GoalsConfig {
+            daily_pomodoros: 0,
+            weekly_pomodoros: 0,
+            weekly_review: true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct AchievementsConfig {
+    pub enabled: bool,
+    // Theme unlocked by an achievement to use instead of [theme], once unlocked
+    pub theme: Option<String>,
+    // The golden tomato in the work animation, once unlocked
+    pub golden_tomato: bool,
+}
+
+impl Default for AchievementsConfig {
+    fn default() -> Self {
+        AchievementsConfig {
+            enabled: true,
+            theme: None,
+            golden_tomato: true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct CatsConfig {
+    pub enabled: bool,
+    // Odds of a finished work session rescuing a cat, in percent
+    pub chance_percent: u32,
+}
+
+impl Default for CatsConfig {
+    fn default() -> Self {
+        CatsConfig {
+            enabled: true,
+            chance_percent: 25,
+        }
+    }
+}
+
+// A tag added to work sessions that start within the rule's hours and days;
+// conditions left out always hold
+#[derive(Clone, Debug, Deserialize)]
+pub struct AutoTagRule {
+    pub tag: String,
+    // "HH:MM"; a rule from 22:00 to 06:00 runs through midnight
+    pub after: Option<String>,
+    pub before: Option<String>,
+    // "weekdays", "weekends", "daily" or days like "mon,thu"
+    pub days: Option<String>,
+}
+
+// Where a notification goes: the first rule for the event whose conditions
+// hold sends it down its channels in place of the built-in ones (the chime,
+// [speech] and [notifications] desktop). Conditions left out always hold
+#[derive(Clone, Debug, Deserialize)]
+pub struct NotifyRule {
+    pub event: NotifyEvent,
+    pub channels: Vec<Channel>,
+    pub session: Option<TimerType>,
+    // Whether the terminal has focus
+    pub focused: Option<bool>,
+    // "HH:MM" and days, as in [[auto_tags]]
+    pub after: Option<String>,
+    pub before: Option<String>,
+    pub days: Option<String>,
+    // Where the webhook channel posts
+    pub webhook: Option<String>,
+}
+
+// Announcements part-way through work, to pace yourself without watching the bar
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct MilestonesConfig {
+    pub enabled: bool,
+    // Progress through the session, in percent
+    pub percent: Vec<u32>,
+    // Minutes before the end
+    pub minutes_left: Vec<u32>,
+    // Soft tone along with the toast and title
+    pub tone: bool,
+}
+
+impl Default for MilestonesConfig {
+    fn default() -> Self {
+        MilestonesConfig {
+            enabled: false,
+            percent: vec![50, 75],
+            minutes_left: vec![5],
+            tone: true,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Waveform {
+    #[default]
+    Square,
+    Triangle,
+    Sine,
+    Noise,
+}
+
+// Background sound while work runs
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ambient {
+    #[default]
+    Off,
+    Ticking,
+    Noise,
+}
+
+// Lead waveform of each sound; bass lines and percussion keep their own
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct SoundsConfig {
+    pub work_complete: Waveform,
+    pub break_complete: Waveform,
+    // Final countdown beeps
+    pub countdown: Waveform,
+    // Theme and sound effects of the animation
+    pub mario: Waveform,
+    // Melodies played instead of the built-in ones: RTTTL inline or in a
+    // file, or a .mid file. A list is a pool to pick from at random
+    pub work_complete_tune: Option<TuneList>,
+    pub break_complete_tune: Option<TuneList>,
+    // Never pick the same melody from a pool twice in a row
+    pub avoid_repeats: bool,
+    // Fades in as work starts and dips under chimes
+    pub ambient: Ambient,
+    // Percent of full volume
+    pub ambient_volume: u32,
+    // Master volume in percent, for chimes, animations and the ambient alike
+    pub volume: u32,
+    pub mute: bool,
+    // No sounds at all between these times, "22:00-07:00"
+    pub quiet_hours: Option<String>,
+}
+
+impl Default for SoundsConfig {
+    fn default() -> Self {
+        SoundsConfig {
+            work_complete: Waveform::default(),
+            break_complete: Waveform::default(),
+            countdown: Waveform::default(),
+            mario: Waveform::default(),
+            work_complete_tune: None,
+            break_complete_tune: None,
+            avoid_repeats: false,
+            ambient: Ambient::default(),
+            ambient_volume: 30,
+            volume: 100,
+            mute: false,
+            quiet_hours: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BreakScreen {
+    #[default]
+    Timer,
+    Breathing,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct BreaksConfig {
+    // What a running break shows in place of the digits
+    pub screen: BreakScreen,
+    // Soft tones as each breath in and out begins
+    pub breathing_tones: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct PausesConfig {
+    // Ask why when pausing work; the timer pauses either way
+    pub ask_reason: bool,
+    // Picked at the prompt with 1 to 9, and counted in the stats Pauses view
+    pub reasons: Vec<String>,
+}
+
+impl Default for PausesConfig {
+    fn default() -> Self {
+        PausesConfig {
+            ask_reason: false,
+            reasons: vec!["phone".to_string(), "colleague".to_string(), "bio".to_string()],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct EyeRestConfig {
+    // 20-20-20 reminders, independent of pomodoros
+    pub enabled: bool,
+    // Minutes of running work between reminders
+    pub every_minutes: u32,
+    // How long the reminder stays up
+    pub seconds: u32,
+    pub chime: bool,
+}
+
+impl Default for EyeRestConfig {
+    fn default() -> Self {
+        EyeRestConfig {
+            enabled: false,
+            every_minutes: 20,
+            seconds: 20,
+            chime: true,
+        }
+    }
+}
+
+// System changes made for the length of each running work session
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct FocusConfig {
+    // Keep the screensaver and sleep away; released on breaks and pauses
+    pub inhibit_sleep: bool,
+    // Turn on the desktop's do-not-disturb mode when work starts and off
+    // when the break starts
+    pub dnd: bool,
+    // Sites to block in the hosts file while work runs
+    pub block_sites: Vec<String>,
+    // What to do when a call starts during work
+    pub meetings: MeetingAction,
+    // Only count work while the terminal has focus: pause once it has been in
+    // the background for away_seconds, and resume on return
+    pub pause_when_away: bool,
+    pub away_seconds: u32,
+    // Lock the desktop session when a break starts; work starts on unlocking
+    // if the break is over by then
+    pub lock_on_break: bool,
+}
+
+impl Default for FocusConfig {
+    fn default() -> Self {
+        FocusConfig {
+            inhibit_sleep: false,
+            dnd: false,
+            block_sites: Vec::new(),
+            meetings: MeetingAction::default(),
+            pause_when_away: false,
+            away_seconds: 60,
+            lock_on_break: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MeetingAction {
+    #[default]
+    Off,
+    // Offer to pause
+    Ask,
+    Pause,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct ActivityWatchConfig {
+    // Send each finished session to an ActivityWatch server
+    pub enabled: bool,
+    pub url: String,
+    // Defaults to cyber-tomato_<hostname>, like ActivityWatch's own watchers
+    pub bucket: Option<String>,
+}
+
+impl Default for ActivityWatchConfig {
+    fn default() -> Self {
+        ActivityWatchConfig {
+            enabled: false,
+            url: "http://localhost:5600".to_string(),
+            bucket: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeTool {
+    Timewarrior,
+    Watson,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct TimeTrackingConfig {
+    // Also log each finished work session with this tool
+    pub tool: Option<TimeTool>,
+    // Watson project for sessions without a task
+    pub project: String,
+}
+
+impl Default for TimeTrackingConfig {
+    fn default() -> Self {
+        TimeTrackingConfig {
+            tool: None,
+            project: "pomodoro".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct CalendarConfig {
+    // A .ics file or an http(s) calendar feed URL
+    pub source: Option<String>,
+    // Show events this close in the status bar
+    pub warn_minutes: u32,
+    pub refresh_minutes: u32,
+    // Don't let auto mode start work that would run into an event
+    pub block_auto_start: bool,
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        CalendarConfig {
+            source: None,
+            warn_minutes: 10,
+            refresh_minutes: 15,
+            block_auto_start: false,
+        }
+    }
+}
+
+// A study-with-me room to post the session state to, shown as how many are
+// focusing there
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct RoomConfig {
+    // An http(s) room URL, e.g. from `cyber-tomato room serve`
+    pub url: Option<String>,
+}
+
+// A small JSON file with the timer's state for status bars and scripts
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct StatusFileConfig {
+    pub enabled: bool,
+    // Defaults to <cache dir>/status.json
+    pub path: Option<PathBuf>,
+    // Also keep a key=value file saying whether work is under way, for chat
+    // bots to auto-reply from
+    pub away_path: Option<PathBuf>,
+}
+
+impl Default for StatusFileConfig {
+    fn default() -> Self {
+        StatusFileConfig {
+            enabled: true,
+            path: None,
+            away_path: None,
+        }
+    }
+}
+
+// A text file with the countdown, for a text source in OBS
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct ObsConfig {
+    pub enabled: bool,
+    // Defaults to <cache dir>/obs.txt
+    pub path: Option<PathBuf>,
+    // With the placeholders of [templates]
+    pub template: String,
+}
+
+impl Default for ObsConfig {
+    fn default() -> Self {
+        ObsConfig {
+            enabled: false,
+            path: None,
+            template: "{session} {remaining}".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct IconsConfig {
+    pub set: IconSet,
+}
+
+// Pixel-art tomato in the title, for terminals with a graphics protocol
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct GraphicsConfig {
+    pub protocol: GraphicsProtocol,
+}
+
+// Wording of the timer's own text, with the placeholders in template.rs
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct TemplatesConfig {
+    pub title: String, // Terminal title
+    // Titles of the desktop notifications when a session ends
+    pub work_complete: String,
+    pub break_complete: String,
+    pub status: String, // `text` in the status file
+    // The auto-reply in the away file and `remote status --emacs/--xmpp`
+    pub away: String,
+}
+
+impl Default for TemplatesConfig {
+    fn default() -> Self {
+        TemplatesConfig {
+            title: "CYBER TOMATO - {session} {clock}".to_string(),
+            work_complete: "Work session complete - time for a break".to_string(),
+            break_complete: "Break is over - back to work".to_string(),
+            status: "{emoji} {remaining}".to_string(),
+            away: "In a pomodoro until {ends}".to_string(),
+        }
+    }
+}
+
+// Actions run once the timer is up, as if typed: "work 50", "theme nord",
+// "mute", "label Draft #writing" and the like (see the README)
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct StartupConfig {
+    pub actions: Vec<String>,
+}
+
+// Rhai files hooked into timer events (see scripts.rs); needs a build with
+// the `scripting` feature
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ScriptsConfig {
+    pub files: Vec<PathBuf>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DigitFont {
+    #[default]
+    Block,
+    // Drawn in braille dots, as large as the window allows
+    Braille,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct LayoutConfig {
+    // Panels to show, top to bottom
+    pub panels: Vec<Panel>,
+    // Rows per panel name, replacing the defaults; 0 shares the space left over
+    pub heights: BTreeMap<String, u16>,
+    pub font: DigitFont,
+    // What the digits count; the toggle_clock key cycles through them
+    pub clock: ClockDisplay,
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        LayoutConfig {
+            panels: Panel::DEFAULT_ORDER.to_vec(),
+            heights: BTreeMap::new(),
+            font: DigitFont::Block,
+            clock: ClockDisplay::Remaining,
+        }
+    }
+}
+
+impl LayoutConfig {
+    pub fn height(&self, panel: Panel) -> u16 {
+        self.heights.get(panel.name()).copied().unwrap_or(panel.default_height())
+    }
+}
+
+// Rewrites a config from an older version in the current format. The
+// rewrite loses comments, so the copy migrate keeps of the old file matters
+fn upgrade(path: &Path, text: String) -> Result<String, String> {
+    let failed = |e: String| format!("{}: upgrading the config: {e}", path.display());
+    let Ok(table) = toml::from_str::<toml::Table>(&text) else {
+        // The parse error is reported with its line
+        return Ok(text);
+    };
+    let found = table.get("version").and_then(toml::Value::as_integer).unwrap_or(1);
+    if found >= SCHEMA.version as i64 {
+        return Ok(text);
+    }
+    let found = found.max(0) as u32;
+    let mut value = serde_json::to_value(&table).map_err(|e| failed(e.to_string()))?;
+    SCHEMA.upgrade(found, &mut value).map_err(failed)?;
+    value["version"] = serde_json::json!(SCHEMA.version);
+    let upgraded = toml::to_string(&value).map_err(|e| failed(e.to_string()))?;
+    migrate::backup(path, found)
+        .and_then(|_| migrate::write_atomic(path, &upgraded))
+        .map_err(|e| failed(e.to_string()))?;
+    Ok(upgraded)
+}
+
+impl Config {
+    pub fn path() -> PathBuf {
+        paths::config_dir().join("config.toml")
+    }
+
+    pub fn load() -> Result<Self, String> {
+        let path = Self::path();
+        let config: Config = match fs::read_to_string(&path) {
+            Ok(text) => toml::from_str(&upgrade(&path, text)?).map_err(|e| format!("{}: {e}", path.display()))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Config::default()),
+            Err(e) => return Err(format!("{}: {e}", path.display())),
+        };
+        match config.validate().first() {
+            Some((key, message)) => Err(format!("{}: {key}: {message}", path.display())),
+            None => Ok(config),
+        }
+    }
+
+    // Checks the TOML types can't express, as (dotted key, message) pairs
+    fn validate(&self) -> Vec<(String, String)> {
+        let mut problems = Vec::new();
+        if let Some(version) = self.version.filter(|&version| version > SCHEMA.version) {
+            problems.push(("version".to_string(), SCHEMA.too_new(version)));
+        }
+        let max = self.timer.max_session_minutes;
+        let lengths = [
+            ("timer.work_minutes", self.timer.work_minutes),
+            ("timer.break_minutes", self.timer.break_minutes),
+            ("timer.long_break_minutes", self.timer.long_break_minutes),
+            ("timer.snooze_minutes", self.timer.snooze_minutes),
+            ("timer.max_session_minutes", max),
+        ];
+        for (key, minutes) in lengths {
+            if minutes == 0 {
+                problems.push((key.to_string(), "must be at least 1".to_string()));
+            } else if max > 0 && minutes > max {
+                problems.push((key.to_string(), format!("must be {max} at most (timer.max_session_minutes)")));
+            }
+        }
+        if self.history.backend == HistoryBackend::Sqlite && !cfg!(feature = "sqlite") {
+            problems.push(("history.backend".to_string(), history::NO_SQLITE.to_string()));
+        }
+        if !self.scripts.files.is_empty() && !cfg!(feature = "scripting") {
+            problems.push(("scripts.files".to_string(), scripts::NO_SCRIPTING.to_string()));
+        }
+        if self.milestones.percent.iter().any(|&p| p == 0 || p >= 100) {
+            problems.push(("milestones.percent".to_string(), "must be between 1 and 99".to_string()));
+        }
+        if self.milestones.minutes_left.contains(&0) {
+            problems.push(("milestones.minutes_left".to_string(), "must be at least 1".to_string()));
+        }
+        if let Some(theme) = &self.achievements.theme
+            && achievements::reward_theme(theme).is_none()
+        {
+            problems.push(("achievements.theme".to_string(), format!("unknown theme '{theme}'")));
+        }
+        if self.cats.chance_percent > 100 {
+            problems.push(("cats.chance_percent".to_string(), "must be at most 100".to_string()));
+        }
+        if self.eye_rest.every_minutes == 0 {
+            problems.push(("eye_rest.every_minutes".to_string(), "must be at least 1".to_string()));
+        }
+        if self.calendar.refresh_minutes == 0 {
+            problems.push(("calendar.refresh_minutes".to_string(), "must be at least 1".to_string()));
+        }
+        if let Some(url) = &self.room.url
+            && !url.starts_with("http://")
+            && !url.starts_with("https://")
+        {
+            problems.push(("room.url".to_string(), "must be an http:// or https:// URL".to_string()));
+        }
+        for domain in &self.focus.block_sites {
+            if let Err(e) = hosts::check_domain(domain) {
+                problems.push(("focus.block_sites".to_string(), e));
+            }
+        }
+        if self.sounds.ambient_volume > 100 {
+            problems.push(("sounds.ambient_volume".to_string(), "must be 100 at most".to_string()));
+        }
+        if self.sounds.volume > 100 {
+            problems.push(("sounds.volume".to_string(), "must be 100 at most".to_string()));
+        }
+        if let Some(hours) = &self.sounds.quiet_hours
+            && let Err(e) = audio::QuietHours::parse(hours)
+        {
+            problems.push(("sounds.quiet_hours".to_string(), e));
+        }
+        let tunes = [
+            ("sounds.work_complete_tune", &self.sounds.work_complete_tune),
+            ("sounds.break_complete_tune", &self.sounds.break_complete_tune),
+            ("theme.work.tune", &self.theme.work.tune),
+            ("theme.break.tune", &self.theme.breaks.tune),
+        ];
+        for (key, list) in tunes {
+            for tune in list.iter().flat_map(TuneList::tunes) {
+                if let Err(e) = audio::load_tune(tune) {
+                    problems.push((key.to_string(), e));
+                }
+            }
+        }
+        for rule in &self.auto_tags {
+            if let Err(e) = auto_tags::check(rule) {
+                problems.push(("auto_tags".to_string(), format!("'{}': {e}", rule.tag)));
+            }
+        }
+        for (i, rule) in self.notify.iter().enumerate() {
+            if let Err(e) = routing::check(rule) {
+                problems.push(("notify".to_string(), format!("rule {}: {e}", i + 1)));
+            }
+        }
+        let startup = std::iter::once(("startup.actions".to_string(), &self.startup))
+            .chain(self.profiles.iter().map(|(name, profile)| (format!("profiles.{name}.actions"), profile)));
+        for (key, startup) in startup {
+            for text in &startup.actions {
+                match startup::parse(text) {
+                    Ok(StartupAction::Theme(name)) if !self.themes.contains_key(&name) => {
+                        problems.push((key.clone(), format!("'{text}': no [themes.{name}] table")));
+                    }
+                    Ok(_) => {}
+                    Err(e) => problems.push((key.clone(), format!("'{text}': {e}"))),
+                }
+            }
+        }
+        if let Err((action, message)) = Keymap::from_config(&self.keys) {
+            problems.push((format!("keys.{action}"), message));
+        }
+        for (i, panel) in self.layout.panels.iter().enumerate() {
+            if self.layout.panels[..i].contains(panel) {
+                problems.push(("layout.panels".to_string(), format!("'{}' is listed twice", panel.name())));
+            }
+        }
+        for name in self.layout.heights.keys() {
+            if !Panel::ALL.iter().any(|panel| panel.name() == name.as_str()) {
+                problems.push((format!("layout.heights.{name}"), "unknown panel".to_string()));
+            }
+        }
+        problems
+    }
+
+    // Watches the config directory rather than the file itself: editors often
+    // save by replacing the file, and the file may not exist yet. The watcher
+    // must be kept alive for events to arrive
+    pub fn watch() -> notify::Result<(RecommendedWatcher, Receiver<()>)> {
+        let path = Self::path();
+        let dir = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+        fs::create_dir_all(&dir)?;
+
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            if let Ok(event) = event
+                && event.paths.iter().any(|p| p.file_name() == path.file_name())
+            {
+                let _ = tx.send(());
+            }
+        })?;
+        watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+        Ok((watcher, rx))
+    }
+
+    // Everything `config check` reports: syntax and type errors, unknown keys,
+    // invalid values and missing files, with line numbers where they can be found
+    pub fn check(text: &str) -> Vec<Problem> {
+        let mut unknown = Vec::new();
+        let parsed = toml::Deserializer::parse(text).and_then(|de| serde_ignored::deserialize(de, |key| unknown.push(key.to_string())));
+        let config: Config = match parsed {
+            Ok(config) => config,
+            Err(e) => {
+                return vec![Problem {
+                    line: e.span().map(|span| text[..span.start].matches('\n').count() + 1),
+                    message: e.message().trim().to_string(),
+                }];
+            }
+        };
+
+        let mut problems: Vec<Problem> = unknown
+            .into_iter()
+            .map(|key| Problem {
+                line: key_line(text, &key),
+                message: format!("unknown key '{key}'"),
+            })
+            .collect();
+        let mut invalid = config.validate();
+        if let Some(file) = &config.quotes.file
+            && let Err(e) = fs::metadata(paths::expand_home(file))
+        {
+            invalid.push(("quotes.file".to_string(), format!("{}: {e}", file.display())));
+        }
+        for file in &config.scripts.files {
+            if let Err(e) = fs::metadata(paths::expand_home(file)) {
+                invalid.push(("scripts.files".to_string(), format!("{}: {e}", file.display())));
+            }
+        }
+        problems.extend(invalid.into_iter().map(|(key, message)| Problem {
+            line: key_line(text, &key),
+            message: format!("{key}: {message}"),
+        }));
+        problems.sort_by_key(|problem| problem.line.unwrap_or(usize::MAX));
+        problems
+    }
+
+    pub fn history_dir(&self) -> PathBuf {
+        self.history_dir_in(&paths::data_dir())
+    }
+
+    pub fn history_dir_in(&self, data_dir: &Path) -> PathBuf {
+        match &self.history.dir {
+            Some(dir) => paths::expand_home(dir),
+            None => data_dir.join("history"),
+        }
+    }
+}
+
+pub struct Problem {
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+// Line (1-based) where a dotted key such as "timer.work_minutes" is set, or
+// where its table starts when the key is a whole table
+fn key_line(text: &str, key: &str) -> Option<usize> {
+    let (table, name) = key.rsplit_once('.').unwrap_or(("", key));
+    let mut current = String::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if let Some(header) = line.strip_prefix('[').and_then(|l| l.split(']').next()) {
+            current = header.trim().to_string();
+            if current == key {
+                return Some(i + 1);
+            }
+        } else if current == table
+            && let Some((left, _)) = line.split_once('=')
+            && left.trim().trim_matches('"') == name
+        {
+            return Some(i + 1);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_reports_lines() {
+        let text = "[timer]\nwork_minutes = 0\nbreak_minute = 5\n\n[keys]\nstats = \"b\"\n[colors]\nprimary = \"red\"\n";
+        let problems: Vec<(Option<usize>, String)> = Config::check(text).into_iter().map(|p| (p.line, p.message)).collect();
+        assert_eq!(
+            problems,
+            vec![
+                (Some(2), "timer.work_minutes: must be at least 1".to_string()),
+                (Some(3), "unknown key 'timer.break_minute'".to_string()),
+                (Some(6), "keys.stats: 'b' is already bound to start_break".to_string()),
+                (Some(7), "unknown key 'colors'".to_string()),
+            ]
+        );
+
+        let problems = Config::check("[timer]\nwork_minutes = \"ten\"\n");
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].line, Some(2));
+
+        let problems = Config::check("[timer]\nmax_session_minutes = 60\nwork_minutes = 90\n");
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].message, "timer.work_minutes: must be 60 at most (timer.max_session_minutes)");
+    }
+
+    #[test]
+    fn test_theme_session_sounds() {
+        let text = "[sounds]\nwork_complete = \"sine\"\n\n[theme.break]\ndigits = \"cyan\"\nwaveform = \"triangle\"\n";
+        let config: Config = toml::from_str(text).unwrap();
+        assert_eq!(config.theme.breaks.digits, Some(ratatui::style::Color::Cyan));
+        // The theme fills in its session type and leaves the rest of [sounds] alone
+        let sounds = config.theme.sounds(&config.sounds);
+        assert_eq!(sounds.break_complete, Waveform::Triangle);
+        assert_eq!(sounds.work_complete, Waveform::Sine);
+    }
+}
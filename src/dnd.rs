@@ -0,0 +1,184 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+
+// The desktop's do-not-disturb mode, switched at session boundaries. Most
+// desktops take an on/off command; KDE keeps notifications inhibited for as
+// long as a helper process runs
+pub struct DoNotDisturb {
+    wanted: Option<bool>, // Last state asked for; a failed switch waits for the next change
+    on: bool,
+    held: Option<Child>,
+    // Exists while a switched mode is on. A run killed before it could switch
+    // back leaves it behind, and the next run switches the mode off
+    marker: PathBuf,
+    backend: fn(bool) -> Option<Backend>,
+}
+
+enum Backend {
+    Switch(Command),
+    Hold(Command),
+}
+
+impl DoNotDisturb {
+    pub fn new(marker: PathBuf) -> Self {
+        Self::with(marker, backend)
+    }
+
+    fn with(marker: PathBuf, backend: fn(bool) -> Option<Backend>) -> Self {
+        DoNotDisturb {
+            wanted: None,
+            on: marker.exists(),
+            held: None,
+            marker,
+            backend,
+        }
+    }
+
+    // Only acts when the wanted state changes, so a failure is reported once
+    // rather than on every frame
+    pub fn set(&mut self, on: bool) -> io::Result<()> {
+        if self.wanted == Some(on) {
+            return Ok(());
+        }
+        self.wanted = Some(on);
+        if on == self.on {
+            return Ok(());
+        }
+        if let Some(mut child) = self.held.take() {
+            drop(child.stdin.take());
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        match (self.backend)(on) {
+            Some(Backend::Switch(mut command)) => {
+                let mut child = spawn(command.stdin(Stdio::null()))?;
+                // Shortcuts can take a moment; reap the child off the UI thread
+                thread::spawn(move || child.wait());
+                self.mark(on);
+            }
+            Some(Backend::Hold(mut command)) if on => self.held = Some(spawn(command.stdin(Stdio::piped()))?),
+            Some(Backend::Hold(_)) => {}
+            None => return Err(io::Error::new(io::ErrorKind::Unsupported, "not supported on this platform")),
+        }
+        self.on = on;
+        Ok(())
+    }
+
+    // A held inhibition ends with us, so only a switch needs the marker
+    fn mark(&self, on: bool) {
+        if on {
+            if let Some(dir) = self.marker.parent() {
+                let _ = fs::create_dir_all(dir);
+            }
+            let _ = fs::write(&self.marker, "");
+        } else {
+            let _ = fs::remove_file(&self.marker);
+        }
+    }
+}
+
+// Errors name the helper, which is usually what is missing
+fn spawn(command: &mut Command) -> io::Result<Child> {
+    command
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", command.get_program().to_string_lossy())))
+}
+
+impl Drop for DoNotDisturb {
+    // Switched modes outlive us, so always leave them off, trying once more
+    // if switching off failed before
+    fn drop(&mut self) {
+        self.wanted = None;
+        let _ = self.set(false);
+    }
+}
+
+// Focus modes have no command line switch; the user creates two shortcuts
+// that turn their chosen focus on and off
+#[cfg(target_os = "macos")]
+fn backend(on: bool) -> Option<Backend> {
+    let name = if on { "Cyber Tomato Focus On" } else { "Cyber Tomato Focus Off" };
+    let mut command = Command::new("shortcuts");
+    command.args(["run", name]);
+    Some(Backend::Switch(command))
+}
+
+// Focus Assist has no public API to switch it
+#[cfg(windows)]
+fn backend(_on: bool) -> Option<Backend> {
+    None
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn backend(on: bool) -> Option<Backend> {
+    let desktop = std::env::var("XDG_CURRENT_DESKTOP").unwrap_or_default().to_ascii_uppercase();
+    if desktop.contains("KDE") {
+        // `cat` keeps the inhibition until our end of its stdin closes
+        let mut command = Command::new("kde-inhibit");
+        command.args(["--notifications", "cat"]);
+        Some(Backend::Hold(command))
+    } else if desktop.contains("GNOME") {
+        let mut command = Command::new("gsettings");
+        command.args(["set", "org.gnome.desktop.notifications", "show-banners", if on { "false" } else { "true" }]);
+        Some(Backend::Switch(command))
+    } else {
+        // dunst, the usual daemon on tiling window managers
+        let mut command = Command::new("dunstctl");
+        command.args(["set-paused", if on { "true" } else { "false" }]);
+        Some(Backend::Switch(command))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn marker(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("cyber-tomato-dnd-{}-{name}", std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn missing(_on: bool) -> Option<Backend> {
+        Some(Backend::Switch(Command::new("cyber-tomato-no-such-helper")))
+    }
+
+    #[cfg(unix)]
+    fn working(_on: bool) -> Option<Backend> {
+        Some(Backend::Switch(Command::new("true")))
+    }
+
+    #[test]
+    fn test_failed_switch_is_not_on() {
+        let path = marker("failed");
+        let mut dnd = DoNotDisturb::with(path.clone(), missing);
+        assert!(dnd.set(true).is_err());
+        assert!(!dnd.on && !path.exists());
+        // Reported once, not again on every frame
+        assert!(dnd.set(true).is_ok());
+        // Nothing to switch back off at the break
+        assert!(dnd.set(false).is_ok());
+        assert!(dnd.set(true).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_marker_outlives_a_crash() {
+        let path = marker("crash");
+        let mut dnd = DoNotDisturb::with(path.clone(), working);
+        dnd.set(true).unwrap();
+        assert!(path.exists());
+        // Killed without dropping
+        std::mem::forget(dnd);
+
+        let mut next = DoNotDisturb::with(path.clone(), working);
+        assert!(next.on);
+        next.set(false).unwrap();
+        assert!(!next.on && !path.exists());
+    }
+}
@@ -0,0 +1,216 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    Frame,
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::config::EyeRestConfig;
+use crate::{history, theme};
+
+// One reminder, appended to eye_rests.jsonl when it ends
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EyeRestRecord {
+    pub at: u64,     // Unix seconds
+    pub taken: bool, // False when dismissed early
+}
+
+pub enum Event {
+    Start,
+    End,
+}
+
+// The 20-20-20 rule: after every stretch of work, look about 20 feet away
+// for 20 seconds. Counts running work time only and ignores pomodoro boundaries
+pub struct EyeRest {
+    config: EyeRestConfig,
+    worked: Duration, // Work time since the last reminder
+    last_tick: Option<Instant>,
+    shown_at: Option<Instant>, // Set while the reminder is on screen
+    path: PathBuf,
+    records: Vec<EyeRestRecord>,
+}
+
+impl EyeRest {
+    pub fn load(config: EyeRestConfig, path: PathBuf) -> Self {
+        let records = fs::read_to_string(&path)
+            .map(|text| text.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+            .unwrap_or_default();
+        EyeRest {
+            config,
+            worked: Duration::ZERO,
+            last_tick: None,
+            shown_at: None,
+            path,
+            records,
+        }
+    }
+
+    pub fn set_config(&mut self, config: EyeRestConfig) {
+        self.config = config;
+    }
+
+    pub fn records(&self) -> &[EyeRestRecord] {
+        &self.records
+    }
+
+    pub fn is_showing(&self) -> bool {
+        self.shown_at.is_some()
+    }
+
+    pub fn chime(&self) -> bool {
+        self.config.chime
+    }
+
+    // Called every frame with whether a work session is running
+    pub fn tick(&mut self, working: bool, time_scale: f64) -> Option<Event> {
+        self.tick_at(Instant::now(), working, time_scale)
+    }
+
+    fn tick_at(&mut self, now: Instant, working: bool, time_scale: f64) -> Option<Event> {
+        let delta = self.last_tick.map(|last| now - last).unwrap_or_default();
+        self.last_tick = Some(now);
+
+        if let Some(shown_at) = self.shown_at {
+            if now.duration_since(shown_at) >= self.length() {
+                self.finish(true);
+                return Some(Event::End);
+            }
+            return None;
+        }
+        if !self.config.enabled || !working {
+            return None;
+        }
+        self.worked += delta.mul_f64(time_scale);
+        if self.worked < Duration::from_secs(self.config.every_minutes as u64 * 60) {
+            return None;
+        }
+        self.worked = Duration::ZERO;
+        self.shown_at = Some(now);
+        Some(Event::Start)
+    }
+
+    pub fn dismiss(&mut self) {
+        if self.is_showing() {
+            self.finish(false);
+        }
+    }
+
+    fn length(&self) -> Duration {
+        Duration::from_secs(self.config.seconds as u64)
+    }
+
+    fn finish(&mut self, taken: bool) {
+        self.shown_at = None;
+        let record = EyeRestRecord {
+            at: history::now_secs(),
+            taken,
+        };
+        // Best-effort like the rest of the stats data
+        if let Ok(line) = serde_json::to_string(&record)
+            && let Some(parent) = self.path.parent()
+            && fs::create_dir_all(parent).is_ok()
+            && let Ok(mut file) = OpenOptions::new().create(true).append(true).open(&self.path)
+        {
+            let _ = writeln!(file, "{line}");
+        }
+        self.records.push(record);
+    }
+}
+
+pub fn render(f: &mut Frame, area: Rect, eye_rest: &EyeRest) {
+    let Some(shown_at) = eye_rest.shown_at else {
+        return;
+    };
+    let left = eye_rest.length().saturating_sub(shown_at.elapsed());
+    let key_style = Style::default().fg(theme::primary()).add_modifier(Modifier::BOLD);
+    let lines = vec![
+        Line::from(""),
+        Line::from(Span::styled("Rest your eyes", key_style)),
+        Line::from(""),
+        Line::from("Look at something about 20 feet (6 m) away"),
+        Line::from(Span::styled(format!("{}s", left.as_secs_f64().ceil()), Style::default().fg(theme::highlight()))),
+        Line::from(""),
+        Line::from(vec![Span::styled("Esc", key_style), Span::raw(" - Skip")]),
+    ];
+    f.render_widget(Clear, area);
+    let popup = Paragraph::new(lines).alignment(Alignment::Center).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("20-20-20")
+            .title_alignment(Alignment::Center)
+            .border_style(Style::default().fg(theme::primary())),
+    );
+    f.render_widget(popup, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eye_rest(name: &str) -> (EyeRest, PathBuf) {
+        let path = std::env::temp_dir().join(format!("cyber-tomato-eye-rest-{}-{name}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let config = EyeRestConfig {
+            enabled: true,
+            every_minutes: 20,
+            ..EyeRestConfig::default()
+        };
+        (EyeRest::load(config, path.clone()), path)
+    }
+
+    #[test]
+    fn test_reminder_after_running_work() {
+        let (mut eye_rest, path) = eye_rest("interval");
+        let start = Instant::now();
+        let mins = |m: u64| start + Duration::from_secs(m * 60);
+        assert!(eye_rest.tick_at(start, true, 1.0).is_none());
+        assert!(eye_rest.tick_at(mins(15), true, 1.0).is_none());
+        // Paused or on a break: the time doesn't count
+        assert!(eye_rest.tick_at(mins(30), false, 1.0).is_none());
+        assert!(eye_rest.tick_at(mins(34), true, 1.0).is_none());
+        assert!(matches!(eye_rest.tick_at(mins(35), true, 1.0), Some(Event::Start)));
+        assert!(eye_rest.is_showing());
+
+        // Ends by itself after `seconds`, counted as taken
+        let shown = mins(35);
+        assert!(eye_rest.tick_at(shown + Duration::from_secs(19), true, 1.0).is_none());
+        assert!(matches!(eye_rest.tick_at(shown + Duration::from_secs(20), true, 1.0), Some(Event::End)));
+        assert!(!eye_rest.is_showing());
+        assert!(eye_rest.records().iter().map(|r| r.taken).eq([true]));
+
+        // The next stretch starts from zero, and a time scale speeds it up
+        assert!(eye_rest.tick_at(mins(36), true, 20.0).is_none());
+        assert!(matches!(eye_rest.tick_at(mins(37), true, 20.0), Some(Event::Start)));
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_dismiss_and_disabled() {
+        let (mut eye_rest, path) = eye_rest("dismiss");
+        let start = Instant::now();
+        eye_rest.tick_at(start, true, 1.0);
+        assert!(matches!(eye_rest.tick_at(start + Duration::from_secs(20 * 60), true, 1.0), Some(Event::Start)));
+        eye_rest.dismiss();
+        assert!(!eye_rest.is_showing());
+        // Dismissing again does nothing
+        eye_rest.dismiss();
+        assert!(eye_rest.records().iter().map(|r| r.taken).eq([false]));
+        // Both the record and the file survive a reload
+        let reloaded = EyeRest::load(eye_rest.config.clone(), path.clone());
+        assert!(reloaded.records().iter().map(|r| r.taken).eq([false]));
+
+        eye_rest.set_config(EyeRestConfig::default());
+        let later = start + Duration::from_secs(60 * 60);
+        assert!(eye_rest.tick_at(later, true, 1.0).is_none());
+        assert!(eye_rest.tick_at(later + Duration::from_secs(60 * 60), true, 1.0).is_none());
+        let _ = fs::remove_file(path);
+    }
+}
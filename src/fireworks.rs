@@ -0,0 +1,250 @@
+use std::f64::consts::TAU;
+use std::hash::{BuildHasher, RandomState};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Stylize},
+    symbols::Marker,
+    widgets::canvas::{Canvas, Circle, Context, Line},
+};
+use rodio::Sink;
+
+use crate::audio::AudioEngine;
+use crate::chiptune::{Envelope, Song, Voice};
+use crate::config::Waveform;
+use crate::mario_animation::{self, MarioAnimation, Particle, SCENE_HEIGHT, SCENE_WIDTH};
+
+pub const LENGTH: Duration = Duration::from_secs(12);
+// No new rockets this close to the end, so the last bursts can fade out
+const LAST_LAUNCH: Duration = Duration::from_secs(9);
+const LAUNCH_EVERY: Duration = Duration::from_millis(700);
+const SPARKS: usize = 36;
+
+const COLORS: [Color; 6] = [
+    Color::LightRed,
+    Color::LightYellow,
+    Color::LightGreen,
+    Color::LightCyan,
+    Color::LightMagenta,
+    Color::White,
+];
+
+struct Rocket {
+    x: f64,
+    y: f64,
+    vy: f64,
+    color: Color,
+}
+
+// Full-screen fireworks for reaching the daily goal. Shares the particles and
+// scene size of the per-session Mario animation, with a longer fanfare
+pub struct Fireworks {
+    rockets: Vec<Rocket>,
+    sparks: Vec<Particle>,
+    launched: u32,
+    random: u64,
+    start_time: Option<Instant>,
+    last_update: Option<Instant>,
+    message: String,
+
+    audio: Rc<AudioEngine>,
+    waveform: Waveform, // Lead voice of the fanfare
+    music_sink: Option<Sink>,
+    sfx_sink: Option<Sink>,
+}
+
+impl Fireworks {
+    pub fn new(audio: Rc<AudioEngine>, waveform: Waveform, message: String) -> Self {
+        Fireworks {
+            rockets: Vec::new(),
+            sparks: Vec::new(),
+            launched: 0,
+            random: RandomState::new().hash_one(()) | 1,
+            start_time: None,
+            last_update: None,
+            message,
+            audio,
+            waveform,
+            music_sink: None,
+            sfx_sink: None,
+        }
+    }
+
+    pub fn start(&mut self) {
+        self.start_time = Some(Instant::now());
+        self.music_sink = self.audio.sink();
+        self.sfx_sink = self.audio.sink();
+        if let Some(sink) = &self.music_sink {
+            let waveform = self.waveform;
+            sink.append(self.audio.clip("fanfare", waveform, || fanfare(waveform)));
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.start_time.is_some_and(|start| start.elapsed() > LENGTH)
+    }
+
+    // xorshift; good enough for where the rockets go
+    fn next(&mut self) -> f64 {
+        self.random ^= self.random << 13;
+        self.random ^= self.random >> 7;
+        self.random ^= self.random << 17;
+        (self.random % 10_000) as f64 / 10_000.0
+    }
+
+    // Moves the show on by the real time since the last call, like the Mario scene
+    pub fn update(&mut self) {
+        let Some(start) = self.start_time else {
+            return;
+        };
+        let now = Instant::now();
+        let elapsed = now - self.last_update.unwrap_or(start);
+        self.last_update = Some(now);
+        self.advance(elapsed, start.elapsed());
+    }
+
+    // Moves the show on by `elapsed`, `shown` into it: the real clock's times
+    // from update, simulated ones from `bench`
+    pub fn advance(&mut self, elapsed: Duration, shown: Duration) {
+        if LAUNCH_EVERY * self.launched <= shown && shown < LAST_LAUNCH {
+            self.launched += 1;
+            let rocket = Rocket {
+                x: SCENE_WIDTH * (0.15 + 0.7 * self.next()),
+                y: 0.0,
+                vy: 3.0 + 1.2 * self.next(),
+                color: COLORS[(self.next() * COLORS.len() as f64) as usize % COLORS.len()],
+            };
+            self.rockets.push(rocket);
+        }
+
+        for t in mario_animation::steps(elapsed) {
+            self.step(t);
+        }
+    }
+
+    // Rockets and sparks alive, for `bench` to see they don't pile up
+    pub fn particles(&self) -> usize {
+        self.rockets.len() + self.sparks.len()
+    }
+
+    // Physics for `t` frames
+    fn step(&mut self, t: f64) {
+        for rocket in &mut self.rockets {
+            rocket.y += rocket.vy * t;
+            rocket.vy -= 0.1 * t;
+        }
+        // Rockets burst as they slow down near the top of their climb
+        let (bursting, climbing) = self.rockets.drain(..).partition(|rocket| rocket.vy < 0.8);
+        self.rockets = climbing;
+        for rocket in bursting {
+            self.burst(&rocket);
+        }
+
+        for spark in &mut self.sparks {
+            spark.step(0.05, 0.018, t);
+            spark.vx *= 0.96f64.powf(t); // Air drag keeps the shell round
+        }
+        self.sparks.retain(|spark| spark.life > 0.0);
+    }
+
+    fn burst(&mut self, rocket: &Rocket) {
+        let speed = 1.2 + self.next();
+        for i in 0..SPARKS {
+            let angle = i as f64 * TAU / SPARKS as f64;
+            // Every other spark a little slower, for a double ring
+            let speed = if i % 2 == 0 { speed } else { speed * 0.6 };
+            self.sparks.push(Particle {
+                x: rocket.x,
+                y: rocket.y,
+                vx: angle.cos() * speed,
+                vy: angle.sin() * speed,
+                life: 1.0,
+                color: rocket.color,
+            });
+        }
+        if let Some(sink) = &self.sfx_sink {
+            let crackle = [
+                (5000.0, Duration::from_millis(60)),
+                (0.0, Duration::from_millis(40)),
+                (3000.0, Duration::from_millis(90)),
+            ];
+            let voice = Voice::new(Waveform::Noise, Envelope::HIT, 0.12);
+            sink.append(self.audio.clip("crackle", Waveform::Noise, || Song::new().track(voice, &crackle)));
+        }
+    }
+
+    pub fn render(&self, area: Rect) -> Canvas<'_, impl Fn(&mut Context)> {
+        let (x_bounds, y_bounds) = MarioAnimation::bounds(area, SCENE_WIDTH / 2.0);
+        // Centered, in canvas units per terminal column
+        let column = (x_bounds[1] - x_bounds[0]) / area.width.max(1) as f64;
+        let message_x = SCENE_WIDTH / 2.0 - self.message.chars().count() as f64 * column / 2.0;
+        Canvas::default()
+            .marker(Marker::Braille)
+            .x_bounds(x_bounds)
+            .y_bounds(y_bounds)
+            .paint(move |ctx| {
+                for rocket in &self.rockets {
+                    ctx.draw(&Line {
+                        x1: rocket.x,
+                        y1: rocket.y - 4.0,
+                        x2: rocket.x,
+                        y2: rocket.y,
+                        color: Color::Yellow,
+                    });
+                }
+                for spark in &self.sparks {
+                    // Embers dim before they go out
+                    let color = if spark.life < 0.3 { Color::DarkGray } else { spark.color };
+                    ctx.draw(&Circle {
+                        x: spark.x,
+                        y: spark.y,
+                        radius: 0.6,
+                        color,
+                    });
+                }
+                ctx.print(message_x, SCENE_HEIGHT * 0.9, self.message.clone().bold().fg(Color::LightYellow));
+            })
+    }
+}
+
+// About eight seconds: three rising phrases and a held high C
+fn fanfare(waveform: Waveform) -> Song {
+    // Melody, bass and length in ms
+    let notes = [
+        (392.00, 130.81, 150),   // G4 over C3
+        (523.25, 130.81, 150),   // C5
+        (659.25, 130.81, 150),   // E5
+        (783.99, 130.81, 450),   // G5
+        (659.25, 130.81, 150),   // E5
+        (783.99, 130.81, 900),   // G5
+        (0.0, 0.0, 300),         // Rest
+        (415.30, 103.83, 150),   // G#4 over G#2
+        (523.25, 103.83, 150),   // C5
+        (622.25, 103.83, 150),   // D#5
+        (830.61, 103.83, 450),   // G#5
+        (622.25, 103.83, 150),   // D#5
+        (830.61, 103.83, 900),   // G#5
+        (0.0, 0.0, 300),         // Rest
+        (466.16, 116.54, 150),   // A#4 over A#2
+        (587.33, 116.54, 150),   // D5
+        (698.46, 116.54, 150),   // F5
+        (932.33, 116.54, 450),   // A#5
+        (932.33, 116.54, 150),   // A#5
+        (932.33, 116.54, 150),   // A#5
+        (932.33, 116.54, 150),   // A#5
+        (1046.50, 130.81, 1800), // C6 over C3
+    ];
+    let ms = Duration::from_millis;
+    let melody: Vec<(f32, Duration)> = notes.iter().map(|&(lead, _, length)| (lead, ms(length))).collect();
+    let bass: Vec<(f32, Duration)> = notes.iter().map(|&(_, bass, length)| (bass, ms(length))).collect();
+    let drums: Vec<(f32, Duration)> = notes
+        .iter()
+        .map(|&(lead, _, length)| (if lead > 0.0 { 6000.0 } else { 0.0 }, ms(length)))
+        .collect();
+    Song::new()
+        .track(Voice::new(waveform, Envelope::HELD, 0.25), &melody)
+        .track(Voice::new(Waveform::Triangle, Envelope::HELD, 0.3), &bass)
+        .track(Voice::new(Waveform::Noise, Envelope::HIT, 0.08), &drums)
+}
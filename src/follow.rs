@@ -0,0 +1,82 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{TimerType, paths};
+
+// The running timer republishes its state at least this often, so followers
+// can tell a live instance from a file left behind by a crash
+pub const HEARTBEAT: Duration = Duration::from_secs(1);
+const STALE_AFTER: Duration = Duration::from_secs(5);
+
+// Snapshot of the running timer that `cyber-tomato watch` mirrors
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SharedState {
+    pub timer_type: TimerType,
+    pub duration_ms: u64,
+    pub elapsed_ms: u64, // Session time at `written_at_ms`
+    pub running: bool,
+    pub time_scale: f64,
+    pub auto: bool,
+    pub completed_sessions: u32,
+    pub task: Option<String>,
+    pub tags: Vec<String>,
+    pub message: Option<String>,
+    pub written_at_ms: u64, // Unix milliseconds
+}
+
+impl SharedState {
+    // Same state apart from the clock moving on
+    pub fn same_as(&self, other: &SharedState) -> bool {
+        let strip = |state: &SharedState| SharedState {
+            elapsed_ms: 0,
+            written_at_ms: 0,
+            ..state.clone()
+        };
+        strip(self) == strip(other)
+    }
+
+    // Session time now, extrapolated from the last write while running
+    pub fn elapsed(&self) -> Duration {
+        let elapsed = Duration::from_millis(self.elapsed_ms);
+        if self.running {
+            elapsed + Duration::from_millis(now_ms().saturating_sub(self.written_at_ms)).mul_f64(self.time_scale)
+        } else {
+            elapsed
+        }
+    }
+}
+
+pub fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+fn path() -> PathBuf {
+    paths::data_dir().join("state.json")
+}
+
+pub fn write(state: &SharedState) -> io::Result<()> {
+    let path = path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Replace atomically so a follower never reads half a file
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string(state)?)?;
+    fs::rename(tmp, path)
+}
+
+pub fn remove() {
+    let _ = fs::remove_file(path());
+}
+
+// The published state, or None when no timer is running
+pub fn read() -> Option<SharedState> {
+    let text = fs::read_to_string(path()).ok()?;
+    let state: SharedState = serde_json::from_str(&text).ok()?;
+    let age = Duration::from_millis(now_ms().saturating_sub(state.written_at_ms));
+    (age < STALE_AFTER).then_some(state)
+}
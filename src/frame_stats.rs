@@ -0,0 +1,149 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::theme;
+
+// Frames the averages and peaks are taken over, a few seconds' worth
+const WINDOW: usize = 120;
+
+// Debug overlay for the main loop: how long each frame takes to draw, how
+// long a key waits to show on screen, how many frames came too late and how
+// many sounds are queued. Measured all the time, so it has numbers as soon
+// as it's shown
+pub struct FrameStats {
+    shown: bool,
+    draws: VecDeque<Duration>,
+    latencies: VecDeque<Duration>, // From reading a key to the end of the next draw
+    budget: Duration,              // Time allowed the last frame: the poll interval
+    frames: u64,
+    dropped: u64,
+    last_frame: Option<Instant>,
+    key_at: Option<Instant>, // A key read whose frame hasn't been drawn yet
+    audio_queue: usize,
+}
+
+impl FrameStats {
+    pub fn new() -> Self {
+        FrameStats {
+            shown: false,
+            draws: VecDeque::new(),
+            latencies: VecDeque::new(),
+            budget: Duration::ZERO,
+            frames: 0,
+            dropped: 0,
+            last_frame: None,
+            key_at: None,
+            audio_queue: 0,
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.shown = !self.shown;
+    }
+
+    // Called as a key is read; only the first of a burst counts, since they
+    // all wait for the same frame
+    pub fn key(&mut self, at: Instant) {
+        self.key_at.get_or_insert(at);
+    }
+
+    // Called after each draw. A frame begun more than one `budget` after the
+    // last was due drops one frame for every budget missed
+    pub fn frame(&mut self, started: Instant, drawn: Instant, budget: Duration, audio_queue: usize) {
+        if let Some(last) = self.last_frame
+            && !self.budget.is_zero()
+        {
+            let missed = started.duration_since(last).as_secs_f64() / self.budget.as_secs_f64();
+            self.dropped += (missed.floor() as u64).saturating_sub(1);
+        }
+        self.frames += 1;
+        self.last_frame = Some(started);
+        self.budget = budget;
+        self.audio_queue = audio_queue;
+        push(&mut self.draws, drawn.duration_since(started));
+        if let Some(at) = self.key_at.take() {
+            push(&mut self.latencies, drawn.duration_since(at));
+        }
+    }
+}
+
+fn push(window: &mut VecDeque<Duration>, value: Duration) {
+    if window.len() >= WINDOW {
+        window.pop_front();
+    }
+    window.push_back(value);
+}
+
+// Average and peak in milliseconds, or None before anything was measured
+fn summary(window: &VecDeque<Duration>) -> Option<(f64, f64)> {
+    let most = window.iter().max()?;
+    let total: Duration = window.iter().sum();
+    Some((total.as_secs_f64() * 1000.0 / window.len() as f64, most.as_secs_f64() * 1000.0))
+}
+
+// In the top-left corner, clear of the toasts, over whatever is on screen
+pub fn render(f: &mut Frame, stats: &FrameStats) {
+    if !stats.shown {
+        return;
+    }
+    let budget = stats.budget.as_secs_f64() * 1000.0;
+    let timing = |label: &str, window| match summary(window) {
+        Some((average, most)) => {
+            let over = most > budget;
+            Line::styled(
+                format!(" {label:<7}{average:>5.1}ms avg {most:>6.1}ms max"),
+                Style::default().fg(if over { Color::Red } else { Color::White }),
+            )
+        }
+        None => Line::from(format!(" {label:<7}    -")),
+    };
+    let lines = vec![
+        timing("Draw", &stats.draws),
+        timing("Input", &stats.latencies),
+        Line::from(format!(" Budget {budget:>5.0}ms a frame")),
+        Line::from(format!(" Dropped {} of {} frames", stats.dropped, stats.frames)),
+        Line::from(format!(" Audio  {} queued", stats.audio_queue)),
+    ];
+    let area = f.area();
+    let overlay = Rect::new(area.x, area.y, 36.min(area.width), (lines.len() as u16 + 2).min(area.height));
+    f.render_widget(Clear, overlay);
+    let widget = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Frame budget")
+            .border_style(Style::default().fg(theme::primary())),
+    );
+    f.render_widget(widget, overlay);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dropped_frames_and_latency() {
+        let budget = Duration::from_millis(33);
+        let ms = Duration::from_millis;
+        let start = Instant::now();
+        let mut stats = FrameStats::new();
+        stats.frame(start, start + ms(2), budget, 0);
+        stats.frame(start + ms(33), start + ms(35), budget, 0);
+        // A key read, then a stall: 140ms until the next frame misses three
+        stats.key(start + ms(40));
+        stats.key(start + ms(50));
+        stats.frame(start + ms(173), start + ms(180), budget, 2);
+        assert_eq!((stats.frames, stats.dropped, stats.audio_queue), (3, 3, 2));
+        assert_eq!(stats.latencies, [ms(140)]);
+        let (average, most) = summary(&stats.draws).unwrap();
+        assert!((average - 11.0 / 3.0).abs() < 0.01 && most == 7.0);
+        assert_eq!(summary(&VecDeque::new()), None);
+    }
+}
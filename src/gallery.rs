@@ -0,0 +1,123 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::achievements::{Reward, Trophies};
+use crate::theme;
+
+// The completion animations there are to watch
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Scene {
+    Mario,
+    GoldenTomato,
+    Fireworks,
+}
+
+impl Scene {
+    pub const ALL: [Scene; 3] = [Scene::Mario, Scene::GoldenTomato, Scene::Fireworks];
+
+    fn name(self) -> &'static str {
+        match self {
+            Scene::Mario => "Mario",
+            Scene::GoldenTomato => "Golden Tomato",
+            Scene::Fireworks => "Fireworks",
+        }
+    }
+
+    // The golden tomato is only on show once earned
+    fn unlocked(self, trophies: &Trophies) -> bool {
+        self != Scene::GoldenTomato || trophies.has_reward(Reward::GoldenTomato)
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Scene::Mario => "Bricks, a tomato and its theme tune after every work session",
+            Scene::GoldenTomato => "The same, for a golden tomato; unlocked by Tomato Farmer",
+            Scene::Fireworks => "For the work session that reaches the daily goal",
+        }
+    }
+}
+
+pub enum Outcome {
+    Stay,
+    Close,
+    Play(Scene),
+}
+
+pub struct GalleryScreen {
+    selected: usize,
+}
+
+impl GalleryScreen {
+    pub fn new() -> Self {
+        GalleryScreen { selected: 0 }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent, trophies: &Trophies) -> Outcome {
+        match key.code {
+            KeyCode::Esc => return Outcome::Close,
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.selected = (self.selected + 1).min(Scene::ALL.len() - 1),
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                let scene = Scene::ALL[self.selected];
+                if scene.unlocked(trophies) {
+                    return Outcome::Play(scene);
+                }
+            }
+            _ => {}
+        }
+        Outcome::Stay
+    }
+}
+
+pub fn render(f: &mut Frame, area: Rect, screen: &GalleryScreen, trophies: &Trophies) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Animations")
+        .title_alignment(Alignment::Center)
+        .border_style(Style::default().fg(theme::primary()));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    let locked = Style::default().fg(Color::DarkGray);
+    let mut lines = Vec::new();
+    for (i, &scene) in Scene::ALL.iter().enumerate() {
+        let marker = if i == screen.selected { "▶" } else { " " };
+        let unlocked = scene.unlocked(trophies);
+        let style = match (unlocked, i == screen.selected) {
+            (false, _) => locked,
+            (true, true) => Style::default().fg(theme::highlight()).add_modifier(Modifier::BOLD),
+            (true, false) => Style::default(),
+        };
+        let name = if unlocked {
+            scene.name().to_string()
+        } else {
+            format!("{} (locked)", scene.name())
+        };
+        lines.push(Line::from(Span::styled(format!(" {marker} {name}"), style)));
+        lines.push(Line::from(Span::styled(format!("   {}", scene.description()), locked)));
+        lines.push(Line::from(""));
+    }
+    f.render_widget(Paragraph::new(lines), chunks[0]);
+
+    let key = Style::default().fg(theme::primary()).add_modifier(Modifier::BOLD);
+    let help = Line::from(vec![
+        Span::styled("↑/↓", key),
+        Span::raw(" - Select | "),
+        Span::styled("Enter", key),
+        Span::raw(" - Play | "),
+        Span::styled("Esc", key),
+        Span::raw(" - Close"),
+    ]);
+    f.render_widget(Paragraph::new(help).alignment(Alignment::Center), chunks[1]);
+}
@@ -0,0 +1,398 @@
+use std::env;
+use std::io::{self, Write};
+
+use crossterm::{cursor::MoveTo, queue};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Modifier;
+use serde::Deserialize;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphicsProtocol {
+    #[default]
+    Auto,
+    Kitty,
+    Iterm2,
+    Sixel,
+    Off,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Sprite {
+    Tomato,
+    Cat, // Shown once a session is over
+}
+
+// Each image covers this many cells; sixel has no scaling, so its pixels
+// assume the common 8x16 cell
+pub const SLOT_WIDTH: u16 = 2;
+
+const TOMATO: [&str; 16] = [
+    "......GG........",
+    "....gggGgggg....",
+    "...gg.gGg..gg...",
+    "....rrgrgrrr....",
+    "..rrrrrrrrrrrr..",
+    ".rrwwrrrrrrrrrr.",
+    ".rwwrrrrrrrrrrr.",
+    "rrwrrrrrrrrrrrRr",
+    "rrrrrrrrrrrrrrRr",
+    "rrrrrrrrrrrrrRRr",
+    "rrrrrrrrrrrrrRRr",
+    ".rrrrrrrrrrrRRr.",
+    ".rrrrrrrrrrRRRr.",
+    "..rrrrrrrRRRrr..",
+    "...rrrrRRRRrr...",
+    ".....rrrrrr.....",
+];
+
+const CAT: [&str; 16] = [
+    ".o............o.",
+    ".oo..........oo.",
+    ".opo........opo.",
+    ".oppo......oppo.",
+    ".oooooooooooooo.",
+    "oooooooooooooooo",
+    "oooggooooooggooo",
+    "ooogkoooooogkooo",
+    "oooooooooooooooo",
+    "ooooooopppoooooo",
+    "wooooooopoooooow",
+    ".wooooowowooooow",
+    "ooooooooooooooo.",
+    ".oooooooooooooo.",
+    "..oooooooooooo..",
+    "....oooooooo....",
+];
+
+fn color(pixel: u8) -> Option<[u8; 3]> {
+    match pixel {
+        b'r' => Some([220, 40, 40]),
+        b'R' => Some([150, 20, 30]),
+        b'w' => Some([255, 240, 230]),
+        b'g' => Some([60, 180, 60]),
+        b'G' => Some([30, 110, 40]),
+        b'o' => Some([240, 150, 50]),
+        b'p' => Some([240, 140, 160]),
+        b'k' => Some([20, 20, 20]),
+        _ => None,
+    }
+}
+
+impl Sprite {
+    fn rows(self) -> &'static [&'static str; 16] {
+        match self {
+            Sprite::Tomato => &TOMATO,
+            Sprite::Cat => &CAT,
+        }
+    }
+
+    // Row-major RGBA
+    fn rgba(self) -> Vec<u8> {
+        let mut pixels = Vec::new();
+        for row in self.rows() {
+            for pixel in row.bytes() {
+                match color(pixel) {
+                    Some([r, g, b]) => pixels.extend([r, g, b, 255]),
+                    None => pixels.extend([0, 0, 0, 0]),
+                }
+            }
+        }
+        pixels
+    }
+
+    fn size(self) -> (u32, u32) {
+        let rows = self.rows();
+        (rows[0].len() as u32, rows.len() as u32)
+    }
+}
+
+// Pixel-art images in terminals that can show them. The UI leaves a slot of
+// concealed blank cells for each image (see `mark_slot`); after every frame
+// the slots are found in the finished buffer and the images written over
+// them with the terminal's graphics protocol. A popup drawn over a slot
+// clears the concealment, so covered images are taken down rather than
+// drawn on top of it
+pub struct Graphics {
+    protocol: GraphicsProtocol, // Never Auto
+    shown: Vec<(u16, u16, Sprite)>,
+}
+
+impl Graphics {
+    pub fn new() -> Self {
+        Graphics {
+            protocol: GraphicsProtocol::Off,
+            shown: Vec::new(),
+        }
+    }
+
+    pub fn set_protocol(&mut self, protocol: GraphicsProtocol) {
+        let protocol = match protocol {
+            GraphicsProtocol::Auto => detect(),
+            protocol => protocol,
+        };
+        if protocol != self.protocol {
+            self.protocol = protocol;
+            self.shown.clear();
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.protocol != GraphicsProtocol::Off
+    }
+
+    // Puts `sprite` in the slots of the frame just drawn. Only changes are
+    // written, unless the screen was cleared and everything has to go again
+    pub fn update(&mut self, out: &mut impl Write, slots: &[(u16, u16)], sprite: Sprite, cleared: bool) -> io::Result<()> {
+        let wanted: Vec<(u16, u16, Sprite)> = if self.enabled() {
+            slots.iter().map(|&(x, y)| (x, y, sprite)).collect()
+        } else {
+            Vec::new()
+        };
+        if wanted == self.shown && !cleared {
+            return Ok(());
+        }
+        // Kitty keeps images apart from the text, so they stay up until
+        // deleted; the others are replaced by whatever text is drawn over them
+        if self.protocol == GraphicsProtocol::Kitty {
+            write!(out, "\x1b_Ga=d,d=I,i={KITTY_ID},q=2\x1b\\")?;
+            if !wanted.is_empty() {
+                kitty_transmit(out, sprite)?;
+            }
+        }
+        for (placement, &(x, y, sprite)) in wanted.iter().enumerate() {
+            queue!(out, MoveTo(x, y))?;
+            match self.protocol {
+                GraphicsProtocol::Kitty => kitty_place(out, placement + 1)?,
+                GraphicsProtocol::Iterm2 => iterm2(out, sprite)?,
+                GraphicsProtocol::Sixel => sixel(out, sprite)?,
+                GraphicsProtocol::Auto | GraphicsProtocol::Off => {}
+            }
+        }
+        out.flush()?;
+        self.shown = wanted;
+        Ok(())
+    }
+}
+
+// Cells of a slot are concealed blanks; nothing else in the UI conceals text
+pub fn mark_slot(buffer: &mut Buffer, x: u16, y: u16) {
+    for dx in 0..SLOT_WIDTH {
+        if let Some(cell) = buffer.cell_mut((x + dx, y)) {
+            cell.set_symbol(" ").modifier.insert(Modifier::HIDDEN);
+        }
+    }
+}
+
+// Top-left cells of the slots still whole after everything else was drawn
+pub fn slots(buffer: &Buffer) -> Vec<(u16, u16)> {
+    let area: Rect = buffer.area;
+    let marked = |x: u16, y: u16| buffer.cell((x, y)).is_some_and(|cell| cell.modifier.contains(Modifier::HIDDEN));
+    let mut slots = Vec::new();
+    for y in area.top()..area.bottom() {
+        let mut x = area.left();
+        while x + SLOT_WIDTH <= area.right() {
+            if (0..SLOT_WIDTH).all(|dx| marked(x + dx, y)) {
+                slots.push((x, y));
+                x += SLOT_WIDTH;
+            } else {
+                x += 1;
+            }
+        }
+    }
+    slots
+}
+
+// Terminals that say who they are. Multiplexers would need the escapes
+// wrapped, so images are left off inside them
+fn detect() -> GraphicsProtocol {
+    let var = |name: &str| env::var(name).unwrap_or_default();
+    if !var("TMUX").is_empty() || var("TERM").starts_with("screen") {
+        return GraphicsProtocol::Off;
+    }
+    if var("TERM") == "xterm-kitty" || !var("KITTY_WINDOW_ID").is_empty() || var("TERM_PROGRAM") == "ghostty" {
+        return GraphicsProtocol::Kitty;
+    }
+    if matches!(var("TERM_PROGRAM").as_str(), "iTerm.app" | "WezTerm") {
+        return GraphicsProtocol::Iterm2;
+    }
+    if matches!(var("TERM").as_str(), "foot" | "foot-extra" | "mlterm") {
+        return GraphicsProtocol::Sixel;
+    }
+    GraphicsProtocol::Off
+}
+
+const KITTY_ID: u32 = 7501;
+
+// Raw RGBA, sent once and then placed in each slot, scaled to it by the terminal
+fn kitty_transmit(out: &mut impl Write, sprite: Sprite) -> io::Result<()> {
+    let (width, height) = sprite.size();
+    let data = base64(&sprite.rgba());
+    // Payloads go in chunks of at most 4096 bytes
+    let chunks: Vec<&[u8]> = data.as_bytes().chunks(4096).collect();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i + 1 < chunks.len());
+        let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+        if i == 0 {
+            write!(out, "\x1b_Ga=t,f=32,s={width},v={height},i={KITTY_ID},q=2,m={more};{chunk}\x1b\\")?;
+        } else {
+            write!(out, "\x1b_Gm={more};{chunk}\x1b\\")?;
+        }
+    }
+    Ok(())
+}
+
+fn kitty_place(out: &mut impl Write, placement: usize) -> io::Result<()> {
+    write!(out, "\x1b_Ga=p,i={KITTY_ID},p={placement},c={SLOT_WIDTH},r=1,C=1,q=2\x1b\\")
+}
+
+fn iterm2(out: &mut impl Write, sprite: Sprite) -> io::Result<()> {
+    let (width, height) = sprite.size();
+    let png = base64(&png(width, height, &sprite.rgba()));
+    write!(out, "\x1b]1337;File=inline=1;width={SLOT_WIDTH};height=1;preserveAspectRatio=1:{png}\x07")
+}
+
+// Bands of six rows, one pass per color; unset pixels keep the background
+fn sixel(out: &mut impl Write, sprite: Sprite) -> io::Result<()> {
+    let (width, height) = sprite.size();
+    let rows = sprite.rows();
+    let mut palette: Vec<u8> = Vec::new();
+    for row in rows {
+        for pixel in row.bytes() {
+            if color(pixel).is_some() && !palette.contains(&pixel) {
+                palette.push(pixel);
+            }
+        }
+    }
+    write!(out, "\x1bP0;1q\"1;1;{width};{height}")?;
+    for (i, &pixel) in palette.iter().enumerate() {
+        let [r, g, b] = color(pixel).unwrap_or_default().map(|c| c as u32 * 100 / 255);
+        write!(out, "#{i};2;{r};{g};{b}")?;
+    }
+    for band in rows.chunks(6) {
+        for (i, &pixel) in palette.iter().enumerate() {
+            let columns: String = (0..width as usize)
+                .map(|x| {
+                    let bits = band
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, row)| row.as_bytes()[x] == pixel)
+                        .fold(0u8, |bits, (dy, _)| bits | 1 << dy);
+                    (63 + bits) as char
+                })
+                .collect();
+            write!(out, "#{i}{columns}$")?;
+        }
+        write!(out, "-")?;
+    }
+    write!(out, "\x1b\\")
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut text = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                text.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                text.push('=');
+            }
+        }
+    }
+    text
+}
+
+// An RGBA PNG with the image data stored uncompressed, which every decoder
+// takes and keeps this free of a compression dependency
+fn png(width: u32, height: u32, rgba: &[u8]) -> Vec<u8> {
+    let mut raw = Vec::with_capacity(rgba.len() + height as usize);
+    for row in rgba.chunks(width as usize * 4) {
+        raw.push(0); // No filter
+        raw.extend_from_slice(row);
+    }
+    let mut zlib = vec![0x78, 0x01];
+    let blocks: Vec<&[u8]> = raw.chunks(0xffff).collect();
+    for (i, block) in blocks.iter().enumerate() {
+        let len = block.len() as u16;
+        zlib.push(u8::from(i + 1 == blocks.len()));
+        zlib.extend(len.to_le_bytes());
+        zlib.extend((!len).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    zlib.extend(adler32(&raw).to_be_bytes());
+
+    let mut ihdr = Vec::new();
+    ihdr.extend(width.to_be_bytes());
+    ihdr.extend(height.to_be_bytes());
+    ihdr.extend([8, 6, 0, 0, 0]); // 8-bit RGBA, no interlacing
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    for (kind, data) in [(b"IHDR", &ihdr), (b"IDAT", &zlib), (b"IEND", &Vec::new())] {
+        png.extend((data.len() as u32).to_be_bytes());
+        let start = png.len();
+        png.extend_from_slice(kind);
+        png.extend_from_slice(data);
+        let crc = crc32(&png[start..]);
+        png.extend(crc.to_be_bytes());
+    }
+    png
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    b << 16 | a
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { crc >> 1 ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sprites_are_square() {
+        for sprite in [Sprite::Tomato, Sprite::Cat] {
+            assert!(sprite.rows().iter().all(|row| row.len() == 16), "{sprite:?}");
+            assert!(sprite.rows().iter().flat_map(|row| row.bytes()).all(|p| p == b'.' || color(p).is_some()));
+        }
+    }
+
+    #[test]
+    fn test_encodings() {
+        assert_eq!(base64(b"Man"), "TWFu");
+        assert_eq!(base64(b"Ma"), "TWE=");
+        assert_eq!(base64(b"M"), "TQ==");
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+        let png = png(1, 1, &[255, 0, 0, 255]);
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]));
+    }
+
+    #[test]
+    fn test_slots() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 10, 3));
+        mark_slot(&mut buffer, 1, 1);
+        mark_slot(&mut buffer, 6, 1);
+        assert_eq!(slots(&buffer), vec![(1, 1), (6, 1)]);
+        // Covered by a popup
+        buffer.cell_mut((7, 1)).unwrap().reset();
+        assert_eq!(slots(&buffer), vec![(1, 1)]);
+    }
+}
@@ -0,0 +1,194 @@
+use std::cell::Cell;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::keymap::{Action, Keymap};
+use crate::theme;
+
+const PAGE: usize = 10;
+
+// The key reference: every action in the keymap with its current keys,
+// grouped by category, scrollable and filtered with `/`
+pub struct HelpScreen {
+    scroll: usize,
+    query: String,
+    searching: bool,         // Typing goes into the query
+    max_scroll: Cell<usize>, // Set by render, which knows the height
+}
+
+impl HelpScreen {
+    pub fn new() -> Self {
+        HelpScreen {
+            scroll: 0,
+            query: String::new(),
+            searching: false,
+            max_scroll: Cell::new(0),
+        }
+    }
+
+    pub fn open(&mut self) {
+        self.scroll = 0;
+        self.query.clear();
+        self.searching = false;
+    }
+
+    // Whether keys should reach the help screen before the keymap
+    pub fn is_searching(&self) -> bool {
+        self.searching
+    }
+
+    // `action` is what the key is bound to. Returns false when the help
+    // screen should close
+    pub fn handle_key(&mut self, key: KeyEvent, action: Option<Action>) -> bool {
+        if self.searching {
+            match key.code {
+                KeyCode::Esc => {
+                    self.searching = false;
+                    self.query.clear();
+                }
+                KeyCode::Enter => self.searching = false,
+                KeyCode::Backspace => {
+                    self.query.pop();
+                }
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => self.query.push(c),
+                _ => {}
+            }
+            self.scroll = 0;
+            return true;
+        }
+
+        match key.code {
+            // Esc clears a filter before it closes
+            KeyCode::Esc if !self.query.is_empty() => self.query.clear(),
+            KeyCode::Esc => return false,
+            KeyCode::Char('/') => {
+                self.searching = true;
+                self.query.clear();
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll += 1,
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(PAGE),
+            KeyCode::PageDown => self.scroll += PAGE,
+            KeyCode::Home => self.scroll = 0,
+            KeyCode::End => self.scroll = self.max_scroll.get(),
+            _ if action == Some(Action::Help) => return false,
+            _ => {}
+        }
+        self.scroll = self.scroll.min(self.max_scroll.get());
+        true
+    }
+}
+
+// Entries whose keys, action name or description contain the query
+fn matching<'a>(entries: &'a [(Action, String)], keymap: &Keymap, query: &str) -> Vec<&'a (Action, String)> {
+    let query = query.to_lowercase();
+    entries
+        .iter()
+        .filter(|(action, description)| {
+            query.is_empty()
+                || keymap.label(*action).to_lowercase().contains(&query)
+                || action.name().contains(&query)
+                || description.to_lowercase().contains(&query)
+        })
+        .collect()
+}
+
+// `entries` describes each action, in Action::ALL order
+pub fn render(f: &mut Frame, area: Rect, help: &HelpScreen, keymap: &Keymap, entries: &[(Action, String)]) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Help")
+        .title_alignment(Alignment::Center)
+        .border_style(Style::default().fg(theme::primary()));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1), Constraint::Length(1)])
+        .split(inner);
+
+    let key_style = Style::default().fg(theme::primary()).add_modifier(Modifier::BOLD);
+    let heading_style = Style::default().fg(theme::highlight()).add_modifier(Modifier::BOLD);
+    let shown = matching(entries, keymap, &help.query);
+    let width = shown.iter().map(|(action, _)| keymap.label(*action).chars().count()).max().unwrap_or(0);
+    let mut lines = Vec::new();
+    let mut category = None;
+    for (action, description) in &shown {
+        if category != Some(action.category()) {
+            if category.is_some() {
+                lines.push(Line::from(""));
+            }
+            category = Some(action.category());
+            lines.push(Line::from(Span::styled(action.category(), heading_style)));
+        }
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {:<width$}", keymap.label(*action)), key_style),
+            Span::raw(format!("  {description}")),
+        ]));
+    }
+    if lines.is_empty() {
+        lines.push(Line::from(format!("  Nothing matches '{}'", help.query)));
+    }
+
+    let visible = chunks[0].height as usize;
+    help.max_scroll.set(lines.len().saturating_sub(visible));
+    let scroll = help.scroll.min(help.max_scroll.get());
+    f.render_widget(Paragraph::new(lines).scroll((scroll as u16, 0)), chunks[0]);
+
+    let status_line = if help.searching || !help.query.is_empty() {
+        let mut spans = vec![
+            Span::raw("  Search: "),
+            Span::styled(help.query.as_str(), Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+        ];
+        if help.searching {
+            spans.push(Span::styled("█", Style::default().fg(theme::primary()))); // Cursor
+        }
+        Line::from(spans)
+    } else {
+        Line::from(format!("  {} action(s)", shown.len()))
+    };
+    f.render_widget(Paragraph::new(status_line), chunks[1]);
+
+    let key = |k: &'static str| Span::styled(k, key_style);
+    let keys = if help.searching {
+        Line::from(vec![key("↵"), Span::raw(" - Done | "), key("Esc"), Span::raw(" - Clear")])
+    } else {
+        Line::from(vec![
+            key("↑/↓"),
+            Span::raw(" - Scroll | "),
+            key("PgUp/PgDn"),
+            Span::raw(" - Page | "),
+            key("/"),
+            Span::raw(" - Search | "),
+            key("Esc"),
+            Span::raw(" - Close"),
+        ])
+    };
+    f.render_widget(Paragraph::new(keys).alignment(Alignment::Center), chunks[2]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matching() {
+        let keymap = Keymap::default();
+        let entries: Vec<(Action, String)> = Action::ALL.iter().map(|action| (*action, format!("Does {}", action.name()))).collect();
+        let names = |query: &str| -> Vec<&str> { matching(&entries, &keymap, query).iter().map(|(action, _)| action.name()).collect() };
+        assert_eq!(names("").len(), Action::ALL.len());
+        // By action name or description, ignoring case
+        assert_eq!(names("SNOOZE"), ["snooze"]);
+        // By key: ^z is suspend
+        assert_eq!(names("^z"), ["suspend"]);
+        assert!(names("nothing like this").is_empty());
+    }
+}
@@ -0,0 +1,784 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::{Datelike, Local, Months, TimeZone};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::TimerType;
+use crate::config::HistoryBackend;
+use crate::migrate::{self, Schema};
+#[cfg(feature = "sqlite")]
+use crate::sqlite_store::SqliteStore;
+
+// History is stored as one append-only JSONL file per machine inside the
+// history directory. Machines never write to each other's files, so a
+// synced or git-managed folder never sees conflicting edits; reading merges
+// every file and drops duplicate session ids.
+//
+// Edits never rewrite old lines: an edited session is appended again with a
+// newer `updated_at`, and a deleted one is appended as a `deleted` tombstone.
+// When merging, the newest version of each id wins.
+//
+// Archiving moves old raw lines out of this machine's file into `archive/`
+// (which is never loaded) and replaces them with one summary record per day,
+// kind, task and tag set. Other machines' old sessions are folded into the
+// summaries too; their files stay untouched and an `archived` tombstone in
+// this machine's file hides their lines.
+//
+// The same versions can instead live in an SQLite database in the history
+// directory (history.backend = "sqlite"); both sit behind HistoryStore and
+// everything above it works the same.
+//
+// Screens read and edit the merged sessions through SessionStore, which
+// History implements over either backend and MemoryStore implements for
+// tests.
+//
+// The records' format has a version (see migrate). A JSONL file may start
+// with a {"version": N} line, which older versions skip like any line that
+// isn't a session; without one it's at 1. Other machines' older files are
+// upgraded as they're read, and only this machine's own file is rewritten.
+// SQLite keeps the version in its user_version.
+
+pub const SCHEMA: Schema = Schema {
+    file: "history",
+    version: 1,
+    steps: &[],
+};
+
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+}
+
+fn header(line: &str) -> Option<u32> {
+    serde_json::from_str::<Header>(line).ok().map(|header| header.version)
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SessionRecord {
+    pub id: String,
+    pub kind: TimerType,
+    pub started_at: u64, // Unix seconds
+    pub duration_secs: u64,
+    pub machine: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub updated_at: u64, // Unix seconds of the last edit, 0 if never edited
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub deleted: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub archived: bool, // A tombstone hiding another machine's lines once summarized
+    #[serde(default = "one", skip_serializing_if = "is_one")]
+    pub sessions: u32, // How many sessions an archive summary stands for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ack_secs: Option<u64>, // How long a finished session rang before a key acknowledged it
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub extended_secs: u64, // Snoozed time, already included in duration_secs
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub away_secs: u64, // Time work sat paused while the terminal was in the background
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app: Option<String>, // Application in focus for most of a work session
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub manual: bool, // Logged by hand for a session done away from the timer
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pauses: Vec<PauseTotal>, // Pauses during a work session, by reason
+}
+
+// Pauses sharing a reason ("" when none was given), counted and added up
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PauseTotal {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub reason: String,
+    pub count: u32,
+    pub secs: u64,
+}
+
+pub fn add_pauses(totals: &mut Vec<PauseTotal>, reason: &str, count: u32, secs: u64) {
+    match totals.iter_mut().find(|total| total.reason == reason) {
+        Some(total) => {
+            total.count += count;
+            total.secs += secs;
+        }
+        None => totals.push(PauseTotal {
+            reason: reason.to_string(),
+            count,
+            secs,
+        }),
+    }
+}
+
+const SUMMARY_PREFIX: &str = "summary-";
+
+// A single work session with nothing recorded yet
+impl Default for SessionRecord {
+    fn default() -> Self {
+        SessionRecord {
+            id: String::new(),
+            kind: TimerType::Work,
+            started_at: 0,
+            duration_secs: 0,
+            machine: String::new(),
+            task: None,
+            tags: Vec::new(),
+            updated_at: 0,
+            deleted: false,
+            archived: false,
+            sessions: 1,
+            ack_secs: None,
+            extended_secs: 0,
+            away_secs: 0,
+            app: None,
+            manual: false,
+            pauses: Vec::new(),
+        }
+    }
+}
+
+impl SessionRecord {
+    pub fn is_summary(&self) -> bool {
+        self.id.starts_with(SUMMARY_PREFIX)
+    }
+}
+
+fn one() -> u32 {
+    1
+}
+
+fn is_one(value: &u32) -> bool {
+    *value == 1
+}
+
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+// Where the raw session versions are kept. History merges them into the
+// live sessions, so a store only has to keep what it is given, in order
+pub trait HistoryStore {
+    // Every version written, tombstones included, oldest first
+    fn load(&self) -> io::Result<Vec<SessionRecord>>;
+    fn append(&mut self, record: &SessionRecord) -> io::Result<()>;
+    // Many at once, for migrating; stores with transactions do better
+    fn append_all(&mut self, records: &[SessionRecord]) -> io::Result<()> {
+        records.iter().try_for_each(|record| self.append(record))
+    }
+    // Takes every version of the sessions in `ids` out of the live history
+    fn archive(&mut self, ids: &HashSet<&str>) -> io::Result<()>;
+}
+
+pub const NO_SQLITE: &str = "this build has no SQLite support (build with --features sqlite)";
+
+pub fn open_store(dir: &Path, backend: HistoryBackend) -> io::Result<Box<dyn HistoryStore>> {
+    match backend {
+        HistoryBackend::Jsonl => {
+            let store = JsonlStore {
+                dir: dir.to_path_buf(),
+                machine: machine_name(),
+            };
+            upgrade_file(&store.own_file())?;
+            Ok(Box::new(store))
+        }
+        #[cfg(feature = "sqlite")]
+        HistoryBackend::Sqlite => Ok(Box::new(SqliteStore::open(&dir.join("history.sqlite3"))?)),
+        #[cfg(not(feature = "sqlite"))]
+        HistoryBackend::Sqlite => Err(io::Error::other(NO_SQLITE)),
+    }
+}
+
+// The JSONL files described at the top
+struct JsonlStore {
+    dir: PathBuf,
+    machine: String,
+}
+
+impl JsonlStore {
+    fn files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = fs::read_dir(&self.dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok().map(|e| e.path()))
+                    .filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext == "jsonl"))
+                    .collect()
+            })
+            .unwrap_or_default();
+        files.sort();
+        files
+    }
+
+    fn own_file(&self) -> PathBuf {
+        self.dir.join(format!("{}.jsonl", self.machine))
+    }
+}
+
+impl HistoryStore for JsonlStore {
+    // A file that can't be read is skipped like a bad line
+    fn load(&self) -> io::Result<Vec<SessionRecord>> {
+        Ok(self.files().iter().flat_map(|file| read_records(file).unwrap_or_default()).collect())
+    }
+
+    fn append(&mut self, record: &SessionRecord) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let mut file = OpenOptions::new().create(true).append(true).open(self.own_file())?;
+        writeln!(file, "{}", serde_json::to_string(record)?)
+    }
+
+    fn append_all(&mut self, records: &[SessionRecord]) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let mut file = BufWriter::new(OpenOptions::new().create(true).append(true).open(self.own_file())?);
+        for record in records {
+            writeln!(file, "{}", serde_json::to_string(record)?)?;
+        }
+        file.flush()
+    }
+
+    // Only this machine's own lines move; other files are never written, so
+    // their sessions are hidden behind a tombstone instead
+    fn archive(&mut self, ids: &HashSet<&str>) -> io::Result<()> {
+        let own = self.own_file();
+        if let Ok(text) = fs::read_to_string(&own) {
+            let (moved, kept): (Vec<&str>, Vec<&str>) = text
+                .lines()
+                .partition(|line| serde_json::from_str::<SessionRecord>(line).is_ok_and(|r| ids.contains(r.id.as_str()) && !r.archived));
+            if !moved.is_empty() {
+                let archive_dir = self.dir.join("archive");
+                fs::create_dir_all(&archive_dir)?;
+                let mut archived = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(archive_dir.join(format!("{}.jsonl", self.machine)))?;
+                for line in &moved {
+                    writeln!(archived, "{line}")?;
+                }
+                archived.sync_all()?;
+                migrate::write_atomic(&own, &kept.iter().map(|line| format!("{line}\n")).collect::<String>())?;
+            }
+        }
+
+        // Whatever is left of these sessions lives in other machines' files
+        let left = merge_records(self.load()?.into_iter().filter(|r| ids.contains(r.id.as_str())).collect());
+        let tombstones: Vec<SessionRecord> = left
+            .into_iter()
+            .filter(|r| !r.archived)
+            .map(|r| SessionRecord {
+                deleted: true,
+                archived: true,
+                updated_at: now_secs().max(r.updated_at + 1),
+                ..r
+            })
+            .collect();
+        self.append_all(&tombstones)
+    }
+}
+
+// Work sessions and focused time over a range
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Totals {
+    pub pomodoros: u64, // Archive summaries count for every session in them
+    pub focus_secs: u64,
+}
+
+// The live sessions, newest version of each only, as the stats and history
+// screens see them
+pub trait SessionStore {
+    // Oldest first
+    fn records(&self) -> &[SessionRecord];
+    fn append(&mut self, record: SessionRecord) -> io::Result<()>;
+    // Store a new version of an existing session (an edit, or an undo back
+    // to an older version)
+    fn update(&mut self, record: SessionRecord) -> io::Result<()>;
+    fn delete(&mut self, id: &str) -> io::Result<()>;
+
+    fn get(&self, id: &str) -> Option<&SessionRecord> {
+        self.records().iter().find(|r| r.id == id)
+    }
+
+    // Sessions that started in [from, to), in Unix seconds
+    fn range(&self, from: u64, to: u64) -> &[SessionRecord] {
+        let records = self.records();
+        let start = records.partition_point(|r| r.started_at < from);
+        let end = records.partition_point(|r| r.started_at < to).max(start);
+        &records[start..end]
+    }
+
+    fn aggregate(&self, from: u64, to: u64) -> Totals {
+        self.range(from, to)
+            .iter()
+            .filter(|r| r.kind == TimerType::Work)
+            .fold(Totals::default(), |totals, r| Totals {
+                pomodoros: totals.pomodoros + r.sessions as u64,
+                focus_secs: totals.focus_secs + r.duration_secs,
+            })
+    }
+}
+
+pub struct History {
+    dir: PathBuf,
+    machine: String,
+    store: Box<dyn HistoryStore>,
+    records: Vec<SessionRecord>,    // Live sessions, oldest first
+    versions: HashMap<String, u64>, // Latest updated_at per id, tombstones included
+}
+
+pub fn now_secs() -> u64 {
+    unix_secs(SystemTime::now())
+}
+
+// Started on the local calendar day
+fn is_today(started_at: u64) -> bool {
+    Local
+        .timestamp_opt(started_at as i64, 0)
+        .single()
+        .is_some_and(|start| start.date_naive() == Local::now().date_naive())
+}
+
+pub fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+// Start of the local month `months` months ago; sessions before it get archived
+pub fn archive_cutoff(months: u32) -> u64 {
+    let today = Local::now().date_naive();
+    let month_start = today.with_day(1).unwrap_or(today);
+    month_start
+        .checked_sub_months(Months::new(months))
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .and_then(|d| Local.from_local_datetime(&d).earliest())
+        .map(|t| t.timestamp().max(0) as u64)
+        .unwrap_or(0)
+}
+
+fn machine_name() -> String {
+    let raw = std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .or_else(|_| fs::read_to_string("/etc/hostname"))
+        .unwrap_or_default();
+    let name: String = raw
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    if name.is_empty() { "local".to_string() } else { name }
+}
+
+impl History {
+    pub fn open(dir: PathBuf, backend: HistoryBackend) -> io::Result<Self> {
+        let store = open_store(&dir, backend)?;
+        let mut history = History {
+            dir,
+            machine: machine_name(),
+            store,
+            records: Vec::new(),
+            versions: HashMap::new(),
+        };
+        history.reload();
+        Ok(history)
+    }
+
+    // Re-read the store, picking up sessions synced from other machines
+    // since startup. An unreadable store reads as empty, like a missing file
+    pub fn reload(&mut self) {
+        let merged = merge_records(self.store.load().unwrap_or_default());
+        self.versions = merged.iter().map(|r| (r.id.clone(), r.updated_at)).collect();
+        self.records = merged.into_iter().filter(|r| !r.deleted).collect();
+    }
+
+    pub fn machine(&self) -> &str {
+        &self.machine
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn new_record(&self, kind: TimerType, started_at: u64, duration_secs: u64) -> SessionRecord {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+        SessionRecord {
+            id: format!("{nanos:x}-{}", self.machine),
+            kind,
+            started_at,
+            duration_secs,
+            machine: self.machine.clone(),
+            ..SessionRecord::default()
+        }
+    }
+
+    // Today's work sessions, oldest first
+    pub fn work_today(&self) -> impl DoubleEndedIterator<Item = &SessionRecord> {
+        self.records
+            .iter()
+            .filter(|r| r.kind == TimerType::Work && !r.is_summary() && is_today(r.started_at))
+    }
+
+    // Compact every session that started before `before` into per-day
+    // summaries. Returns how many sessions were archived
+    pub fn archive(&mut self, before: u64) -> io::Result<usize> {
+        let latest = merge_records(self.store.load()?);
+
+        // Ids whose newest version is old. Another machine's archived
+        // tombstones count, so this machine's own lines behind them move too
+        let old_ids: HashSet<&str> = latest
+            .iter()
+            .filter(|r| r.started_at < before && !r.is_summary())
+            .map(|r| r.id.as_str())
+            .collect();
+        if old_ids.is_empty() {
+            return Ok(0);
+        }
+        let to_summarize: Vec<&SessionRecord> = latest.iter().filter(|r| old_ids.contains(r.id.as_str()) && !r.deleted).collect();
+
+        self.store.archive(&old_ids)?;
+
+        let archived = to_summarize.iter().map(|r| r.sessions as usize).sum();
+        // Sessions landing in an existing summary's group are folded into it
+        let mut groups: Vec<SessionRecord> = latest.iter().filter(|r| r.is_summary() && !r.deleted).cloned().collect();
+        let mut changed = HashSet::new();
+        for record in to_summarize {
+            let id = summary_id(record);
+            match groups.iter_mut().find(|g| g.id == id) {
+                Some(group) => {
+                    group.started_at = group.started_at.min(record.started_at);
+                    group.duration_secs += record.duration_secs;
+                    group.sessions += record.sessions;
+                    group.extended_secs += record.extended_secs;
+                    group.away_secs += record.away_secs;
+                    for pause in &record.pauses {
+                        add_pauses(&mut group.pauses, &pause.reason, pause.count, pause.secs);
+                    }
+                }
+                None => groups.push(SessionRecord {
+                    id: id.clone(),
+                    machine: "archive".to_string(),
+                    ack_secs: None,
+                    manual: false,
+                    ..record.clone()
+                }),
+            }
+            changed.insert(id);
+        }
+
+        self.reload();
+        for group in groups.into_iter().filter(|g| changed.contains(&g.id)) {
+            self.update(group)?;
+        }
+        Ok(archived)
+    }
+
+    // Import sessions from a history file that lives outside the history
+    // directory (e.g. copied from another machine), keeping only sessions
+    // and edits newer than what we already have
+    pub fn import(&mut self, path: &Path) -> io::Result<usize> {
+        let mut imported = 0;
+        for record in merge_records(read_records(path)?) {
+            let newer = match self.versions.get(&record.id) {
+                Some(&version) => record.updated_at > version,
+                None => true,
+            };
+            if newer {
+                self.append(record)?;
+                imported += 1;
+            }
+        }
+        Ok(imported)
+    }
+}
+
+impl SessionStore for History {
+    fn records(&self) -> &[SessionRecord] {
+        &self.records
+    }
+
+    fn append(&mut self, record: SessionRecord) -> io::Result<()> {
+        self.store.append(&record)?;
+        self.versions.insert(record.id.clone(), record.updated_at);
+        self.records.retain(|r| r.id != record.id);
+        if !record.deleted {
+            let pos = self.records.partition_point(|r| r.started_at <= record.started_at);
+            self.records.insert(pos, record);
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, mut record: SessionRecord) -> io::Result<()> {
+        let previous = self.versions.get(&record.id).copied().unwrap_or(0);
+        record.updated_at = now_secs().max(previous + 1);
+        self.append(record)
+    }
+
+    fn delete(&mut self, id: &str) -> io::Result<()> {
+        match self.get(id).cloned() {
+            Some(mut record) => {
+                record.deleted = true;
+                self.update(record)
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+// Stable across machines and runs, so archiving the same day twice (or on
+// two machines) yields the same summary id
+fn summary_id(record: &SessionRecord) -> String {
+    let day = Local
+        .timestamp_opt(record.started_at as i64, 0)
+        .single()
+        .map(|t| t.format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+    let key = format!("{:?}|{}|{}", record.kind, record.task.as_deref().unwrap_or(""), record.tags.join(","));
+    // FNV-1a: unlike DefaultHasher its output never changes between Rust releases
+    let hash = key.bytes().fold(0xcbf29ce484222325u64, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3));
+    format!("{SUMMARY_PREFIX}{day}-{hash:016x}")
+}
+
+// Lines that fail to parse (half-written lines, leftover merge markers) are
+// skipped rather than failing the whole load. A file from a newer version is
+// read as far as it parses
+fn read_records(path: &Path) -> io::Result<Vec<SessionRecord>> {
+    let mut lines = BufReader::new(File::open(path)?).lines().map_while(Result::ok).peekable();
+    let version = lines.peek().and_then(|line| header(line)).unwrap_or(1);
+    if version >= SCHEMA.version {
+        return Ok(lines.filter_map(|line| serde_json::from_str(&line).ok()).collect());
+    }
+    SCHEMA.check(version).map_err(io::Error::other)?;
+    Ok(lines
+        .filter_map(|line| {
+            let mut value = serde_json::from_str(&line).ok()?;
+            SCHEMA.upgrade(version, &mut value).ok()?;
+            serde_json::from_value(value).ok()
+        })
+        .collect())
+}
+
+// Rewrites this machine's file in the current format, keeping a copy, before
+// anything is appended to it. Appending to a newer one would mix formats
+fn upgrade_file(path: &Path) -> io::Result<()> {
+    let Ok(text) = fs::read_to_string(path) else {
+        return Ok(());
+    };
+    let version = text.lines().next().and_then(header);
+    let found = version.unwrap_or(1);
+    if found == SCHEMA.version {
+        return Ok(());
+    }
+    SCHEMA.check(found).map_err(io::Error::other)?;
+    let mut upgraded = format!("{}\n", json!({ "version": SCHEMA.version }));
+    for line in text.lines().skip(version.is_some() as usize) {
+        // Lines that aren't JSON are kept as they are, like elsewhere
+        let line = match serde_json::from_str::<Value>(line) {
+            Ok(mut value) => {
+                SCHEMA.upgrade(found, &mut value).map_err(io::Error::other)?;
+                value.to_string()
+            }
+            Err(_) => line.to_string(),
+        };
+        upgraded.push_str(&line);
+        upgraded.push('\n');
+    }
+    migrate::backup(path, found)?;
+    migrate::write_atomic(path, &upgraded)
+}
+
+// Keep the newest version of every id (later lines win ties), tombstones included
+fn merge_records(records: Vec<SessionRecord>) -> Vec<SessionRecord> {
+    let mut latest: HashMap<String, SessionRecord> = HashMap::new();
+    for record in records {
+        match latest.get(&record.id) {
+            Some(existing) if existing.updated_at > record.updated_at => {}
+            _ => {
+                latest.insert(record.id.clone(), record);
+            }
+        }
+    }
+    let mut merged: Vec<SessionRecord> = latest.into_values().collect();
+    merged.sort_by(|a, b| a.started_at.cmp(&b.started_at).then_with(|| a.id.cmp(&b.id)));
+    merged
+}
+
+// Sessions kept in memory only, for testing screens without files
+#[cfg(test)]
+#[derive(Default)]
+pub struct MemoryStore {
+    records: Vec<SessionRecord>,
+}
+
+#[cfg(test)]
+impl SessionStore for MemoryStore {
+    fn records(&self) -> &[SessionRecord] {
+        &self.records
+    }
+
+    fn append(&mut self, record: SessionRecord) -> io::Result<()> {
+        self.records.retain(|r| r.id != record.id);
+        let pos = self.records.partition_point(|r| r.started_at <= record.started_at);
+        self.records.insert(pos, record);
+        Ok(())
+    }
+
+    fn update(&mut self, record: SessionRecord) -> io::Result<()> {
+        self.append(record)
+    }
+
+    fn delete(&mut self, id: &str) -> io::Result<()> {
+        self.records.retain(|r| r.id != id);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: &str, started_at: u64) -> SessionRecord {
+        SessionRecord {
+            id: id.to_string(),
+            started_at,
+            duration_secs: 1500,
+            ..SessionRecord::default()
+        }
+    }
+
+    #[test]
+    fn test_merge_records_dedupes_by_id() {
+        let merged = merge_records(vec![record("b", 20), record("a", 10), record("b", 20), record("c", 5)]);
+        let ids: Vec<&str> = merged.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["c", "a", "b"]);
+    }
+
+    #[test]
+    fn test_merge_records_keeps_newest_version() {
+        let mut edited = record("a", 10);
+        edited.duration_secs = 60;
+        edited.updated_at = 100;
+        let mut deleted = record("b", 20);
+        deleted.deleted = true;
+        deleted.updated_at = 50;
+
+        // Order of files must not matter: the newest version wins either way
+        let merged = merge_records(vec![edited.clone(), record("a", 10), record("b", 20), deleted.clone()]);
+        assert_eq!(merged, vec![edited, deleted]);
+    }
+
+    #[test]
+    fn test_range_and_aggregate() {
+        let mut store = MemoryStore::default();
+        for (id, started_at) in [("c", 300), ("a", 100), ("b", 200)] {
+            store.append(record(id, started_at)).unwrap();
+        }
+        let mut rest = record("r", 250);
+        rest.kind = TimerType::Break;
+        store.append(rest).unwrap();
+
+        let ids: Vec<&str> = store.range(150, 300).iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["b", "r"]);
+        assert!(store.range(400, 500).is_empty());
+        assert_eq!(
+            store.aggregate(0, 1000),
+            Totals {
+                pomodoros: 3,
+                focus_secs: 4500
+            }
+        );
+
+        store.delete("b").unwrap();
+        assert_eq!(store.aggregate(150, 300).pomodoros, 0);
+    }
+
+    #[test]
+    fn test_version_header() {
+        let dir = std::env::temp_dir().join(format!("cyber-tomato-version-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let line = serde_json::to_string(&record("a", 10)).unwrap();
+        let other = dir.join("other-machine.jsonl");
+        fs::write(&other, format!("{{\"version\":1}}\n{line}\n")).unwrap();
+        assert_eq!(read_records(&other).unwrap(), vec![record("a", 10)]);
+
+        // Appending to this machine's file from a newer version would mix formats
+        fs::write(
+            dir.join(format!("{}.jsonl", machine_name())),
+            format!("{{\"version\":{}}}\n", SCHEMA.version + 1),
+        )
+        .unwrap();
+        assert!(open_store(&dir, HistoryBackend::Jsonl).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_archive_compacts_old_sessions() {
+        let dir = std::env::temp_dir().join(format!("cyber-tomato-archive-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut history = History::open(dir.clone(), HistoryBackend::Jsonl).unwrap();
+
+        let day = Local.with_ymd_and_hms(2024, 1, 10, 9, 0, 0).unwrap().timestamp() as u64;
+        let old = |history: &History, offset: u64, task: &str| {
+            let mut r = history.new_record(TimerType::Work, day + offset, 1500);
+            r.id = format!("old-{offset}");
+            r.task = Some(task.to_string());
+            r
+        };
+        for record in [old(&history, 0, "a"), old(&history, 3600, "a"), old(&history, 7200, "b")] {
+            history.append(record).unwrap();
+        }
+        let recent = history.new_record(TimerType::Work, day + 365 * 86400, 1500);
+        history.append(recent.clone()).unwrap();
+
+        let cutoff = day + 86400;
+        assert_eq!(history.archive(cutoff).unwrap(), 3);
+        assert_eq!(history.archive(cutoff).unwrap(), 0);
+
+        let summaries: Vec<&SessionRecord> = history.records().iter().filter(|r| r.is_summary()).collect();
+        assert_eq!(summaries.len(), 2);
+        let a = summaries.iter().find(|r| r.task.as_deref() == Some("a")).unwrap();
+        assert_eq!((a.sessions, a.duration_secs), (2, 3000));
+        assert!(history.get(&recent.id).is_some());
+
+        // A late-synced session for an archived day folds into its summary
+        history.append(old(&history, 1800, "a")).unwrap();
+        assert_eq!(history.archive(cutoff).unwrap(), 1);
+        let a = history.records().iter().find(|r| r.is_summary() && r.task.as_deref() == Some("a")).unwrap();
+        assert_eq!(a.sessions, 3);
+
+        let archived = fs::read_to_string(dir.join("archive").join(format!("{}.jsonl", history.machine))).unwrap();
+        assert_eq!(archived.lines().count(), 4);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_archive_leaves_other_machines_files() {
+        let dir = std::env::temp_dir().join(format!("cyber-tomato-archive-others-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let day = Local.with_ymd_and_hms(2024, 1, 10, 9, 0, 0).unwrap().timestamp() as u64;
+        let other = dir.join("other-machine.jsonl");
+        let synced = format!("{}\n", serde_json::to_string(&record("theirs", day)).unwrap());
+        fs::write(&other, &synced).unwrap();
+
+        let mut history = History::open(dir.clone(), HistoryBackend::Jsonl).unwrap();
+        history.append(record("ours", day + 60)).unwrap();
+        assert_eq!(history.archive(day + 86400).unwrap(), 2);
+        assert_eq!(fs::read_to_string(&other).unwrap(), synced);
+        assert!(history.get("theirs").is_none() && history.get("ours").is_none());
+        let summary = history.records().iter().find(|r| r.is_summary()).unwrap();
+        assert_eq!(summary.sessions, 2);
+
+        // The tombstone stays put and isn't written twice
+        assert_eq!(history.archive(day + 86400).unwrap(), 0);
+        history.reload();
+        assert_eq!(history.records().len(), 1);
+        let own = fs::read_to_string(dir.join(format!("{}.jsonl", history.machine))).unwrap();
+        assert_eq!(own.matches("\"archived\":true").count(), 1);
+        let archived = fs::read_to_string(dir.join("archive").join(format!("{}.jsonl", history.machine))).unwrap();
+        assert_eq!(archived.lines().count(), 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,357 @@
+use chrono::{Local, TimeZone};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Row, Table},
+};
+
+use crate::history::{SessionRecord, SessionStore};
+use crate::{TimerType, parse_label, theme};
+
+const PAGE: usize = 10;
+const UNDO_LIMIT: usize = 50;
+
+enum EditField {
+    Label,
+    Duration,
+}
+
+pub struct HistoryBrowser {
+    selected: usize, // Index into the newest-first list
+    editing: Option<EditField>,
+    input: String,
+    undo: Vec<SessionRecord>, // Versions to restore, most recent change last
+    message: Option<String>,
+}
+
+impl HistoryBrowser {
+    pub fn new() -> Self {
+        HistoryBrowser {
+            selected: 0,
+            editing: None,
+            input: String::new(),
+            undo: Vec::new(),
+            message: None,
+        }
+    }
+
+    pub fn open(&mut self) {
+        self.selected = 0;
+        self.editing = None;
+        self.message = None;
+    }
+
+    fn selected_record<'a>(&self, history: &'a dyn SessionStore) -> Option<&'a SessionRecord> {
+        history.records().iter().rev().nth(self.selected)
+    }
+
+    // Returns false when the browser should close
+    pub fn handle_key(&mut self, key: KeyEvent, history: &mut dyn SessionStore) -> bool {
+        if self.editing.is_some() {
+            self.handle_edit_key(key, history);
+            return true;
+        }
+
+        let count = history.records().len();
+        self.message = None;
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('h') => return false,
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.selected += 1,
+            KeyCode::PageUp => self.selected = self.selected.saturating_sub(PAGE),
+            KeyCode::PageDown => self.selected += PAGE,
+            KeyCode::Home => self.selected = 0,
+            KeyCode::End => self.selected = count.saturating_sub(1),
+            KeyCode::Char('e') => {
+                if let Some(record) = self.selected_record(history) {
+                    let mut parts: Vec<String> = record.task.iter().cloned().collect();
+                    parts.extend(record.tags.iter().map(|tag| format!("#{tag}")));
+                    self.input = parts.join(" ");
+                    self.editing = Some(EditField::Label);
+                }
+            }
+            KeyCode::Char('d') => {
+                if let Some(record) = self.selected_record(history) {
+                    self.input = format!("{}:{:02}", record.duration_secs / 60, record.duration_secs % 60);
+                    self.editing = Some(EditField::Duration);
+                }
+            }
+            KeyCode::Delete | KeyCode::Char('D') => {
+                if let Some(record) = self.selected_record(history).cloned() {
+                    self.apply(history, record.clone(), |h| h.delete(&record.id));
+                }
+            }
+            KeyCode::Char('u') => match self.undo.pop() {
+                Some(previous) => match history.update(previous) {
+                    Ok(()) => self.message = Some("Undone".to_string()),
+                    Err(e) => self.message = Some(format!("Undo failed: {e}")),
+                },
+                None => self.message = Some("Nothing to undo".to_string()),
+            },
+            _ => {}
+        }
+        self.selected = self.selected.min(history.records().len().saturating_sub(1));
+        true
+    }
+
+    fn handle_edit_key(&mut self, key: KeyEvent, history: &mut dyn SessionStore) {
+        match key.code {
+            KeyCode::Esc => self.editing = None,
+            KeyCode::Enter => {
+                let Some(mut record) = self.selected_record(history).cloned() else {
+                    self.editing = None;
+                    return;
+                };
+                let previous = record.clone();
+                match self.editing {
+                    Some(EditField::Label) => {
+                        (record.task, record.tags) = parse_label(&self.input);
+                    }
+                    Some(EditField::Duration) => match parse_duration(&self.input) {
+                        Some(secs) => record.duration_secs = secs,
+                        None => {
+                            self.message = Some("Use minutes (25) or minutes:seconds (24:30)".to_string());
+                            return;
+                        }
+                    },
+                    None => {}
+                }
+                self.editing = None;
+                self.apply(history, previous, |h| h.update(record));
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => self.input.push(c),
+            _ => {}
+        }
+    }
+
+    fn apply(&mut self, history: &mut dyn SessionStore, previous: SessionRecord, change: impl FnOnce(&mut dyn SessionStore) -> std::io::Result<()>) {
+        match change(history) {
+            Ok(()) => {
+                self.undo.push(previous);
+                if self.undo.len() > UNDO_LIMIT {
+                    self.undo.remove(0);
+                }
+            }
+            Err(e) => self.message = Some(format!("Could not save: {e}")),
+        }
+    }
+}
+
+// "25" -> 25 minutes, "24:30" -> 24 minutes 30 seconds
+fn parse_duration(input: &str) -> Option<u64> {
+    let secs = match input.trim().split_once(':') {
+        Some((mins, secs)) => {
+            let secs: u64 = secs.trim().parse().ok().filter(|s| *s < 60)?;
+            mins.trim().parse::<u64>().ok()?.checked_mul(60)?.checked_add(secs)?
+        }
+        None => input.trim().parse::<u64>().ok()?.checked_mul(60)?,
+    };
+    (secs > 0).then_some(secs)
+}
+
+pub fn render(f: &mut Frame, area: Rect, history: &dyn SessionStore, browser: &HistoryBrowser) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("History")
+        .title_alignment(Alignment::Center)
+        .border_style(Style::default().fg(theme::primary()));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(1), Constraint::Length(1)])
+        .split(inner);
+
+    let records: Vec<&SessionRecord> = history.records().iter().rev().collect();
+    let visible = chunks[0].height.saturating_sub(1) as usize; // minus header
+    let offset = (browser.selected + 1).saturating_sub(visible);
+
+    let rows: Vec<Row> = records
+        .iter()
+        .enumerate()
+        .skip(offset)
+        .take(visible)
+        .map(|(i, record)| {
+            let when = Local
+                .timestamp_opt(record.started_at as i64, 0)
+                .single()
+                .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_default();
+            let kind = match record.kind {
+                TimerType::Work => "Work",
+                TimerType::Break => "Break",
+            };
+            // Archive summaries stand for several sessions
+            let kind = if record.is_summary() {
+                format!("{kind}×{}", record.sessions)
+            } else {
+                kind.to_string()
+            };
+            let duration = format!("{:>3}:{:02}", record.duration_secs / 60, record.duration_secs % 60);
+            let tags = record.tags.iter().map(|t| format!("#{t}")).collect::<Vec<_>>().join(" ");
+            let app = record.app.clone().unwrap_or_default();
+            let row = Row::new(vec![when, kind, duration, record.task.clone().unwrap_or_default(), tags, app]);
+            if i == browser.selected {
+                row.style(Style::default().fg(Color::Black).bg(theme::primary()))
+            } else {
+                row
+            }
+        })
+        .collect();
+
+    if records.is_empty() {
+        f.render_widget(Paragraph::new("  No sessions recorded yet"), chunks[0]);
+    } else {
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(17),
+                Constraint::Length(9),
+                Constraint::Length(7),
+                Constraint::Percentage(40),
+                Constraint::Percentage(35),
+                Constraint::Percentage(25),
+            ],
+        )
+        .header(Row::new(vec!["Started", "Type", "Length", "Task", "Tags", "App"]).style(Style::default().fg(theme::highlight()).add_modifier(Modifier::BOLD)));
+        f.render_widget(table, chunks[0]);
+    }
+
+    let prompt = match browser.editing {
+        Some(EditField::Label) => Some("Label (task #tag)"),
+        Some(EditField::Duration) => Some("Length (mm or mm:ss)"),
+        None => None,
+    };
+    let status_line = match (prompt, &browser.message) {
+        (Some(prompt), message) => {
+            let mut spans = vec![
+                Span::raw(format!("  {prompt}: ")),
+                Span::styled(browser.input.as_str(), Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+                Span::styled("█", Style::default().fg(theme::primary())), // Cursor
+            ];
+            if let Some(message) = message {
+                spans.push(Span::styled(format!("  {message}"), Style::default().fg(Color::Yellow)));
+            }
+            Line::from(spans)
+        }
+        (None, Some(message)) => Line::from(Span::styled(format!("  {message}"), Style::default().fg(theme::highlight()))),
+        (None, None) => Line::from(format!("  {} session(s)", records.len())),
+    };
+    f.render_widget(Paragraph::new(status_line), chunks[1]);
+
+    let key = |k: &'static str| Span::styled(k, Style::default().fg(theme::primary()).add_modifier(Modifier::BOLD));
+    let help = if browser.editing.is_some() {
+        Line::from(vec![key("↵"), Span::raw(" - Save | "), key("Esc"), Span::raw(" - Cancel")])
+    } else {
+        Line::from(vec![
+            key("↑/↓"),
+            Span::raw(" - Move | "),
+            key("e"),
+            Span::raw(" - Label | "),
+            key("d"),
+            Span::raw(" - Length | "),
+            key("D"),
+            Span::raw(" - Delete | "),
+            key("u"),
+            Span::raw(" - Undo | "),
+            key("h/Esc"),
+            Span::raw(" - Close"),
+        ])
+    };
+    f.render_widget(Paragraph::new(help).alignment(Alignment::Center), chunks[2]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::MemoryStore;
+
+    fn record(id: &str, started_at: u64) -> SessionRecord {
+        SessionRecord {
+            id: id.to_string(),
+            started_at,
+            duration_secs: 1500,
+            ..SessionRecord::default()
+        }
+    }
+
+    fn press(browser: &mut HistoryBrowser, history: &mut MemoryStore, keys: &[KeyCode]) -> bool {
+        keys.iter().all(|code| browser.handle_key(KeyEvent::new(*code, KeyModifiers::NONE), history))
+    }
+
+    fn chars(text: &str) -> Vec<KeyCode> {
+        text.chars().map(KeyCode::Char).collect()
+    }
+
+    #[test]
+    fn test_edit_delete_undo() {
+        let mut history = MemoryStore::default();
+        for (id, at) in [("a", 100), ("b", 200), ("c", 300)] {
+            history.append(record(id, at)).unwrap();
+        }
+        let mut browser = HistoryBrowser::new();
+
+        // Newest first: moving down once selects "b"
+        press(&mut browser, &mut history, &[KeyCode::Char('j'), KeyCode::Char('e')]);
+        press(&mut browser, &mut history, &chars("Docs #writing"));
+        press(&mut browser, &mut history, &[KeyCode::Enter]);
+        let b = history.get("b").unwrap();
+        assert_eq!((b.task.as_deref(), b.tags.as_slice()), (Some("Docs"), ["writing".to_string()].as_slice()));
+
+        // A length that doesn't parse keeps the input open and the record as it was
+        press(&mut browser, &mut history, &[KeyCode::Char('d')]);
+        assert_eq!(browser.input, "25:00");
+        press(&mut browser, &mut history, &[KeyCode::Char('x'), KeyCode::Enter]);
+        assert!(browser.editing.is_some() && browser.message.is_some());
+        press(&mut browser, &mut history, &[KeyCode::Backspace; 6]);
+        press(&mut browser, &mut history, &chars("24:30"));
+        press(&mut browser, &mut history, &[KeyCode::Enter]);
+        assert_eq!(history.get("b").unwrap().duration_secs, 24 * 60 + 30);
+
+        // Deleting the oldest moves the selection back onto the list
+        press(&mut browser, &mut history, &[KeyCode::End, KeyCode::Char('D')]);
+        assert!(history.get("a").is_none());
+        assert_eq!(browser.selected, 1);
+
+        // Undo restores the delete, then the length, then the label
+        press(&mut browser, &mut history, &[KeyCode::Char('u')]);
+        assert!(history.get("a").is_some());
+        press(&mut browser, &mut history, &[KeyCode::Char('u')]);
+        assert_eq!(history.get("b").unwrap().duration_secs, 1500);
+        press(&mut browser, &mut history, &[KeyCode::Char('u')]);
+        assert_eq!(history.get("b").unwrap().task, None);
+        press(&mut browser, &mut history, &[KeyCode::Char('u')]);
+        assert_eq!(browser.message.as_deref(), Some("Nothing to undo"));
+
+        assert!(!press(&mut browser, &mut history, &[KeyCode::Esc]));
+    }
+
+    #[test]
+    fn test_empty_history() {
+        let mut history = MemoryStore::default();
+        let mut browser = HistoryBrowser::new();
+        press(
+            &mut browser,
+            &mut history,
+            &[KeyCode::Down, KeyCode::PageDown, KeyCode::Char('e'), KeyCode::Char('D')],
+        );
+        assert_eq!(browser.selected, 0);
+        assert!(browser.editing.is_none());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("25"), Some(1500));
+        assert_eq!(parse_duration(" 24:30 "), Some(1470));
+        assert_eq!(parse_duration("0"), None);
+        assert_eq!(parse_duration("24:60"), None);
+        assert_eq!(parse_duration("soon"), None);
+    }
+}
@@ -0,0 +1,193 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use crate::migrate;
+
+// Distracting sites are blocked by pointing them at 0.0.0.0 inside a marked
+// block of the hosts file. The block is only ever added and removed as a
+// whole, so the rest of the file is left exactly as it was
+const BEGIN: &str = "# BEGIN cyber-tomato block";
+const END: &str = "# END cyber-tomato block";
+
+#[cfg(windows)]
+fn path() -> PathBuf {
+    let root = env::var_os("SystemRoot").unwrap_or_else(|| "C:\\Windows".into());
+    PathBuf::from(root).join("System32\\drivers\\etc\\hosts")
+}
+
+#[cfg(not(windows))]
+fn path() -> PathBuf {
+    PathBuf::from("/etc/hosts")
+}
+
+// A plain host name: dot-separated labels of letters, digits and hyphens.
+// The hosts file is written as root, so anything else, a newline especially,
+// could slip in lines of its own
+pub fn check_domain(domain: &str) -> Result<(), String> {
+    let label = |label: &str| !label.is_empty() && label.len() <= 63 && label.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-');
+    if domain.len() <= 253 && domain.split('.').all(label) {
+        Ok(())
+    } else {
+        Err(format!("{domain:?} is not a domain name"))
+    }
+}
+
+// `text` without the marked block. A BEGIN with no END after it isn't a
+// block, and everything from it on is kept as it is
+fn strip(text: &str) -> String {
+    if !text.lines().any(|line| line.trim() == BEGIN) {
+        return text.to_string();
+    }
+    let mut kept = String::new();
+    let mut block: Option<String> = None; // Lines since a BEGIN, held until its END
+    for line in text.lines() {
+        match line.trim() {
+            BEGIN => {
+                kept.push_str(&block.take().unwrap_or_default());
+                block = Some(format!("{line}\n"));
+            }
+            END if block.is_some() => block = None,
+            _ => {
+                let lines = block.as_mut().unwrap_or(&mut kept);
+                lines.push_str(line);
+                lines.push('\n');
+            }
+        }
+    }
+    kept.push_str(&block.unwrap_or_default());
+    kept
+}
+
+// `text` with a block for `domains`, or without one when there are none
+fn with_block(text: &str, domains: &[String]) -> String {
+    let mut text = strip(text);
+    if domains.is_empty() {
+        return text;
+    }
+    if !text.is_empty() && !text.ends_with('\n') {
+        text.push('\n');
+    }
+    text.push_str(BEGIN);
+    text.push('\n');
+    for domain in domains {
+        let domain = domain.trim_start_matches("www.");
+        for host in [domain.to_string(), format!("www.{domain}")] {
+            text.push_str(&format!("0.0.0.0 {host}\n:: {host}\n"));
+        }
+    }
+    text.push_str(END);
+    text.push('\n');
+    text
+}
+
+// Blocks `domains`, replacing any earlier block; an empty list unblocks.
+// Nothing is written unless every domain is a valid name
+pub fn apply(domains: &[String]) -> io::Result<()> {
+    if let Some(e) = domains.iter().find_map(|domain| check_domain(domain).err()) {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, e));
+    }
+    let path = path();
+    let text = fs::read_to_string(&path)?;
+    let updated = with_block(&text, domains);
+    // Writing needs root, so don't write when nothing changes
+    if updated == text {
+        return Ok(());
+    }
+    // A half-written hosts file would break name lookups for everything
+    migrate::write_atomic(&path, &updated)
+}
+
+// Keeps the hosts file in step with the running session. Without write access
+// it falls back to `sudo -n cyber-tomato hosts ...`, which works once a
+// sudoers rule allows it without a password
+pub struct SiteBlocker {
+    applied: Option<Vec<String>>, // None until the first update, so leftovers from a crash are cleared
+}
+
+impl SiteBlocker {
+    pub fn new() -> Self {
+        SiteBlocker { applied: None }
+    }
+
+    // Errors only when the wanted list changes, never on every frame
+    pub fn set(&mut self, domains: &[String]) -> Result<(), String> {
+        if self.applied.as_deref() == Some(domains) {
+            return Ok(());
+        }
+        self.applied = Some(domains.to_vec());
+        match apply(domains) {
+            Err(e) if e.kind() == io::ErrorKind::PermissionDenied => via_sudo(domains),
+            result => result.map_err(|e| format!("{}: {e}", path().display())),
+        }
+    }
+}
+
+impl Drop for SiteBlocker {
+    fn drop(&mut self) {
+        let _ = self.set(&[]);
+    }
+}
+
+fn via_sudo(domains: &[String]) -> Result<(), String> {
+    let exe = env::current_exe().map_err(|e| e.to_string())?;
+    let mut command = Command::new("sudo");
+    command.arg("-n").arg(exe).arg("hosts");
+    if domains.is_empty() {
+        command.arg("unblock");
+    } else {
+        command.arg("block").args(domains);
+    }
+    match command.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null()).status() {
+        Ok(status) if status.success() => Ok(()),
+        _ => Err(format!(
+            "{} is not writable; see \"Blocking Sites\" in the README for a sudo rule",
+            path().display()
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_round_trip() {
+        let original = "127.0.0.1 localhost\n::1 localhost\n";
+        let blocked = with_block(original, &["www.reddit.com".to_string()]);
+        assert!(blocked.starts_with(original));
+        assert!(blocked.contains("0.0.0.0 reddit.com\n"));
+        assert!(blocked.contains(":: www.reddit.com\n"));
+        // Blocking again replaces the block instead of stacking a second one
+        let reblocked = with_block(&blocked, &["x.com".to_string()]);
+        assert_eq!(reblocked.matches(BEGIN).count(), 1);
+        assert!(!reblocked.contains("reddit"));
+        assert_eq!(with_block(&reblocked, &[]), original);
+    }
+
+    #[test]
+    fn test_unterminated_markers() {
+        let hosts = format!("127.0.0.1 localhost\n{BEGIN}\n10.0.0.1 nas\n");
+        assert_eq!(strip(&hosts), hosts);
+        let blocked = with_block(&hosts, &["x.com".to_string()]);
+        assert!(blocked.starts_with(&hosts));
+        assert_eq!(with_block(&blocked, &[]), hosts);
+
+        // A stray END is left alone too
+        let stray = format!("{END}\n127.0.0.1 localhost\n");
+        assert_eq!(strip(&stray), stray);
+    }
+
+    #[test]
+    fn test_rejected_domains() {
+        assert!(check_domain("news.ycombinator.com").is_ok());
+        assert!(check_domain("my-site.co.uk").is_ok());
+        for domain in ["x\n1.2.3.4 bank.com", "a b.com", "", "example..com", ".com", "x.com#", "bank.com\t"] {
+            assert!(check_domain(domain).is_err(), "{domain:?}");
+        }
+        let domains = ["x.com".to_string(), "y.com\n1.2.3.4 bank.com".to_string()];
+        assert_eq!(apply(&domains).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    }
+}
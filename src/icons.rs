@@ -0,0 +1,128 @@
+use std::env;
+use std::sync::RwLock;
+
+use serde::Deserialize;
+
+use crate::TimerType;
+
+// Glyphs for the status bar, the daily goal dots and the text handed to
+// status bars. Nerd Font glyphs need a patched font, which can't be detected,
+// so `auto` only chooses between emoji and plain ASCII
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IconSet {
+    #[default]
+    Auto,
+    Emoji,
+    Nerd,
+    Ascii,
+}
+
+pub struct Icons {
+    pub work: &'static str,
+    pub rest: &'static str, // Breaks
+    pub paused: &'static str,
+    pub done: &'static str, // A pomodoro toward the daily goal
+    pub todo: &'static str, // One still to go
+}
+
+const EMOJI: Icons = Icons {
+    work: "🍅",
+    rest: "☕",
+    paused: "⏸",
+    done: "●",
+    todo: "○",
+};
+
+// Font Awesome glyphs, which every Nerd Font includes: clock, coffee, pause,
+// circle and circle outline
+const NERD: Icons = Icons {
+    work: "\u{f017}",
+    rest: "\u{f0f4}",
+    paused: "\u{f04c}",
+    done: "\u{f111}",
+    todo: "\u{f10c}",
+};
+
+const ASCII: Icons = Icons {
+    work: "W",
+    rest: "B",
+    paused: "||",
+    done: "*",
+    todo: ".",
+};
+
+// Longest goal still drawn as dots; bigger ones only show as numbers
+pub const MAX_DOTS: u32 = 12;
+
+static CURRENT: RwLock<IconSet> = RwLock::new(IconSet::Emoji);
+
+pub fn set(set: IconSet) {
+    let set = match set {
+        IconSet::Auto if supports_emoji() => IconSet::Emoji,
+        IconSet::Auto => IconSet::Ascii,
+        set => set,
+    };
+    if let Ok(mut current) = CURRENT.write() {
+        *current = set;
+    }
+}
+
+pub fn current() -> &'static Icons {
+    match CURRENT.read().map(|set| *set).unwrap_or_default() {
+        IconSet::Nerd => &NERD,
+        IconSet::Ascii => &ASCII,
+        IconSet::Emoji | IconSet::Auto => &EMOJI,
+    }
+}
+
+impl Icons {
+    pub fn session(&self, timer_type: &TimerType) -> &'static str {
+        match timer_type {
+            TimerType::Work => self.work,
+            TimerType::Break => self.rest,
+        }
+    }
+
+    // ●●●○○ for 3 of 5; empty without a goal or with one too long to draw
+    pub fn dots(&self, done: u32, goal: u32) -> String {
+        if goal == 0 || goal > MAX_DOTS {
+            return String::new();
+        }
+        let done = done.min(goal);
+        format!("{}{}", self.done.repeat(done as usize), self.todo.repeat((goal - done) as usize))
+    }
+}
+
+// The Linux console and dumb terminals have no emoji, and without a UTF-8
+// locale the terminal may not decode them at all. Windows terminals are
+// UTF-8 without saying so
+fn supports_emoji() -> bool {
+    let term = env::var("TERM").unwrap_or_default();
+    if term == "linux" || term == "dumb" {
+        return false;
+    }
+    if cfg!(windows) {
+        return true;
+    }
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|name| env::var(name).ok().filter(|value| !value.is_empty()))
+        .unwrap_or_default()
+        .to_lowercase();
+    locale.contains("utf-8") || locale.contains("utf8")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dots() {
+        assert_eq!(EMOJI.dots(3, 5), "●●●○○");
+        assert_eq!(ASCII.dots(7, 4), "****");
+        assert_eq!(ASCII.dots(0, 2), "..");
+        assert_eq!(EMOJI.dots(3, 0), "");
+        assert_eq!(EMOJI.dots(3, MAX_DOTS + 1), "");
+    }
+}
@@ -0,0 +1,101 @@
+use std::io;
+use std::process::{self, Child, Command, Stdio};
+
+// Keeps the screensaver and system sleep away while held. The inhibition is
+// owned by a helper process that also watches for our exit, so it is
+// released even if the timer crashes instead of quitting
+pub struct Inhibitor {
+    child: Option<Child>,
+    unavailable: bool, // The helper failed once; don't retry every frame
+}
+
+impl Inhibitor {
+    pub fn new() -> Self {
+        Inhibitor {
+            child: None,
+            unavailable: false,
+        }
+    }
+
+    // Errors only on the first failed attempt
+    pub fn set(&mut self, active: bool) -> io::Result<()> {
+        if !active {
+            self.release();
+            return Ok(());
+        }
+        if let Some(child) = &mut self.child {
+            // A helper that exits on its own (no logind, say) holds nothing
+            return match child.try_wait() {
+                Ok(Some(status)) => {
+                    self.child = None;
+                    self.unavailable = true;
+                    Err(io::Error::other(format!("helper exited with {status}")))
+                }
+                _ => Ok(()),
+            };
+        }
+        if self.unavailable {
+            return Ok(());
+        }
+        let result = command(process::id())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "not supported on this platform"))
+            .and_then(|mut command| command.stdin(Stdio::piped()).stdout(Stdio::null()).stderr(Stdio::null()).spawn());
+        match result {
+            Ok(child) => {
+                self.child = Some(child);
+                Ok(())
+            }
+            Err(e) => {
+                self.unavailable = true;
+                Err(e)
+            }
+        }
+    }
+
+    fn release(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            drop(child.stdin.take());
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+impl Drop for Inhibitor {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+// caffeinate takes IOKit power assertions and exits with the watched process
+#[cfg(target_os = "macos")]
+fn command(pid: u32) -> Option<Command> {
+    let mut command = Command::new("caffeinate");
+    command.args(["-d", "-i", "-w", &pid.to_string()]);
+    Some(command)
+}
+
+// SetThreadExecutionState only lasts as long as the calling thread, so a
+// hidden PowerShell sets it and then waits for the timer to exit
+#[cfg(windows)]
+fn command(pid: u32) -> Option<Command> {
+    let script = format!(
+        "$k = Add-Type -Name Power -Namespace CyberTomato -PassThru -MemberDefinition \
+         '[DllImport(\"kernel32.dll\")] public static extern uint SetThreadExecutionState(uint flags);'; \
+         [void]$k::SetThreadExecutionState(0x80000003); Wait-Process -Id {pid}"
+    );
+    let mut command = Command::new("powershell");
+    command.args(["-NoProfile", "-WindowStyle", "Hidden", "-Command", &script]);
+    Some(command)
+}
+
+// logind idle and sleep inhibitor; desktops honour it for screen blanking.
+// The D-Bus ScreenSaver.Inhibit call would need a connection held open by
+// this process, which systemd-inhibit gives us without a D-Bus dependency.
+// `cat` holds it until our end of its stdin closes
+#[cfg(not(any(target_os = "macos", windows)))]
+fn command(_pid: u32) -> Option<Command> {
+    let mut command = Command::new("systemd-inhibit");
+    command.args(["--what=idle:sleep", "--who=Cyber Tomato", "--why=Work session running", "--mode=block", "cat"]);
+    Some(command)
+}
@@ -0,0 +1,57 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+// Runs `job` off the UI thread; its result waits in the receiver, for a
+// `tick` to pick up
+pub fn run<T: Send + 'static>(job: impl FnOnce() -> T + Send + 'static) -> Receiver<T> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = sender.send(job());
+    });
+    receiver
+}
+
+// Fire-and-forget work for an integration, such as a request or a helper
+// command per finished session. Failures come back through `errors` to be
+// shown as toasts
+pub struct Jobs {
+    sender: Sender<String>,
+    errors: Receiver<String>,
+}
+
+impl Jobs {
+    pub fn new() -> Self {
+        let (sender, errors) = mpsc::channel();
+        Jobs { sender, errors }
+    }
+
+    pub fn spawn(&self, job: impl FnOnce() -> Result<(), String> + Send + 'static) {
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            if let Err(e) = job() {
+                let _ = sender.send(e);
+            }
+        });
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = String> + '_ {
+        self.errors.try_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_jobs_report_failures() {
+        let jobs = Jobs::new();
+        jobs.spawn(|| Ok(()));
+        jobs.spawn(|| Err("Helper: not found".to_string()));
+        assert_eq!(jobs.errors.recv_timeout(Duration::from_secs(5)), Ok("Helper: not found".to_string()));
+        assert_eq!(jobs.errors().count(), 0);
+
+        assert_eq!(run(|| 6 * 7).recv_timeout(Duration::from_secs(5)), Ok(42));
+    }
+}
@@ -0,0 +1,353 @@
+use std::collections::BTreeMap;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    StartWork,
+    StartBreak,
+    CustomTimer,
+    ResetDurations,
+    TogglePause,
+    Snooze,
+    ToggleMode,
+    ToggleClock,
+    Techniques,
+    Label,
+    Plan,
+    Alarms,
+    Stats,
+    History,
+    Review,
+    AddSession,
+    RemoveSession,
+    NextQuote,
+    Mario,
+    Gallery,
+    Preview,
+    Achievements,
+    Cats,
+    Suspend,
+    FrameStats,
+    Help,
+    Quit,
+}
+
+impl Action {
+    pub const ALL: [Action; 27] = [
+        Action::StartWork,
+        Action::StartBreak,
+        Action::CustomTimer,
+        Action::ResetDurations,
+        Action::TogglePause,
+        Action::Snooze,
+        Action::ToggleMode,
+        Action::ToggleClock,
+        Action::Techniques,
+        Action::Label,
+        Action::Plan,
+        Action::Alarms,
+        Action::Stats,
+        Action::History,
+        Action::Review,
+        Action::AddSession,
+        Action::RemoveSession,
+        Action::NextQuote,
+        Action::Mario,
+        Action::Gallery,
+        Action::Preview,
+        Action::Achievements,
+        Action::Cats,
+        Action::Suspend,
+        Action::FrameStats,
+        Action::Help,
+        Action::Quit,
+    ];
+
+    // Name used in the [keys] table of config.toml
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::StartWork => "start_work",
+            Action::StartBreak => "start_break",
+            Action::CustomTimer => "custom_timer",
+            Action::ResetDurations => "reset_durations",
+            Action::TogglePause => "toggle_pause",
+            Action::Snooze => "snooze",
+            Action::ToggleMode => "toggle_mode",
+            Action::ToggleClock => "toggle_clock",
+            Action::Techniques => "techniques",
+            Action::Label => "label",
+            Action::Plan => "plan",
+            Action::Alarms => "alarms",
+            Action::Stats => "stats",
+            Action::History => "history",
+            Action::Review => "review",
+            Action::AddSession => "add_session",
+            Action::RemoveSession => "remove_session",
+            Action::NextQuote => "next_quote",
+            Action::Mario => "mario",
+            Action::Gallery => "gallery",
+            Action::Preview => "preview",
+            Action::Achievements => "achievements",
+            Action::Cats => "cats",
+            Action::Suspend => "suspend",
+            Action::FrameStats => "frame_stats",
+            Action::Help => "help",
+            Action::Quit => "quit",
+        }
+    }
+
+    // Heading the action is listed under in the help screen; ALL keeps each
+    // category together
+    pub fn category(self) -> &'static str {
+        match self {
+            Action::StartWork
+            | Action::StartBreak
+            | Action::CustomTimer
+            | Action::ResetDurations
+            | Action::TogglePause
+            | Action::Snooze
+            | Action::ToggleMode
+            | Action::ToggleClock
+            | Action::Techniques => "Timer",
+            Action::Label | Action::Plan | Action::Alarms | Action::Stats | Action::History | Action::Review | Action::AddSession | Action::RemoveSession => {
+                "Tasks and history"
+            }
+            Action::NextQuote | Action::Mario | Action::Gallery | Action::Preview | Action::Achievements | Action::Cats => "Extras",
+            Action::Suspend | Action::FrameStats | Action::Help | Action::Quit => "Application",
+        }
+    }
+
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Action::StartWork => &["w"],
+            Action::StartBreak => &["b"],
+            Action::CustomTimer => &["c"],
+            Action::ResetDurations => &["d"],
+            Action::TogglePause => &["space", "enter"],
+            Action::Snooze => &["z"],
+            Action::ToggleMode => &["t"],
+            Action::ToggleClock => &["e"],
+            Action::Techniques => &["T"],
+            Action::Label => &["l"],
+            Action::Plan => &["p"],
+            Action::Alarms => &["o"],
+            Action::Stats => &["s"],
+            Action::History => &["h"],
+            Action::Review => &["r"],
+            Action::AddSession => &["+"],
+            Action::RemoveSession => &["-"],
+            Action::NextQuote => &["n"],
+            Action::Mario => &["m"],
+            Action::Gallery => &["g"],
+            Action::Preview => &["v"],
+            Action::Achievements => &["a"],
+            Action::Cats => &["k"],
+            Action::Suspend => &["ctrl+z"],
+            Action::FrameStats => &["f12"],
+            Action::Help => &["x"],
+            Action::Quit => &["q", "esc", "ctrl+c"],
+        }
+    }
+}
+
+// A binding in config.toml is either one key or a list of keys
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyList {
+    fn keys(&self) -> Vec<&str> {
+        match self {
+            KeyList::One(key) => vec![key.as_str()],
+            KeyList::Many(keys) => keys.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Key {
+    fn from_event(event: KeyEvent) -> Self {
+        let mut modifiers = event.modifiers;
+        // Shift is already part of the character ('D' vs 'd')
+        if let KeyCode::Char(_) = event.code {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        Key { code: event.code, modifiers }
+    }
+
+    fn label(self) -> String {
+        let key = match self.code {
+            KeyCode::Char(' ') => "␣".to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::Enter => "↵".to_string(),
+            KeyCode::Esc => "Esc".to_string(),
+            KeyCode::Tab => "Tab".to_string(),
+            KeyCode::Backspace => "⌫".to_string(),
+            KeyCode::Delete => "Del".to_string(),
+            KeyCode::Up => "↑".to_string(),
+            KeyCode::Down => "↓".to_string(),
+            KeyCode::Left => "←".to_string(),
+            KeyCode::Right => "→".to_string(),
+            KeyCode::Home => "Home".to_string(),
+            KeyCode::End => "End".to_string(),
+            KeyCode::PageUp => "PgUp".to_string(),
+            KeyCode::PageDown => "PgDn".to_string(),
+            KeyCode::F(n) => format!("F{n}"),
+            _ => "?".to_string(),
+        };
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            format!("^{key}")
+        } else if self.modifiers.contains(KeyModifiers::ALT) {
+            format!("M-{key}")
+        } else {
+            key
+        }
+    }
+}
+
+// "q", "space", "ctrl+c", "alt+enter", "f5"
+fn parse_key(text: &str) -> Result<Key, String> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = text.trim();
+    while let Some((modifier, tail)) = rest.split_once('+').filter(|(_, tail)| !tail.is_empty()) {
+        modifiers |= match modifier.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" => KeyModifiers::CONTROL,
+            "alt" | "meta" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            _ => return Err(format!("unknown modifier '{modifier}' in '{text}'")),
+        };
+        rest = tail;
+    }
+
+    let mut chars = rest.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => KeyCode::Char(c),
+        _ => match rest.to_ascii_lowercase().as_str() {
+            "space" => KeyCode::Char(' '),
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                Some(n @ 1..=12) => KeyCode::F(n),
+                _ => return Err(format!("unknown key '{text}'")),
+            },
+        },
+    };
+
+    // shift+a is written as A
+    if let KeyCode::Char(c) = code
+        && modifiers.contains(KeyModifiers::SHIFT)
+    {
+        modifiers.remove(KeyModifiers::SHIFT);
+        return Ok(Key {
+            code: KeyCode::Char(c.to_ascii_uppercase()),
+            modifiers,
+        });
+    }
+    Ok(Key { code, modifiers })
+}
+
+#[derive(Debug)]
+pub struct Keymap {
+    bindings: Vec<(Key, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_config(&BTreeMap::new()).expect("default key bindings are valid")
+    }
+}
+
+impl Keymap {
+    // Bindings in config replace the defaults of the actions they name. Errors
+    // carry the offending action name so they can be traced back to a line
+    pub fn from_config(keys: &BTreeMap<String, KeyList>) -> Result<Self, (String, String)> {
+        if let Some(name) = keys.keys().find(|name| !Action::ALL.iter().any(|a| a.name() == name.as_str())) {
+            return Err((name.clone(), "unknown action".to_string()));
+        }
+
+        let mut bindings: Vec<(Key, Action)> = Vec::new();
+        for action in Action::ALL {
+            let texts = match keys.get(action.name()) {
+                Some(list) => list.keys(),
+                None => action.default_keys().to_vec(),
+            };
+            for text in texts {
+                let key = parse_key(text).map_err(|e| (action.name().to_string(), e))?;
+                if let Some((_, other)) = bindings.iter().find(|(k, _)| *k == key) {
+                    return Err((action.name().to_string(), format!("'{text}' is already bound to {}", other.name())));
+                }
+                bindings.push((key, action));
+            }
+        }
+        Ok(Keymap { bindings })
+    }
+
+    pub fn action(&self, event: KeyEvent) -> Option<Action> {
+        let key = Key::from_event(event);
+        self.bindings.iter().find(|(k, _)| *k == key).map(|(_, action)| *action)
+    }
+
+    // "␣/↵" for display in help and status lines
+    pub fn label(&self, action: Action) -> String {
+        let labels: Vec<String> = self.bindings.iter().filter(|(_, a)| *a == action).map(|(key, _)| key.label()).collect();
+        if labels.is_empty() { "-".to_string() } else { labels.join("/") }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_default_bindings() {
+        let keymap = Keymap::default();
+        assert_eq!(keymap.action(event(KeyCode::Char('w'), KeyModifiers::NONE)), Some(Action::StartWork));
+        assert_eq!(keymap.action(event(KeyCode::Char('c'), KeyModifiers::CONTROL)), Some(Action::Quit));
+        assert_eq!(keymap.action(event(KeyCode::Char('c'), KeyModifiers::NONE)), Some(Action::CustomTimer));
+        assert_eq!(keymap.action(event(KeyCode::Char('z'), KeyModifiers::NONE)), Some(Action::Snooze));
+        assert_eq!(keymap.action(event(KeyCode::Char('y'), KeyModifiers::NONE)), None);
+        assert_eq!(keymap.label(Action::TogglePause), "␣/↵");
+    }
+
+    #[test]
+    fn test_config_overrides_and_errors() {
+        let mut keys = BTreeMap::new();
+        keys.insert("start_work".to_string(), KeyList::Many(vec!["shift+w".to_string(), "f5".to_string()]));
+        let keymap = Keymap::from_config(&keys).unwrap();
+        assert_eq!(keymap.action(event(KeyCode::Char('W'), KeyModifiers::SHIFT)), Some(Action::StartWork));
+        assert_eq!(keymap.action(event(KeyCode::F(5), KeyModifiers::NONE)), Some(Action::StartWork));
+        assert_eq!(keymap.action(event(KeyCode::Char('w'), KeyModifiers::NONE)), None);
+
+        keys.insert("stats".to_string(), KeyList::One("b".to_string()));
+        assert_eq!(Keymap::from_config(&keys).unwrap_err().0, "stats");
+
+        let mut keys = BTreeMap::new();
+        keys.insert("fly".to_string(), KeyList::One("f".to_string()));
+        assert_eq!(Keymap::from_config(&keys).unwrap_err(), ("fly".to_string(), "unknown action".to_string()));
+    }
+}
@@ -0,0 +1,133 @@
+use std::io;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::Receiver;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::jobs;
+
+const CHECK_EVERY: Duration = Duration::from_secs(3);
+// A lock not seen by then didn't happen, or can't be seen
+const LOCK_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Locks the desktop session when a break starts, so the break is spent away
+// from the keyboard, and notices the unlock on return. The lock state is
+// only checked, in the background, between locking and the unlock
+pub struct ScreenLock {
+    locked_at: Option<Instant>, // Set by lock until the unlock is seen
+    seen_locked: bool,
+    checked_at: Option<Instant>,
+    pending: Option<Receiver<Option<bool>>>,
+}
+
+impl ScreenLock {
+    pub fn new() -> Self {
+        ScreenLock {
+            locked_at: None,
+            seen_locked: false,
+            checked_at: None,
+            pending: None,
+        }
+    }
+
+    pub fn lock(&mut self) -> io::Result<()> {
+        let mut command = lock_command().ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "not supported on this platform"))?;
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", command.get_program().to_string_lossy())))?;
+        thread::spawn(move || child.wait());
+        self.locked_at = Some(Instant::now());
+        self.seen_locked = false;
+        self.checked_at = None;
+        Ok(())
+    }
+
+    // Called every frame; true once when the session locked by `lock` is
+    // unlocked again
+    pub fn tick(&mut self) -> bool {
+        let Some(locked_at) = self.locked_at else {
+            return false;
+        };
+        if let Some(receiver) = &self.pending
+            && let Ok(locked) = receiver.try_recv()
+        {
+            self.pending = None;
+            match locked {
+                Some(true) => self.seen_locked = true,
+                Some(false) if self.seen_locked => {
+                    self.locked_at = None;
+                    return true;
+                }
+                // The locker can take a moment to start
+                Some(false) if locked_at.elapsed() < LOCK_TIMEOUT => {}
+                // No way to tell, or it never locked
+                _ => {
+                    self.locked_at = None;
+                    return false;
+                }
+            }
+        }
+        if self.pending.is_none() && self.checked_at.is_none_or(|at| at.elapsed() >= CHECK_EVERY) {
+            self.checked_at = Some(Instant::now());
+            self.pending = Some(jobs::run(is_locked));
+        }
+        false
+    }
+}
+
+// The output of a check command, None when it couldn't run
+fn output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).stdin(Stdio::null()).stderr(Stdio::null()).output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// logind asks the session's screen locker to lock; GNOME, KDE and xss-lock
+// listen for it
+#[cfg(not(any(target_os = "macos", windows)))]
+fn lock_command() -> Option<Command> {
+    let mut command = Command::new("loginctl");
+    command.arg("lock-session");
+    Some(command)
+}
+
+// Lockers set LockedHint while the screen is locked; ones that don't can't
+// be told from unlocked
+#[cfg(not(any(target_os = "macos", windows)))]
+fn is_locked() -> Option<bool> {
+    let session = std::env::var("XDG_SESSION_ID").unwrap_or_else(|_| "self".to_string());
+    match output("loginctl", &["show-session", &session, "--property=LockedHint", "--value"])?.trim() {
+        "yes" => Some(true),
+        "no" => Some(false),
+        _ => None,
+    }
+}
+
+// Sleeping the display locks it when a password is required right after
+// sleep, the default
+#[cfg(target_os = "macos")]
+fn lock_command() -> Option<Command> {
+    let mut command = Command::new("pmset");
+    command.arg("displaysleepnow");
+    Some(command)
+}
+
+#[cfg(target_os = "macos")]
+fn is_locked() -> Option<bool> {
+    Some(output("ioreg", &["-n", "Root", "-d1"])?.contains("\"CGSSessionScreenIsLocked\"=Yes"))
+}
+
+#[cfg(windows)]
+fn lock_command() -> Option<Command> {
+    let mut command = Command::new("rundll32.exe");
+    command.arg("user32.dll,LockWorkStation");
+    Some(command)
+}
+
+// The lock screen is drawn by LogonUI, which only runs while it shows
+#[cfg(windows)]
+fn is_locked() -> Option<bool> {
+    Some(output("tasklist", &["/FI", "IMAGENAME eq LogonUI.exe", "/NH"])?.contains("LogonUI.exe"))
+}
@@ -1,53 +1,175 @@
 use std::{
+    fs,
     io::{self, Write},
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
     time::{Duration, Instant},
 };
 
+use chrono::Local;
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
+    event::{self, DisableFocusChange, EnableFocusChange, Event, KeyCode, KeyEvent, KeyModifiers},
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use ratatui::{
     Frame, Terminal,
     backend::CrosstermBackend,
-    layout::{Alignment, Constraint, Direction, Layout},
+    layout::{Alignment, Constraint, Direction, Layout, Margin},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Gauge, Paragraph},
+    widgets::{Block, Borders, Paragraph},
 };
+use serde::{Deserialize, Serialize};
 
+mod achievements;
+mod active_app;
+mod activitywatch;
+mod alarms;
 mod ascii_digits;
 mod audio;
+mod auto_tags;
+mod away;
+mod backup;
+mod bench;
+mod braille_digits;
+mod breathing;
+mod calendar;
+mod cats;
+mod chiptune;
+mod cli;
+mod clock;
+mod config;
+mod dnd;
+mod eye_rest;
+mod fireworks;
+mod follow;
+mod frame_stats;
+mod gallery;
+mod graphics;
+mod help;
+mod history;
+mod history_browser;
+mod hosts;
+mod icons;
+mod inhibit;
+mod jobs;
+mod keymap;
+mod lock;
 mod mario_animation;
+mod meeting;
+mod midi;
+mod migrate;
+mod milestones;
+mod notifications;
+mod obs;
+mod once;
+mod panels;
+mod paths;
+mod plugins;
+mod progress_bar;
+mod quotes;
+mod remote;
+mod report;
+mod review;
+mod room;
+mod routing;
+mod rtttl;
+mod scripts;
+mod session;
+mod speech;
+#[cfg(feature = "sqlite")]
+mod sqlite_store;
+mod startup;
+mod stats;
+mod status;
+mod summary;
+mod tasks;
+mod technique;
+mod template;
+mod theme;
+mod time_tracking;
+mod toast;
+use achievements::{Reward, Trophies};
+use active_app::AppSampler;
+use activitywatch::ActivityWatch;
+use alarms::{Alarm, AlarmPane, Alarms};
 use ascii_digits::create_time_display_lines;
-use audio::AudioManager;
+use audio::{AudioEngine, AudioManager};
+use calendar::Calendar;
+use cats::{Cats, CollectionScreen};
+use config::{
+    AchievementsConfig, AutoTagRule, BreakScreen, BreaksConfig, CatsConfig, Config, CountdownConfig, DigitFont, ExtendBreak, FocusConfig, GoalsConfig,
+    LayoutConfig, MeetingAction, MilestonesConfig, NotifyRule, PausesConfig, TemplatesConfig, Waveform,
+};
+use dnd::DoNotDisturb;
+use eye_rest::EyeRest;
+use fireworks::Fireworks;
+use follow::SharedState;
+use frame_stats::FrameStats;
+use gallery::{GalleryScreen, Outcome, Scene};
+use graphics::{Graphics, Sprite};
+use help::HelpScreen;
+use history::{History, SessionRecord, SessionStore};
+use history_browser::HistoryBrowser;
+use hosts::SiteBlocker;
+use inhibit::Inhibitor;
+use keymap::{Action, Keymap};
+use lock::ScreenLock;
 use mario_animation::MarioAnimation;
-
-#[derive(Clone, Debug, PartialEq)]
+use meeting::MeetingDetector;
+use notifications::{Button, Notifier};
+use obs::ObsOverlay;
+use panels::Panel;
+use plugins::Plugins;
+use quotes::Quotes;
+use review::{Reviews, WeekReview};
+use room::{Room, RoomState};
+use routing::{Channel, NotifyEvent, Webhooks};
+use scripts::{ScriptCommand, Scripts};
+use session::{PomodoroSession, SessionState};
+use speech::Speaker;
+use startup::StartupAction;
+use status::{Status, StatusFile};
+use tasks::{TaskList, TaskPane};
+use technique::{Pick, Technique, TechniquePicker};
+use theme::Theme;
+use time_tracking::TimeTracker;
+use toast::Toasts;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 enum TimerType {
     Work,
     Break,
 }
 
+// A notification, before [[notify]] rules pick where it goes
+struct Notice {
+    event: NotifyEvent,
+    title: String,
+    body: String,
+    speech: String,
+    buttons: Vec<Button>,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 enum TimerMode {
     Auto,
     Manual,
 }
 
-#[derive(Clone)]
-struct PomodoroSession {
-    timer_type: TimerType,
-    duration: Duration,
-    elapsed: Duration,
-    is_running: bool,
-    start_time: Option<Instant>,
+// A finished session that is still ringing in nag mode; the session itself
+// is in overtime
+struct PendingAck {
+    last_nag: Instant,
+    record_id: Option<String>,
 }
 
-const HIGHLIGHT_COLOR: Color = Color::Rgb(0, 255, 150);
-const PRIMARY_COLOR: Color = Color::LightGreen; // Color::Rgb(144, 255, 161); //Color::Rgb(80,250,123);
-
 fn set_terminal_title(title: &str) {
     print!("\x1b]0;{title}\x07");
     io::stdout().flush().unwrap_or(());
@@ -58,77 +180,565 @@ struct PomodoroTimer {
     mode: TimerMode,
     completed_sessions: u32,
     show_controls_popup: bool,
+    help: HelpScreen,
     show_custom_input: bool,
     custom_input: String,
+    custom_error: Option<String>, // Why the last input wasn't taken
     show_mario_animation: bool,
+    fireworks: Option<Fireworks>, // Shown instead of Mario when the daily goal is reached
     mario_animation: MarioAnimation,
+    audio: Rc<AudioEngine>, // Shared by the chimes and the animation
     audio_manager: AudioManager,
-    custom_work_duration: Duration,
-    custom_break_duration: Duration,
+    mario_waveform: Waveform,
+    work_duration: Duration, // From the config or the technique in use
+    break_duration: Duration,
+    long_break_duration: Duration,
+    long_break_every: u32, // 0 for no long breaks
+    cycle: u32,            // Pomodoros since the last long break
+    technique: Option<&'static Technique>,
+    technique_picker: Option<TechniquePicker>,
+    custom: Option<(Duration, Duration)>, // Work and break of a custom timer, used until reset
+    max_session: Duration,
+    history: History,
+    reviews: Reviews,
+    review: Option<(WeekReview, bool)>, // Shown, and whether it's the week so far
+    goals: GoalsConfig,
+    review_checked: Option<String>, // Week last looked at for a review to show
+    trophies: Trophies,
+    show_trophies: bool,
+    achievements: AchievementsConfig,
+    theme: Theme, // From the config, unless an unlocked theme replaces it
+    // Set by startup actions, and kept over config reloads
+    theme_name: Option<String>, // A [themes.NAME] table to use instead of [theme]
+    mute: Option<bool>,         // In place of sounds.mute
+    cats: Cats,
+    cats_config: CatsConfig,
+    show_cats: bool,
+    cats_screen: CollectionScreen,
+    gallery: Option<GalleryScreen>,
+    last_scene: Scene, // What the replay key plays
+    show_stats: bool,
+    stats: stats::StatsScreen,
+    task: Option<String>,
+    tags: Vec<String>,
+    auto_tags: Vec<AutoTagRule>,
+    notify_rules: Vec<NotifyRule>,
+    webhooks: Webhooks,
+    show_label_input: bool,
+    label_input: String,
+    show_history: bool,
+    history_browser: HistoryBrowser,
+    tasks: TaskList,
+    show_tasks: bool,
+    task_pane: TaskPane,
+    alarms: Alarms,
+    show_alarms: bool,
+    alarm_pane: AlarmPane,
+    quotes: Option<Quotes>,
+    message: Option<String>, // Quote shown after a session completes
+    desktop_notifications: bool,
+    suppress_when_focused: bool,
+    terminal_focused: Option<bool>, // None until the terminal reports focus changes
+    focus_lost_at: Option<Instant>,
+    away_since: Option<Instant>, // When work was paused for the terminal losing focus
+    resized: bool,               // Redraw from scratch on the next frame
+    count_suspended: bool,
+    confirm_restart: Duration,
+    restart_pressed: Option<(Action, Instant)>, // First press of a restart waiting for the second
+    notifier: Notifier,
+    keymap: Keymap,
+    toasts: Toasts,
+    frame_stats: FrameStats,
+    countdown: CountdownConfig,
+    last_countdown_beep: Option<u64>, // Seconds left when the countdown last beeped
+    milestones: MilestonesConfig,
+    milestone_elapsed: Option<Duration>,   // Work time on the clock when milestones were last checked
+    milestone: Option<(String, Instant)>,  // Last milestone reached, shown in the terminal title for a while
+    budget_alerted: Option<(String, u32)>, // Task and budget in minutes already alerted about
+    time_scale: f64,                       // Debug speed-up from --time-scale; 1.0 is real time
+    layout: LayoutConfig,
+    watching: bool,                            // Read-only mirror of another instance (`watch`)
+    published: Option<(Instant, SharedState)>, // Last state written for followers
+    status_file: StatusFile,
+    obs: ObsOverlay,
+    breaks: BreaksConfig,
+    pauses: PausesConfig,
+    breath_phase: Option<breathing::Phase>,
+    eye_rest: EyeRest,
+    focus: FocusConfig,
+    inhibitor: Inhibitor,
+    dnd: DoNotDisturb,
+    site_blocker: SiteBlocker,
+    nag_seconds: u32,
+    pending_ack: Option<PendingAck>,
+    auto_start_break: bool,
+    auto_start_work: bool,
+    hold_for_animation: bool,
+    held_for_animation: bool, // The auto-started session waits for the animation to end
+    snooze_minutes: u32,
+    snoozable: bool,                  // The last session to finish was work, so it can be extended
+    last_work_record: Option<String>, // History id of that session
+    extending: bool,                  // The running session is a snooze of it
+    extend_break: ExtendBreak,
+    overtime: Duration,                // Worked past the planned length since the last break
+    break_extension: Option<Duration>, // Offered for the running break, waiting for y/n
+    activity_watch: ActivityWatch,
+    time_tracker: TimeTracker,
+    speaker: Speaker,
+    calendar: Calendar,
+    room: Room,
+    capture_app: bool,
+    app_sampler: AppSampler,
+    meeting: MeetingDetector,
+    screen_lock: ScreenLock,
+    show_meeting_prompt: bool,
+    show_pause_prompt: bool, // Asking why work was just paused
+    scripts: Scripts,
+    plugins: Plugins,
+    script_status: Option<String>, // Status bar text set by a script
+    templates: TemplatesConfig,
+    graphics: Graphics,
 }
 
-impl PomodoroTimer {
-    fn new() -> Result<Self, Box<dyn std::error::Error>> {
-        let current_session = PomodoroSession {
-            timer_type: TimerType::Work,
-            duration: Duration::from_secs(25 * 60), // 25 minutes
-            elapsed: Duration::from_secs(0),
-            is_running: false,
-            start_time: None,
-        };
+// One frame per tick; animations advance one step per frame and the gauge is
+// redrawn with sub-second progress each time
+const TICK_RATE: Duration = Duration::from_millis(100);
+// Redraws while an animation plays; their physics follow the clock, not this
+const ANIMATION_FRAME: Duration = Duration::from_millis(33);
+// Editors fire several events per save; wait for them to settle before reloading
+const CONFIG_RELOAD_DELAY: Duration = Duration::from_millis(250);
+// How long a milestone stays in the terminal title
+const MILESTONE_TITLE: Duration = Duration::from_secs(30);
+// How soon the second press has to follow to confirm a restart
+const RESTART_CONFIRM_WINDOW: Duration = Duration::from_secs(1);
+// Shorter break extensions aren't worth offering
+const MIN_BREAK_EXTENSION: Duration = Duration::from_secs(30);
 
-        Ok(PomodoroTimer {
+impl PomodoroTimer {
+    // Everything the timer keeps is read from `data_dir` (and the history dir)
+    fn new(config: &Config, data_dir: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let current_session = PomodoroSession::new(TimerType::Work, Duration::from_secs(config.timer.work_minutes as u64 * 60));
+        let audio = Rc::new(AudioEngine::new());
+        let history = open_history(config, data_dir)?;
+        let reviews = Reviews::load(&history);
+        let trophies = Trophies::load(&history);
+
+        let mut timer = PomodoroTimer {
             current_session,
             mode: TimerMode::Auto,
             completed_sessions: 0,
             show_controls_popup: false,
+            help: HelpScreen::new(),
             show_custom_input: false,
             custom_input: String::new(),
+            custom_error: None,
             show_mario_animation: false,
-            mario_animation: MarioAnimation::new(),
-            audio_manager: AudioManager {},
-            custom_work_duration: Duration::from_secs(25 * 60),
-            custom_break_duration: Duration::from_secs(5 * 60),
-        })
+            fireworks: None,
+            mario_animation: MarioAnimation::new(audio.clone(), Waveform::default()),
+            audio_manager: AudioManager::new(audio.clone()),
+            mario_waveform: Waveform::default(),
+            audio,
+            // Durations, quotes, notifications and keys are filled in by apply_config
+            work_duration: Duration::ZERO,
+            break_duration: Duration::ZERO,
+            long_break_duration: Duration::ZERO,
+            long_break_every: 0,
+            cycle: 0,
+            technique: None,
+            technique_picker: None,
+            custom: None,
+            max_session: Duration::ZERO,
+            history,
+            reviews,
+            review: None,
+            goals: GoalsConfig::default(),
+            review_checked: None,
+            trophies,
+            show_trophies: false,
+            achievements: AchievementsConfig::default(),
+            theme: Theme::default(),
+            theme_name: None,
+            mute: None,
+            cats: Cats::load(data_dir.join("cats.jsonl")),
+            cats_config: CatsConfig::default(),
+            show_cats: false,
+            cats_screen: CollectionScreen::new(),
+            gallery: None,
+            last_scene: Scene::Mario,
+            show_stats: false,
+            stats: stats::StatsScreen::new(config.stats.days),
+            task: None,
+            tags: Vec::new(),
+            auto_tags: Vec::new(),
+            notify_rules: Vec::new(),
+            webhooks: Webhooks::new(),
+            show_label_input: false,
+            label_input: String::new(),
+            show_history: false,
+            history_browser: HistoryBrowser::new(),
+            tasks: TaskList::load(user_file(config, data_dir, "tasks.json")),
+            show_tasks: false,
+            task_pane: TaskPane::new(),
+            alarms: Alarms::load(user_file(config, data_dir, "alarms.json")),
+            show_alarms: false,
+            alarm_pane: AlarmPane::new(),
+            quotes: None,
+            message: None,
+            desktop_notifications: false,
+            suppress_when_focused: false,
+            terminal_focused: None,
+            focus_lost_at: None,
+            away_since: None,
+            resized: false,
+            count_suspended: false,
+            confirm_restart: Duration::ZERO,
+            restart_pressed: None,
+            notifier: Notifier::new(),
+            keymap: Keymap::default(),
+            toasts: Toasts::new(),
+            frame_stats: FrameStats::new(),
+            countdown: CountdownConfig::default(),
+            last_countdown_beep: None,
+            milestones: MilestonesConfig::default(),
+            milestone_elapsed: None,
+            milestone: None,
+            budget_alerted: None,
+            time_scale: 1.0,
+            layout: LayoutConfig::default(),
+            watching: false,
+            published: None,
+            status_file: StatusFile::new(),
+            obs: ObsOverlay::new(),
+            breaks: BreaksConfig::default(),
+            pauses: PausesConfig::default(),
+            breath_phase: None,
+            eye_rest: EyeRest::load(config.eye_rest.clone(), data_dir.join("eye_rests.jsonl")),
+            focus: FocusConfig::default(),
+            inhibitor: Inhibitor::new(),
+            dnd: DoNotDisturb::new(data_dir.join("dnd-on")),
+            site_blocker: SiteBlocker::new(),
+            nag_seconds: 0,
+            pending_ack: None,
+            auto_start_break: true,
+            auto_start_work: true,
+            hold_for_animation: true,
+            held_for_animation: false,
+            snooze_minutes: 0,
+            snoozable: false,
+            last_work_record: None,
+            extending: false,
+            extend_break: ExtendBreak::Off,
+            overtime: Duration::ZERO,
+            break_extension: None,
+            activity_watch: ActivityWatch::new(config.activitywatch.clone()),
+            time_tracker: TimeTracker::new(config.time_tracking.clone()),
+            speaker: Speaker::new(config.speech.clone()),
+            calendar: Calendar::new(config.calendar.clone()),
+            room: Room::new(config.room.clone()),
+            capture_app: false,
+            app_sampler: AppSampler::new(),
+            meeting: MeetingDetector::new(),
+            screen_lock: ScreenLock::new(),
+            show_meeting_prompt: false,
+            show_pause_prompt: false,
+            scripts: Scripts::load(&config.scripts.files),
+            plugins: Plugins::new(),
+            script_status: None,
+            templates: TemplatesConfig::default(),
+            graphics: Graphics::new(),
+        };
+        timer.apply_config(config)?;
+        Ok(timer)
+    }
+
+    // Settings that can change while running, applied at startup and on reload.
+    // Durations only affect sessions started afterwards
+    fn apply_config(&mut self, config: &Config) -> Result<(), String> {
+        self.keymap = Keymap::from_config(&config.keys).map_err(|(action, e)| format!("keys.{action}: {e}"))?;
+        self.theme = self
+            .theme_name
+            .as_ref()
+            .and_then(|name| config.themes.get(name))
+            .unwrap_or(&config.theme)
+            .clone();
+        self.achievements = config.achievements.clone();
+        self.cats_config = config.cats.clone();
+        self.apply_theme();
+        self.work_duration = Duration::from_secs(config.timer.work_minutes as u64 * 60);
+        self.break_duration = Duration::from_secs(config.timer.break_minutes as u64 * 60);
+        self.long_break_duration = Duration::from_secs(config.timer.long_break_minutes as u64 * 60);
+        self.long_break_every = config.timer.long_break_every;
+        if let Some(technique) = self.technique {
+            let minutes = |minutes: u32| Duration::from_secs(minutes as u64 * 60);
+            self.work_duration = minutes(technique.work_minutes);
+            self.break_duration = minutes(technique.break_minutes);
+            self.long_break_duration = minutes(technique.long_break_minutes);
+            self.long_break_every = technique.long_break_every;
+        }
+        self.max_session = Duration::from_secs(config.timer.max_session_minutes as u64 * 60);
+        self.status_file.set_config(&config.status_file);
+        self.obs.set_config(&config.obs);
+        self.auto_tags = config.auto_tags.clone();
+        self.notify_rules = config.notify.clone();
+        self.snooze_minutes = config.timer.snooze_minutes;
+        self.count_suspended = config.timer.count_suspended;
+        self.confirm_restart = Duration::from_secs(config.timer.confirm_restart_seconds as u64);
+        self.extend_break = config.timer.extend_break;
+        self.auto_start_break = config.timer.auto_start_break;
+        self.auto_start_work = config.timer.auto_start_work;
+        self.hold_for_animation = config.timer.hold_for_animation;
+        self.quotes = Quotes::from_config(&config.quotes);
+        self.desktop_notifications = config.notifications.desktop;
+        self.suppress_when_focused = config.notifications.suppress_when_focused;
+        self.nag_seconds = config.notifications.nag_seconds;
+        self.countdown = config.countdown.clone();
+        self.goals = config.goals.clone();
+        self.milestones = config.milestones.clone();
+        let mut sounds = self.theme.sounds(&config.sounds);
+        sounds.mute = self.mute.unwrap_or(sounds.mute);
+        self.audio_manager.set_config(sounds);
+        self.mario_waveform = config.sounds.mario;
+        self.layout = config.layout.clone();
+        self.templates = config.templates.clone();
+        icons::set(config.icons.set);
+        self.graphics.set_protocol(config.graphics.protocol);
+        self.breaks = config.breaks.clone();
+        self.pauses = config.pauses.clone();
+        self.eye_rest.set_config(config.eye_rest.clone());
+        self.focus = config.focus.clone();
+        self.activity_watch.set_config(config.activitywatch.clone());
+        self.time_tracker.set_config(config.time_tracking.clone());
+        self.speaker.set_config(config.speech.clone());
+        self.calendar.set_config(config.calendar.clone());
+        self.room.set_config(config.room.clone());
+        self.capture_app = config.history.capture_app;
+        Ok(())
+    }
+
+    // The [startup] actions, then the profile's, in order. One that fails is
+    // reported and the rest still run
+    fn run_startup(&mut self, config: &Config, actions: &[String]) {
+        for text in actions {
+            if let Err(e) = startup::parse(text).and_then(|action| self.startup_action(config, action)) {
+                self.toasts.error(format!("Startup action '{text}': {e}"));
+            }
+        }
+    }
+
+    fn startup_action(&mut self, config: &Config, action: StartupAction) -> Result<(), String> {
+        match action {
+            StartupAction::Theme(name) => {
+                if !config.themes.contains_key(&name) {
+                    return Err(format!("no [themes.{name}] table"));
+                }
+                self.theme_name = Some(name);
+                self.apply_config(config)?;
+            }
+            StartupAction::Mute(mute) => {
+                self.mute = Some(mute);
+                self.apply_config(config)?;
+            }
+            StartupAction::Work(None) => self.start_work_session(),
+            StartupAction::Work(Some(length)) => self.start_custom_session(length, None),
+            StartupAction::Break(None) => self.start_break_session(),
+            StartupAction::Break(Some(length)) => self.start_timer(TimerType::Break, length),
+            StartupAction::Label(label) => (self.task, self.tags) = parse_label(&label),
+            StartupAction::Manual(manual) => self.mode = if manual { TimerMode::Manual } else { TimerMode::Auto },
+            StartupAction::Command(command) => self.remote_command(&command)?,
+        }
+        Ok(())
+    }
+
+    // Lengths from `technique`, or from the config with None. They replace a
+    // custom timer, and a work session that hasn't started takes them too
+    fn use_technique(&mut self, technique: Option<&'static Technique>, config: &Config) -> Result<(), String> {
+        self.technique = technique;
+        self.custom = None;
+        self.apply_config(config)?;
+        if self.current_session.state == SessionState::Idle {
+            let duration = match self.current_session.timer_type {
+                TimerType::Work => self.work_duration,
+                TimerType::Break => self.next_break_duration(),
+            };
+            self.current_session = PomodoroSession::new(self.current_session.timer_type.clone(), duration);
+        }
+        Ok(())
+    }
+
+    // Scripts start over on a reload, so edits to them are picked up too
+    fn reload_config(&mut self) {
+        let reloaded = Config::load().and_then(|config| {
+            self.apply_config(&config)?;
+            self.scripts = Scripts::load(&config.scripts.files);
+            Ok(())
+        });
+        match reloaded {
+            Ok(()) => self.toasts.info("Config reloaded"),
+            Err(e) => self.toasts.error(format!("Config not reloaded: {e}")),
+        }
     }
 
     fn start_timer(&mut self, timer_type: TimerType, duration: Duration) {
-        self.current_session = PomodoroSession {
-            timer_type,
-            duration,
-            elapsed: Duration::from_secs(0),
-            is_running: true,
-            start_time: Some(Instant::now()),
-        };
+        // Moving on counts as noticing the last one finished
+        self.acknowledge();
+        self.snoozable = false;
+        self.extending = false;
+        self.app_sampler.take();
+        self.milestone = None;
+        self.held_for_animation = false;
+        self.break_extension = None;
+        self.current_session = PomodoroSession::started(timer_type, duration);
+        self.emit_event("start");
+    }
+
+    // Lengths of the next sessions: a custom timer's until the defaults are
+    // back, the configured ones otherwise
+    fn next_work_duration(&self) -> Duration {
+        self.custom.map_or(self.work_duration, |(work, _)| work)
+    }
+
+    fn next_break_duration(&self) -> Duration {
+        match self.custom {
+            Some((_, brk)) => brk,
+            None if self.long_break_due(0) => self.long_break_duration,
+            None => self.break_duration,
+        }
+    }
+
+    // Whether the break after `ahead` more pomodoros is a long one. Custom
+    // timers keep their own break length
+    fn long_break_due(&self, ahead: u32) -> bool {
+        self.custom.is_none() && self.long_break_every > 0 && self.cycle + ahead >= self.long_break_every
+    }
+
+    // Leaves a custom timer; the sessions after this one get the configured lengths
+    fn reset_durations(&mut self) {
+        self.custom = None;
+        self.toasts.info(format!(
+            "Back to {} min work, {} min breaks",
+            self.work_duration.as_secs() / 60,
+            self.break_duration.as_secs() / 60
+        ));
     }
 
     fn start_work_session(&mut self) {
-        self.start_timer(TimerType::Work, self.custom_work_duration);
+        self.start_timer(TimerType::Work, self.next_work_duration());
+        if let Some(event) = self.meeting_during_work() {
+            self.toasts.error(format!("{event} - before this session ends"));
+        }
+    }
+
+    // The calendar event a work session started now would run into
+    fn meeting_during_work(&self) -> Option<String> {
+        let now = history::now_secs();
+        let end = now + self.next_work_duration().as_secs();
+        self.calendar.collision(now, end).map(|event| event.describe(now))
     }
 
     fn start_break_session(&mut self) {
-        self.start_timer(TimerType::Break, self.custom_break_duration);
+        // Time rung so far counts as overtime before the break is sized
+        self.acknowledge();
+        let overtime = std::mem::take(&mut self.overtime);
+        let duration = self.next_break_duration();
+        if self.long_break_due(0) {
+            self.cycle = 0;
+        }
+        self.start_timer(TimerType::Break, duration);
+        self.offer_break_extension(overtime);
+        if self.focus.lock_on_break
+            && !self.watching
+            && let Err(e) = self.screen_lock.lock()
+        {
+            self.toasts.error(format!("Could not lock the screen: {e}"));
+        }
     }
 
-    fn start_custom_session(&mut self, work_mins: u32, break_mins: Option<u32>) {
-        self.custom_work_duration = Duration::from_secs((work_mins * 60) as u64);
-        if let Some(break_mins) = break_mins {
-            self.custom_break_duration = Duration::from_secs((break_mins * 60) as u64);
-        } else {
-            // Use default 5 minutes for break if not specified
-            self.custom_break_duration = Duration::from_secs(5 * 60);
+    // Break time owed for `overtime`, at the planned break to work ratio
+    fn break_extension_for(&self, overtime: Duration) -> Duration {
+        let work = self.next_work_duration();
+        if work.is_zero() {
+            return Duration::ZERO;
+        }
+        let extra = overtime.mul_f64(self.current_session.duration.as_secs_f64() / work.as_secs_f64());
+        Duration::from_secs(extra.as_secs())
+    }
+
+    fn offer_break_extension(&mut self, overtime: Duration) {
+        let extra = self.break_extension_for(overtime);
+        if extra < MIN_BREAK_EXTENSION {
+            return;
+        }
+        match self.extend_break {
+            ExtendBreak::Auto => self.extend_running_break(extra),
+            ExtendBreak::Ask => self.break_extension = Some(extra),
+            ExtendBreak::Off => {}
+        }
+    }
+
+    fn extend_running_break(&mut self, extra: Duration) {
+        self.break_extension = None;
+        if self.current_session.timer_type != TimerType::Break || self.current_session.is_finished() {
+            return;
+        }
+        self.current_session.duration += extra;
+        self.toasts
+            .info(format!("Break extended by {} for the overtime", PomodoroTimer::format_duration(extra)));
+    }
+
+    // Whether a w or b press may throw away the session in progress. Past
+    // `confirm_restart` of it, only a second press of the same key within
+    // RESTART_CONFIRM_WINDOW does
+    fn confirm_restart(&mut self, action: Action) -> bool {
+        let (elapsed, _) = self.get_timer_progress();
+        let in_progress = self.current_session.is_running() || self.current_session.is_paused();
+        if !in_progress || self.confirm_restart.is_zero() || elapsed < self.confirm_restart {
+            return true;
+        }
+        let pressed = self.restart_pressed.take();
+        if pressed.is_some_and(|(first, at)| first == action && at.elapsed() < RESTART_CONFIRM_WINDOW) {
+            return true;
+        }
+        self.restart_pressed = Some((action, Instant::now()));
+        let kind = match self.current_session.timer_type {
+            TimerType::Work => "work",
+            TimerType::Break => "break",
+        };
+        self.toasts.info(format!(
+            "Press {} again to throw away {} of {kind}",
+            self.keymap.label(action),
+            Self::format_duration(elapsed)
+        ));
+        false
+    }
+
+    // Keeps working on a session that just finished, even after auto mode has
+    // moved on to the break. The extra time is added to that session's record
+    fn snooze(&mut self) {
+        if !self.snoozable {
+            return;
         }
+        let minutes = self.snooze_minutes;
+        self.start_timer(TimerType::Work, Duration::from_secs(minutes as u64 * 60));
+        self.extending = true;
+        self.toasts.info(format!("Snoozed: +{minutes} min"));
+    }
+
+    // Without a break length the configured one goes with the custom work
+    fn start_custom_session(&mut self, work: Duration, brk: Option<Duration>) {
+        self.custom = Some((work, brk.unwrap_or(self.break_duration)));
         self.start_work_session();
     }
 
     fn show_custom_input_dialog(&mut self) {
         self.show_custom_input = true;
         self.custom_input.clear();
+        self.custom_error = None;
     }
 
     fn hide_custom_input_dialog(&mut self) {
         self.show_custom_input = false;
         self.custom_input.clear();
+        self.custom_error = None;
     }
 
     fn parse_and_start_custom_timer(&mut self) {
@@ -141,96 +751,697 @@ impl PomodoroTimer {
 
         let result = self.parse_custom_input(input);
         match result {
-            Ok((work_mins, break_mins)) => {
+            Ok((work, brk)) => {
                 self.hide_custom_input_dialog();
-                self.start_custom_session(work_mins, break_mins);
-            }
-            Err(_) => {
-                // Invalid input - keep dialog open for correction
-                // In a full implementation, could show error message
+                self.start_custom_session(work, brk);
             }
+            // The dialog stays open for a correction
+            Err(e) => self.custom_error = Some(e),
         }
     }
 
-    fn parse_custom_input(&self, input: &str) -> Result<(u32, Option<u32>), String> {
-        if input.contains(',') {
-            // Format: "work,break" (e.g., "30,10")
-            let parts: Vec<&str> = input.split(',').collect();
-            if parts.len() != 2 {
-                return Err("Invalid format. Use 'work,break' or just 'work'".to_string());
-            }
-
-            let work_mins = parts[0].trim().parse::<u32>().map_err(|_| "Invalid work minutes")?;
-            let break_mins = parts[1].trim().parse::<u32>().map_err(|_| "Invalid break minutes")?;
-
-            if work_mins == 0 || break_mins == 0 {
-                return Err("Minutes must be greater than 0".to_string());
+    // "work,break" or just "work", each as cli::parse_duration takes them and
+    // no longer than timer.max_session_minutes
+    fn parse_custom_input(&self, input: &str) -> Result<(Duration, Option<Duration>), String> {
+        let length = |text: &str, what: &str| {
+            let duration = cli::parse_duration(text.trim())?;
+            if duration > self.max_session {
+                return Err(format!(
+                    "{what} can be {} minutes at most (timer.max_session_minutes)",
+                    self.max_session.as_secs() / 60
+                ));
             }
-
-            Ok((work_mins, Some(break_mins)))
-        } else {
-            // Format: "work" (e.g., "20") - use default 5min break
-            let work_mins = input.parse::<u32>().map_err(|_| "Invalid work minutes")?;
-
-            if work_mins == 0 {
-                return Err("Minutes must be greater than 0".to_string());
-            }
-
-            Ok((work_mins, None)) // Will use default 5min break
+            Ok(duration)
+        };
+        match input.split_once(',') {
+            Some((work, brk)) => Ok((length(work, "Work")?, Some(length(brk, "A break")?))),
+            None => Ok((length(input, "Work")?, None)),
         }
     }
 
     fn toggle_timer(&mut self) {
-        if self.current_session.is_running {
-            self.pause_timer();
+        if self.current_session.pause(self.time_scale) {
+            self.toasts.info("Paused");
+            self.emit_event("pause");
+            self.show_pause_prompt = self.pauses.ask_reason && !self.pauses.reasons.is_empty() && self.current_session.timer_type == TimerType::Work;
+        } else if self.current_session.resume() {
+            self.toasts.info("Resumed");
+            self.emit_event("resume");
         } else {
-            self.resume_timer();
+            // A finished session can't be picked up again
+            self.toasts.info(format!(
+                "Session over - {} starts work, {} a break",
+                self.keymap.label(Action::StartWork),
+                self.keymap.label(Action::StartBreak)
+            ));
         }
     }
 
     fn pause_timer(&mut self) {
-        if self.current_session.is_running {
-            if let Some(start_time) = self.current_session.start_time {
-                self.current_session.elapsed += start_time.elapsed();
-            }
-            self.current_session.is_running = false;
-            self.current_session.start_time = None;
+        if self.current_session.pause(self.time_scale) {
+            self.emit_event("pause");
         }
     }
 
     fn resume_timer(&mut self) {
-        if !self.current_session.is_running {
-            self.current_session.is_running = true;
-            self.current_session.start_time = Some(Instant::now());
+        if self.current_session.resume() {
+            self.emit_event("resume");
         }
     }
 
-    fn complete_session(&mut self) {
-        self.completed_sessions += 1;
-        self.play_notification();
+    // Tells scripts and plugins what the session just did
+    fn emit_event(&mut self, name: &str) {
+        let (elapsed, total) = self.get_timer_progress();
+        let event = scripts::Event {
+            timer_type: match self.current_session.timer_type {
+                TimerType::Work => "work",
+                TimerType::Break => "break",
+            },
+            duration_secs: total.as_secs(),
+            remaining_secs: total.saturating_sub(elapsed).as_secs(),
+            task: self.task.clone(),
+            tags: self.tags.clone(),
+        };
+        self.scripts.emit(name, &event);
+        self.plugins.emit(name, &event);
+    }
 
-        // Show Mario animation for work session completion
-        if matches!(self.current_session.timer_type, TimerType::Work) {
-            self.show_mario_animation = true;
-            self.mario_animation = MarioAnimation::new();
-            self.mario_animation.start();
+    // Carries out what script handlers asked for since the last tick
+    fn update_scripts(&mut self) {
+        let commands: Vec<ScriptCommand> = self.scripts.commands().collect();
+        for command in commands {
+            match command {
+                ScriptCommand::Notify(title, body) => self.notifier.send(&title, &body, Vec::new()),
+                ScriptCommand::Toast(text) => self.toasts.info(text),
+                ScriptCommand::Status(text) => self.script_status = Some(text).filter(|text| !text.is_empty()),
+                ScriptCommand::Work(None) => self.start_work_session(),
+                ScriptCommand::Work(Some(length)) => self.start_timer(TimerType::Work, length),
+                ScriptCommand::Break(None) => self.start_break_session(),
+                ScriptCommand::Break(Some(length)) => self.start_timer(TimerType::Break, length),
+                ScriptCommand::Pause => self.pause_timer(),
+                ScriptCommand::Resume => self.resume_timer(),
+            }
+        }
+    }
+
+    fn complete_session(&mut self) {
+        self.emit_event("complete");
+        let is_work = self.current_session.timer_type == TimerType::Work;
+        let extended = self.extending;
+        // A snooze lengthens the session it extends rather than counting as another
+        let record_id = if extended {
+            self.overtime += self.current_session.duration;
+            self.extend_last_work()
+        } else {
+            self.completed_sessions += 1;
+            if is_work {
+                self.cycle += 1;
+            }
+            self.record_session()
+        };
+        if is_work && record_id.is_some() {
+            self.update_achievements();
+            self.rescue_cat();
+        }
+        self.next_quote();
+        let template = match self.current_session.timer_type {
+            TimerType::Work => &self.templates.work_complete,
+            TimerType::Break => &self.templates.break_complete,
+        };
+        let title = template::render(template, &self.status());
+        let speech = match self.current_session.timer_type {
+            TimerType::Work => {
+                let finished = if extended { "Snooze" } else { "Work session" };
+                let minutes = (self.next_break_duration().as_secs() / 60).max(1);
+                format!("{finished} complete. Take a {minutes} minute break.")
+            }
+            TimerType::Break => "Break is over. Time to focus.".to_string(),
+        };
+        let notice = Notice {
+            event: NotifyEvent::Complete,
+            title,
+            body: self.message.clone().unwrap_or_default(),
+            speech,
+            buttons: self.notification_buttons(is_work),
+        };
+        self.notify(notice, Self::play_notification);
+
+        // Show Mario animation for work session completion, fireworks for the one reaching the daily goal
+        if is_work && !extended {
+            if record_id.is_some() && self.reached_daily_goal() {
+                self.play_scene(Scene::Fireworks);
+            } else if self.golden_tomato() {
+                self.play_scene(Scene::GoldenTomato);
+            } else {
+                self.play_scene(Scene::Mario);
+            }
         }
 
+        let meeting = self.meeting_during_work().filter(|_| !is_work && self.calendar.blocks_auto_start());
         match (&self.current_session.timer_type, &self.mode) {
-            (TimerType::Work, TimerMode::Auto) => {
+            (TimerType::Work, TimerMode::Auto) if self.auto_start_break => {
                 // Auto mode: switch to break after work
                 self.start_break_session();
             }
-            (TimerType::Break, TimerMode::Auto) => {
+            (TimerType::Break, TimerMode::Auto) if self.auto_start_work && meeting.is_none() => {
                 // Auto mode: switch to work after break
                 self.start_work_session();
             }
             _ => {
-                // Manual mode: stop timer
-                self.current_session.is_running = false;
-                self.current_session.start_time = None;
+                // Manual mode, or auto mode waiting for a key: stop timer, ringing on in nag mode
+                self.current_session.finish(self.nag_seconds > 0);
+                if let Some(event) = &meeting
+                    && self.mode == TimerMode::Auto
+                {
+                    self.toasts.error(format!("Not starting work: {event}"));
+                }
+                if self.nag_seconds > 0 {
+                    self.pending_ack = Some(PendingAck {
+                        last_nag: Instant::now(),
+                        record_id: record_id.clone(),
+                    });
+                }
+            }
+        }
+        // Whatever auto mode started holds still until the animation is over,
+        // rather than eating into the break unwatched
+        if self.hold_for_animation && self.is_animating() && self.current_session.is_running() {
+            let session = &self.current_session;
+            self.current_session = PomodoroSession::new(session.timer_type.clone(), session.duration);
+            self.held_for_animation = true;
+        }
+        // Set after the auto switch, which would clear it
+        self.snoozable = is_work;
+        self.last_work_record = record_id.filter(|_| is_work);
+    }
+
+    // Adds the finished snooze to the work session it extended
+    fn extend_last_work(&mut self) -> Option<String> {
+        let id = self.last_work_record.clone()?;
+        let mut record = self.history.get(&id).cloned()?;
+        let secs = self.current_session.duration.as_secs();
+        record.duration_secs += secs;
+        record.extended_secs += secs;
+        // Other tools see the snooze as its own interval
+        let started_at = self.current_session.started_at.map(history::unix_secs).unwrap_or_else(history::now_secs);
+        self.export_session(&SessionRecord {
+            started_at,
+            duration_secs: secs,
+            ..record.clone()
+        });
+        self.history.update(record).ok().map(|_| id)
+    }
+
+    // Id of the appended record, if there is one
+    fn record_session(&mut self) -> Option<String> {
+        // Sped-up debug runs would flood the real history with fake sessions
+        if self.time_scale != 1.0 {
+            return None;
+        }
+        let started_at = self.current_session.started_at.map(history::unix_secs).unwrap_or_else(history::now_secs);
+        let mut record = self
+            .history
+            .new_record(self.current_session.timer_type.clone(), started_at, self.current_session.duration.as_secs());
+        if self.current_session.timer_type == TimerType::Work {
+            record.task = self.task.clone();
+            record.tags = self.tags.clone();
+            record.app = self.app_sampler.take();
+            record.away_secs = self.current_session.away.as_secs();
+            record.pauses = self.current_session.pauses.clone();
+            auto_tags::apply(&self.auto_tags, &mut record);
+        }
+        self.export_session(&record);
+        // History is best-effort: a read-only or missing sync folder must not stop the timer
+        let id = record.id.clone();
+        self.history.append(record).ok().map(|_| id)
+    }
+
+    // Logs a work session done away from the computer as if it had just
+    // finished, so the day's count and streaks stay honest
+    fn add_session(&mut self) {
+        let duration = self.work_duration.as_secs();
+        let mut record = self.history.new_record(TimerType::Work, history::now_secs().saturating_sub(duration), duration);
+        record.task = self.task.clone();
+        record.tags = self.tags.clone();
+        record.manual = true;
+        auto_tags::apply(&self.auto_tags, &mut record);
+        self.export_session(&record);
+        match self.history.append(record) {
+            Ok(()) => {
+                self.completed_sessions += 1;
+                let today = self.history.work_today().count();
+                self.toasts.info(format!("Logged a {} min session ({today} today)", duration / 60));
+                self.update_achievements();
+                if self.reached_daily_goal() {
+                    self.play_scene(Scene::Fireworks);
+                }
+            }
+            Err(e) => self.toasts.error(format!("Could not log the session: {e}")),
+        }
+    }
+
+    // Takes back today's latest work session, logged or timed
+    fn remove_session(&mut self) {
+        let Some(record) = self.history.work_today().next_back().cloned() else {
+            self.toasts.info("No work sessions today");
+            return;
+        };
+        match self.history.delete(&record.id) {
+            Ok(()) => {
+                self.completed_sessions = self.completed_sessions.saturating_sub(1);
+                if self.last_work_record.as_ref() == Some(&record.id) {
+                    self.last_work_record = None;
+                    self.snoozable = false;
+                }
+                let today = self.history.work_today().count();
+                self.toasts.info(format!("Removed a {} min session ({today} today)", record.duration_secs / 60));
+            }
+            Err(e) => self.toasts.error(format!("Could not remove the session: {e}")),
+        }
+    }
+
+    // Hands a finished session to the other tools that track time
+    fn export_session(&self, record: &SessionRecord) {
+        self.activity_watch.push(record);
+        self.time_tracker.push(record);
+    }
+
+    fn report_integration_errors(&mut self) {
+        let errors = self
+            .activity_watch
+            .errors()
+            .chain(self.time_tracker.errors())
+            .chain(self.calendar.errors())
+            .chain(self.room.errors())
+            .chain(self.speaker.errors())
+            .chain(self.webhooks.errors())
+            .chain(self.scripts.errors())
+            .chain(self.plugins.errors());
+        for error in errors {
+            self.toasts.error(error);
+        }
+    }
+
+    fn update_meeting(&mut self) {
+        let working = self.current_session.is_running() && self.current_session.timer_type == TimerType::Work;
+        if !working {
+            self.show_meeting_prompt = false;
+        }
+        if !self.meeting.tick(working && self.focus.meetings != MeetingAction::Off) {
+            return;
+        }
+        match self.focus.meetings {
+            MeetingAction::Pause => {
+                self.pause_timer();
+                self.current_session.set_pause_reason("call");
+                self.toasts.info("Call detected - work paused");
+            }
+            MeetingAction::Ask => self.show_meeting_prompt = true,
+            MeetingAction::Off => {}
+        }
+    }
+
+    // Back from a break spent locked out: work starts if the break is over
+    fn update_lock(&mut self) {
+        if !self.screen_lock.tick() || self.current_session.timer_type != TimerType::Break {
+            return;
+        }
+        if self.current_session.is_finished() {
+            self.start_work_session();
+            self.toasts.info("Welcome back - work started");
+        } else if self.current_session.is_running() {
+            let (elapsed, duration) = self.get_timer_progress();
+            let left = Self::format_duration(duration.saturating_sub(elapsed));
+            self.toasts.info(format!("Welcome back - {left} of the break left"));
+        }
+    }
+
+    // Pauses work once the terminal has been in the background for a while
+    // and resumes it on return, logging the time away with the session
+    fn update_away(&mut self) {
+        if let Some(since) = self.away_since
+            && self.terminal_focused == Some(true)
+        {
+            self.away_since = None;
+            // Unless the session was resumed or replaced in the meantime
+            if self.current_session.is_paused() {
+                let away = self.scaled_elapsed(since);
+                self.current_session.away += away;
+                self.resume_timer();
+                self.toasts.info(format!("Welcome back - resumed after {}m away", away.as_secs().div_ceil(60)));
+            }
+            return;
+        }
+        let working = self.current_session.timer_type == TimerType::Work && self.current_session.is_running();
+        let grace = Duration::from_secs(self.focus.away_seconds as u64);
+        if self.focus.pause_when_away && working && self.away_since.is_none() && self.focus_lost_at.is_some_and(|lost| lost.elapsed() >= grace) {
+            self.pause_timer();
+            self.current_session.set_pause_reason("away");
+            self.away_since = Some(Instant::now());
+            self.toasts.info("Terminal left - work paused");
+        }
+    }
+
+    fn update_nag(&mut self) {
+        if let Some(pending) = &mut self.pending_ack
+            && pending.last_nag.elapsed() >= Duration::from_secs(self.nag_seconds as u64)
+        {
+            pending.last_nag = Instant::now();
+            self.audio_manager.play_nag_chime();
+        }
+    }
+
+    // Any key stops the nag; the delay is kept with the session
+    fn acknowledge(&mut self) {
+        let Some(pending) = self.pending_ack.take() else {
+            return;
+        };
+        let rang = self.current_session.acknowledge();
+        if let Some(rang) = rang
+            && self.current_session.timer_type == TimerType::Work
+        {
+            self.overtime += rang;
+        }
+        if let (Some(rang), Some(mut record)) = (rang, pending.record_id.and_then(|id| self.history.get(&id).cloned())) {
+            record.ack_secs = Some(rang.as_secs());
+            let _ = self.history.update(record);
+        }
+    }
+
+    // Actions from `cyber-tomato remote`, named like the [keys] actions
+    fn remote_command(&mut self, command: &str) -> Result<(), String> {
+        match command {
+            "start_work" => self.start_work_session(),
+            "start_break" => self.start_break_session(),
+            "pause" if self.current_session.is_running() => self.toggle_timer(),
+            "resume" if self.current_session.is_paused() => self.toggle_timer(),
+            "pause" | "resume" => {}
+            "toggle_pause" => self.toggle_timer(),
+            "snooze" if self.snoozable => self.snooze(),
+            "snooze" => return Err("No finished work session to snooze".to_string()),
+            "next_quote" => self.next_quote(),
+            "add_session" => self.add_session(),
+            "remove_session" => self.remove_session(),
+            _ => return Err(format!("Unknown command: {command}")),
+        }
+        Ok(())
+    }
+
+    // When the next session waits for a key, the notification can start it,
+    // or snooze a finished work session. Clicks come back as remote commands
+    fn notification_buttons(&self, is_work: bool) -> Vec<Button> {
+        let auto_start = if is_work { self.auto_start_break } else { self.auto_start_work };
+        if self.mode == TimerMode::Auto && auto_start {
+            return Vec::new();
+        }
+        let button = |label: String, command| Button { label, command };
+        if is_work {
+            vec![
+                button("Start break".to_string(), "start_break"),
+                button(format!("Snooze {}m", self.snooze_minutes), "snooze"),
+            ]
+        } else {
+            vec![button("Start work".to_string(), "start_work")]
+        }
+    }
+
+    fn next_quote(&mut self) {
+        self.message = self.quotes.as_mut().map(|quotes| quotes.next().to_string());
+    }
+
+    // The week so far, on demand; only finished weeks are written down
+    fn show_review(&mut self) {
+        self.history.reload();
+        let today = Local::now().date_naive();
+        let review = review::summarize(self.history.records(), today, self.goals.weekly_pomodoros);
+        self.review = Some((review, true));
+    }
+
+    // Last week's review, the first time the timer runs in a new week
+    fn update_review(&mut self) {
+        let today = Local::now().date_naive();
+        let week = review::week_id(today);
+        if !self.goals.weekly_review || self.review_checked.as_ref() == Some(&week) {
+            return;
+        }
+        self.review_checked = Some(week);
+        let last_week = today - chrono::Duration::days(7);
+        if self.reviews.has(&review::week_id(last_week)) {
+            return;
+        }
+        self.history.reload();
+        let review = review::summarize(self.history.records(), last_week, self.goals.weekly_pomodoros);
+        // Nothing to look back on
+        if review.pomodoros == 0 {
+            return;
+        }
+        if let Err(e) = self.reviews.append(review.clone()) {
+            self.toasts.error(format!("Could not save the weekly review: {e}"));
+        }
+        self.review = Some((review, false));
+    }
+
+    fn is_animating(&self) -> bool {
+        self.show_mario_animation || self.fireworks.is_some()
+    }
+
+    fn update_animations(&mut self) {
+        if self.show_mario_animation {
+            self.mario_animation.update();
+            if self.mario_animation.is_finished() {
+                self.show_mario_animation = false;
+            }
+        }
+        if let Some(fireworks) = &mut self.fireworks {
+            fireworks.update();
+            if fireworks.is_finished() {
+                self.fireworks = None;
             }
         }
+        // Also covers an animation dismissed with a key since the last call
+        if self.held_for_animation && !self.is_animating() {
+            self.held_for_animation = false;
+            self.current_session.resume();
+        }
+    }
+
+    // Starts a completion animation, which the replay key then plays again
+    fn play_scene(&mut self, scene: Scene) {
+        self.last_scene = scene;
+        match scene {
+            Scene::Mario => self.start_mario_animation(false),
+            Scene::GoldenTomato => self.start_mario_animation(true),
+            Scene::Fireworks => self.start_fireworks(),
+        }
+    }
+
+    fn start_mario_animation(&mut self, golden: bool) {
+        self.show_mario_animation = true;
+        self.mario_animation = MarioAnimation::new(self.audio.clone(), self.mario_waveform);
+        self.mario_animation.set_golden(golden);
+        self.mario_animation.start();
+    }
+
+    fn golden_tomato(&self) -> bool {
+        self.achievements.enabled && self.achievements.golden_tomato && self.trophies.has_reward(Reward::GoldenTomato)
+    }
+
+    // Whether the latest work session was the one that reached the daily goal
+    fn reached_daily_goal(&self) -> bool {
+        let goal = self.goals.daily_pomodoros;
+        goal > 0 && self.history.work_today().count() == goal as usize
+    }
+
+    fn start_fireworks(&mut self) {
+        let goal = self.goals.daily_pomodoros;
+        let message = match goal {
+            // Only played from the gallery without a goal
+            0 => "Daily goal reached!".to_string(),
+            1 => "Daily goal reached: 1 pomodoro!".to_string(),
+            goal => format!("Daily goal reached: {goal} pomodoros!"),
+        };
+        let mut fireworks = Fireworks::new(self.audio.clone(), self.mario_waveform, message);
+        fireworks.start();
+        self.fireworks = Some(fireworks);
+    }
+
+    fn apply_theme(&self) {
+        let unlocked = match &self.achievements.theme {
+            Some(name) if self.achievements.enabled => self.trophies.theme(name),
+            _ => None,
+        };
+        theme::set(unlocked.unwrap_or_else(|| self.theme.clone()));
+    }
+
+    // Unlocks what the history has newly earned; called when it gains a work session
+    fn update_achievements(&mut self) {
+        if !self.achievements.enabled {
+            return;
+        }
+        match self.trophies.unlock(self.history.records()) {
+            Ok(new) if new.is_empty() => {}
+            Ok(new) => {
+                let names: Vec<&str> = new.iter().map(|a| a.name).collect();
+                let key = self.keymap.label(Action::Achievements);
+                self.toasts.info(format!("Achievement unlocked: {} ({key} to see)", names.join(", ")));
+                // A theme picked ahead of time takes effect as soon as it's earned
+                self.apply_theme();
+            }
+            Err(e) => self.toasts.error(format!("Could not save achievements: {e}")),
+        }
+    }
+
+    fn rescue_cat(&mut self) {
+        if !self.cats_config.enabled {
+            return;
+        }
+        match self.cats.rescue(self.cats_config.chance_percent) {
+            Ok(Some(cat)) => {
+                let message = format!(
+                    "You rescued {}, a {} cat! ({} to see)",
+                    cat.name,
+                    cat.breed().name,
+                    self.keymap.label(Action::Cats)
+                );
+                self.toasts.info(message);
+            }
+            Ok(None) => {}
+            Err(e) => self.toasts.error(format!("Could not save the cat: {e}")),
+        }
+    }
+
+    fn show_stats_screen(&mut self) {
+        // Pick up sessions synced from other machines since startup
+        self.history.reload();
+        self.show_stats = true;
+    }
+
+    fn toggle_help(&mut self) {
+        self.help.open();
+        self.show_controls_popup = !self.show_controls_popup;
+    }
+
+    // Keys for the open help screen; false leaves the key to the keymap, so
+    // quitting and suspending still work with it open
+    fn help_key(&mut self, key: KeyEvent) -> bool {
+        let action = self.keymap.action(key);
+        if !self.help.is_searching() && key.code != KeyCode::Esc && matches!(action, Some(Action::Quit | Action::Suspend)) {
+            return false;
+        }
+        self.show_controls_popup = self.help.handle_key(key, action);
+        true
+    }
+
+    // What each action does, for the help screen, in Action::ALL order
+    fn help_entries(&self) -> Vec<(Action, String)> {
+        Action::ALL
+            .iter()
+            .map(|&action| {
+                let description = match action {
+                    Action::StartWork => format!("Start {} mins Work", self.work_duration.as_secs() / 60),
+                    Action::StartBreak => format!("Start {} mins Break", self.break_duration.as_secs() / 60),
+                    Action::CustomTimer => "Custom timer".to_string(),
+                    Action::ResetDurations => "Back to the configured session lengths".to_string(),
+                    Action::TogglePause => "Pause/Resume timer".to_string(),
+                    Action::Snooze => format!("Snooze finished work +{} mins", self.snooze_minutes),
+                    Action::ToggleMode => "Toggle Manual/Auto mode".to_string(),
+                    Action::ToggleClock => "Digits: time left, time spent or end time".to_string(),
+                    Action::Techniques => "Techniques: Classic, DeskTime, Ultradian...".to_string(),
+                    Action::Label => "Label task/tags".to_string(),
+                    Action::Plan => "Plan tasks and estimates".to_string(),
+                    Action::Alarms => "Alarms at set times".to_string(),
+                    Action::Stats => "Stats".to_string(),
+                    Action::History => "History (edit/delete)".to_string(),
+                    Action::Review => "Weekly review: goal vs. actual".to_string(),
+                    Action::AddSession => format!("Log a {} min session done away from the timer", self.work_duration.as_secs() / 60),
+                    Action::RemoveSession => "Remove today's latest work session".to_string(),
+                    Action::NextQuote => "Next quote".to_string(),
+                    Action::Mario => "Replay the last animation".to_string(),
+                    Action::Gallery => "Animation gallery".to_string(),
+                    Action::Preview => "Preview completion sound".to_string(),
+                    Action::Achievements => "Achievements and what they unlock".to_string(),
+                    Action::Cats => "Rescued cats".to_string(),
+                    Action::Suspend => "Suspend to the shell (fg resumes)".to_string(),
+                    Action::FrameStats => "Frame budget overlay, for debugging".to_string(),
+                    Action::Help => "Show/hide this help".to_string(),
+                    Action::Quit => "Exit application".to_string(),
+                };
+                (action, description)
+            })
+            .collect()
+    }
+
+    fn show_history_browser(&mut self) {
+        self.history.reload();
+        self.history_browser.open();
+        self.show_history = true;
+    }
+
+    fn show_task_pane(&mut self) {
+        self.history.reload();
+        self.task_pane.open();
+        self.show_tasks = true;
+    }
+
+    fn show_alarm_pane(&mut self) {
+        self.alarm_pane.open();
+        self.show_alarms = true;
+    }
+
+    fn select_task(&mut self, task: tasks::Task) {
+        self.task = Some(task.name);
+        self.tags = task.tags;
+        self.show_tasks = false;
+    }
+
+    fn show_label_input_dialog(&mut self) {
+        self.show_label_input = true;
+        self.label_input = self.label_text();
+    }
+
+    fn hide_label_input_dialog(&mut self) {
+        self.show_label_input = false;
+        self.label_input.clear();
+    }
+
+    // "Write report #work #writing" -> task "Write report", tags [work, writing]
+    fn apply_label_input(&mut self) {
+        let (task, tags) = parse_label(&self.label_input);
+        self.task = task;
+        self.tags = tags;
+        self.hide_label_input_dialog();
+    }
+
+    fn label_text(&self) -> String {
+        let mut parts: Vec<String> = self.task.iter().cloned().collect();
+        parts.extend(self.tags.iter().map(|tag| format!("#{tag}")));
+        parts.join(" ")
+    }
+
+    // What comes after this session: "Next: Break 5m in 12:30" when auto mode
+    // will start it by itself, "Next: Break 5m" when it waits for a key
+    fn next_session(&self) -> Option<String> {
+        let session = &self.current_session;
+        if session.state == SessionState::Idle || self.held_for_animation {
+            return None;
+        }
+        // The running pomodoro counts toward a long break once it's done
+        let ahead = u32::from(!session.is_finished() && !self.extending);
+        let (next, duration, auto_start) = match session.timer_type {
+            TimerType::Work if self.long_break_due(ahead) => ("Long break", self.long_break_duration, self.auto_start_break),
+            TimerType::Work => ("Break", self.next_break_duration(), self.auto_start_break),
+            TimerType::Break => ("Work", self.next_work_duration(), self.auto_start_work),
+        };
+        let length = match duration.as_secs() {
+            secs if secs % 60 == 0 => format!("{}m", secs / 60),
+            secs => format!("{}m{}s", secs / 60, secs % 60),
+        };
+        let mut text = format!("Next: {next} {length}");
+        if self.mode == TimerMode::Auto && auto_start && !session.is_finished() {
+            let (elapsed, total) = self.get_timer_progress();
+            text.push_str(&format!(" in {}", clock::minutes(total.saturating_sub(elapsed).as_secs(), ':')));
+        }
+        Some(text)
     }
 
     fn toggle_mode(&mut self) {
@@ -238,23 +1449,20 @@ impl PomodoroTimer {
             TimerMode::Manual => TimerMode::Auto,
             TimerMode::Auto => TimerMode::Manual,
         };
+        self.toasts.info(match self.mode {
+            TimerMode::Auto => "Auto mode",
+            TimerMode::Manual => "Manual mode",
+        });
     }
 
-    fn get_timer_progress(&self) -> (Duration, Duration) {
-        let current_elapsed = if self.current_session.is_running {
-            if let Some(start_time) = self.current_session.start_time {
-                self.current_session.elapsed + start_time.elapsed()
-            } else {
-                self.current_session.elapsed
-            }
-        } else {
-            self.current_session.elapsed
-        };
+    fn scaled_elapsed(&self, start_time: Instant) -> Duration {
+        start_time.elapsed().mul_f64(self.time_scale)
+    }
 
-        (current_elapsed, self.current_session.duration)
+    fn get_timer_progress(&self) -> (Duration, Duration) {
+        (self.current_session.elapsed(self.time_scale), self.current_session.duration)
     }
 
-    #[allow(dead_code)]
     fn format_duration(duration: Duration) -> String {
         let total_seconds = duration.as_secs();
         let minutes = total_seconds / 60;
@@ -262,13 +1470,321 @@ impl PomodoroTimer {
         format!("{minutes:02}:{seconds:02}")
     }
 
-    fn play_notification(&self) {
-        match self.current_session.timer_type {
+    // Sends `notice` down the channels of the first [[notify]] rule that
+    // matches, or without one the built-in ones: `sound`, speech when [speech]
+    // is on (budgets aren't spoken), and a desktop notification unless the
+    // terminal has focus and that's asked to hold it back
+    fn notify(&mut self, notice: Notice, sound: impl FnOnce(&mut Self)) {
+        let session = self.current_session.timer_type.clone();
+        let now = Local::now().naive_local();
+        let (channels, webhook) = match routing::route(&self.notify_rules, notice.event, &session, self.terminal_focused, now) {
+            Some(rule) => (rule.channels.clone(), rule.webhook.clone()),
+            None => {
+                // Someone looking at the timer already knows
+                let watching = self.suppress_when_focused && self.terminal_focused == Some(true);
+                let mut channels = vec![Channel::Sound];
+                if self.speaker.enabled() && notice.event != NotifyEvent::Budget {
+                    channels.push(Channel::Speech);
+                }
+                if self.desktop_notifications && !watching {
+                    channels.push(Channel::Desktop);
+                }
+                (channels, None)
+            }
+        };
+        if channels.contains(&Channel::Sound) {
+            sound(self);
+        }
+        if channels.contains(&Channel::Desktop) {
+            self.notifier.send(&notice.title, &notice.body, notice.buttons);
+        }
+        if channels.contains(&Channel::Speech) {
+            self.speaker.say(&notice.speech);
+        }
+        if let Some(url) = webhook.filter(|_| channels.contains(&Channel::Webhook)) {
+            self.webhooks.post(&url, notice.event, &session, &notice.title, &notice.body);
+        }
+        if channels.contains(&Channel::Bell) {
+            routing::bell();
+        }
+    }
+
+    fn play_notification(&mut self) {
+        let played = match self.current_session.timer_type {
             TimerType::Work => self.audio_manager.play_work_complete_sound(),
             TimerType::Break => {
                 // Play the combined notification + music sequence for break completion
-                self.audio_manager.play_break_complete_music();
+                self.audio_manager.play_break_complete_music()
+            }
+        };
+        if !played {
+            self.toasts.error("Audio device unavailable — muted");
+        }
+    }
+
+    // Notes focus changes and resizes; key presses are handed back to the loop
+    fn take_key(&mut self, event: Event) -> Option<KeyEvent> {
+        match event {
+            Event::Key(key) => return Some(key),
+            Event::FocusGained => {
+                self.terminal_focused = Some(true);
+                self.focus_lost_at = None;
             }
+            Event::FocusLost => {
+                self.terminal_focused = Some(false);
+                self.focus_lost_at = Some(Instant::now());
+            }
+            Event::Resize(..) => self.resized = true,
+            _ => {}
+        }
+        None
+    }
+
+    // Plays what the session on the clock will end with, to try out sounds
+    // and melody pools without waiting for it
+    fn preview_sound(&mut self) {
+        let sound = match self.current_session.timer_type {
+            TimerType::Work => "work",
+            TimerType::Break => "break",
+        };
+        self.toasts.info(format!("Previewing the {sound} complete sound"));
+        self.play_notification();
+    }
+
+    // Whole seconds left (rounded up) while inside the final countdown
+    fn countdown_seconds_left(&self) -> Option<u64> {
+        if !self.current_session.is_running() || self.countdown.seconds == 0 {
+            return None;
+        }
+        let (elapsed, total) = self.get_timer_progress();
+        let remaining = total.saturating_sub(elapsed);
+        let secs = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+        (secs > 0 && secs <= self.countdown.seconds as u64).then_some(secs)
+    }
+
+    // Writes the state `cyber-tomato watch` mirrors whenever it changes, and
+    // at least every heartbeat so followers can spot a dead instance
+    fn publish_state(&mut self) {
+        let state = SharedState {
+            timer_type: self.current_session.timer_type.clone(),
+            duration_ms: self.current_session.duration.as_millis() as u64,
+            elapsed_ms: self.get_timer_progress().0.as_millis() as u64,
+            running: self.current_session.is_running(),
+            time_scale: self.time_scale,
+            auto: self.mode == TimerMode::Auto,
+            completed_sessions: self.completed_sessions,
+            task: self.task.clone(),
+            tags: self.tags.clone(),
+            message: self.message.clone(),
+            written_at_ms: follow::now_ms(),
+        };
+        if let Some((at, last)) = &self.published
+            && at.elapsed() < follow::HEARTBEAT
+            && last.same_as(&state)
+        {
+            return;
+        }
+        // Followers are a nicety; a failed write must not disturb the timer
+        let _ = follow::write(&state);
+        self.published = Some((Instant::now(), state));
+    }
+
+    fn write_status(&mut self) {
+        let mut status = self.status();
+        status.text = template::render(&self.templates.status, &status);
+        status.away = template::render(&self.templates.away, &status);
+        self.obs.update(&status);
+        self.status_file.update(status);
+    }
+
+    fn status(&self) -> Status {
+        let session = &self.current_session;
+        let state = if session.is_running() {
+            "running"
+        } else if session.is_paused() {
+            "paused"
+        } else if session.is_finished() {
+            "finished"
+        } else {
+            "idle"
+        };
+        let (elapsed, total) = self.get_timer_progress();
+        Status {
+            session: session.timer_type.clone(),
+            state,
+            remaining_secs: total.saturating_sub(elapsed).as_secs(),
+            duration_secs: total.as_secs(),
+            completed_sessions: self.completed_sessions,
+            today: self.history.work_today().count() as u32,
+            daily_goal: self.goals.daily_pomodoros,
+            task: self.task.clone(),
+            tags: self.tags.clone(),
+            text: String::new(),
+            away: String::new(),
+            clock: self.layout.clock,
+        }
+    }
+
+    fn follow(&mut self, state: Option<SharedState>) {
+        let Some(state) = state else {
+            self.current_session = PomodoroSession::new(self.current_session.timer_type.clone(), self.current_session.duration);
+            self.message = Some("Waiting for a running timer...".to_string());
+            return;
+        };
+        if state.completed_sessions != self.completed_sessions {
+            // Keep the sessions panel in step with the sessions the instance records
+            self.history.reload();
+        }
+        self.current_session = PomodoroSession::mirror(
+            state.timer_type.clone(),
+            Duration::from_millis(state.duration_ms),
+            state.elapsed(),
+            state.running,
+        );
+        self.time_scale = state.time_scale;
+        self.mode = if state.auto { TimerMode::Auto } else { TimerMode::Manual };
+        self.completed_sessions = state.completed_sessions;
+        self.task = state.task;
+        self.tags = state.tags;
+        self.message = state.message;
+    }
+
+    // A started break shows the breathing guide instead of the digits
+    fn is_breathing(&self) -> bool {
+        self.breaks.screen == BreakScreen::Breathing
+            && self.current_session.timer_type == TimerType::Break
+            && (self.current_session.is_running() || self.current_session.is_paused())
+    }
+
+    fn update_breathing(&mut self) {
+        let phase = self.is_breathing().then(|| breathing::phase_at(self.get_timer_progress().0).0);
+        if self.breaks.breathing_tones
+            && self.current_session.is_running()
+            && phase != self.breath_phase
+            && let Some(freq) = phase.and_then(breathing::Phase::tone)
+        {
+            self.audio_manager.play_soft_tones(&[freq]);
+        }
+        self.breath_phase = phase;
+    }
+
+    fn update_eye_rest(&mut self) {
+        let working = self.current_session.timer_type == TimerType::Work && self.current_session.is_running();
+        let chime: &[f32] = match self.eye_rest.tick(working, self.time_scale) {
+            Some(eye_rest::Event::Start) => &[659.25, 880.0], // E5 A5
+            Some(eye_rest::Event::End) => &[880.0, 659.25],
+            None => return,
+        };
+        if self.eye_rest.chime() {
+            self.audio_manager.play_soft_tones(chime);
+        }
+    }
+
+    // Applies the [focus] settings while work runs and lifts them otherwise
+    fn update_focus(&mut self) {
+        let working = self.current_session.timer_type == TimerType::Work && self.current_session.is_running();
+        if let Err(e) = self.inhibitor.set(self.focus.inhibit_sleep && working) {
+            self.toasts.error(format!("Could not inhibit sleep: {e}"));
+        }
+        // Do-not-disturb stays on through pauses and goes off with the break
+        let work_started = self.current_session.timer_type == TimerType::Work && (working || self.current_session.is_paused());
+        if let Err(e) = self.dnd.set(self.focus.dnd && work_started) {
+            self.toasts.error(format!("Could not switch do-not-disturb: {e}"));
+        }
+        let block: &[String] = if work_started { &self.focus.block_sites } else { &[] };
+        if let Err(e) = self.site_blocker.set(block) {
+            self.toasts.error(format!("Could not block sites: {e}"));
+        }
+    }
+
+    // Ambient sound plays while work runs, and dips under the chimes
+    fn update_ambient(&mut self) {
+        let working = self.current_session.timer_type == TimerType::Work && self.current_session.is_running();
+        self.audio_manager.set_ambient(working);
+        self.audio.tick();
+    }
+
+    fn update_countdown(&mut self) {
+        let secs = self.countdown_seconds_left();
+        if self.countdown.beep && secs.is_some() && secs != self.last_countdown_beep {
+            self.audio_manager.play_countdown_beep();
+        }
+        self.last_countdown_beep = secs;
+    }
+
+    // Toast, tone and title when running work passes one of the [milestones]
+    fn update_milestones(&mut self) {
+        let working = self.current_session.timer_type == TimerType::Work && self.current_session.is_running();
+        if !self.milestones.enabled || !working {
+            self.milestone_elapsed = None;
+            return;
+        }
+        let elapsed = self.get_timer_progress().0;
+        let Some(before) = self.milestone_elapsed.replace(elapsed) else {
+            return;
+        };
+        let Some(label) = milestones::crossed(&self.milestones, self.current_session.duration, before, elapsed) else {
+            return;
+        };
+        self.toasts.info(label.clone());
+        if self.milestones.tone {
+            self.audio_manager.play_soft_tones(&[523.25, 659.25]); // C5 E5
+        }
+        self.milestone = Some((label, Instant::now()));
+    }
+
+    // Alerts once when work on the current task passes its time budget
+    fn update_budget(&mut self) {
+        let working = self.current_session.timer_type == TimerType::Work && self.current_session.is_running();
+        let Some(task) = self.task.as_deref().and_then(|name| self.tasks.get(name)) else {
+            return;
+        };
+        let Some(budget) = task.budget_minutes else {
+            return;
+        };
+        let alerted = self
+            .budget_alerted
+            .as_ref()
+            .is_some_and(|(name, minutes)| *name == task.name && *minutes == budget);
+        if !working || alerted {
+            return;
+        }
+        let secs = tasks::focused_secs(self.history.records(), &task.name) + self.get_timer_progress().0.as_secs();
+        if !tasks::is_over_budget(task, secs) {
+            return;
+        }
+        let text = format!("Time budget for {} used up: {}", task.name, tasks::budget_text(task, secs));
+        self.budget_alerted = Some((task.name.clone(), budget));
+        self.toasts.error(text.clone());
+        let notice = Notice {
+            event: NotifyEvent::Budget,
+            title: "Task over budget".to_string(),
+            body: text.clone(),
+            speech: text,
+            buttons: Vec::new(),
+        };
+        self.notify(notice, |timer| timer.audio_manager.play_nag_chime());
+    }
+
+    // Rings alarms whose time has come, mid-session or not
+    fn update_alarms(&mut self) {
+        let due = self.alarms.due(Local::now().naive_local());
+        for alarm in &due {
+            let text = format!("{} at {}", alarm.label(), alarm.time);
+            self.toasts.info(text.clone());
+            let notice = Notice {
+                event: NotifyEvent::Alarm,
+                title: "Alarm".to_string(),
+                body: text,
+                speech: alarm.label().to_string(),
+                buttons: Vec::new(),
+            };
+            self.notify(notice, |timer| timer.audio_manager.play_alarm());
+        }
+        if due.iter().any(Alarm::is_once)
+            && let Err(e) = self.alarms.save()
+        {
+            self.toasts.error(format!("Could not save alarms: {e}"));
         }
     }
 
@@ -278,171 +1794,414 @@ impl PomodoroTimer {
     }
 }
 
-fn ui(f: &mut Frame, timer: &PomodoroTimer) {
-    // Update terminal title with countdown
-    let (elapsed, total) = timer.get_timer_progress();
-    let remaining = if total > elapsed { total - elapsed } else { Duration::from_secs(0) };
-    let remaining_minutes = remaining.as_secs() / 60;
-    let remaining_seconds = remaining.as_secs() % 60;
+fn open_history(config: &Config, data_dir: &Path) -> io::Result<History> {
+    let mut history = History::open(config.history_dir_in(data_dir), config.history.backend)?;
+    if let Some(months) = config.history.archive_after_months {
+        // Best-effort like every other history write
+        let _ = history.archive(history::archive_cutoff(months));
+    }
+    Ok(history)
+}
 
-    let session_type = match timer.current_session.timer_type {
-        TimerType::Work => "Work",
-        TimerType::Break => "Break",
-    };
+// Tasks and alarms follow a history dir set in the config, so a synced folder
+// carries them too. The first time, the copy in the data dir is taken along
+fn user_file(config: &Config, data_dir: &Path, name: &str) -> PathBuf {
+    let local = data_dir.join(name);
+    if config.history.dir.is_none() {
+        return local;
+    }
+    let path = config.history_dir_in(data_dir).join(name);
+    if !path.exists() && local.exists() {
+        // Best-effort: without the copy the list starts out empty there
+        let _ = fs::create_dir_all(config.history_dir_in(data_dir)).and_then(|_| fs::copy(&local, &path));
+    }
+    path
+}
+
+fn parse_label(input: &str) -> (Option<String>, Vec<String>) {
+    let mut words = Vec::new();
+    let mut tags: Vec<String> = Vec::new();
+    for word in input.split_whitespace() {
+        match word.strip_prefix('#') {
+            Some(tag) if !tag.is_empty() => {
+                if !tags.iter().any(|t| t == tag) {
+                    tags.push(tag.to_string());
+                }
+            }
+            _ => words.push(word),
+        }
+    }
+    let task = if words.is_empty() { None } else { Some(words.join(" ")) };
+    (task, tags)
+}
 
-    let title = format!("CYBER TOMATO - {session_type} {remaining_minutes:02}:{remaining_seconds:02}");
+fn ui(f: &mut Frame, timer: &PomodoroTimer) {
+    // Update terminal title with countdown
+    let mut title = template::render(&timer.templates.title, &timer.status());
+    if let Some((milestone, at)) = &timer.milestone
+        && at.elapsed() < MILESTONE_TITLE
+    {
+        title = format!("{milestone} - {title}");
+    }
     set_terminal_title(&title);
 
+    if let Some(fireworks) = &timer.fireworks {
+        f.render_widget(fireworks.render(f.area()), f.area());
+        return;
+    }
+
     // If Mario animation is active, show it fullscreen
     if timer.show_mario_animation {
         let mario_canvas = timer.mario_animation.render(f.area());
         f.render_widget(mario_canvas, f.area());
+        if let Some(message) = &timer.message {
+            let area = f.area();
+            let line_area = ratatui::layout::Rect::new(area.x, area.bottom().saturating_sub(1), area.width, 1);
+            let quote = Paragraph::new(message.as_str())
+                .style(Style::default().fg(theme::highlight()).add_modifier(Modifier::ITALIC))
+                .alignment(Alignment::Center);
+            f.render_widget(quote, line_area);
+        }
         return;
     }
 
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3), // Title
-            Constraint::Length(7), // ASCII countdown (5 lines + padding)
-            Constraint::Length(3), // Progress bar
-            Constraint::Length(3), // Status
-        ])
-        .split(f.area());
+    let breathing = timer.is_breathing();
+    let braille = timer.layout.font == DigitFont::Braille;
+    let panel_areas = panels::split(f.area(), &timer.layout, timer.message.is_some(), breathing || braille);
+    let area_of = |panel: Panel| panel_areas.iter().find(|(p, _)| *p == panel).map(|(_, area)| *area);
 
     // Title
-    let title = Paragraph::new("CYBER TOMATO")
-        .style(Style::default().fg(PRIMARY_COLOR).add_modifier(Modifier::BOLD))
+    const NAME: &str = "CYBER TOMATO";
+    let title = Paragraph::new(NAME)
+        .style(Style::default().fg(theme::primary()).add_modifier(Modifier::BOLD))
         .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(PRIMARY_COLOR)));
-    f.render_widget(title, chunks[0]);
+        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(theme::primary())));
+    if let Some(area) = area_of(Panel::Title) {
+        f.render_widget(title, area);
+        // Room for an image either side of the name, centered like it
+        let inner = area.inner(Margin::new(1, 1));
+        let (name, gap) = (NAME.len() as u16, graphics::SLOT_WIDTH + 1);
+        if timer.graphics.enabled() && inner.height > 0 && inner.width >= name + 2 * gap {
+            let x = inner.x + (inner.width - name) / 2;
+            graphics::mark_slot(f.buffer_mut(), x - gap, inner.y);
+            graphics::mark_slot(f.buffer_mut(), x + name + 1, inner.y);
+        }
+    }
 
     // ASCII Art Countdown Timer
     let (elapsed, total) = timer.get_timer_progress();
     let remaining = if total > elapsed { total - elapsed } else { Duration::from_secs(0) };
 
-    let remaining_minutes = remaining.as_secs() / 60;
-    let remaining_seconds = remaining.as_secs() % 60;
-    let time_display = format!("{remaining_minutes:02}:{remaining_seconds:02}");
+    let (elapsed_secs, remaining_secs) = (elapsed.min(total).as_secs(), remaining.as_secs());
+    let clock = timer.layout.clock;
+    let paused = timer.current_session.is_paused();
+    // The colon blinks once a second while running and stays lit otherwise
+    let colon = if timer.current_session.is_running() && remaining.subsec_millis() < 500 {
+        ' '
+    } else {
+        ':'
+    };
+    let time_display = clock.digits(elapsed_secs, remaining_secs, colon);
 
-    // Get the session type color
-    let timer_color = match timer.current_session.timer_type {
-        TimerType::Work => PRIMARY_COLOR,
-        TimerType::Break => Color::default(),
+    let progress_ratio = if total.is_zero() {
+        0.0
+    } else {
+        (elapsed.as_secs_f64() / total.as_secs_f64()).min(1.0)
     };
 
-    let countdown_lines = create_time_display_lines(&time_display, timer_color);
+    // The session type's colors, blended along the progress colors if it has them
+    let timer_type = &timer.current_session.timer_type;
+    let timer_color = theme::session_color(timer_type, progress_ratio);
+    let gauge_color = theme::gauge_color(timer_type, progress_ratio);
+    let border_color = theme::border(timer_type);
+
+    // Flash the digits during the final countdown
+    let flash = timer.countdown.pulse && timer.countdown_seconds_left().is_some() && remaining.subsec_millis() >= 500;
+    let digit_color = if flash { Color::Red } else { timer_color };
+
+    // Paused sessions render dimmed and struck through under a PAUSED banner
+    let paused_style = Style::default().add_modifier(Modifier::DIM | Modifier::CROSSED_OUT);
+    let mut countdown_lines = create_time_display_lines(&time_display, digit_color);
+    if paused {
+        countdown_lines = countdown_lines.into_iter().map(|line| line.patch_style(paused_style)).collect();
+    }
+    let banner = if paused {
+        Line::from(Span::styled(
+            " PAUSED ",
+            Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD),
+        ))
+    } else {
+        Line::from("")
+    };
 
-    let countdown_paragraph = Paragraph::new(countdown_lines).alignment(Alignment::Center).block(
-        Block::default()
-            .borders(Borders::ALL)
-            .title("")
-            .border_style(Style::default().fg(PRIMARY_COLOR)),
-    );  
+    let mut digits_block = Block::default()
+        .borders(Borders::ALL)
+        .title(banner)
+        .title_alignment(Alignment::Center)
+        .border_style(Style::default().fg(border_color));
+    if let Some(label) = clock.label()
+        && !breathing
+    {
+        digits_block = digits_block.title_bottom(Line::from(format!(" {label} ")).centered());
+    }
 
-    f.render_widget(countdown_paragraph, chunks[1]);
+    if let Some(area) = area_of(Panel::Digits) {
+        if breathing {
+            // The time moves to the bottom edge under the breathing guide
+            let time = clock.text(elapsed_secs, remaining_secs);
+            let digits_block = digits_block.title_bottom(Line::from(format!(" {time} ")).centered());
+            breathing::render(f, area, digits_block, elapsed, timer_color);
+        } else if braille {
+            let inner = digits_block.inner(area);
+            f.render_widget(digits_block, area);
+            // Too small for braille falls back to the block font
+            if !braille_digits::render(f, inner, &time_display, digit_color) {
+                f.render_widget(Paragraph::new(countdown_lines).alignment(Alignment::Center), inner);
+            } else if paused {
+                f.buffer_mut().set_style(inner, paused_style);
+            }
+        } else {
+            let countdown_paragraph = Paragraph::new(countdown_lines).alignment(Alignment::Center).block(digits_block);
+            f.render_widget(countdown_paragraph, area);
+        }
+    }
 
     // Progress bar
-    let (elapsed, total) = timer.get_timer_progress();
-    let progress_ratio = if total.as_secs() > 0 {
-        (elapsed.as_secs() as f64 / total.as_secs() as f64).min(1.0)
+    // Round down so 100% only shows once the session is really over
+    let progress_label = Span::styled(
+        format!(" {:.0}% ", (progress_ratio * 100.0).floor()),
+        Style::default().fg(gauge_color).bg(Color::default()),
+    );
+
+    let progress_block = Block::default()
+        .borders(Borders::ALL)
+        .title("Progress")
+        .border_style(Style::default().fg(border_color));
+    let progress_style = if paused {
+        Style::default().fg(gauge_color).add_modifier(Modifier::DIM)
     } else {
-        0.0
+        Style::default().fg(gauge_color)
     };
-
-    let progress_label = Span::styled(format!(" {:.0}% ", progress_ratio * 100.0), Style::default().fg(timer_color).bg(Color::default()));
-
-    let progress_bar = Gauge::default()
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Progress")
-                .border_style(Style::default().fg(PRIMARY_COLOR)),
-        )
-        .gauge_style(Style::default().fg(timer_color).bg(Color::default())) 
-        
-        .ratio(progress_ratio)
-        .label(progress_label);
-    f.render_widget(progress_bar, chunks[2]);
+    let progress = progress_bar::Progress {
+        ratio: progress_ratio,
+        total,
+        style: progress_style,
+        label: progress_label,
+        timer_type,
+    };
+    if let Some(area) = area_of(Panel::Progress) {
+        progress_bar::render(f, area, progress_block, progress);
+    }
 
     // Status
-    let mode_text = match timer.mode {
-        TimerMode::Manual => "Manual",
-        TimerMode::Auto => "Auto",
+    let mode_text = match (&timer.mode, timer.auto_start_break, timer.auto_start_work) {
+        (TimerMode::Manual, _, _) => "Manual",
+        (TimerMode::Auto, true, true) => "Auto",
+        (TimerMode::Auto, true, false) => "Auto (breaks only)",
+        (TimerMode::Auto, false, true) => "Auto (work only)",
+        (TimerMode::Auto, false, false) => "Auto (none)",
     };
 
-    let status_text = match timer.current_session.timer_type {
-        TimerType::Work => "Working",
-        TimerType::Break => "On Break",
+    let session = &timer.current_session;
+    let status_text = match (&session.timer_type, paused) {
+        _ if timer.held_for_animation => "Up next",
+        _ if session.state == SessionState::Idle => "Ready",
+        (TimerType::Work, _) if session.is_finished() => "Work done",
+        (TimerType::Break, _) if session.is_finished() => "Break over",
+        (TimerType::Work, false) => "Working",
+        (TimerType::Break, false) => "On Break",
+        (TimerType::Work, true) => "Work paused",
+        (TimerType::Break, true) => "Break paused",
     };
 
-    let status = Paragraph::new(vec![Line::from(vec![
-        Span::raw(format!(
-            "  Mode: {} | Status: {} | Done: {} | ",
-            mode_text, status_text, timer.completed_sessions
-        )),
-        Span::styled("x", Style::default().fg(PRIMARY_COLOR).add_modifier(Modifier::BOLD)),
-        Span::raw(": Help  "),
-    ])])
-    .alignment(Alignment::Left)
-    .block(
-        Block::default()
-            .borders(Borders::ALL)
-            .title("Status")
-            .border_style(Style::default().fg(PRIMARY_COLOR)),
-    );
-    f.render_widget(status, chunks[3]);
+    let icons = icons::current();
+    let icon = if paused { icons.paused } else { icons.session(&session.timer_type) };
+    let mut status_spans = vec![Span::raw(format!(
+        "  Mode: {} | Status: {} {} | Done: {} | ",
+        mode_text, icon, status_text, timer.completed_sessions
+    ))];
+    if timer.watching {
+        status_spans.push(Span::styled("Watching | ", Style::default().fg(Color::Yellow)));
+    }
+    if let Some(next) = timer.next_session() {
+        status_spans.push(Span::raw(format!("{next} | ")));
+    }
+    if let Some((work, brk)) = timer.custom {
+        let custom = format!("Custom {}/{} | ", PomodoroTimer::format_duration(work), PomodoroTimer::format_duration(brk));
+        status_spans.push(Span::styled(custom, Style::default().fg(theme::highlight())));
+    }
+    if timer.time_scale != 1.0 {
+        status_spans.push(Span::styled(format!("Time ×{} | ", timer.time_scale), Style::default().fg(Color::Yellow)));
+    }
+    if timer.goals.daily_pomodoros > 0 {
+        let done = timer.history.work_today().count() as u32;
+        let color = if done >= timer.goals.daily_pomodoros {
+            Color::Green
+        } else {
+            theme::highlight()
+        };
+        let goal = timer.goals.daily_pomodoros;
+        // Dots for goals short enough to draw
+        let text = match icons.dots(done, goal) {
+            dots if dots.is_empty() => format!("Today: {done}/{goal} | "),
+            dots => format!("Today: {dots} {done}/{goal} | "),
+        };
+        status_spans.push(Span::styled(text, Style::default().fg(color)));
+    }
+    if timer.goals.weekly_pomodoros > 0 {
+        let done = review::pomodoros(timer.history.records(), Local::now().date_naive());
+        let color = if done >= timer.goals.weekly_pomodoros {
+            Color::Green
+        } else {
+            theme::highlight()
+        };
+        status_spans.push(Span::styled(
+            format!("Week: {done}/{} | ", timer.goals.weekly_pomodoros),
+            Style::default().fg(color),
+        ));
+    }
+    if let Some(text) = &timer.script_status {
+        status_spans.push(Span::styled(format!("{text} | "), Style::default().fg(theme::highlight())));
+    }
+    let now = history::now_secs();
+    if let Some(counts) = timer.room.counts() {
+        status_spans.push(Span::styled(
+            format!("Room: {} of {} focusing | ", counts.focusing, counts.present),
+            Style::default().fg(theme::highlight()),
+        ));
+    }
+    if let Some(event) = timer.calendar.upcoming(now) {
+        status_spans.push(Span::styled(format!("{} | ", event.describe(now)), Style::default().fg(Color::Yellow)));
+    }
+    let label = timer.label_text();
+    if !label.is_empty() {
+        status_spans.push(Span::styled(label, Style::default().fg(theme::highlight())));
+        // Planned tasks show pomodoros so far against the estimate
+        if let Some(task) = timer.task.as_deref().and_then(|name| timer.tasks.get(name)) {
+            let done = tasks::actual(timer.history.records(), &task.name);
+            let color = if tasks::is_over_estimate(task, done) {
+                tasks::OVER_ESTIMATE_COLOR
+            } else {
+                theme::highlight()
+            };
+            status_spans.push(Span::styled(
+                format!(" [{}]", tasks::progress_text(Some(task), done)),
+                Style::default().fg(color),
+            ));
+            // And time so far against the budget, the running session included
+            if task.budget_minutes.is_some() {
+                let session = &timer.current_session;
+                let running = session.timer_type == TimerType::Work && !session.is_finished();
+                let secs = tasks::focused_secs(timer.history.records(), &task.name) + if running { elapsed.as_secs() } else { 0 };
+                let color = if tasks::is_over_budget(task, secs) {
+                    tasks::OVER_ESTIMATE_COLOR
+                } else {
+                    theme::highlight()
+                };
+                status_spans.push(Span::styled(format!(" [{}]", tasks::budget_text(task, secs)), Style::default().fg(color)));
+            }
+        }
+        status_spans.push(Span::raw(" | "));
+    }
+    status_spans.push(Span::styled(
+        timer.keymap.label(Action::Help),
+        Style::default().fg(theme::primary()).add_modifier(Modifier::BOLD),
+    ));
+    status_spans.push(Span::raw(": Help  "));
+
+    let status = Paragraph::new(vec![Line::from(status_spans)]).alignment(Alignment::Left).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Status")
+            .border_style(Style::default().fg(theme::primary())),
+    );
+    if let Some(area) = area_of(Panel::Status) {
+        f.render_widget(status, area);
+    }
+
+    if let (Some(message), Some(area)) = (&timer.message, area_of(Panel::Quote)) {
+        let quote = Paragraph::new(message.as_str())
+            .style(Style::default().fg(theme::highlight()).add_modifier(Modifier::ITALIC))
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(theme::primary())));
+        f.render_widget(quote, area);
+    }
+
+    if let Some(area) = area_of(Panel::Sessions) {
+        panels::render_sessions(f, area, timer.history.records());
+    }
+
+    // Key reference
+    if timer.show_controls_popup {
+        let popup_area = centered_rect(60, 70, f.area());
+        f.render_widget(ratatui::widgets::Clear, popup_area);
+        help::render(f, popup_area, &timer.help, &timer.keymap, &timer.help_entries());
+    }
+
+    // Stats screen
+    if timer.show_stats {
+        let popup_area = centered_rect(90, 80, f.area());
+        f.render_widget(ratatui::widgets::Clear, popup_area);
+        stats::render(f, popup_area, &timer.history, &timer.stats, &timer.tasks, timer.eye_rest.records());
+    }
+
+    // Cat collection
+    if timer.show_cats {
+        let popup_area = centered_rect(80, 80, f.area());
+        f.render_widget(ratatui::widgets::Clear, popup_area);
+        cats::render(f, popup_area, &timer.cats_screen, &timer.cats);
+    }
+
+    if let Some(picker) = &timer.technique_picker {
+        let popup_area = centered_rect(60, 70, f.area());
+        f.render_widget(ratatui::widgets::Clear, popup_area);
+        technique::render(f, popup_area, picker, timer.technique);
+    }
+
+    // Animation gallery
+    if let Some(gallery) = &timer.gallery {
+        let popup_area = centered_rect(60, 60, f.area());
+        f.render_widget(ratatui::widgets::Clear, popup_area);
+        gallery::render(f, popup_area, gallery, &timer.trophies);
+    }
+
+    // Trophy screen
+    if timer.show_trophies {
+        let popup_area = centered_rect(60, 70, f.area());
+        f.render_widget(ratatui::widgets::Clear, popup_area);
+        achievements::render(f, popup_area, &timer.trophies);
+    }
+
+    // Weekly review
+    if let Some((review, so_far)) = &timer.review {
+        let popup_area = centered_rect(60, 70, f.area());
+        f.render_widget(ratatui::widgets::Clear, popup_area);
+        review::render(f, popup_area, review, *so_far);
+    }
+
+    // History browser
+    if timer.show_history {
+        let popup_area = centered_rect(90, 80, f.area());
+        f.render_widget(ratatui::widgets::Clear, popup_area);
+        history_browser::render(f, popup_area, &timer.history, &timer.history_browser);
+    }
+
+    // Task planning pane
+    if timer.show_tasks {
+        let popup_area = centered_rect(80, 70, f.area());
+        f.render_widget(ratatui::widgets::Clear, popup_area);
+        tasks::render(f, popup_area, &timer.tasks, &timer.task_pane, timer.history.records(), timer.task.as_deref());
+    }
 
-    // Controls popup
-    if timer.show_controls_popup {
-        let popup_area = centered_rect(60, 60, f.area());
+    // Alarm list
+    if timer.show_alarms {
+        let popup_area = centered_rect(80, 60, f.area());
         f.render_widget(ratatui::widgets::Clear, popup_area);
+        alarms::render(f, popup_area, &timer.alarms, &timer.alarm_pane, Local::now().naive_local());
+    }
 
-        let controls_popup = Paragraph::new(vec![
-            Line::from(""),
-            Line::from(vec![Span::styled("CONTROLS", Style::default().fg(PRIMARY_COLOR).add_modifier(Modifier::BOLD))]).alignment(Alignment::Center),
-            Line::from(""),
-            Line::from(vec![
-                Span::styled("  w  ", Style::default().fg(PRIMARY_COLOR).add_modifier(Modifier::BOLD)),
-                Span::raw(" - Start 25 mins Work"),
-            ]),
-            Line::from(vec![
-                Span::styled("  b  ", Style::default().fg(PRIMARY_COLOR).add_modifier(Modifier::BOLD)),
-                Span::raw(" - Start 5 mins Break"),
-            ]),
-            Line::from(vec![
-                Span::styled("  c  ", Style::default().fg(PRIMARY_COLOR).add_modifier(Modifier::BOLD)),
-                Span::raw(" - Custom timer"),
-            ]),
-            Line::from(vec![
-                Span::styled(" ␣/↵ ", Style::default().fg(PRIMARY_COLOR).add_modifier(Modifier::BOLD)),
-                Span::raw(" - Pause/Resume timer"),
-            ]),
-            Line::from(vec![
-                Span::styled("  t  ", Style::default().fg(PRIMARY_COLOR).add_modifier(Modifier::BOLD)),
-                Span::raw(" - Toggle Manual/Auto mode"),
-            ]),
-            Line::from(vec![
-                Span::styled("  m  ", Style::default().fg(PRIMARY_COLOR).add_modifier(Modifier::BOLD)),
-                Span::raw(" - Mario animation"),
-            ]),
-            Line::from(vec![
-                Span::styled("Esc  ", Style::default().fg(PRIMARY_COLOR).add_modifier(Modifier::BOLD)),
-                Span::raw(" - Close this popup"),
-            ]),
-            Line::from(vec![
-                Span::styled("q/Esc", Style::default().fg(PRIMARY_COLOR).add_modifier(Modifier::BOLD)),
-                Span::raw(" - Exit application"),
-            ]),
-        ])
-        .alignment(Alignment::Left)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Help")
-                .border_style(Style::default().fg(PRIMARY_COLOR)),
-        );
-        f.render_widget(controls_popup, popup_area);
+    if timer.show_label_input {
+        render_label_input(f, timer);
     }
 
     // Custom input dialog
@@ -454,33 +2213,39 @@ fn ui(f: &mut Frame, timer: &PomodoroTimer) {
             // Line::from(""),
             // Line::from(vec![Span::styled(
             //     "CUSTOM TIMER",
-            //     Style::default().fg(PRIMARY_COLOR).add_modifier(Modifier::BOLD),
+            //     Style::default().fg(theme::primary()).add_modifier(Modifier::BOLD),
             // )])
             // .alignment(Alignment::Center),
             Line::from(""),
             Line::from(vec![
                 Span::raw("  Format: "),
-                Span::styled("work,break", Style::default().fg(HIGHLIGHT_COLOR)),
+                Span::styled("work,break", Style::default().fg(theme::highlight())),
                 Span::raw(" or "),
-                Span::styled("work", Style::default().fg(HIGHLIGHT_COLOR)),
+                Span::styled("work", Style::default().fg(theme::highlight())),
             ]),
             Line::from(vec![
                 Span::raw("  Examples: "),
-                Span::styled("30,10", Style::default().fg(HIGHLIGHT_COLOR)),
+                Span::styled("30,10", Style::default().fg(theme::highlight())),
+                Span::raw(" or "),
+                Span::styled("20", Style::default().fg(theme::highlight())),
                 Span::raw(" or "),
-                Span::styled("20", Style::default().fg(HIGHLIGHT_COLOR)),
+                Span::styled("25:30,90s", Style::default().fg(theme::highlight())),
             ]),
+            Line::from("  Minutes unless marked s or h; 0.5 is 30 seconds"),
             Line::from(""),
             Line::from(vec![
                 Span::raw("  Input: "),
                 Span::styled(&timer.custom_input, Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
-                Span::styled("█", Style::default().fg(PRIMARY_COLOR)), // Cursor
+                Span::styled("█", Style::default().fg(theme::primary())), // Cursor
             ]),
-            Line::from(""),
+            Line::from(Span::styled(
+                format!("  {}", timer.custom_error.as_deref().unwrap_or("")),
+                Style::default().fg(Color::Red),
+            )),
             Line::from(vec![
-                Span::styled("↵", Style::default().fg(PRIMARY_COLOR).add_modifier(Modifier::BOLD)),
+                Span::styled("↵", Style::default().fg(theme::primary()).add_modifier(Modifier::BOLD)),
                 Span::raw(" - Confirm | "),
-                Span::styled("x", Style::default().fg(PRIMARY_COLOR).add_modifier(Modifier::BOLD)),
+                Span::styled("x", Style::default().fg(theme::primary()).add_modifier(Modifier::BOLD)),
                 Span::raw(" - Cancel"),
             ]),
         ])
@@ -489,11 +2254,167 @@ fn ui(f: &mut Frame, timer: &PomodoroTimer) {
             Block::default()
                 .borders(Borders::ALL)
                 .title("Custom Timer")
-                .border_style(Style::default().fg(PRIMARY_COLOR))
+                .border_style(Style::default().fg(theme::primary()))
                 .title_alignment(Alignment::Center),
         );
         f.render_widget(input_popup, popup_area);
     }
+
+    // Nag mode flashes the whole screen along with each chime
+    if timer
+        .pending_ack
+        .as_ref()
+        .is_some_and(|pending| pending.last_nag.elapsed() < Duration::from_millis(500))
+    {
+        let area = f.area();
+        f.buffer_mut().set_style(area, Style::default().add_modifier(Modifier::REVERSED));
+    }
+
+    if timer.eye_rest.is_showing() {
+        eye_rest::render(f, centered_rect(50, 40, f.area()), &timer.eye_rest);
+    }
+
+    if timer.show_meeting_prompt {
+        render_meeting_prompt(f);
+    }
+
+    if let Some(extra) = timer.break_extension {
+        render_break_extension_prompt(f, extra);
+    }
+
+    if timer.show_pause_prompt && timer.current_session.is_paused() {
+        render_pause_prompt(f, &timer.pauses.reasons);
+    }
+
+    toast::render(f, &timer.toasts);
+}
+
+fn render_meeting_prompt(f: &mut Frame) {
+    let popup_area = centered_rect(50, 30, f.area());
+    f.render_widget(ratatui::widgets::Clear, popup_area);
+
+    let key_style = Style::default().fg(theme::primary()).add_modifier(Modifier::BOLD);
+    let prompt = Paragraph::new(vec![
+        Line::from(""),
+        Line::from("Your microphone or camera is in use."),
+        Line::from("Pause the work session for the call?"),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("y", key_style),
+            Span::raw(" - Pause | "),
+            Span::styled("n", key_style),
+            Span::raw("/"),
+            Span::styled("Esc", key_style),
+            Span::raw(" - Keep working"),
+        ]),
+    ])
+    .alignment(Alignment::Center)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Call Detected")
+            .border_style(Style::default().fg(theme::primary()))
+            .title_alignment(Alignment::Center),
+    );
+    f.render_widget(prompt, popup_area);
+}
+
+fn render_break_extension_prompt(f: &mut Frame, extra: Duration) {
+    let popup_area = centered_rect(50, 30, f.area());
+    f.render_widget(ratatui::widgets::Clear, popup_area);
+
+    let key_style = Style::default().fg(theme::primary()).add_modifier(Modifier::BOLD);
+    let prompt = Paragraph::new(vec![
+        Line::from(""),
+        Line::from("You worked past the end of the session."),
+        Line::from(format!("Extend this break by {}?", PomodoroTimer::format_duration(extra))),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("y", key_style),
+            Span::raw(" - Extend | "),
+            Span::styled("n", key_style),
+            Span::raw("/"),
+            Span::styled("Esc", key_style),
+            Span::raw(" - Keep it as is"),
+        ]),
+    ])
+    .alignment(Alignment::Center)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Overtime")
+            .border_style(Style::default().fg(theme::primary()))
+            .title_alignment(Alignment::Center),
+    );
+    f.render_widget(prompt, popup_area);
+}
+
+fn render_pause_prompt(f: &mut Frame, reasons: &[String]) {
+    let popup_area = centered_rect(50, 30, f.area());
+    f.render_widget(ratatui::widgets::Clear, popup_area);
+
+    let key_style = Style::default().fg(theme::primary()).add_modifier(Modifier::BOLD);
+    let mut choices = Vec::new();
+    for (i, reason) in reasons.iter().take(9).enumerate() {
+        choices.push(Span::styled((i + 1).to_string(), key_style));
+        choices.push(Span::raw(format!(" {reason}  ")));
+    }
+    let prompt = Paragraph::new(vec![
+        Line::from(""),
+        Line::from("Work paused. What interrupted it?"),
+        Line::from(""),
+        Line::from(choices),
+        Line::from(""),
+        Line::from(vec![Span::styled("Esc", key_style), Span::raw(" - Don't say")]),
+    ])
+    .alignment(Alignment::Center)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Paused")
+            .border_style(Style::default().fg(theme::primary()))
+            .title_alignment(Alignment::Center),
+    );
+    f.render_widget(prompt, popup_area);
+}
+
+fn render_label_input(f: &mut Frame, timer: &PomodoroTimer) {
+    let popup_area = centered_rect(70, 50, f.area());
+    f.render_widget(ratatui::widgets::Clear, popup_area);
+
+    let input_popup = Paragraph::new(vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("  Format: "),
+            Span::styled("task name #tag #tag", Style::default().fg(theme::highlight())),
+        ]),
+        Line::from(vec![
+            Span::raw("  Example: "),
+            Span::styled("Quarterly report #work #writing", Style::default().fg(theme::highlight())),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("  Label: "),
+            Span::styled(&timer.label_input, Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+            Span::styled("█", Style::default().fg(theme::primary())), // Cursor
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("↵", Style::default().fg(theme::primary()).add_modifier(Modifier::BOLD)),
+            Span::raw(" - Confirm (empty clears) | "),
+            Span::styled("Esc", Style::default().fg(theme::primary()).add_modifier(Modifier::BOLD)),
+            Span::raw(" - Cancel"),
+        ]),
+    ])
+    .alignment(Alignment::Left)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Task Label")
+            .border_style(Style::default().fg(theme::primary()))
+            .title_alignment(Alignment::Center),
+    );
+    f.render_widget(input_popup, popup_area);
 }
 
 fn centered_rect(percent_x: u16, percent_y: u16, r: ratatui::prelude::Rect) -> ratatui::prelude::Rect {
@@ -516,8 +2437,9 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: ratatui::prelude::Rect) -> r
         .split(popup_layout[1])[1]
 }
 
-fn run_timer() -> Result<(), Box<dyn std::error::Error>> {
-    let mut timer = match PomodoroTimer::new() {
+fn run_timer(options: cli::RunOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let config = cli::load_config()?;
+    let mut timer = match PomodoroTimer::new(&config, &paths::data_dir()) {
         Ok(t) => t,
         Err(e) => {
             eprintln!("Timer initialization failed: {e}");
@@ -525,6 +2447,24 @@ fn run_timer() -> Result<(), Box<dyn std::error::Error>> {
             std::process::exit(1);
         }
     };
+    timer.update_achievements();
+    if let Some(scale) = options.time_scale {
+        timer.time_scale = scale;
+    }
+    if options.technique.is_some() {
+        timer.use_technique(options.technique, &config).map_err(cli::Exit::Config)?;
+    }
+    timer.watching = options.watch;
+    if !timer.watching {
+        timer.plugins = Plugins::start();
+        let mut actions = config.startup.actions.clone();
+        if let Some(name) = &options.profile {
+            let missing = || cli::Exit::Config(format!("No [profiles.{name}] in the config"));
+            let profile = config.profiles.get(name).ok_or_else(missing)?;
+            actions.extend(profile.actions.iter().cloned());
+        }
+        timer.run_startup(&config, &actions);
+    }
 
     match enable_raw_mode() {
         Ok(_) => {}
@@ -542,6 +2482,8 @@ fn run_timer() -> Result<(), Box<dyn std::error::Error>> {
             return Err(e.into());
         }
     }
+    // Terminals that don't report focus just never send the events
+    let _ = execute!(stdout, EnableFocusChange);
 
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = match Terminal::new(backend) {
@@ -552,188 +2494,596 @@ fn run_timer() -> Result<(), Box<dyn std::error::Error>> {
         }
     };
 
-    let result = main_loop(&mut terminal, &mut timer);
+    let started = history::now_secs();
+    let result = if timer.watching {
+        watch_loop(&mut terminal, &mut timer)
+    } else {
+        let result = main_loop(&mut terminal, &mut timer);
+        timer.room.leave();
+        follow::remove();
+        timer.status_file.remove();
+        timer.obs.clear();
+        result
+    };
 
     // Audio cleanup is now handled automatically by each individual playback
 
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(terminal.backend_mut(), DisableFocusChange, LeaveAlternateScreen)?;
     terminal.show_cursor()?;
 
     // Restore terminal title
     set_terminal_title("Terminal");
 
-    result
+    result?;
+    // A record of the run for the scrollback, now the alternate screen is gone
+    if !timer.watching {
+        let store = &timer.history;
+        let ours = |r: &&SessionRecord| r.machine == store.machine() && r.started_at >= started;
+        let run: Vec<&SessionRecord> = store.records().iter().filter(ours).collect();
+        let today: Vec<&SessionRecord> = store.work_today().collect();
+        for line in summary::lines(&run, &today, started, history::now_secs()) {
+            cli::say!("{line}");
+        }
+    }
+    // Quitting mid-session tells a wrapper script the session didn't finish
+    let session = &timer.current_session;
+    if !timer.watching && (session.is_running() || session.is_paused()) {
+        return Err(cli::Exit::Interrupted.into());
+    }
+    Ok(())
+}
+
+// A frame, then the images it left room for
+fn draw(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, timer: &mut PomodoroTimer) -> io::Result<()> {
+    // Half-drawn frames from the old size would otherwise linger, in the
+    // animation especially
+    let cleared = std::mem::take(&mut timer.resized);
+    if cleared {
+        terminal.clear()?;
+    }
+    let frame = terminal.draw(|f| {
+        ui(f, timer);
+        frame_stats::render(f, &timer.frame_stats);
+    })?;
+    let slots = if timer.graphics.enabled() {
+        graphics::slots(frame.buffer)
+    } else {
+        Vec::new()
+    };
+    let sprite = if timer.current_session.is_finished() { Sprite::Cat } else { Sprite::Tomato };
+    timer.graphics.update(terminal.backend_mut(), &slots, sprite, cleared)
 }
 
 fn main_loop(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, timer: &mut PomodoroTimer) -> Result<(), Box<dyn std::error::Error>> {
+    // Live reload is best-effort; without a watcher the config is read once
+    let config_watch = Config::watch().ok();
+    let mut reload_at: Option<Instant> = None;
+    // Remote control too; a second instance just doesn't get it
+    let remote = remote::Server::bind().ok();
+    let signals = Signals::register();
+
     loop {
-        terminal.draw(|f| ui(f, timer))?;
-
-        if let Ok(true) = event::poll(Duration::from_millis(100)) {
-            if let Ok(Event::Key(key)) = event::read() {
-                // Handle Mario animation first
-                if timer.show_mario_animation {
-                    if let KeyEvent {
-                        code: KeyCode::Esc | KeyCode::Enter | KeyCode::Char(' '),
-                        modifiers: KeyModifiers::NONE,
-                        ..
-                    } = key {
-                        timer.show_mario_animation = false;
+        if signals.terminated() {
+            break;
+        }
+        signals.handle(terminal, timer, true);
+        // Before any key is handled, since most of them skip the rest of the loop
+        timer.update_animations();
+        let poll = if timer.is_animating() { ANIMATION_FRAME } else { TICK_RATE };
+        let started = Instant::now();
+        draw(terminal, timer)?;
+        timer.frame_stats.frame(started, Instant::now(), poll, timer.audio.queued());
+
+        if let Ok(true) = event::poll(poll)
+            && let Ok(event) = event::read()
+            && let Some(key) = timer.take_key(event)
+        {
+            timer.frame_stats.key(Instant::now());
+            // Acknowledging doesn't use up the key, so `w` both stops the nag and starts work
+            timer.acknowledge();
+
+            // Handle Mario animation and fireworks first
+            if timer.is_animating() {
+                // Snoozing as the bell rings shouldn't wait for the animation
+                if timer.keymap.action(key) == Some(Action::Snooze) {
+                    timer.show_mario_animation = false;
+                    timer.fireworks = None;
+                    timer.snooze();
+                } else if timer.keymap.action(key) == Some(Action::FrameStats) {
+                    // The animation is what the overlay is most often wanted for
+                    timer.frame_stats.toggle();
+                } else if let KeyEvent {
+                    code: KeyCode::Esc | KeyCode::Enter | KeyCode::Char(' '),
+                    modifiers: KeyModifiers::NONE,
+                    ..
+                } = key
+                {
+                    timer.show_mario_animation = false;
+                    timer.fireworks = None;
+                }
+                continue;
+            }
+
+            // The eye rest reminder is brief; other keys wait until it ends
+            if timer.eye_rest.is_showing() {
+                if key.code == KeyCode::Esc {
+                    timer.eye_rest.dismiss();
+                }
+                continue;
+            }
+
+            // A reason key answers the pause prompt; any other key dismisses it
+            // and then does what it always does
+            if timer.show_pause_prompt {
+                timer.show_pause_prompt = false;
+                let picked = match key.code {
+                    KeyCode::Char(c) if timer.current_session.is_paused() => {
+                        c.to_digit(10).and_then(|n| timer.pauses.reasons.get((n as usize).checked_sub(1)?)).cloned()
                     }
+                    _ => None,
+                };
+                if let Some(reason) = picked {
+                    timer.current_session.set_pause_reason(&reason);
+                    timer.toasts.info(format!("Paused: {reason}"));
+                    continue;
+                }
+                if key.code == KeyCode::Esc {
                     continue;
                 }
+            }
 
-                // Handle custom input dialog
-                if timer.show_custom_input {
-                    match key {
-                        KeyEvent {
-                            code: KeyCode::Char('x'),
-                            modifiers: KeyModifiers::NONE,
-                            ..
-                        } => {
-                            timer.hide_custom_input_dialog();
-                        }
-                        KeyEvent {
-                            code: KeyCode::Enter,
-                            modifiers: KeyModifiers::NONE,
-                            ..
-                        } => {
-                            timer.parse_and_start_custom_timer();
-                        }
-                        KeyEvent {
-                            code: KeyCode::Backspace,
-                            modifiers: KeyModifiers::NONE,
-                            ..
-                        } => {
-                            timer.custom_input.pop();
-                        }
-                        KeyEvent {
-                            code: KeyCode::Char(c),
-                            modifiers: KeyModifiers::NONE,
-                            ..
-                        } => {
-                            if c.is_ascii_digit() || c == ',' {
-                                timer.custom_input.push(c);
-                            }
-                        }
-                        _ => {}
+            if let Some(extra) = timer.break_extension {
+                match key.code {
+                    KeyCode::Char('y') => timer.extend_running_break(extra),
+                    KeyCode::Char('n') | KeyCode::Esc => timer.break_extension = None,
+                    _ => {}
+                }
+                continue;
+            }
+
+            if timer.show_meeting_prompt {
+                match key.code {
+                    KeyCode::Char('y') => {
+                        timer.show_meeting_prompt = false;
+                        timer.pause_timer();
+                        timer.current_session.set_pause_reason("call");
+                        timer.toasts.info("Paused for the call");
                     }
-                    continue;
+                    KeyCode::Char('n') | KeyCode::Esc => timer.show_meeting_prompt = false,
+                    _ => {}
                 }
+                continue;
+            }
 
-                match key {
-                    // Handle help popup ESC to close popup
-                    KeyEvent {
-                        code: KeyCode::Esc,
-                        modifiers: KeyModifiers::NONE,
-                        ..
-                    } => {
-                        if timer.show_controls_popup {
-                            timer.show_controls_popup = false;
-                        } else {
-                            break; // Exit app if no popup is open
+            // Handle weekly review
+            if timer.review.is_some() {
+                if matches!(key.code, KeyCode::Esc | KeyCode::Enter) || timer.keymap.action(key) == Some(Action::Review) {
+                    timer.review = None;
+                }
+                continue;
+            }
+
+            // Handle cat collection
+            if timer.show_cats {
+                if !timer.cats_screen.handle_key(key) || timer.keymap.action(key) == Some(Action::Cats) {
+                    timer.show_cats = false;
+                }
+                continue;
+            }
+
+            if let Some(picker) = &mut timer.technique_picker {
+                match picker.handle_key(key) {
+                    Pick::Close => timer.technique_picker = None,
+                    Pick::Use(technique) => {
+                        timer.technique_picker = None;
+                        let name = technique.map_or("the config's lengths".to_string(), |t| format!("{} {}", t.name, t.summary()));
+                        match Config::load().and_then(|config| timer.use_technique(technique, &config)) {
+                            Ok(()) => timer.toasts.info(format!("Using {name}")),
+                            Err(e) => timer.toasts.error(e),
                         }
                     }
-                    
-                    // Quit with 'q' or Ctrl+C
-                    KeyEvent {
-                        code: KeyCode::Char('q'),
-                        modifiers: KeyModifiers::NONE,
-                        ..
-                    }
-                    | KeyEvent {
-                        code: KeyCode::Char('c'),
-                        modifiers: KeyModifiers::CONTROL,
-                        ..
-                    } => break,
+                    Pick::Stay if timer.keymap.action(key) == Some(Action::Techniques) => timer.technique_picker = None,
+                    Pick::Stay => {}
+                }
+                continue;
+            }
 
-                    KeyEvent {
-                        code: KeyCode::Char('w'),
-                        modifiers: KeyModifiers::NONE,
-                        ..
-                    } => {
-                        timer.start_work_session();
-                    }
+            // Handle animation gallery; it stays open under what it plays
+            if let Some(gallery) = &mut timer.gallery {
+                match gallery.handle_key(key, &timer.trophies) {
+                    Outcome::Close => timer.gallery = None,
+                    Outcome::Play(scene) => timer.play_scene(scene),
+                    Outcome::Stay if timer.keymap.action(key) == Some(Action::Gallery) => timer.gallery = None,
+                    Outcome::Stay => {}
+                }
+                continue;
+            }
 
-                    KeyEvent {
-                        code: KeyCode::Char('b'),
-                        modifiers: KeyModifiers::NONE,
-                        ..
-                    } => {
-                        timer.start_break_session();
-                    }
+            // Handle trophy screen
+            if timer.show_trophies {
+                if key.code == KeyCode::Esc || timer.keymap.action(key) == Some(Action::Achievements) {
+                    timer.show_trophies = false;
+                }
+                continue;
+            }
 
-                    KeyEvent {
-                        code: KeyCode::Char('c'),
-                        modifiers: KeyModifiers::NONE,
-                        ..
-                    } => {
-                        timer.show_custom_input_dialog();
+            // Handle stats screen
+            if timer.show_stats {
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('s') => timer.show_stats = false,
+                    KeyCode::Tab => timer.stats.next_view(),
+                    KeyCode::Char('+' | '=') => timer.stats.adjust_days(1),
+                    KeyCode::Char('-') => timer.stats.adjust_days(-1),
+                    KeyCode::Left => timer.stats.shift(1),
+                    KeyCode::Right => timer.stats.shift(-1),
+                    _ => {}
+                }
+                continue;
+            }
+
+            // Handle history browser
+            if timer.show_history {
+                timer.show_history = timer.history_browser.handle_key(key, &mut timer.history);
+                continue;
+            }
+
+            // Handle task planning pane
+            if timer.show_tasks {
+                match timer.task_pane.handle_key(key, &mut timer.tasks) {
+                    tasks::PaneAction::Close => timer.show_tasks = false,
+                    tasks::PaneAction::Select(task) => timer.select_task(task),
+                    tasks::PaneAction::None => {}
+                }
+                continue;
+            }
+
+            // Handle alarm list
+            if timer.show_alarms {
+                timer.show_alarms = timer.alarm_pane.handle_key(key, &mut timer.alarms);
+                continue;
+            }
+
+            // Handle task label dialog
+            if timer.show_label_input {
+                match key.code {
+                    KeyCode::Esc => timer.hide_label_input_dialog(),
+                    KeyCode::Enter => timer.apply_label_input(),
+                    KeyCode::Backspace => {
+                        timer.label_input.pop();
                     }
+                    KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => timer.label_input.push(c),
+                    _ => {}
+                }
+                continue;
+            }
 
+            // Handle custom input dialog
+            if timer.show_custom_input {
+                match key {
                     KeyEvent {
-                        code: KeyCode::Enter | KeyCode::Char(' '),
+                        code: KeyCode::Char('x'),
                         modifiers: KeyModifiers::NONE,
                         ..
                     } => {
-                        timer.toggle_timer();
+                        timer.hide_custom_input_dialog();
                     }
-
                     KeyEvent {
-                        code: KeyCode::Char('t'),
+                        code: KeyCode::Enter,
                         modifiers: KeyModifiers::NONE,
                         ..
                     } => {
-                        timer.toggle_mode();
+                        timer.parse_and_start_custom_timer();
                     }
-
                     KeyEvent {
-                        code: KeyCode::Char('x'),
+                        code: KeyCode::Backspace,
                         modifiers: KeyModifiers::NONE,
                         ..
                     } => {
-                        timer.show_controls_popup = !timer.show_controls_popup;
+                        timer.custom_input.pop();
+                        timer.custom_error = None;
                     }
-
-                    // Removed Up/Down navigation since we no longer have a menu
                     KeyEvent {
-                        code: KeyCode::Char('m'),
+                        code: KeyCode::Char(c),
                         modifiers: KeyModifiers::NONE,
                         ..
-                    } => {
-                        // Manual trigger for Mario animation (for testing)
-                        timer.show_mario_animation = true;
-                        timer.mario_animation = MarioAnimation::new();
-                        timer.mario_animation.start();
+                    } if c.is_ascii_digit() || ",.:smh".contains(c) => {
+                        timer.custom_input.push(c);
+                        timer.custom_error = None;
                     }
-
                     _ => {}
                 }
+                continue;
+            }
+
+            // The help screen takes its own keys, including Esc, before they can quit
+            if timer.show_controls_popup && timer.help_key(key) {
+                continue;
+            }
+
+            match timer.keymap.action(key) {
+                Some(Action::Quit) => break,
+                Some(Action::Suspend) => request_stop(timer),
+                // The keys always start the configured lengths, leaving any custom timer
+                Some(Action::StartWork) if timer.confirm_restart(Action::StartWork) => {
+                    timer.custom = None;
+                    timer.start_work_session();
+                }
+                Some(Action::StartBreak) if timer.confirm_restart(Action::StartBreak) => {
+                    timer.custom = None;
+                    timer.start_break_session();
+                }
+                Some(Action::StartWork | Action::StartBreak) => {}
+                Some(Action::CustomTimer) => timer.show_custom_input_dialog(),
+                Some(Action::ResetDurations) => timer.reset_durations(),
+                Some(Action::TogglePause) => timer.toggle_timer(),
+                Some(Action::Snooze) => timer.snooze(),
+                Some(Action::ToggleMode) => timer.toggle_mode(),
+                Some(Action::ToggleClock) => timer.layout.clock = timer.layout.clock.next(),
+                Some(Action::Techniques) => timer.technique_picker = Some(TechniquePicker::new(timer.technique)),
+                Some(Action::Help) => timer.toggle_help(),
+                Some(Action::FrameStats) => timer.frame_stats.toggle(),
+                Some(Action::Stats) => timer.show_stats_screen(),
+                Some(Action::Label) => timer.show_label_input_dialog(),
+                Some(Action::History) => timer.show_history_browser(),
+                Some(Action::Review) => timer.show_review(),
+                Some(Action::AddSession) => timer.add_session(),
+                Some(Action::RemoveSession) => timer.remove_session(),
+                Some(Action::NextQuote) => timer.next_quote(),
+                Some(Action::Plan) => timer.show_task_pane(),
+                Some(Action::Alarms) => timer.show_alarm_pane(),
+                Some(Action::Mario) => timer.play_scene(timer.last_scene),
+                Some(Action::Gallery) => timer.gallery = Some(GalleryScreen::new()),
+                Some(Action::Preview) => timer.preview_sound(),
+                Some(Action::Achievements) => timer.show_trophies = true,
+                Some(Action::Cats) => {
+                    timer.cats_screen = CollectionScreen::new();
+                    timer.show_cats = true;
+                }
+                None => {}
             }
         }
 
-        // Update Mario animation
-        if timer.show_mario_animation {
-            timer.mario_animation.update();
-            if timer.mario_animation.is_finished() {
-                timer.show_mario_animation = false;
+        if let Some((_, changes)) = &config_watch
+            && changes.try_iter().count() > 0
+        {
+            reload_at = Some(Instant::now() + CONFIG_RELOAD_DELAY);
+        }
+        if reload_at.is_some_and(|at| Instant::now() >= at) {
+            reload_at = None;
+            timer.reload_config();
+        }
+
+        timer.calendar.tick();
+        let working = timer.current_session.is_running() && timer.current_session.timer_type == TimerType::Work;
+        timer.room.tick(match timer.current_session.timer_type {
+            _ if !timer.current_session.is_running() => RoomState::Idle,
+            TimerType::Work => RoomState::Focusing,
+            TimerType::Break => RoomState::Break,
+        });
+        timer.app_sampler.tick(timer.capture_app && working);
+        timer.report_integration_errors();
+        timer.toasts.tick();
+
+        if let Some(server) = &remote {
+            server.poll(|command| timer.remote_command(command));
+        }
+        let commands: Vec<(String, String)> = timer.plugins.commands().collect();
+        for (plugin, command) in commands {
+            if let Err(e) = timer.remote_command(&command) {
+                timer.toasts.error(format!("Plugin {plugin}: {e}"));
+            }
+        }
+        // A toast clicked after the timer has moved on is stale
+        let clicks: Vec<&str> = timer.notifier.clicks().collect();
+        for command in clicks {
+            if timer.current_session.is_running() || timer.current_session.is_paused() {
+                continue;
+            }
+            if let Err(e) = timer.remote_command(command) {
+                timer.toasts.error(e);
             }
         }
 
+        timer.update_countdown();
+        timer.update_milestones();
+        timer.update_budget();
+        timer.update_alarms();
+        timer.update_review();
+        timer.update_breathing();
+        timer.update_eye_rest();
+        timer.update_nag();
+        timer.update_meeting();
+        timer.update_away();
+        timer.update_lock();
+        timer.update_scripts();
+
         // Check if timer finished
-        if timer.current_session.is_running && timer.is_timer_finished() {
+        if timer.current_session.is_running() && timer.is_timer_finished() {
             timer.complete_session();
         }
+
+        timer.update_focus();
+        timer.update_ambient();
+        timer.publish_state();
+        timer.write_status();
+    }
+
+    Ok(())
+}
+
+// Signals the loops act on between frames
+struct Signals {
+    terminated: Arc<AtomicBool>, // SIGTERM or SIGHUP
+    stop: Arc<AtomicBool>,       // SIGTSTP, from Ctrl-Z or another shell
+    continued: Arc<AtomicBool>,  // SIGCONT
+}
+
+impl Signals {
+    fn register() -> Self {
+        let signals = Signals {
+            terminated: Arc::new(AtomicBool::new(false)),
+            stop: Arc::new(AtomicBool::new(false)),
+            continued: Arc::new(AtomicBool::new(false)),
+        };
+        #[cfg(unix)]
+        {
+            use signal_hook::consts::{SIGCONT, SIGHUP, SIGTERM, SIGTSTP};
+            // kill and a closed terminal end the run the way quitting does,
+            // so the site block and the do-not-disturb switch are undone on
+            // the way out
+            for (signal, flag) in [
+                (SIGTERM, &signals.terminated),
+                (SIGHUP, &signals.terminated),
+                (SIGTSTP, &signals.stop),
+                (SIGCONT, &signals.continued),
+            ] {
+                let _ = signal_hook::flag::register(signal, Arc::clone(flag));
+            }
+        }
+        signals
+    }
+
+    fn terminated(&self) -> bool {
+        self.terminated.load(Ordering::Relaxed)
+    }
+
+    // Stops when asked to, and takes the terminal back after a stop that
+    // couldn't be caught (SIGSTOP), as the shell may have reset it meanwhile.
+    // Work is paused while stopped when `pause_work` and the config say so
+    fn handle(&self, terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, timer: &mut PomodoroTimer, pause_work: bool) {
+        let result = if self.stop.swap(false, Ordering::Relaxed) {
+            let pause = pause_work && !timer.count_suspended && timer.current_session.is_running();
+            if pause {
+                timer.pause_timer();
+            }
+            let result = suspend(terminal);
+            if pause {
+                timer.resume_timer();
+            }
+            result
+        } else if self.continued.load(Ordering::Relaxed) {
+            take_terminal(terminal)
+        } else {
+            return;
+        };
+        // Continuing is what brought us back
+        self.continued.store(false, Ordering::Relaxed);
+        if let Err(e) = result {
+            timer.toasts.error(format!("Could not suspend: {e}"));
+        }
+        // Images went with the screen
+        timer.resized = true;
+    }
+}
+
+// Raw mode turns Ctrl-Z into a key press. It's sent back as SIGTSTP so it
+// takes the same way as one from another shell
+#[cfg(unix)]
+fn request_stop(timer: &mut PomodoroTimer) {
+    if let Err(e) = signal_hook::low_level::raise(signal_hook::consts::SIGTSTP) {
+        timer.toasts.error(format!("Could not suspend: {e}"));
+    }
+}
+
+#[cfg(not(unix))]
+fn request_stop(timer: &mut PomodoroTimer) {
+    timer.toasts.error("Could not suspend: job control needs a Unix shell");
+}
+
+// Stops the process the way the shell would, handing the terminal back
+// first and taking it again on fg
+#[cfg(unix)]
+fn suspend(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), DisableFocusChange, LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    // Our SIGTSTP handler took the place of the default stop; returns once
+    // the shell continues us
+    signal_hook::low_level::emulate_default_handler(signal_hook::consts::SIGTSTP)?;
+    take_terminal(terminal)
+}
+
+#[cfg(not(unix))]
+fn suspend(_terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> io::Result<()> {
+    Err(io::Error::other("job control needs a Unix shell"))
+}
+
+fn take_terminal(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> io::Result<()> {
+    enable_raw_mode()?;
+    execute!(terminal.backend_mut(), EnterAlternateScreen, EnableFocusChange)?;
+    terminal.clear()
+}
+
+// Read-only copy of the running instance: only help, suspend and quit respond
+fn watch_loop(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, timer: &mut PomodoroTimer) -> Result<(), Box<dyn std::error::Error>> {
+    let signals = Signals::register();
+    loop {
+        if signals.terminated() {
+            break;
+        }
+        signals.handle(terminal, timer, false);
+        timer.follow(follow::read());
+        draw(terminal, timer)?;
+
+        if let Ok(true) = event::poll(TICK_RATE)
+            && let Ok(event) = event::read()
+            && let Some(key) = timer.take_key(event)
+        {
+            if timer.show_controls_popup && timer.help_key(key) {
+                continue;
+            }
+            match timer.keymap.action(key) {
+                Some(Action::Quit) => break,
+                Some(Action::Help) => timer.toggle_help(),
+                Some(Action::Suspend) => request_stop(timer),
+                _ => {}
+            }
+        }
     }
 
     Ok(())
 }
 
 fn main() {
-    if let Err(e) = run_timer() {
-        eprintln!("Error: {e}");
-        std::process::exit(1);
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args = match cli::parse_args(&args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}\n");
+            cli::print_usage();
+            std::process::exit(cli::EXIT_CONFIG);
+        }
+    };
+    cli::set_quiet(args.quiet);
+
+    let result = match args.command {
+        cli::Command::Run(options) => run_timer(options),
+        cli::Command::Once(options) => once::run(options),
+        cli::Command::Backup { output } => cli::run_backup(output),
+        cli::Command::Restore { archive, force } => cli::run_restore(archive, force),
+        cli::Command::Merge { file } => cli::run_merge(file),
+        cli::Command::Log { duration } => cli::run_log(duration),
+        cli::Command::Archive { months } => cli::run_archive(months),
+        cli::Command::Report { html, last_week } => cli::run_report(html, last_week),
+        cli::Command::Migrate { to } => cli::run_migrate(to),
+        cli::Command::ConfigCheck { file } => cli::run_config_check(file),
+        cli::Command::Hosts { block } => cli::run_hosts(block),
+        cli::Command::Remote { command, format } => remote::run(&command, format),
+        cli::Command::RoomServe { port } => room::serve(port).map_err(Into::into),
+        cli::Command::Bench { sessions } => bench::run(sessions),
+        cli::Command::Help => {
+            cli::print_usage();
+            Ok(())
+        }
+    };
+
+    if let Err(e) = result {
+        // The status says it all for an interrupted run
+        if !matches!(e.downcast_ref(), Some(cli::Exit::Interrupted)) {
+            eprintln!("Error: {e}");
+        }
+        std::process::exit(cli::exit_code(e.as_ref()));
     }
 }
 
@@ -749,12 +3099,93 @@ mod tests {
         assert_eq!(PomodoroTimer::format_duration(Duration::from_secs(125)), "02:05");
     }
 
+    #[test]
+    fn test_parse_label() {
+        assert_eq!(parse_label(""), (None, vec![]));
+        assert_eq!(
+            parse_label("Write  report #work #writing #work"),
+            (Some("Write report".to_string()), vec!["work".to_string(), "writing".to_string()])
+        );
+        assert_eq!(parse_label("#solo #"), (Some("#".to_string()), vec!["solo".to_string()]));
+    }
+
+    // Kept away from the real data dir, which holds the user's own history
+    fn timer(name: &str) -> PomodoroTimer {
+        let data_dir = std::env::temp_dir().join(format!("cyber-tomato-timer-{}-{name}", std::process::id()));
+        PomodoroTimer::new(&Config::default(), &data_dir).unwrap()
+    }
+
+    #[test]
+    fn test_user_file_follows_history_dir() {
+        let dir = std::env::temp_dir().join(format!("cyber-tomato-user-file-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let data_dir = dir.join("data");
+        fs::create_dir_all(&data_dir).unwrap();
+        fs::write(data_dir.join("tasks.json"), "[]").unwrap();
+        let mut config = Config::default();
+        assert_eq!(user_file(&config, &data_dir, "tasks.json"), data_dir.join("tasks.json"));
+
+        config.history.dir = Some(dir.join("sync"));
+        let synced = user_file(&config, &data_dir, "tasks.json");
+        assert_eq!(synced, dir.join("sync").join("tasks.json"));
+        assert_eq!(fs::read_to_string(synced).unwrap(), "[]");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_timer_creation() {
-        let timer = PomodoroTimer::new().unwrap();
+        let timer = timer("creation");
         assert_eq!(timer.mode, TimerMode::Auto);
         assert_eq!(timer.completed_sessions, 0);
         assert_eq!(timer.current_session.timer_type, TimerType::Work);
-        assert!(!timer.current_session.is_running);
+        assert!(!timer.current_session.is_running());
+    }
+
+    #[test]
+    fn test_break_extension() {
+        let mut timer = timer("break-extension");
+        timer.extend_break = ExtendBreak::Auto;
+        // 10 minutes over a 25 minute pomodoro earns 2 more minutes of a 5 minute break
+        timer.overtime = Duration::from_secs(10 * 60);
+        timer.start_break_session();
+        assert_eq!(timer.current_session.duration, Duration::from_secs(7 * 60));
+        assert_eq!(timer.overtime, Duration::ZERO);
+
+        timer.extend_break = ExtendBreak::Ask;
+        timer.overtime = Duration::from_secs(60);
+        timer.start_break_session();
+        // 12 seconds isn't worth asking about
+        assert_eq!(timer.break_extension, None);
+        timer.overtime = Duration::from_secs(5 * 60);
+        timer.start_break_session();
+        assert_eq!(timer.break_extension, Some(Duration::from_secs(60)));
+        assert_eq!(timer.current_session.duration, Duration::from_secs(5 * 60));
+    }
+
+    #[test]
+    fn test_next_session() {
+        let mut timer = timer("next-session");
+        assert_eq!(timer.next_session(), None);
+        let elapsed = Duration::from_secs(10 * 60);
+        timer.current_session = PomodoroSession::mirror(TimerType::Work, Duration::from_secs(25 * 60), elapsed, false);
+        assert_eq!(timer.next_session().as_deref(), Some("Next: Break 5m in 15:00"));
+        timer.mode = TimerMode::Manual;
+        timer.custom = Some((Duration::from_secs(50 * 60), Duration::from_secs(90)));
+        assert_eq!(timer.next_session().as_deref(), Some("Next: Break 1m30s"));
+    }
+
+    #[test]
+    fn test_technique_long_breaks() {
+        let config = Config::default();
+        let mut timer = timer("technique");
+        timer.use_technique(technique::find("classic"), &config).unwrap();
+        assert_eq!(timer.work_duration, Duration::from_secs(25 * 60));
+        timer.cycle = 3;
+        assert!(!timer.long_break_due(0));
+        assert!(timer.long_break_due(1));
+        timer.cycle = 4;
+        assert_eq!(timer.next_break_duration(), Duration::from_secs(15 * 60));
+        timer.use_technique(None, &config).unwrap();
+        assert_eq!(timer.next_break_duration(), Duration::from_secs(5 * 60));
     }
 }
@@ -4,11 +4,32 @@ use ratatui::{
     symbols::Marker,
     widgets::canvas::{Canvas, Circle, Context, Line, Rectangle},
 };
-use rodio::{OutputStream, OutputStreamBuilder, Sink, Source};
-use std::f32::consts::PI;
-use std::sync::{Arc, Mutex};
+use rodio::Sink;
+use std::rc::Rc;
 use std::time::{Duration, Instant};
 
+use crate::audio::AudioEngine;
+use crate::chiptune::{Envelope, Song, Voice};
+use crate::config::Waveform;
+
+// Speeds, gravity and fading are per frame of this many seconds, the rate the
+// scene was first written for
+const BASE_FRAME: f64 = 0.1;
+const MAX_STEP: Duration = Duration::from_millis(33);
+// After a stall (a suspended terminal, say) the scene picks up where it was
+// rather than jumping ahead
+const MAX_CATCH_UP: Duration = Duration::from_millis(250);
+
+// How long the scene plays
+pub const LENGTH: Duration = Duration::from_secs(10);
+
+// Size of the scene in canvas units
+pub const SCENE_WIDTH: f64 = 240.0;
+pub const SCENE_HEIGHT: f64 = 100.0;
+// Fewest braille dots per canvas unit before the view narrows to follow the
+// cat; the cat is about 8 units wide
+const MIN_SCALE: f64 = 0.8;
+
 pub struct MarioAnimation {
     cat_x: f64,
     cat_y: f64,
@@ -21,29 +42,56 @@ pub struct MarioAnimation {
     tomato_hit: bool,
     tomato_exploding: bool,
     tomato_particles: Vec<Particle>,
+    golden: bool, // Unlocked by an achievement
 
     bricks: Vec<Brick>,
     bricks_hit: bool,
     animation_frame: u32,
-    started: bool,
     start_time: Option<Instant>,
+    last_update: Option<Instant>,
     ground_y: f64,
 
-    // Audio system
-    _stream: Option<OutputStream>,
-    music_sink: Option<Arc<Mutex<Sink>>>,
-    sfx_sink: Option<Arc<Mutex<Sink>>>,
+    // Audio system; the sinks are only created when the animation starts, at
+    // the engine's master volume, and stay None while sound is silenced
+    audio: Rc<AudioEngine>,
+    waveform: Waveform, // Lead voice of the theme and sound effects
+    music_sink: Option<Sink>,
+    sfx_sink: Option<Sink>,
     music_started: bool,
 }
 
 #[derive(Clone)]
-struct Particle {
-    x: f64,
-    y: f64,
-    vx: f64,
-    vy: f64,
-    life: f64,
-    color: Color,
+pub struct Particle {
+    pub x: f64,
+    pub y: f64,
+    pub vx: f64,
+    pub vy: f64,
+    pub life: f64, // Fades from 1.0; gone at 0
+    pub color: Color,
+}
+
+impl Particle {
+    // `t` frames of flight; `gravity` and `fade` are per frame
+    pub fn step(&mut self, gravity: f64, fade: f64, t: f64) {
+        self.x += self.vx * t;
+        self.y += self.vy * t;
+        self.vy -= gravity * t;
+        self.life -= fade * t;
+    }
+}
+
+// Frames of BASE_FRAME to advance by for `elapsed` real time, in steps no
+// longer than MAX_STEP so nothing moves far enough to skip a collision
+pub fn steps(elapsed: Duration) -> impl Iterator<Item = f64> {
+    let mut remaining = elapsed.min(MAX_CATCH_UP).as_secs_f64();
+    std::iter::from_fn(move || {
+        if remaining <= 0.0 {
+            return None;
+        }
+        let step = remaining.min(MAX_STEP.as_secs_f64());
+        remaining -= step;
+        Some(step / BASE_FRAME)
+    })
 }
 
 #[derive(Clone)]
@@ -56,7 +104,7 @@ struct Brick {
 }
 
 impl MarioAnimation {
-    pub fn new() -> Self {
+    pub fn new(audio: Rc<AudioEngine>, waveform: Waveform) -> Self {
         let ground_y = 10.0;
         let tomato_x = 120.0;
         let tomato_y = 75.0; // High up in the brick block
@@ -73,20 +121,6 @@ impl MarioAnimation {
             });
         }
 
-        // Initialize audio system for music and sound effects
-        let (stream, music_sink, sfx_sink) = if let Ok(builder) = OutputStreamBuilder::from_default_device() {
-            if let Ok(mut stream) = builder.open_stream_or_fallback() {
-            stream.log_on_drop(false);
-            let music_sink = Sink::connect_new(stream.mixer());
-            let sfx_sink = Sink::connect_new(stream.mixer());
-                (Some(stream), Some(Arc::new(Mutex::new(music_sink))), Some(Arc::new(Mutex::new(sfx_sink))))
-            } else {
-                (None, None, None)
-            }
-        } else {
-            (None, None, None)
-        };
-
         Self {
             cat_x: 20.0,
             cat_y: ground_y,
@@ -99,49 +133,83 @@ impl MarioAnimation {
             tomato_hit: false,
             tomato_exploding: false,
             tomato_particles: Vec::new(),
+            golden: false,
 
             bricks,
             bricks_hit: false,
             animation_frame: 0,
-            started: false,
             start_time: None,
+            last_update: None,
             ground_y,
 
-            _stream: stream,
-            music_sink,
-            sfx_sink,
+            audio,
+            waveform,
+            music_sink: None,
+            sfx_sink: None,
             music_started: false,
         }
     }
 
+    pub fn set_golden(&mut self, golden: bool) {
+        self.golden = golden;
+    }
+
+    // Body and shine of the tomato
+    fn tomato_colors(&self) -> (Color, Color) {
+        if self.golden {
+            (Color::Rgb(255, 200, 0), Color::Rgb(255, 240, 150))
+        } else {
+            (Color::Red, Color::Rgb(255, 100, 100))
+        }
+    }
+
     pub fn start(&mut self) {
-        self.started = true;
         self.start_time = Some(Instant::now());
+        self.music_sink = self.audio.sink();
+        self.sfx_sink = self.audio.sink();
         self.start_mario_theme();
     }
 
     pub fn is_finished(&self) -> bool {
-        if let Some(start_time) = self.start_time {
-            start_time.elapsed() > Duration::from_secs(10) // Longer duration for full sequence
-        } else {
-            false
-        }
+        self.start_time.is_some_and(|start_time| start_time.elapsed() > LENGTH)
     }
 
+    // Moves the scene on by the real time since the last call, however often
+    // that is
     pub fn update(&mut self) {
-        if !self.started {
+        let Some(start_time) = self.start_time else {
             return;
+        };
+        let now = Instant::now();
+        let elapsed = now - self.last_update.unwrap_or(start_time);
+        self.last_update = Some(now);
+        self.advance(elapsed, start_time.elapsed());
+    }
+
+    // Moves the scene on by `elapsed`, `shown` into it: the real clock's
+    // times from update, simulated ones from `bench`
+    pub fn advance(&mut self, elapsed: Duration, shown: Duration) {
+        // Frame count for the tail sway and flashes, at the rate they were drawn for
+        self.animation_frame = (shown.as_secs_f64() / BASE_FRAME) as u32;
+        for t in steps(elapsed) {
+            self.step(t);
         }
+    }
 
-        self.animation_frame += 1;
+    // Particles alive, for `bench` to see they don't pile up
+    pub fn particles(&self) -> usize {
+        self.tomato_particles.len() + self.bricks.iter().map(|brick| brick.break_particles.len()).sum::<usize>()
+    }
 
+    // Physics for `t` frames
+    fn step(&mut self, t: f64) {
         // Cat physics
-        self.cat_x += self.cat_vx;
-        self.cat_y += self.cat_vy;
+        self.cat_x += self.cat_vx * t;
+        self.cat_y += self.cat_vy * t;
 
         // Gravity for Cat
         if self.cat_y > self.ground_y {
-            self.cat_vy -= 1.5; // Gravity
+            self.cat_vy -= 1.5 * t; // Gravity
         } else {
             self.cat_y = self.ground_y;
             if self.cat_vy < 0.0 {
@@ -174,8 +242,8 @@ impl MarioAnimation {
         // Tomato physics after bricks are hit
         if self.bricks_hit && !self.tomato_hit {
             if self.tomato_y > self.ground_y + 5.0 {
-                self.tomato_vy += 0.5; // Gravity acceleration
-                self.tomato_y -= self.tomato_vy; // Fall down
+                self.tomato_vy += 0.5 * t; // Gravity acceleration
+                self.tomato_y -= self.tomato_vy * t; // Fall down
             } else {
                 // Tomato reaches ground
                 self.tomato_y = self.ground_y + 5.0;
@@ -186,15 +254,12 @@ impl MarioAnimation {
         }
 
         // Update particles
-        self.update_particles();
+        self.update_particles(t);
 
         // Update brick particles
         for brick in &mut self.bricks {
             for particle in &mut brick.break_particles {
-                particle.x += particle.vx;
-                particle.y += particle.vy;
-                particle.vy -= 0.3; // Gravity on particles
-                particle.life -= 0.02;
+                particle.step(0.3, 0.02, t);
             }
             brick.break_particles.retain(|p| p.life > 0.0);
         }
@@ -253,7 +318,7 @@ impl MarioAnimation {
                 vy: angle.sin() * speed + 2.0,
                 life: 1.0,
                 color: if i % 3 == 0 {
-                    Color::Red
+                    self.tomato_colors().0
                 } else if i % 3 == 1 {
                     Color::Green
                 } else {
@@ -263,27 +328,44 @@ impl MarioAnimation {
         }
     }
 
-    fn update_particles(&mut self) {
+    fn update_particles(&mut self, t: f64) {
         for particle in &mut self.tomato_particles {
-            particle.x += particle.vx;
-            particle.y += particle.vy;
-            particle.vy -= 0.2; // Gravity
-            particle.life -= 0.015;
+            particle.step(0.2, 0.015, t);
         }
         self.tomato_particles.retain(|p| p.life > 0.0);
     }
 
-    pub fn render(&self, _area: Rect) -> Canvas<'_, impl Fn(&mut Context)> {
+    // Canvas bounds for `area`, worked out again every frame so a resize takes
+    // effect at once. The scene keeps its proportions and its full height;
+    // spare room goes to the sides or above it instead of stretching. Where
+    // the whole width would shrink the sprites past making out, a window of
+    // it follows `focus_x` instead
+    pub fn bounds(area: Rect, focus_x: f64) -> ([f64; 2], [f64; 2]) {
+        // Braille dots are about square: 2 across and 4 down in a cell
+        let (dots_x, dots_y) = (area.width.max(1) as f64 * 2.0, area.height.max(1) as f64 * 4.0);
+        let fit = (dots_x / SCENE_WIDTH).min(dots_y / SCENE_HEIGHT);
+        let scale = if fit >= MIN_SCALE { fit } else { MIN_SCALE.min(dots_y / SCENE_HEIGHT) };
+        let (width, height) = (dots_x / scale, dots_y / scale);
+        let left = if width >= SCENE_WIDTH {
+            (SCENE_WIDTH - width) / 2.0
+        } else {
+            (focus_x - width / 2.0).clamp(0.0, SCENE_WIDTH - width)
+        };
+        ([left, left + width], [0.0, height])
+    }
+
+    pub fn render(&self, area: Rect) -> Canvas<'_, impl Fn(&mut Context)> {
+        let (x_bounds, y_bounds) = Self::bounds(area, self.cat_x);
         Canvas::default()
             .marker(Marker::Braille)
-            .x_bounds([0.0, 240.0])
-            .y_bounds([0.0, 100.0])
-            .paint(|ctx| {
+            .x_bounds(x_bounds)
+            .y_bounds(y_bounds)
+            .paint(move |ctx| {
                 // Draw ground
                 ctx.draw(&Line {
-                    x1: 0.0,
+                    x1: x_bounds[0],
                     y1: self.ground_y - 2.0,
-                    x2: 240.0,
+                    x2: x_bounds[1],
                     y2: self.ground_y - 2.0,
                     color: Color::Green,
                 });
@@ -358,277 +440,18 @@ impl MarioAnimation {
     }
 
     fn draw_mario(&self, ctx: &mut Context, x: f64, y: f64) {
-        // ASCII-style cat based on:
-        //     ^~^  
-        // _  ('Y') 
-        //  \ /   \
-        //   (\|||/)
-
-        // Ears: ^~^
-        // Left ear ^
-        ctx.draw(&Line {
-            x1: x - 3.0,
-            y1: y + 12.0,
-            x2: x - 2.0,
-            y2: y + 14.0,
-            color: Color::Rgb(255, 255, 150), // Light yellow
-        });
-        ctx.draw(&Line {
-            x1: x - 2.0,
-            y1: y + 14.0,
-            x2: x - 1.0,
-            y2: y + 12.0,
-            color: Color::Rgb(255, 255, 150), // Light yellow
-        });
-        
-        // Middle ~
-        ctx.draw(&Line {
-            x1: x - 0.5,
-            y1: y + 13.0,
-            x2: x + 0.5,
-            y2: y + 12.5,
-            color: Color::Rgb(255, 255, 150), // Light yellow
-        });
-        
-        // Right ear ^
-        ctx.draw(&Line {
-            x1: x + 1.0,
-            y1: y + 12.0,
-            x2: x + 2.0,
-            y2: y + 14.0,
-            color: Color::Rgb(255, 255, 150), // Light yellow
-        });
-        ctx.draw(&Line {
-            x1: x + 2.0,
-            y1: y + 14.0,
-            x2: x + 3.0,
-            y2: y + 12.0,
-            color: Color::Rgb(255, 255, 150), // Light yellow
-        });
-
-        // Face outline: ('Y')
-        // Left parenthesis (
-        ctx.draw(&Line {
-            x1: x - 2.5,
-            y1: y + 11.0,
-            x2: x - 3.0,
-            y2: y + 9.0,
-            color: Color::Rgb(255, 255, 150), // Light yellow
-        });
-        ctx.draw(&Line {
-            x1: x - 3.0,
-            y1: y + 9.0,
-            x2: x - 2.5,
-            y2: y + 7.0,
-            color: Color::Rgb(255, 255, 150), // Light yellow
-        });
-        
-        // Right parenthesis )
-        ctx.draw(&Line {
-            x1: x + 2.5,
-            y1: y + 11.0,
-            x2: x + 3.0,
-            y2: y + 9.0,
-            color: Color::Rgb(255, 255, 150), // Light yellow
-        });
-        ctx.draw(&Line {
-            x1: x + 3.0,
-            y1: y + 9.0,
-            x2: x + 2.5,
-            y2: y + 7.0,
-            color: Color::Rgb(255, 255, 150), // Light yellow
-        });
-
-        // Eyes: apostrophes ' '
-        ctx.draw(&Line {
-            x1: x - 1.0,
-            y1: y + 10.0,
-            x2: x - 0.8,
-            y2: y + 9.5,
-            color: Color::Black,
-        });
-        ctx.draw(&Line {
-            x1: x + 0.8,
-            y1: y + 10.0,
-            x2: x + 1.0,
-            y2: y + 9.5,
-            color: Color::Black,
-        });
-
-        // Nose and mouth: Y
-        // Y top left
-        ctx.draw(&Line {
-            x1: x - 0.5,
-            y1: y + 8.5,
-            x2: x,
-            y2: y + 8.0,
-            color: Color::Rgb(255, 182, 193), // Light pink
-        });
-        // Y top right
-        ctx.draw(&Line {
-            x1: x + 0.5,
-            y1: y + 8.5,
-            x2: x,
-            y2: y + 8.0,
-            color: Color::Rgb(255, 182, 193), // Light pink
-        });
-        // Y bottom
-        ctx.draw(&Line {
-            x1: x,
-            y1: y + 8.0,
-            x2: x,
-            y2: y + 7.0,
-            color: Color::Rgb(255, 182, 193), // Light pink
-        });
-
-        // Body outline: \ /   \
-        // Left side \
-        ctx.draw(&Line {
-            x1: x - 2.0,
-            y1: y + 6.0,
-            x2: x - 4.0,
-            y2: y + 2.0,
-            color: Color::Rgb(255, 255, 150), // Light yellow
-        });
-        // Right side /
-        ctx.draw(&Line {
-            x1: x + 2.0,
-            y1: y + 6.0,
-            x2: x + 4.0,
-            y2: y + 2.0,
-            color: Color::Rgb(255, 255, 150), // Light yellow
-        });
-
-        // Legs: (\|||/)
-        // Left parenthesis (
-        ctx.draw(&Line {
-            x1: x - 3.5,
-            y1: y + 2.0,
-            x2: x - 4.0,
-            y2: y,
-            color: Color::Rgb(255, 255, 150), // Light yellow
-        });
-        ctx.draw(&Line {
-            x1: x - 4.0,
-            y1: y,
-            x2: x - 3.5,
-            y2: y - 2.0,
-            color: Color::Rgb(255, 255, 150), // Light yellow
-        });
-
-        // Right parenthesis )
-        ctx.draw(&Line {
-            x1: x + 3.5,
-            y1: y + 2.0,
-            x2: x + 4.0,
-            y2: y,
-            color: Color::Rgb(255, 255, 150), // Light yellow
-        });
-        ctx.draw(&Line {
-            x1: x + 4.0,
-            y1: y,
-            x2: x + 3.5,
-            y2: y - 2.0,
-            color: Color::Rgb(255, 255, 150), // Light yellow
-        });
-
-        // Four legs: \|||/
-        // Left leg \
-        ctx.draw(&Line {
-            x1: x - 2.0,
-            y1: y + 1.0,
-            x2: x - 3.0,
-            y2: y - 2.0,
-            color: Color::Rgb(255, 255, 200), // Pale yellow/white
-        });
-        // Center legs |||
-        ctx.draw(&Line {
-            x1: x - 0.5,
-            y1: y + 1.0,
-            x2: x - 0.5,
-            y2: y - 2.0,
-            color: Color::Rgb(255, 255, 200), // Pale yellow/white
-        });
-        ctx.draw(&Line {
-            x1: x,
-            y1: y + 1.0,
-            x2: x,
-            y2: y - 2.0,
-            color: Color::Rgb(255, 255, 200), // Pale yellow/white
-        });
-        ctx.draw(&Line {
-            x1: x + 0.5,
-            y1: y + 1.0,
-            x2: x + 0.5,
-            y2: y - 2.0,
-            color: Color::Rgb(255, 255, 200), // Pale yellow/white
-        });
-        // Right leg /
-        ctx.draw(&Line {
-            x1: x + 2.0,
-            y1: y + 1.0,
-            x2: x + 3.0,
-            y2: y - 2.0,
-            color: Color::Rgb(255, 255, 200), // Pale yellow/white
-        });
-
-        // Paws (small circles at leg ends)
-        ctx.draw(&Circle {
-            x: x - 3.0,
-            y: y - 2.0,
-            radius: 0.4,
-            color: Color::Rgb(255, 192, 203), // Pink paws
-        });
-        ctx.draw(&Circle {
-            x: x - 0.5,
-            y: y - 2.0,
-            radius: 0.4,
-            color: Color::Rgb(255, 192, 203), // Pink paws
-        });
-        ctx.draw(&Circle {
-            x: x,
-            y: y - 2.0,
-            radius: 0.4,
-            color: Color::Rgb(255, 192, 203), // Pink paws
-        });
-        ctx.draw(&Circle {
-            x: x + 0.5,
-            y: y - 2.0,
-            radius: 0.4,
-            color: Color::Rgb(255, 192, 203), // Pink paws
-        });
-        ctx.draw(&Circle {
-            x: x + 3.0,
-            y: y - 2.0,
-            radius: 0.4,
-            color: Color::Rgb(255, 192, 203), // Pink paws
-        });
-
-        // Tail (simple curved behind)
-        let tail_sway = if self.animation_frame % 20 < 10 { 0.5 } else { -0.5 };
-        ctx.draw(&Line {
-            x1: x - 3.5,
-            y1: y + 3.0,
-            x2: x - 5.0 + tail_sway,
-            y2: y + 6.0,
-            color: Color::Rgb(255, 255, 120), // Slightly darker yellow
-        });
-        ctx.draw(&Line {
-            x1: x - 5.0 + tail_sway,
-            y1: y + 6.0,
-            x2: x - 4.0 + tail_sway,
-            y2: y + 9.0,
-            color: Color::Rgb(255, 255, 120), // Slightly darker yellow
-        });
+        draw_cat(ctx, x, y, &YELLOW_CAT, self.animation_frame);
     }
 
     fn draw_tomato(&self, ctx: &mut Context, x: f64, y: f64) {
+        let (body, shine) = self.tomato_colors();
+
         // Tomato body (main red circle)
         ctx.draw(&Circle {
             x,
             y: y + 1.0,
             radius: 4.0,
-            color: Color::Red,
+            color: body,
         });
 
         // Tomato shine/highlight
@@ -636,7 +459,7 @@ impl MarioAnimation {
             x: x - 1.5,
             y: y + 2.5,
             radius: 0.8,
-            color: Color::Rgb(255, 100, 100), // Lighter red
+            color: shine, // Lighter shade
         });
 
         // Tomato leaves/stem (green top)
@@ -718,80 +541,23 @@ impl MarioAnimation {
         self.music_started = true;
 
         if let Some(ref sink) = self.music_sink {
-            let sink = sink.lock().unwrap();
-
-            // Mario Bros main theme melody (simplified)
-            let mario_theme = vec![
-                (659.25, 150), // E5
-                (659.25, 150), // E5
-                (0.0, 150),    // Rest
-                (659.25, 150), // E5
-                (0.0, 150),    // Rest
-                (523.25, 150), // C5
-                (659.25, 150), // E5
-                (0.0, 150),    // Rest
-                (783.99, 150), // G5
-                (0.0, 450),    // Rest
-                (392.00, 150), // G4
-                (0.0, 450),    // Rest
-                (523.25, 150), // C5
-                (0.0, 300),    // Rest
-                (392.00, 150), // G4
-                (0.0, 300),    // Rest
-                (329.63, 150), // E4
-                (0.0, 300),    // Rest
-                (440.00, 150), // A4
-                (0.0, 150),    // Rest
-                (493.88, 150), // B4
-                (0.0, 150),    // Rest
-                (466.16, 150), // A#4
-                (440.00, 150), // A4
-                (0.0, 150),    // Rest
-                (392.00, 200), // G4
-                (659.25, 200), // E5
-                (783.99, 200), // G5
-                (880.00, 150), // A5
-                (0.0, 150),    // Rest
-                (698.46, 150), // F5
-                (783.99, 150), // G5
-                (0.0, 150),    // Rest
-                (659.25, 150), // E5
-                (0.0, 150),    // Rest
-                (523.25, 150), // C5
-                (587.33, 150), // D5
-                (493.88, 150), // B4
-                (0.0, 300),    // Rest
-            ];
-
-            for (freq, duration_ms) in mario_theme {
-                if freq > 0.0 {
-                    let source = MarioTone::new(freq, Duration::from_millis(duration_ms));
-                    sink.append(source);
-                } else {
-                    // Rest/silence
-                    let silence = rodio::source::Zero::new(1, 44100)
-                        .take_duration(Duration::from_millis(duration_ms))
-                        .buffered();
-                    sink.append(silence);
-                }
-            }
+            let waveform = self.waveform;
+            sink.append(self.audio.clip("mario_theme", waveform, || mario_theme(waveform)));
         }
     }
 
     fn play_jump_sound(&self) {
         if let Some(ref sink) = self.sfx_sink {
-            let sink = sink.lock().unwrap();
             let jump_tones = [
                 (523.25, Duration::from_millis(100)), // C5
                 (659.25, Duration::from_millis(100)), // E5
             ];
-            self.play_sound_effect(&sink, &jump_tones);
+            self.play_sound_effect(sink, "jump", &jump_tones);
         }
     }
 
     fn play_brick_break_sound(&self) {
         if let Some(ref sink) = self.sfx_sink {
-            let sink = sink.lock().unwrap();
             let break_tones = [
                 (1046.50, Duration::from_millis(80)),  // C6
                 (0.0, Duration::from_millis(20)),      // Rest
@@ -799,13 +565,12 @@ impl MarioAnimation {
                 (0.0, Duration::from_millis(20)),      // Rest
                 (1318.51, Duration::from_millis(120)), // E6
             ];
-            self.play_sound_effect(&sink, &break_tones);
+            self.play_sound_effect(sink, "brick_break", &break_tones);
         }
     }
 
     fn play_powerup_sound(&self) {
         if let Some(ref sink) = self.sfx_sink {
-            let sink = sink.lock().unwrap();
             let powerup_tones = [
                 (392.00, Duration::from_millis(100)),  // G4
                 (523.25, Duration::from_millis(100)),  // C5
@@ -814,83 +579,389 @@ impl MarioAnimation {
                 (1046.50, Duration::from_millis(100)), // C6
                 (1318.51, Duration::from_millis(300)), // E6
             ];
-            self.play_sound_effect(&sink, &powerup_tones);
+            self.play_sound_effect(sink, "powerup", &powerup_tones);
         }
     }
 
-    fn play_sound_effect(&self, sink: &std::sync::MutexGuard<Sink>, tones: &[(f32, Duration)]) {
-        for (freq, dur) in tones {
-            if *freq == 0.0 {
-                let silence = rodio::source::Zero::new(1, 44100).take_duration(*dur).buffered();
-                sink.append(silence);
-            } else {
-                let source = MarioTone::new(*freq, *dur);
-                sink.append(source);
-            }
-        }
+    fn play_sound_effect(&self, sink: &Sink, name: &'static str, tones: &[(f32, Duration)]) {
+        let voice = Voice::new(self.waveform, Envelope::HELD, 0.3);
+        sink.append(self.audio.clip(name, self.waveform, || Song::new().track(voice, tones)));
     }
 }
 
-// Custom audio source for Mario-style tones
-struct MarioTone {
-    freq: f32,
-    duration: Duration,
-    sample_rate: u32,
-    sample_idx: usize,
-    total_samples: usize,
+// Mario Bros main theme (simplified)
+fn mario_theme(waveform: Waveform) -> Song {
+    // Melody, bass and length in ms
+    let mario_theme = [
+        (659.25, 146.83, 150), // E5 over D3
+        (659.25, 146.83, 150), // E5 over D3
+        (0.0, 0.0, 150),       // Rest
+        (659.25, 146.83, 150), // E5 over D3
+        (0.0, 0.0, 150),       // Rest
+        (523.25, 146.83, 150), // C5 over D3
+        (659.25, 146.83, 150), // E5 over D3
+        (0.0, 0.0, 150),       // Rest
+        (783.99, 196.00, 150), // G5 over G3
+        (0.0, 0.0, 450),       // Rest
+        (392.00, 98.00, 150),  // G4 over G2
+        (0.0, 0.0, 450),       // Rest
+        (523.25, 196.00, 150), // C5 over G3
+        (0.0, 0.0, 300),       // Rest
+        (392.00, 164.81, 150), // G4 over E3
+        (0.0, 0.0, 300),       // Rest
+        (329.63, 130.81, 150), // E4 over C3
+        (0.0, 0.0, 300),       // Rest
+        (440.00, 174.61, 150), // A4 over F3
+        (0.0, 0.0, 150),       // Rest
+        (493.88, 196.00, 150), // B4 over G3
+        (0.0, 0.0, 150),       // Rest
+        (466.16, 185.00, 150), // A#4 over F#3
+        (440.00, 174.61, 150), // A4 over F3
+        (0.0, 0.0, 150),       // Rest
+        (392.00, 164.81, 200), // G4 over E3
+        (659.25, 261.63, 200), // E5 over C4
+        (783.99, 329.63, 200), // G5 over E4
+        (880.00, 349.23, 150), // A5 over F4
+        (0.0, 0.0, 150),       // Rest
+        (698.46, 293.66, 150), // F5 over D4
+        (783.99, 329.63, 150), // G5 over E4
+        (0.0, 0.0, 150),       // Rest
+        (659.25, 261.63, 150), // E5 over C4
+        (0.0, 0.0, 150),       // Rest
+        (523.25, 220.00, 150), // C5 over A3
+        (587.33, 246.94, 150), // D5 over B3
+        (493.88, 196.00, 150), // B4 over G3
+        (0.0, 0.0, 300),       // Rest
+    ];
+
+    let ms = Duration::from_millis;
+    let melody: Vec<(f32, Duration)> = mario_theme.iter().map(|&(lead, _, length)| (lead, ms(length))).collect();
+    let bass: Vec<(f32, Duration)> = mario_theme.iter().map(|&(_, bass, length)| (bass, ms(length))).collect();
+    // A hi-hat tick on every melody note
+    let drums: Vec<(f32, Duration)> = mario_theme
+        .iter()
+        .map(|&(lead, _, length)| (if lead > 0.0 { 8000.0 } else { 0.0 }, ms(length)))
+        .collect();
+    Song::new()
+        .track(Voice::new(waveform, Envelope::HELD, 0.25), &melody)
+        .track(Voice::new(Waveform::Triangle, Envelope::HELD, 0.3), &bass)
+        .track(Voice::new(Waveform::Noise, Envelope::HIT, 0.08), &drums)
 }
 
-impl MarioTone {
-    fn new(freq: f32, duration: Duration) -> Self {
-        let sample_rate = 44100;
-        let total_samples = (duration.as_secs_f32() * sample_rate as f32) as usize;
-        Self {
-            freq,
-            duration,
-            sample_rate,
-            sample_idx: 0,
-            total_samples,
-        }
-    }
+// Colors of the cat's coat
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Coat {
+    pub fur: Color, // Ears, face and body
+    pub legs: Color,
+    pub tail: Color,
+    pub eyes: Color,
 }
 
-impl Iterator for MarioTone {
-    type Item = f32;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.sample_idx >= self.total_samples {
-            return None;
-        }
-
-        let t = self.sample_idx as f32 / self.sample_rate as f32;
-        let phase = 2.0 * PI * self.freq * t;
-
-        // Square wave with envelope for classic Mario sound
-        let square_wave = if (phase % (2.0 * PI)) < PI { 0.3 } else { -0.3 };
-
-        // Envelope with decay
-        let envelope = (-3.0 * t).exp();
+// The cat of the work completion animation
+pub const YELLOW_CAT: Coat = Coat {
+    fur: Color::Rgb(255, 255, 150),  // Light yellow
+    legs: Color::Rgb(255, 255, 200), // Pale yellow/white
+    tail: Color::Rgb(255, 255, 120), // Slightly darker yellow
+    eyes: Color::Black,
+};
 
-        let sample = square_wave * envelope;
-        self.sample_idx += 1;
-        Some(sample)
-    }
+// The cat standing at (x, y), about 10 wide and 16 tall in canvas units;
+// `frame` sways the tail
+pub fn draw_cat(ctx: &mut Context, x: f64, y: f64, coat: &Coat, frame: u32) {
+    // ASCII-style cat based on:
+    //     ^~^  
+    // _  ('Y') 
+    //  \ /   \
+    //   (\|||/)
+
+    // Ears: ^~^
+    // Left ear ^
+    ctx.draw(&Line {
+        x1: x - 3.0,
+        y1: y + 12.0,
+        x2: x - 2.0,
+        y2: y + 14.0,
+        color: coat.fur,
+    });
+    ctx.draw(&Line {
+        x1: x - 2.0,
+        y1: y + 14.0,
+        x2: x - 1.0,
+        y2: y + 12.0,
+        color: coat.fur,
+    });
+    
+    // Middle ~
+    ctx.draw(&Line {
+        x1: x - 0.5,
+        y1: y + 13.0,
+        x2: x + 0.5,
+        y2: y + 12.5,
+        color: coat.fur,
+    });
+    
+    // Right ear ^
+    ctx.draw(&Line {
+        x1: x + 1.0,
+        y1: y + 12.0,
+        x2: x + 2.0,
+        y2: y + 14.0,
+        color: coat.fur,
+    });
+    ctx.draw(&Line {
+        x1: x + 2.0,
+        y1: y + 14.0,
+        x2: x + 3.0,
+        y2: y + 12.0,
+        color: coat.fur,
+    });
+
+    // Face outline: ('Y')
+    // Left parenthesis (
+    ctx.draw(&Line {
+        x1: x - 2.5,
+        y1: y + 11.0,
+        x2: x - 3.0,
+        y2: y + 9.0,
+        color: coat.fur,
+    });
+    ctx.draw(&Line {
+        x1: x - 3.0,
+        y1: y + 9.0,
+        x2: x - 2.5,
+        y2: y + 7.0,
+        color: coat.fur,
+    });
+    
+    // Right parenthesis )
+    ctx.draw(&Line {
+        x1: x + 2.5,
+        y1: y + 11.0,
+        x2: x + 3.0,
+        y2: y + 9.0,
+        color: coat.fur,
+    });
+    ctx.draw(&Line {
+        x1: x + 3.0,
+        y1: y + 9.0,
+        x2: x + 2.5,
+        y2: y + 7.0,
+        color: coat.fur,
+    });
+
+    // Eyes: apostrophes ' '
+    ctx.draw(&Line {
+        x1: x - 1.0,
+        y1: y + 10.0,
+        x2: x - 0.8,
+        y2: y + 9.5,
+        color: coat.eyes,
+    });
+    ctx.draw(&Line {
+        x1: x + 0.8,
+        y1: y + 10.0,
+        x2: x + 1.0,
+        y2: y + 9.5,
+        color: coat.eyes,
+    });
+
+    // Nose and mouth: Y
+    // Y top left
+    ctx.draw(&Line {
+        x1: x - 0.5,
+        y1: y + 8.5,
+        x2: x,
+        y2: y + 8.0,
+        color: Color::Rgb(255, 182, 193), // Light pink
+    });
+    // Y top right
+    ctx.draw(&Line {
+        x1: x + 0.5,
+        y1: y + 8.5,
+        x2: x,
+        y2: y + 8.0,
+        color: Color::Rgb(255, 182, 193), // Light pink
+    });
+    // Y bottom
+    ctx.draw(&Line {
+        x1: x,
+        y1: y + 8.0,
+        x2: x,
+        y2: y + 7.0,
+        color: Color::Rgb(255, 182, 193), // Light pink
+    });
+
+    // Body outline: \ /   \
+    // Left side \
+    ctx.draw(&Line {
+        x1: x - 2.0,
+        y1: y + 6.0,
+        x2: x - 4.0,
+        y2: y + 2.0,
+        color: coat.fur,
+    });
+    // Right side /
+    ctx.draw(&Line {
+        x1: x + 2.0,
+        y1: y + 6.0,
+        x2: x + 4.0,
+        y2: y + 2.0,
+        color: coat.fur,
+    });
+
+    // Legs: (\|||/)
+    // Left parenthesis (
+    ctx.draw(&Line {
+        x1: x - 3.5,
+        y1: y + 2.0,
+        x2: x - 4.0,
+        y2: y,
+        color: coat.fur,
+    });
+    ctx.draw(&Line {
+        x1: x - 4.0,
+        y1: y,
+        x2: x - 3.5,
+        y2: y - 2.0,
+        color: coat.fur,
+    });
+
+    // Right parenthesis )
+    ctx.draw(&Line {
+        x1: x + 3.5,
+        y1: y + 2.0,
+        x2: x + 4.0,
+        y2: y,
+        color: coat.fur,
+    });
+    ctx.draw(&Line {
+        x1: x + 4.0,
+        y1: y,
+        x2: x + 3.5,
+        y2: y - 2.0,
+        color: coat.fur,
+    });
+
+    // Four legs: \|||/
+    // Left leg \
+    ctx.draw(&Line {
+        x1: x - 2.0,
+        y1: y + 1.0,
+        x2: x - 3.0,
+        y2: y - 2.0,
+        color: coat.legs,
+    });
+    // Center legs |||
+    ctx.draw(&Line {
+        x1: x - 0.5,
+        y1: y + 1.0,
+        x2: x - 0.5,
+        y2: y - 2.0,
+        color: coat.legs,
+    });
+    ctx.draw(&Line {
+        x1: x,
+        y1: y + 1.0,
+        x2: x,
+        y2: y - 2.0,
+        color: coat.legs,
+    });
+    ctx.draw(&Line {
+        x1: x + 0.5,
+        y1: y + 1.0,
+        x2: x + 0.5,
+        y2: y - 2.0,
+        color: coat.legs,
+    });
+    // Right leg /
+    ctx.draw(&Line {
+        x1: x + 2.0,
+        y1: y + 1.0,
+        x2: x + 3.0,
+        y2: y - 2.0,
+        color: coat.legs,
+    });
+
+    // Paws (small circles at leg ends)
+    ctx.draw(&Circle {
+        x: x - 3.0,
+        y: y - 2.0,
+        radius: 0.4,
+        color: Color::Rgb(255, 192, 203), // Pink paws
+    });
+    ctx.draw(&Circle {
+        x: x - 0.5,
+        y: y - 2.0,
+        radius: 0.4,
+        color: Color::Rgb(255, 192, 203), // Pink paws
+    });
+    ctx.draw(&Circle {
+        x,
+        y: y - 2.0,
+        radius: 0.4,
+        color: Color::Rgb(255, 192, 203), // Pink paws
+    });
+    ctx.draw(&Circle {
+        x: x + 0.5,
+        y: y - 2.0,
+        radius: 0.4,
+        color: Color::Rgb(255, 192, 203), // Pink paws
+    });
+    ctx.draw(&Circle {
+        x: x + 3.0,
+        y: y - 2.0,
+        radius: 0.4,
+        color: Color::Rgb(255, 192, 203), // Pink paws
+    });
+
+    // Tail (simple curved behind)
+    let tail_sway = if frame % 20 < 10 { 0.5 } else { -0.5 };
+    ctx.draw(&Line {
+        x1: x - 3.5,
+        y1: y + 3.0,
+        x2: x - 5.0 + tail_sway,
+        y2: y + 6.0,
+        color: coat.tail,
+    });
+    ctx.draw(&Line {
+        x1: x - 5.0 + tail_sway,
+        y1: y + 6.0,
+        x2: x - 4.0 + tail_sway,
+        y2: y + 9.0,
+        color: coat.tail,
+    });
 }
 
-impl Source for MarioTone {
-    fn current_span_len(&self) -> Option<usize> {
-        Some(self.total_samples - self.sample_idx)
-    }
-
-    fn channels(&self) -> u16 {
-        1
-    }
-
-    fn sample_rate(&self) -> u32 {
-        self.sample_rate
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steps() {
+        let frames = |ms: u64| steps(Duration::from_millis(ms)).collect::<Vec<f64>>();
+        assert!(frames(0).is_empty());
+        // A frame at 30 FPS is a third of the original frame
+        let third = frames(33);
+        assert_eq!(third.len(), 1);
+        assert!((third[0] - 0.33).abs() < 1e-9);
+        // Slow frames are split up, and long stalls cut short
+        assert_eq!(frames(100).len(), 4);
+        assert!((frames(100).iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!((frames(5000).iter().sum::<f64>() - 2.5).abs() < 1e-9);
     }
 
-    fn total_duration(&self) -> Option<Duration> {
-        Some(self.duration)
+    #[test]
+    fn test_bounds() {
+        let area = |width, height| Rect::new(0, 0, width, height);
+        // Wide: margins at the sides
+        assert_eq!(MarioAnimation::bounds(area(160, 25), 20.0), ([-40.0, 280.0], [0.0, 100.0]));
+        // Tall: room above
+        assert_eq!(MarioAnimation::bounds(area(120, 40), 20.0), ([0.0, 240.0], [0.0, 160.0]));
+        // Narrow: a window that follows the cat and stays inside the scene
+        let (x, y) = MarioAnimation::bounds(area(40, 30), 100.0);
+        assert_eq!(x, [50.0, 150.0]);
+        assert!(y[1] >= SCENE_HEIGHT);
+        assert_eq!(MarioAnimation::bounds(area(40, 30), 5.0).0, [0.0, 100.0]);
+        assert_eq!(MarioAnimation::bounds(area(40, 30), 235.0).0, [140.0, 240.0]);
     }
 }
@@ -0,0 +1,174 @@
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, Instant};
+
+use crate::jobs;
+
+const CHECK_EVERY: Duration = Duration::from_secs(5);
+
+// Apps whose recording means a call. Browsers are where Meet, Jitsi and the
+// like run; anything else recording, a voice memo or a level meter, isn't one
+#[cfg(not(any(target_os = "macos", windows)))]
+const CALL_APPS: [&str; 17] = [
+    "zoom", "teams", "slack", "discord", "skype", "webex", "signal", "telegram", "element", "mumble", "firefox", "chrome", "chromium", "brave", "edge",
+    "vivaldi", "opera",
+];
+
+// Notices calls by the microphone or camera being in use. Checked in the
+// background every few seconds while work runs
+pub struct MeetingDetector {
+    in_call: bool, // As of the last check
+    // The call going on was announced already. Whatever was done about it,
+    // it isn't announced again until it ends, and it's watched until then
+    handled: bool,
+    checked_at: Option<Instant>,
+    pending: Option<Receiver<bool>>,
+}
+
+impl MeetingDetector {
+    pub fn new() -> Self {
+        MeetingDetector {
+            in_call: false,
+            handled: false,
+            checked_at: None,
+            pending: None,
+        }
+    }
+
+    // Called every frame; true once when a call starts while `active`
+    pub fn tick(&mut self, active: bool) -> bool {
+        if let Some(receiver) = &self.pending
+            && let Ok(in_call) = receiver.try_recv()
+        {
+            self.pending = None;
+            self.heard(in_call);
+        }
+        let started = self.started(active);
+        if (active || self.handled) && self.pending.is_none() && self.checked_at.is_none_or(|at| at.elapsed() >= CHECK_EVERY) {
+            self.checked_at = Some(Instant::now());
+            self.pending = Some(jobs::run(in_call));
+        }
+        started
+    }
+
+    fn heard(&mut self, in_call: bool) {
+        self.in_call = in_call;
+        if !in_call {
+            self.handled = false;
+        }
+    }
+
+    fn started(&mut self, active: bool) -> bool {
+        if !active && !self.handled {
+            // Not checked meanwhile, so it would be stale when work resumes
+            self.in_call = false;
+        }
+        let started = active && self.in_call && !self.handled;
+        self.handled |= started;
+        started
+    }
+}
+
+// A call app recording from a microphone, or anything holding a camera open
+#[cfg(not(any(target_os = "macos", windows)))]
+fn in_call() -> bool {
+    use std::fs;
+    use std::process::{Command, Stdio};
+
+    let recording = Command::new("pactl")
+        .args(["list", "source-outputs"])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .is_ok_and(|output| output.status.success() && call_app_recording(&String::from_utf8_lossy(&output.stdout)));
+    if recording {
+        return true;
+    }
+    // Only our own user's processes are visible, which is where calls run
+    let Ok(processes) = fs::read_dir("/proc") else {
+        return false;
+    };
+    processes
+        .flatten()
+        .filter_map(|process| fs::read_dir(process.path().join("fd")).ok())
+        .flatten()
+        .flatten()
+        .any(|fd| fs::read_link(fd.path()).is_ok_and(|target| target.starts_with("/dev/video")))
+}
+
+// Whether a recording stream in `pactl list source-outputs` is a call app's,
+// going by its application name and binary
+#[cfg(not(any(target_os = "macos", windows)))]
+fn call_app_recording(listing: &str) -> bool {
+    listing
+        .lines()
+        .filter_map(|line| line.trim().split_once(" = "))
+        .filter(|(key, _)| matches!(*key, "application.name" | "application.process.binary"))
+        .map(|(_, value)| value.trim_matches('"').to_ascii_lowercase())
+        .any(|app| CALL_APPS.iter().any(|call_app| app.contains(call_app)))
+}
+
+// The privacy settings record when each app last started and stopped using
+// the microphone or camera; a stop time of 0 means it is still in use
+#[cfg(windows)]
+fn in_call() -> bool {
+    use std::process::{Command, Stdio};
+
+    let script = "$base = 'HKCU:\\Software\\Microsoft\\Windows\\CurrentVersion\\CapabilityAccessManager\\ConsentStore'; \
+         Get-ChildItem \"$base\\microphone\", \"$base\\webcam\" -Recurse -ErrorAction SilentlyContinue | \
+         Get-ItemProperty | Where-Object { $_.LastUsedTimeStop -eq 0 } | Select-Object -First 1 | \
+         ForEach-Object { 'in-use' }";
+    Command::new("powershell")
+        .args(["-NoProfile", "-WindowStyle", "Hidden", "-Command", script])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).contains("in-use"))
+}
+
+// macOS only tells apps about their own capture sessions
+#[cfg(target_os = "macos")]
+fn in_call() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_each_call_announced_once() {
+        let mut detector = MeetingDetector::new();
+        detector.heard(true);
+        assert!(detector.started(true));
+        // Resumed or dismissed during the call: not asked again
+        assert!(!detector.started(true));
+        detector.heard(true);
+        assert!(!detector.started(false));
+        assert!(!detector.started(true));
+
+        // The call ends, and the next one is announced
+        detector.heard(false);
+        assert!(!detector.started(true));
+        detector.heard(true);
+        assert!(detector.started(true));
+
+        // A call seen before a break is forgotten, as it isn't watched through it
+        let mut detector = MeetingDetector::new();
+        detector.heard(true);
+        assert!(!detector.started(false));
+        assert!(!detector.started(true));
+    }
+
+    #[cfg(not(any(target_os = "macos", windows)))]
+    #[test]
+    fn test_call_apps() {
+        let stream = |name: &str, binary: &str| {
+            format!("Source Output #7\n\tProperties:\n\t\tapplication.name = \"{name}\"\n\t\tapplication.process.binary = \"{binary}\"\n")
+        };
+        assert!(call_app_recording(&stream("ZOOM VoiceEngine", "zoom")));
+        assert!(call_app_recording(&stream("Google Chrome", "chrome")));
+        assert!(!call_app_recording(&stream("PulseAudio Volume Control", "pavucontrol")));
+        assert!(!call_app_recording(&stream("GNOME Sound Recorder", "gnome-sound-recorder")));
+        assert!(!call_app_recording(""));
+    }
+}
@@ -0,0 +1,150 @@
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use midly::{MetaMessage, MidiMessage, Smf, Timing, TrackEventKind};
+
+use crate::rtttl::Melody;
+
+// Notes sounding at once; any more are left out
+const MAX_VOICES: usize = 4;
+// Completion music, not a whole song
+const MAX_SECONDS: f64 = 30.0;
+// General MIDI puts drums on channel 10, which tones can't play
+const DRUM_CHANNEL: u8 = 9;
+
+// A standard MIDI file as up to MAX_VOICES melodies to play together. Notes
+// are spread over the voices in order; leading silence is trimmed
+pub fn load(path: &Path) -> Result<Vec<Melody>, String> {
+    let bytes = fs::read(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    parse(&bytes).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+struct Note {
+    start: f64, // Seconds
+    end: f64,
+    key: u8,
+}
+
+fn parse(bytes: &[u8]) -> Result<Vec<Melody>, String> {
+    let smf = Smf::parse(bytes).map_err(|e| e.to_string())?;
+
+    // All tracks merged into one timeline, as the tempo map lives in the first
+    let mut events = Vec::new();
+    for track in &smf.tracks {
+        let mut tick = 0u64;
+        for event in track {
+            tick += event.delta.as_int() as u64;
+            events.push((tick, event.kind));
+        }
+    }
+    events.sort_by_key(|(tick, _)| *tick);
+
+    let mut micros_per_beat = 500_000.0; // 120 bpm until told otherwise
+    let seconds_per_tick = |micros_per_beat: f64| match smf.header.timing {
+        Timing::Metrical(ticks_per_beat) => micros_per_beat / 1_000_000.0 / ticks_per_beat.as_int().max(1) as f64,
+        Timing::Timecode(fps, subframes) => 1.0 / (fps.as_int() as f64 * subframes.max(1) as f64),
+    };
+    let (mut now, mut last_tick) = (0.0, 0);
+    let mut held: Vec<(u8, u8, f64)> = Vec::new(); // Channel, key, start
+    let mut notes = Vec::new();
+    for (tick, kind) in events {
+        now += (tick - last_tick) as f64 * seconds_per_tick(micros_per_beat);
+        last_tick = tick;
+        match kind {
+            TrackEventKind::Meta(MetaMessage::Tempo(tempo)) => micros_per_beat = tempo.as_int() as f64,
+            TrackEventKind::Midi { channel, message } if channel.as_int() != DRUM_CHANNEL => {
+                let channel = channel.as_int();
+                let (key, on) = match message {
+                    MidiMessage::NoteOn { key, vel } => (key.as_int(), vel.as_int() > 0),
+                    MidiMessage::NoteOff { key, .. } => (key.as_int(), false),
+                    _ => continue,
+                };
+                if let Some(i) = held.iter().position(|&(c, k, _)| c == channel && k == key) {
+                    let (_, _, start) = held.remove(i);
+                    notes.push(Note { start, end: now, key });
+                }
+                if on {
+                    held.push((channel, key, now));
+                }
+            }
+            _ => {}
+        }
+    }
+    if notes.is_empty() {
+        return Err("no notes outside the drum channel".to_string());
+    }
+    notes.sort_by(|a, b| a.start.total_cmp(&b.start));
+
+    // Each voice plays one note at a time
+    let offset = notes[0].start;
+    let mut voices: Vec<(f64, Melody)> = Vec::new(); // Where each voice is up to
+    for note in notes {
+        let (start, end) = (note.start - offset, (note.end - offset).min(MAX_SECONDS));
+        if start >= MAX_SECONDS || end <= start {
+            continue;
+        }
+        let voice = match voices.iter().position(|(at, _)| *at <= start) {
+            Some(i) => i,
+            None if voices.len() < MAX_VOICES => {
+                voices.push((0.0, Vec::new()));
+                voices.len() - 1
+            }
+            None => continue,
+        };
+        let (at, melody) = &mut voices[voice];
+        if start > *at {
+            melody.push((0.0, Duration::from_secs_f64(start - *at)));
+        }
+        let freq = 440.0 * 2f32.powf((note.key as f32 - 69.0) / 12.0);
+        melody.push((freq, Duration::from_secs_f64(end - start)));
+        *at = end;
+    }
+    Ok(voices.into_iter().map(|(_, melody)| melody).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use midly::num::{u4, u7, u15, u24, u28};
+    use midly::{Format, Header, TrackEvent};
+
+    #[test]
+    fn test_parse() {
+        let event = |delta: u32, kind| TrackEvent { delta: u28::new(delta), kind };
+        let note = |channel: u8, key: u8, vel: u8| TrackEventKind::Midi {
+            channel: u4::new(channel),
+            message: MidiMessage::NoteOn {
+                key: u7::new(key),
+                vel: u7::new(vel),
+            },
+        };
+        // 240 bpm at 100 ticks a beat: a tick is 2.5ms
+        let tempo = vec![event(0, TrackEventKind::Meta(MetaMessage::Tempo(u24::new(250_000))))];
+        let music = vec![
+            event(100, note(0, 69, 100)), // A4 after a beat of silence
+            event(0, note(0, 57, 100)),   // A3 together with it
+            event(0, note(9, 36, 100)),   // Kick drum, left out
+            event(100, note(0, 69, 0)),
+            event(100, note(0, 57, 0)),
+            event(0, note(0, 81, 100)), // A5 once both have ended
+            event(200, note(0, 81, 0)),
+        ];
+        let smf = Smf {
+            header: Header::new(Format::Parallel, Timing::Metrical(u15::new(100))),
+            tracks: vec![tempo, music],
+        };
+        let mut bytes = Vec::new();
+        smf.write_std(&mut bytes).unwrap();
+
+        let voices = parse(&bytes).unwrap();
+        let ms = |melody: &Melody| -> Vec<(u32, u32)> {
+            let ms = |length: &Duration| (length.as_secs_f64() * 1000.0).round() as u32;
+            melody.iter().map(|(freq, length)| (freq.round() as u32, ms(length))).collect()
+        };
+        // The chord is split over two voices, and the first picks up the next note
+        assert_eq!(voices.len(), 2);
+        assert_eq!(ms(&voices[0]), [(440, 250), (0, 250), (880, 500)]);
+        assert_eq!(ms(&voices[1]), [(220, 500)]);
+    }
+}
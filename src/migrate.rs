@@ -0,0 +1,190 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+
+// Every file the timer keeps records the version of its format. Loading an
+// older one runs the steps from that version up to the current one, after
+// copying the file aside, so a format change never strands anyone's data.
+// A file from a newer version is read as far as it parses but never written
+
+// Upgrades a document, or one history record, by one version
+pub type Step = fn(&mut Value);
+
+pub struct Schema {
+    pub file: &'static str, // For messages
+    pub version: u32,
+    // The upgrades that lead up to `version`, oldest first; versions before
+    // the first of them can't be read any more
+    pub steps: &'static [Step],
+}
+
+impl Schema {
+    // The steps that upgrade version `found`, or why it can't be upgraded
+    fn steps_from(&self, found: u32) -> Result<&'static [Step], String> {
+        if found > self.version {
+            return Err(self.too_new(found));
+        }
+        let first = self.version - self.steps.len() as u32;
+        let skip = found
+            .checked_sub(first)
+            .ok_or_else(|| format!("{} is at version {found}, too old to upgrade", self.file))?;
+        Ok(&self.steps[skip as usize..])
+    }
+
+    pub fn check(&self, found: u32) -> Result<(), String> {
+        self.steps_from(found).map(|_| ())
+    }
+
+    pub fn upgrade(&self, found: u32, value: &mut Value) -> Result<(), String> {
+        for step in self.steps_from(found)? {
+            step(value);
+        }
+        Ok(())
+    }
+
+    pub fn too_new(&self, found: u32) -> String {
+        format!(
+            "{} is from a newer cyber-tomato (version {found}; this one reads up to {})",
+            self.file, self.version
+        )
+    }
+}
+
+// Copies `path` to `<path>.v<version>.bak` before it's upgraded. An existing
+// copy is kept: it's the older one
+pub fn backup(path: &Path, version: u32) -> io::Result<PathBuf> {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".v{version}.bak"));
+    let copy = path.with_file_name(name);
+    if !copy.exists() {
+        fs::copy(path, &copy)?;
+    }
+    Ok(copy)
+}
+
+// Replaces `path` in one step, so a crash never leaves it half-written
+pub fn write_atomic(path: &Path, contents: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    let tmp = path.with_file_name(name);
+    fs::write(&tmp, contents)?;
+    fs::rename(tmp, path)
+}
+
+// A list file such as tasks.json, as loaded
+pub struct List<T> {
+    pub items: Vec<T>,
+    // Why it mustn't be saved over: it's from a newer version, or the
+    // upgrade couldn't keep a copy of the old one
+    pub locked: Option<String>,
+}
+
+// Lists are stored as {"version": N, "<key>": [...]}. Ones saved before
+// versions were recorded are the bare list, version 0, so every list's
+// first step wraps it. An older file is upgraded and saved straight away;
+// a missing or unreadable one reads as empty
+pub fn read_list<T: DeserializeOwned>(path: &Path, schema: &Schema, key: &str) -> List<T> {
+    let mut list = List {
+        items: Vec::new(),
+        locked: None,
+    };
+    let Some(mut value) = fs::read_to_string(path).ok().and_then(|text| serde_json::from_str::<Value>(&text).ok()) else {
+        return list;
+    };
+    let found = match &value {
+        Value::Array(_) => 0,
+        _ => value.get("version").and_then(Value::as_u64).unwrap_or(0) as u32,
+    };
+    if found > schema.version {
+        list.locked = Some(schema.too_new(found));
+    } else if found < schema.version {
+        if let Err(e) = schema.upgrade(found, &mut value) {
+            list.locked = Some(e);
+            return list;
+        }
+        value["version"] = json!(schema.version);
+        let saved = backup(path, found).and_then(|_| write_atomic(path, &serde_json::to_string_pretty(&value)?));
+        if let Err(e) = saved {
+            list.locked = Some(format!("{} was upgraded but not saved: {e}", schema.file));
+        }
+    }
+    // Items a newer version changed beyond recognition are skipped
+    if let Some(Value::Array(items)) = value.get_mut(key) {
+        list.items = items.drain(..).filter_map(|item| serde_json::from_value(item).ok()).collect();
+    }
+    list
+}
+
+pub fn write_list<T: Serialize>(path: &Path, schema: &Schema, key: &str, items: &[T]) -> io::Result<()> {
+    let value = json!({ "version": schema.version, key: items });
+    write_atomic(path, &serde_json::to_string_pretty(&value)?)
+}
+
+// The first step of every list: the bare list goes under `key`
+pub fn wrap_list(value: &mut Value, key: &str) {
+    if value.is_array() {
+        *value = json!({ key: value.take() });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NUMBERS: Schema = Schema {
+        file: "numbers.json",
+        version: 2,
+        steps: &[|value| wrap_list(value, "numbers"), double],
+    };
+
+    fn double(value: &mut Value) {
+        if let Some(Value::Array(numbers)) = value.get_mut("numbers") {
+            for n in numbers {
+                *n = json!(n.as_u64().unwrap_or(0) * 2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_list_upgrades_with_backup() {
+        let dir = std::env::temp_dir().join(format!("cyber-tomato-migrate-{}", std::process::id()));
+        let path = dir.join("numbers.json");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, "[1, 2]").unwrap();
+
+        let list: List<u64> = read_list(&path, &NUMBERS, "numbers");
+        assert_eq!(list.items, vec![2, 4]);
+        assert!(list.locked.is_none());
+        assert_eq!(fs::read_to_string(dir.join("numbers.json.v0.bak")).unwrap(), "[1, 2]");
+        // Saved upgraded, so the next load leaves it alone
+        let saved: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved, json!({"version": 2, "numbers": [2, 4]}));
+        assert_eq!(read_list::<u64>(&path, &NUMBERS, "numbers").items, vec![2, 4]);
+
+        fs::write(&path, r#"{"version": 3, "numbers": [5, "five"]}"#).unwrap();
+        let newer: List<u64> = read_list(&path, &NUMBERS, "numbers");
+        assert_eq!(newer.items, vec![5]);
+        assert!(newer.locked.is_some());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_upgrade_refuses_unknown_versions() {
+        let mut value = json!({});
+        assert!(NUMBERS.upgrade(3, &mut value).is_err());
+        const LATER: Schema = Schema {
+            file: "later.json",
+            version: 5,
+            steps: &[double],
+        };
+        assert!(LATER.upgrade(3, &mut value).is_err());
+        assert!(LATER.upgrade(4, &mut value).is_ok());
+    }
+}
@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use crate::config::MilestonesConfig;
+
+// Points in a work session worth announcing, as time elapsed when each is
+// reached and what to call it. Ones that fall outside the session are left out
+fn marks(config: &MilestonesConfig, duration: Duration) -> Vec<(Duration, String)> {
+    let mut marks = Vec::new();
+    for &percent in &config.percent {
+        let label = if percent == 50 { "Halfway".to_string() } else { format!("{percent}% done") };
+        marks.push((duration.mul_f64(percent as f64 / 100.0), label));
+    }
+    for &minutes in &config.minutes_left {
+        let left = Duration::from_secs(minutes as u64 * 60);
+        if left < duration {
+            let unit = if minutes == 1 { "minute" } else { "minutes" };
+            marks.push((duration - left, format!("{minutes} {unit} left")));
+        }
+    }
+    marks.retain(|(at, _)| !at.is_zero() && *at < duration);
+    marks.sort_by_key(|(at, _)| *at);
+    marks
+}
+
+// The latest milestone passed as the clock went from `before` to `after`.
+// Several passed at once (after a suspend) are announced as the last one
+pub fn crossed(config: &MilestonesConfig, duration: Duration, before: Duration, after: Duration) -> Option<String> {
+    marks(config, duration)
+        .into_iter()
+        .filter(|(at, _)| before < *at && *at <= after)
+        .map(|(_, label)| label)
+        .next_back()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crossed() {
+        let config = MilestonesConfig::default();
+        let min = |m: u64| Duration::from_secs(m * 60);
+        let duration = min(25);
+        assert_eq!(crossed(&config, duration, min(12), min(13)).as_deref(), Some("Halfway"));
+        assert_eq!(crossed(&config, duration, min(18), min(19)).as_deref(), Some("75% done"));
+        assert_eq!(crossed(&config, duration, min(19), min(20)).as_deref(), Some("5 minutes left"));
+        assert_eq!(crossed(&config, duration, min(13), min(18)), None);
+        // Jumping past several announces the last
+        assert_eq!(crossed(&config, duration, min(1), min(24)).as_deref(), Some("5 minutes left"));
+        // A session shorter than the reminder only gets the percentages
+        assert_eq!(crossed(&config, min(4), Duration::ZERO, min(4)).as_deref(), Some("75% done"));
+    }
+}
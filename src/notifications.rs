@@ -0,0 +1,161 @@
+use std::env;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+// A button on the notification. Clicking it runs `command` as if it came from
+// `cyber-tomato remote`
+#[derive(Clone)]
+pub struct Button {
+    pub label: String,
+    pub command: &'static str,
+}
+
+// How notifications reach the desktop, picked for the OS we run on
+#[derive(Clone, Copy)]
+enum Backend {
+    NotifySend,
+    AppleScript,
+    // Notification Center alerts with buttons through the alerter helper
+    // (github.com/vjeantet/alerter), when it is installed
+    Alerter,
+    // WinRT toasts through PowerShell
+    WindowsToast,
+}
+
+impl Backend {
+    fn current() -> Backend {
+        match std::env::consts::OS {
+            "macos" if on_path("alerter") => Backend::Alerter,
+            "macos" => Backend::AppleScript,
+            "windows" => Backend::WindowsToast,
+            _ => Backend::NotifySend,
+        }
+    }
+}
+
+// Fire-and-forget desktop notifications. Failures (no notification daemon,
+// missing helper binary) are ignored: the TUI is the primary feedback
+pub struct Notifier {
+    backend: Backend,
+    sender: Sender<&'static str>,
+    clicks: Receiver<&'static str>,
+}
+
+impl Notifier {
+    pub fn new() -> Self {
+        let (sender, clicks) = mpsc::channel();
+        Notifier {
+            backend: Backend::current(),
+            sender,
+            clicks,
+        }
+    }
+
+    // Backends without buttons show the notification without them
+    pub fn send(&self, title: &str, body: &str, buttons: Vec<Button>) {
+        let mut command = match self.backend {
+            Backend::NotifySend => notify_send(title, body),
+            Backend::AppleScript => apple_script(title, body),
+            Backend::Alerter => alerter(title, body, &buttons),
+            Backend::WindowsToast => windows_toast(title, body, &buttons),
+        };
+        command.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::null());
+        let sender = self.sender.clone();
+        // Wait on a background thread so the child is reaped without blocking the UI.
+        // Helpers with buttons stay up until the notification is clicked or
+        // dismissed and then print the clicked button's label or command
+        thread::spawn(move || {
+            let Ok(output) = command.output() else {
+                return;
+            };
+            let clicked = String::from_utf8_lossy(&output.stdout);
+            let clicked = clicked.trim();
+            if let Some(button) = buttons.iter().find(|b| clicked == b.label || clicked == b.command) {
+                let _ = sender.send(button.command);
+            }
+        });
+    }
+
+    // Commands of buttons clicked since the last call
+    pub fn clicks(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.clicks.try_iter()
+    }
+}
+
+fn notify_send(title: &str, body: &str) -> Command {
+    let mut command = Command::new("notify-send");
+    command.args(["--app-name", "Cyber Tomato", title, body]);
+    command
+}
+
+fn apple_script(title: &str, body: &str) -> Command {
+    let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+    let mut command = Command::new("osascript");
+    command
+        .arg("-e")
+        .arg(format!("display notification \"{}\" with title \"{}\"", escape(body), escape(title)));
+    command
+}
+
+fn alerter(title: &str, body: &str, buttons: &[Button]) -> Command {
+    let mut command = Command::new("alerter");
+    command.args(["-title", title, "-message", body, "-group", "cyber-tomato"]);
+    if buttons.is_empty() {
+        // Nothing to wait for, so let it go like a banner
+        command.args(["-timeout", "10"]);
+    } else {
+        let labels: Vec<&str> = buttons.iter().map(|b| b.label.as_str()).collect();
+        command.args(["-actions", &labels.join(","), "-closeLabel", "Dismiss", "-timeout", "900"]);
+    }
+    command
+}
+
+// Toasts need a registered app id, so they are raised under PowerShell's own.
+// The toast XML travels in an environment variable to stay clear of quoting
+const TOAST_SCRIPT: &str = r#"
+[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] | Out-Null
+[Windows.Data.Xml.Dom.XmlDocument, Windows.Data.Xml.Dom.XmlDocument, ContentType = WindowsRuntime] | Out-Null
+$xml = New-Object Windows.Data.Xml.Dom.XmlDocument
+$xml.LoadXml($env:CYBER_TOMATO_TOAST)
+$toast = New-Object Windows.UI.Notifications.ToastNotification $xml
+Register-ObjectEvent $toast Activated -SourceIdentifier clicked | Out-Null
+Register-ObjectEvent $toast Dismissed -SourceIdentifier dismissed | Out-Null
+$app = '{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\WindowsPowerShell\v1.0\powershell.exe'
+[Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier($app).Show($toast)
+if ($env:CYBER_TOMATO_WAIT) {
+    $event = Wait-Event -Timeout 900
+    if ($event.SourceIdentifier -eq 'clicked') { $event.SourceArgs[1].Arguments }
+}
+"#;
+
+fn windows_toast(title: &str, body: &str, buttons: &[Button]) -> Command {
+    let escape = |s: &str| s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;");
+    let actions: String = buttons
+        .iter()
+        .map(|b| {
+            format!(
+                r#"<action content="{}" arguments="{}" activationType="foreground"/>"#,
+                escape(&b.label),
+                escape(b.command)
+            )
+        })
+        .collect();
+    let xml = format!(
+        r#"<toast><visual><binding template="ToastGeneric"><text>{}</text><text>{}</text></binding></visual><actions>{actions}</actions></toast>"#,
+        escape(title),
+        escape(body)
+    );
+    let mut command = Command::new("powershell");
+    command
+        .args(["-NoProfile", "-WindowStyle", "Hidden", "-Command", TOAST_SCRIPT])
+        .env("CYBER_TOMATO_TOAST", xml);
+    if !buttons.is_empty() {
+        command.env("CYBER_TOMATO_WAIT", "1");
+    }
+    command
+}
+
+fn on_path(program: &str) -> bool {
+    env::var_os("PATH").is_some_and(|paths| env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+}
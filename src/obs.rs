@@ -0,0 +1,73 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::config::ObsConfig;
+use crate::paths;
+use crate::status::Status;
+use crate::template;
+
+pub fn default_path() -> PathBuf {
+    paths::cache_dir().join("obs.txt")
+}
+
+// A text file with the countdown for a streaming overlay: OBS text sources
+// can read from a file and restyle it, so the text stays plain
+pub struct ObsOverlay {
+    path: Option<PathBuf>, // None when turned off
+    template: String,
+    last: Option<String>,
+}
+
+impl ObsOverlay {
+    pub fn new() -> Self {
+        ObsOverlay {
+            path: None,
+            template: String::new(),
+            last: None,
+        }
+    }
+
+    pub fn set_config(&mut self, config: &ObsConfig) {
+        let path = config
+            .enabled
+            .then(|| config.path.as_deref().map(paths::expand_home).unwrap_or_else(default_path));
+        if path != self.path {
+            self.clear();
+            self.path = path;
+        }
+        self.template = config.template.clone();
+        self.last = None;
+    }
+
+    pub fn update(&mut self, status: &Status) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let text = template::render(&self.template, status);
+        if self.last.as_ref() == Some(&text) {
+            return;
+        }
+        // The stream goes on without it; a failed write must not disturb the timer
+        let _ = write(path, &text);
+        self.last = Some(text);
+    }
+
+    // Blank rather than removed, which some text sources show as an error
+    pub fn clear(&mut self) {
+        if let Some(path) = &self.path {
+            let _ = write(path, "");
+        }
+        self.last = None;
+    }
+}
+
+fn write(path: &Path, text: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Replace atomically so the overlay never shows half a line
+    let tmp = path.with_extension("txt.tmp");
+    fs::write(&tmp, text)?;
+    fs::rename(tmp, path)
+}
@@ -0,0 +1,239 @@
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::PomodoroTimer;
+use crate::audio::{AudioEngine, AudioManager};
+use crate::cli::{self, Exit, OnceOptions};
+
+// One line of output in --stdin and --json-events modes
+#[derive(Serialize)]
+struct Event<'a> {
+    event: &'a str, // start, tick, status, paused, resumed, added, complete or error
+    state: &'a str, // running, paused or done
+    left_secs: u64,
+    duration_secs: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+}
+
+struct Countdown {
+    duration: Duration,   // Grows with `add`
+    end: Option<Instant>, // None while paused
+    left: Duration,       // Time left when paused
+}
+
+impl Countdown {
+    fn new(duration: Duration) -> Self {
+        Countdown {
+            duration,
+            end: Some(Instant::now() + duration),
+            left: duration,
+        }
+    }
+
+    fn left(&self) -> Duration {
+        match self.end {
+            Some(end) => end.saturating_duration_since(Instant::now()),
+            None => self.left,
+        }
+    }
+
+    fn pause(&mut self) {
+        if self.end.is_some() {
+            self.left = self.left();
+            self.end = None;
+        }
+    }
+
+    fn resume(&mut self) {
+        if self.end.is_none() {
+            self.end = Some(Instant::now() + self.left);
+        }
+    }
+
+    fn add(&mut self, extra: Duration) {
+        self.duration += extra;
+        match &mut self.end {
+            Some(end) => *end += extra,
+            None => self.left += extra,
+        }
+    }
+
+    fn event<'a>(&self, event: &'a str, message: Option<String>) -> Event<'a> {
+        let left = self.left();
+        Event {
+            event,
+            state: match self.end {
+                _ if left.is_zero() => "done",
+                Some(_) => "running",
+                None => "paused",
+            },
+            left_secs: left.as_secs_f64().ceil() as u64,
+            duration_secs: self.duration.as_secs(),
+            message,
+        }
+    }
+}
+
+// A single countdown without the TUI, for shell scripts and makefiles. Ctrl-C,
+// kill and `stop` end it early with the interrupted exit status
+pub fn run(options: OnceOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let interrupted = Arc::new(AtomicBool::new(false));
+    #[cfg(unix)]
+    for signal in [signal_hook::consts::SIGINT, signal_hook::consts::SIGTERM] {
+        signal_hook::flag::register(signal, Arc::clone(&interrupted))?;
+    }
+    let mut countdown = Countdown::new(options.duration);
+    let json = options.stdin || options.json_events;
+    // A live countdown only makes sense on a terminal; pipes get the final line
+    let live = !options.quiet && !json && io::stdout().is_terminal();
+    let mut commands = options.stdin.then(read_commands);
+    let mut stdout = io::stdout();
+    if options.json_events {
+        emit(&countdown.event("start", None))?;
+    }
+    let mut shown_secs = None;
+    loop {
+        let left = countdown.left();
+        let secs = left.as_secs_f64().ceil() as u64;
+        if shown_secs != Some(secs) {
+            if live {
+                write!(stdout, "\r{}", PomodoroTimer::format_duration(Duration::from_secs(secs)))?;
+                stdout.flush()?;
+            } else if options.json_events && shown_secs.is_some() && secs > 0 {
+                emit(&countdown.event("tick", None))?;
+            }
+            shown_secs = Some(secs);
+        }
+        if left.is_zero() {
+            break;
+        }
+        if interrupted.load(Ordering::Relaxed) {
+            if live {
+                writeln!(stdout)?;
+            }
+            return Err(Exit::Interrupted.into());
+        }
+        // Wake on whole seconds left so the display never skips one
+        let to_next = Duration::from_nanos(left.subsec_nanos() as u64);
+        let wait = if to_next.is_zero() { Duration::from_secs(1) } else { to_next };
+        let Some(receiver) = &commands else {
+            thread::sleep(wait);
+            continue;
+        };
+        match receiver.recv_timeout(wait) {
+            Ok(line) => {
+                let event = match line.split_whitespace().collect::<Vec<_>>()[..] {
+                    ["status"] => countdown.event("status", None),
+                    ["pause"] => {
+                        countdown.pause();
+                        countdown.event("paused", None)
+                    }
+                    ["resume"] => {
+                        countdown.resume();
+                        countdown.event("resumed", None)
+                    }
+                    ["add", amount] => match cli::parse_duration(amount) {
+                        Ok(extra) => {
+                            countdown.add(extra);
+                            countdown.event("added", None)
+                        }
+                        Err(e) => countdown.event("error", Some(e)),
+                    },
+                    // Ends the run like Ctrl-C would, without the chime
+                    ["stop"] => return Err(Exit::Interrupted.into()),
+                    [] => continue,
+                    _ => countdown.event("error", Some(format!("Unknown command: {}", line.trim()))),
+                };
+                emit(&event)?;
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            // stdin closed; the countdown carries on without commands, and
+            // with nothing left to resume it, a paused one carries on too
+            Err(RecvTimeoutError::Disconnected) => {
+                commands = None;
+                if countdown.end.is_none() {
+                    countdown.resume();
+                    emit(&countdown.event("resumed", Some("stdin closed".to_string())))?;
+                }
+            }
+        }
+    }
+
+    if json {
+        emit(&countdown.event("complete", None))?;
+    } else if !options.quiet {
+        if live {
+            writeln!(stdout)?;
+        }
+        println!("Done");
+    }
+    if !options.quiet {
+        let audio = AudioManager::new(Rc::new(AudioEngine::new()));
+        if audio.play_work_complete_sound() {
+            audio.wait();
+        } else {
+            eprintln!("Audio device unavailable");
+        }
+    }
+    Ok(())
+}
+
+// Lines from stdin, read on their own thread so the countdown keeps ticking
+fn read_commands() -> Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines().map_while(Result::ok) {
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+fn emit(event: &Event) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    writeln!(stdout, "{}", serde_json::to_string(event)?)?;
+    // Callers read line by line, so don't leave events sitting in a buffer
+    stdout.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_countdown_pause_resume_add() {
+        let mut countdown = Countdown::new(Duration::from_secs(60));
+        assert_eq!(countdown.event("start", None).state, "running");
+
+        countdown.pause();
+        let left = countdown.left();
+        assert!(left <= Duration::from_secs(60) && left > Duration::from_secs(59));
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(countdown.left(), left);
+        // Pausing twice doesn't lose the time left
+        countdown.pause();
+        assert_eq!(countdown.left(), left);
+
+        countdown.add(Duration::from_secs(30));
+        let paused = countdown.event("added", None);
+        assert_eq!((paused.state, paused.left_secs, paused.duration_secs), ("paused", 90, 90));
+
+        countdown.resume();
+        countdown.add(Duration::from_secs(10));
+        let running = countdown.event("status", None);
+        assert_eq!((running.state, running.left_secs, running.duration_secs), ("running", 100, 100));
+
+        let done = Countdown::new(Duration::ZERO).event("complete", None);
+        assert_eq!((done.state, done.left_secs), ("done", 0));
+    }
+}
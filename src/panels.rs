@@ -0,0 +1,159 @@
+use chrono::{Local, TimeZone};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+use serde::Deserialize;
+
+use crate::config::LayoutConfig;
+use crate::history::SessionRecord;
+use crate::{TimerType, theme};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Panel {
+    Title,
+    Digits,
+    Progress,
+    Status,
+    Quote,
+    Sessions,
+}
+
+impl Panel {
+    pub const ALL: [Panel; 6] = [Panel::Title, Panel::Digits, Panel::Progress, Panel::Status, Panel::Quote, Panel::Sessions];
+
+    pub const DEFAULT_ORDER: [Panel; 5] = [Panel::Title, Panel::Digits, Panel::Progress, Panel::Status, Panel::Quote];
+
+    // Name used in the [layout] table of config.toml
+    pub fn name(self) -> &'static str {
+        match self {
+            Panel::Title => "title",
+            Panel::Digits => "digits",
+            Panel::Progress => "progress",
+            Panel::Status => "status",
+            Panel::Quote => "quote",
+            Panel::Sessions => "sessions",
+        }
+    }
+
+    // Rows the panel takes; 0 shares whatever space the fixed panels leave
+    pub fn default_height(self) -> u16 {
+        match self {
+            Panel::Digits => 7, // 5 digit lines + borders
+            Panel::Sessions => 0,
+            _ => 3,
+        }
+    }
+}
+
+// Panels given up, in this order, when the terminal is too short for them all.
+// The digits and progress bar always stay
+const DROP_ORDER: [Panel; 4] = [Panel::Sessions, Panel::Quote, Panel::Title, Panel::Status];
+// What a panel that shares the left-over space needs to be worth showing
+const MIN_FILL_ROWS: u16 = 3;
+
+// Areas for the configured panels, top to bottom. The quote panel only
+// appears once a session has completed; `expand_digits` lets the digits panel
+// take the free space for the breathing guide or braille digits. Worked out
+// every frame from the current size, so a resize hides or brings back panels
+// straight away
+pub fn split(area: Rect, layout: &LayoutConfig, show_quote: bool, expand_digits: bool) -> Vec<(Panel, Rect)> {
+    let mut panels: Vec<Panel> = layout.panels.iter().copied().filter(|&panel| panel != Panel::Quote || show_quote).collect();
+    let rows = |panels: &[Panel]| -> u16 {
+        panels
+            .iter()
+            .map(|&panel| match layout.height(panel) {
+                0 => MIN_FILL_ROWS,
+                rows => rows,
+            })
+            .sum()
+    };
+    for drop in DROP_ORDER {
+        if rows(&panels) <= area.height {
+            break;
+        }
+        panels.retain(|&panel| panel != drop);
+    }
+    let constraints = panels.iter().map(|&panel| match layout.height(panel) {
+        _ if panel == Panel::Digits && expand_digits => Constraint::Fill(1),
+        0 => Constraint::Fill(1),
+        rows => Constraint::Length(rows),
+    });
+    let areas = Layout::vertical(constraints).split(area);
+    panels.into_iter().zip(areas.iter().copied()).collect()
+}
+
+// Today's sessions, newest first
+pub fn render_sessions(f: &mut Frame, area: Rect, records: &[SessionRecord]) {
+    let today = Local::now().date_naive();
+    let todays: Vec<(String, &SessionRecord)> = records
+        .iter()
+        .rev()
+        .filter(|record| !record.is_summary())
+        .filter_map(|record| {
+            let start = Local.timestamp_opt(record.started_at as i64, 0).single()?;
+            (start.date_naive() == today).then(|| (start.format("%H:%M").to_string(), record))
+        })
+        .collect();
+    let work = todays.iter().filter(|(_, record)| record.kind == TimerType::Work).count();
+
+    let lines: Vec<Line> = if todays.is_empty() {
+        vec![Line::from(Span::styled("No sessions yet today", Style::default().fg(Color::DarkGray)))]
+    } else {
+        todays
+            .into_iter()
+            .map(|(time, record)| {
+                let kind = match record.kind {
+                    TimerType::Work => "Work ",
+                    TimerType::Break => "Break",
+                };
+                let color = theme::digits(&record.kind);
+                let mut label: Vec<String> = record.task.iter().cloned().collect();
+                label.extend(record.tags.iter().map(|tag| format!("#{tag}")));
+                Line::from(vec![
+                    Span::raw(format!(" {time}  ")),
+                    Span::styled(kind, Style::default().fg(color).add_modifier(Modifier::BOLD)),
+                    Span::raw(format!(" {:>3}m  ", record.duration_secs / 60)),
+                    Span::styled(label.join(" "), Style::default().fg(theme::highlight())),
+                ])
+            })
+            .collect()
+    };
+
+    let sessions = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Today: {work} pomodoros"))
+            .border_style(Style::default().fg(theme::primary())),
+    );
+    f.render_widget(sessions, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_drops_panels_when_short() {
+        let layout = LayoutConfig {
+            panels: Panel::ALL.to_vec(),
+            ..LayoutConfig::default()
+        };
+        let shown = |height: u16| -> Vec<Panel> {
+            split(Rect::new(0, 0, 80, height), &layout, true, false)
+                .into_iter()
+                .map(|(panel, _)| panel)
+                .collect()
+        };
+        // 3 + 7 + 3 + 3 + 3 fixed rows, and 3 for the sessions list
+        assert_eq!(shown(22), Panel::ALL);
+        assert_eq!(shown(21), [Panel::Title, Panel::Digits, Panel::Progress, Panel::Status, Panel::Quote]);
+        assert_eq!(shown(12), [Panel::Digits, Panel::Progress]);
+        // Too short for even those: they share what there is
+        assert_eq!(shown(5), [Panel::Digits, Panel::Progress]);
+    }
+}
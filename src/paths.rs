@@ -0,0 +1,46 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+const APP_DIR: &str = "cyber-tomato";
+
+fn home_dir() -> PathBuf {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+fn xdg_dir(var: &str, windows_var: &str, fallback: &str) -> PathBuf {
+    if let Some(dir) = env::var_os(var).filter(|d| !d.is_empty()) {
+        return PathBuf::from(dir).join(APP_DIR);
+    }
+    if cfg!(windows)
+        && let Some(dir) = env::var_os(windows_var)
+    {
+        return PathBuf::from(dir).join(APP_DIR);
+    }
+    home_dir().join(fallback).join(APP_DIR)
+}
+
+// ~/.config/cyber-tomato - user settings
+pub fn config_dir() -> PathBuf {
+    xdg_dir("XDG_CONFIG_HOME", "APPDATA", ".config")
+}
+
+// ~/.local/share/cyber-tomato - history, tasks and other user data
+pub fn data_dir() -> PathBuf {
+    xdg_dir("XDG_DATA_HOME", "LOCALAPPDATA", ".local/share")
+}
+
+// ~/.cache/cyber-tomato - files other programs read, safe to delete
+pub fn cache_dir() -> PathBuf {
+    xdg_dir("XDG_CACHE_HOME", "LOCALAPPDATA", ".cache")
+}
+
+// Expand a leading `~/` so config values like "~/Sync/tomato" work
+pub fn expand_home(path: &Path) -> PathBuf {
+    match path.strip_prefix("~") {
+        Ok(rest) => home_dir().join(rest),
+        Err(_) => path.to_path_buf(),
+    }
+}
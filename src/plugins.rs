@@ -0,0 +1,237 @@
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use serde::Serialize;
+
+use crate::paths;
+use crate::scripts::Event;
+
+// Executables in <config dir>/plugins, started with the timer. Each gets one
+// JSON object per line on stdin for every event (the same ones and fields
+// scripts get, plus "event"), and may write commands to its stdout one per
+// line, named like `cyber-tomato remote`'s actions. Stdin closes when the
+// timer quits; a plugin that exits early just stops getting events, and one
+// that doesn't read them only holds up its own writer thread
+pub struct Plugins {
+    running: Vec<Plugin>,
+    commands: Receiver<(String, String)>,
+    errors: Receiver<String>,
+}
+
+struct Plugin {
+    lines: Sender<String>, // To the thread writing its stdin
+}
+
+#[derive(Serialize)]
+struct Message<'a> {
+    event: &'a str,
+    #[serde(flatten)]
+    session: &'a Event,
+}
+
+fn dir() -> PathBuf {
+    paths::config_dir().join("plugins")
+}
+
+impl Plugins {
+    // None running, as in watch mode
+    pub fn new() -> Self {
+        let (_, commands) = mpsc::channel();
+        let (_, errors) = mpsc::channel();
+        Plugins {
+            running: Vec::new(),
+            commands,
+            errors,
+        }
+    }
+
+    // Starts every plugin in the directory, in name order
+    pub fn start() -> Self {
+        let (commands_sender, commands) = mpsc::channel();
+        let (sender, errors) = mpsc::channel();
+        let mut files: Vec<PathBuf> = match fs::read_dir(dir()) {
+            Ok(entries) => entries.flatten().map(|entry| entry.path()).filter(|path| is_plugin(path)).collect(),
+            Err(_) => Vec::new(),
+        };
+        files.sort();
+        let mut running = Vec::new();
+        for path in files {
+            let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+            match spawn(&name, &path, commands_sender.clone()) {
+                Ok(stdin) => running.push(Plugin {
+                    lines: write_lines(name, stdin, sender.clone()),
+                }),
+                Err(e) => {
+                    let _ = sender.send(format!("Plugin {name}: {e}"));
+                }
+            }
+        }
+        Plugins { running, commands, errors }
+    }
+
+    pub fn emit(&mut self, event: &str, session: &Event) {
+        if self.running.is_empty() {
+            return;
+        }
+        let Ok(line) = serde_json::to_string(&Message { event, session }) else {
+            return;
+        };
+        // Its writer thread ends when the plugin has exited, and it's dropped
+        self.running.retain(|plugin| plugin.lines.send(line.clone()).is_ok());
+    }
+
+    // Commands written since the last call, with the plugin that sent each
+    pub fn commands(&self) -> impl Iterator<Item = (String, String)> + '_ {
+        self.commands.try_iter()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = String> + '_ {
+        self.errors.try_iter()
+    }
+}
+
+// Executable files; dotfiles, editor backups and READMEs next to them are left alone
+fn is_plugin(path: &Path) -> bool {
+    let hidden = path.file_name().is_none_or(|name| name.to_string_lossy().starts_with('.'));
+    let Ok(metadata) = fs::metadata(path) else {
+        return false;
+    };
+    !hidden && metadata.is_file() && is_executable(&metadata)
+}
+
+#[cfg(unix)]
+fn is_executable(metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_metadata: &fs::Metadata) -> bool {
+    true
+}
+
+// Writes each event line to the plugin's stdin on a thread of its own, so
+// the UI never waits on a full pipe. Ends, reporting why, when the plugin
+// has exited, and closes stdin once the timer drops the sender
+fn write_lines(name: String, mut stdin: ChildStdin, errors: Sender<String>) -> Sender<String> {
+    let (lines, receiver) = mpsc::channel::<String>();
+    thread::spawn(move || {
+        for line in receiver {
+            if let Err(e) = writeln!(stdin, "{line}").and_then(|_| stdin.flush()) {
+                let _ = errors.send(format!("Plugin {name} stopped: {e}"));
+                break;
+            }
+        }
+    });
+    lines
+}
+
+// Reads the plugin's commands on a thread of its own until it closes stdout
+fn spawn(name: &str, path: &Path, commands: Sender<(String, String)>) -> std::io::Result<ChildStdin> {
+    let mut child = Command::new(path).stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::null()).spawn()?;
+    let stdout = child.stdout.take().expect("stdout is piped");
+    let stdin = child.stdin.take().expect("stdin is piped");
+    let name = name.to_string();
+    thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            let line = line.trim();
+            if !line.is_empty() && commands.send((name.clone(), line.to_string())).is_err() {
+                break;
+            }
+        }
+        // Reap it once it's done
+        let _ = child.wait();
+    });
+    Ok(stdin)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use std::time::Duration;
+
+    #[test]
+    fn test_plugin_round_trip() {
+        let dir = std::env::temp_dir().join(format!("cyber-tomato-plugins-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("echo-type");
+        // Starts a break once told work is complete
+        fs::write(
+            &path,
+            "#!/bin/sh\nread line\necho \"$line\" | grep -q '\"event\":\"complete\",\"type\":\"work\"' && echo start_break\n",
+        )
+        .unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        fs::write(dir.join(".hidden"), "").unwrap();
+        assert!(is_plugin(&path));
+        assert!(!is_plugin(&dir.join(".hidden")));
+
+        let (sender, commands) = mpsc::channel();
+        let (errors_sender, errors) = mpsc::channel();
+        let stdin = spawn("echo-type", &path, sender).unwrap();
+        let mut plugins = Plugins {
+            running: vec![Plugin {
+                lines: write_lines("echo-type".to_string(), stdin, errors_sender),
+            }],
+            commands,
+            errors,
+        };
+        let event = Event {
+            timer_type: "work",
+            duration_secs: 1500,
+            remaining_secs: 0,
+            task: None,
+            tags: Vec::new(),
+        };
+        plugins.emit("complete", &event);
+        let command = plugins.commands.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(command, ("echo-type".to_string(), "start_break".to_string()));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_stuck_and_exited_plugins() {
+        let dir = std::env::temp_dir().join(format!("cyber-tomato-plugins-stuck-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let stuck = dir.join("stuck");
+        fs::write(&stuck, "#!/bin/sh\nexec sleep 5\n").unwrap();
+        let exits = dir.join("exits");
+        fs::write(&exits, "#!/bin/sh\nexit 0\n").unwrap();
+        for path in [&stuck, &exits] {
+            fs::set_permissions(path, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let (sender, commands) = mpsc::channel();
+        let (errors_sender, errors) = mpsc::channel();
+        let running = [("stuck", &stuck), ("exits", &exits)]
+            .into_iter()
+            .map(|(name, path)| Plugin {
+                lines: write_lines(name.to_string(), spawn(name, path, sender.clone()).unwrap(), errors_sender.clone()),
+            })
+            .collect();
+        let mut plugins = Plugins { running, commands, errors };
+        let event = Event {
+            timer_type: "work",
+            duration_secs: 1500,
+            remaining_secs: 0,
+            task: Some("x".repeat(1000)),
+            tags: Vec::new(),
+        };
+        // Far more than a pipe holds, without anyone reading it
+        let started = std::time::Instant::now();
+        while plugins.running.len() == 2 && started.elapsed() < Duration::from_secs(3) {
+            for _ in 0..200 {
+                plugins.emit("tick", &event);
+            }
+        }
+        assert!(started.elapsed() < Duration::from_secs(3));
+        assert_eq!(plugins.running.len(), 1);
+        assert!(plugins.errors.recv_timeout(Duration::from_secs(5)).unwrap().starts_with("Plugin exits stopped"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
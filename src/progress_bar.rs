@@ -0,0 +1,115 @@
+use std::time::Duration;
+
+use ratatui::{
+    Frame,
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    text::Span,
+    widgets::{Block, Gauge},
+};
+use serde::Deserialize;
+
+use crate::{TimerType, theme};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProgressBarStyle {
+    // Unicode block gauge
+    #[default]
+    Solid,
+    // Fills each cell dot by dot
+    Braille,
+    // Cells colored along the theme's progress colors
+    Gradient,
+    // One segment per minute of the session
+    Slices,
+}
+
+// Left column bottom-up, then right column bottom-up
+const BRAILLE_DOTS: [u32; 8] = [0x40, 0x04, 0x02, 0x01, 0x80, 0x20, 0x10, 0x08];
+const PARTIAL_BLOCKS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+const SLICE_EMPTY: char = '░';
+
+pub struct Progress<'a> {
+    pub ratio: f64,
+    pub total: Duration, // Session length, sets the number of slices
+    pub style: Style,    // Fill color and modifiers
+    pub label: Span<'a>,
+    pub timer_type: &'a TimerType, // Whose progress colors the gradient follows
+}
+
+pub fn render(f: &mut Frame, area: Rect, block: Block, progress: Progress) {
+    let ratio = progress.ratio.clamp(0.0, 1.0);
+    let bar_style = theme::progress_bar();
+    if bar_style == ProgressBarStyle::Solid {
+        let gauge = Gauge::default()
+            .block(block)
+            .gauge_style(progress.style.bg(Color::default()))
+            .ratio(ratio)
+            .use_unicode(true) // Partial blocks so the bar moves every tick, not every cell
+            .label(progress.label);
+        f.render_widget(gauge, area);
+        return;
+    }
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    if inner.is_empty() {
+        return;
+    }
+    let buf = f.buffer_mut();
+    for y in inner.top()..inner.bottom() {
+        match bar_style {
+            ProgressBarStyle::Braille => fill_braille(buf, inner, y, ratio, progress.style),
+            ProgressBarStyle::Gradient => fill_gradient(buf, inner, y, ratio, progress.timer_type, progress.style),
+            _ => fill_slices(buf, inner, y, ratio, progress.total, progress.style),
+        }
+    }
+
+    // Centered on the middle row like the Gauge label
+    let width = progress.label.width() as u16;
+    let x = inner.x + inner.width.saturating_sub(width) / 2;
+    let y = inner.y + inner.height / 2;
+    buf.set_span(x, y, &progress.label, inner.width);
+}
+
+fn fill_braille(buf: &mut Buffer, area: Rect, y: u16, ratio: f64, style: Style) {
+    let dots = (ratio * area.width as f64 * 8.0).round() as usize;
+    for i in 0..area.width {
+        let filled = dots.saturating_sub(i as usize * 8).min(8);
+        let code = BRAILLE_DOTS[..filled].iter().fold(0x2800, |code, dot| code | dot);
+        let symbol = char::from_u32(code).unwrap_or(' ');
+        buf[(area.x + i, y)].set_char(symbol).set_style(style);
+    }
+}
+
+fn fill_gradient(buf: &mut Buffer, area: Rect, y: u16, ratio: f64, timer_type: &TimerType, style: Style) {
+    let eighths = (ratio * area.width as f64 * 8.0).round() as usize;
+    for i in 0..area.width {
+        let filled = eighths.saturating_sub(i as usize * 8).min(8);
+        let symbol = if filled == 8 { '█' } else { PARTIAL_BLOCKS[filled] };
+        // Each cell takes the color of its own position, so the bar shows where it is headed
+        let color = theme::gradient_color(timer_type, i as f64 / area.width.saturating_sub(1).max(1) as f64);
+        buf[(area.x + i, y)].set_char(symbol).set_style(style.fg(color));
+    }
+}
+
+fn fill_slices(buf: &mut Buffer, area: Rect, y: u16, ratio: f64, total: Duration, style: Style) {
+    // A minute per slice, as long as each slice stays at least two cells wide
+    let minutes = total.as_secs().div_ceil(60).max(1);
+    let slices = minutes.min((area.width as u64 + 1) / 3).max(1) as u16;
+    let cells = area.width - (slices - 1); // One gap between neighbouring slices
+    let filled = (ratio * cells as f64).round() as u16;
+
+    let mut x = area.x;
+    for slice in 0..slices {
+        let (start, end) = (slice * cells / slices, (slice + 1) * cells / slices);
+        for cell in start..end {
+            let symbol = if cell < filled { '█' } else { SLICE_EMPTY };
+            buf[(x, y)].set_char(symbol).set_style(style);
+            x += 1;
+        }
+        x += 1;
+    }
+}
@@ -0,0 +1,57 @@
+use std::fs;
+
+use crate::config::QuotesConfig;
+use crate::history;
+use crate::paths;
+
+const DEFAULT_QUOTES: &[&str] = &[
+    "Focus is a muscle. You just trained it.",
+    "Small steps every day add up to big results.",
+    "The secret of getting ahead is getting started. - Mark Twain",
+    "Done is better than perfect.",
+    "It always seems impossible until it's done. - Nelson Mandela",
+    "You don't have to see the whole staircase, just take the first step. - Martin Luther King Jr.",
+    "Rest is not idleness. Stretch, breathe, look away from the screen.",
+    "Action is the foundational key to all success. - Pablo Picasso",
+    "One pomodoro at a time.",
+    "Well begun is half done. - Aristotle",
+];
+
+pub struct Quotes {
+    messages: Vec<String>,
+    index: usize,
+}
+
+impl Quotes {
+    // None when quotes are disabled
+    pub fn from_config(config: &QuotesConfig) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+
+        let mut messages = config.messages.clone();
+        if let Some(file) = &config.file {
+            // A missing or unreadable file just leaves the configured messages
+            if let Ok(text) = fs::read_to_string(paths::expand_home(file)) {
+                messages.extend(
+                    text.lines()
+                        .map(str::trim)
+                        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                        .map(str::to_string),
+                );
+            }
+        }
+        if messages.is_empty() {
+            messages = DEFAULT_QUOTES.iter().map(|q| q.to_string()).collect();
+        }
+
+        // Start somewhere different each run so the first quotes don't repeat
+        let index = history::now_secs() as usize % messages.len();
+        Some(Quotes { messages, index })
+    }
+
+    pub fn next(&mut self) -> &str {
+        self.index = (self.index + 1) % self.messages.len();
+        &self.messages[self.index]
+    }
+}
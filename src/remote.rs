@@ -0,0 +1,229 @@
+use std::io;
+#[cfg(unix)]
+use std::io::{BufRead, BufReader, Write};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+#[cfg(unix)]
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::away::Away;
+use crate::cli::{self, say};
+use crate::clock::ClockDisplay;
+use crate::{TimerType, follow, paths, status, template};
+
+// Control surface for editor plugins and scripts. Queries read the state the
+// running timer already publishes for watch mode, so a statusline can poll
+// them cheaply; actions go to the timer over a Unix socket next to it. Each
+// connection carries one command line and gets one JSON line back
+pub const ACTIONS: [&str; 9] = [
+    "start_work",
+    "start_break",
+    "pause",
+    "resume",
+    "toggle_pause",
+    "snooze",
+    "next_quote",
+    "add_session",
+    "remove_session",
+];
+
+#[derive(Serialize, Deserialize)]
+struct Reply {
+    ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+// Answer to `remote status`
+#[derive(Serialize)]
+struct Status {
+    state: &'static str, // none (no timer running), idle, running or paused
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kind: Option<TimerType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    left_secs: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration_secs: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    completed_sessions: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    task: Option<String>,
+}
+
+// How `remote status` answers: JSON, or for chat bots an Emacs plist or an
+// XMPP presence
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StatusFormat {
+    Json,
+    Emacs,
+    Xmpp,
+}
+
+fn socket_path() -> PathBuf {
+    paths::data_dir().join("remote.sock")
+}
+
+fn status(state: Option<follow::SharedState>) -> Status {
+    let Some(state) = state else {
+        return Status {
+            state: "none",
+            kind: None,
+            left_secs: None,
+            duration_secs: None,
+            completed_sessions: None,
+            task: None,
+        };
+    };
+    let elapsed = state.elapsed().as_millis() as u64;
+    let left_ms = state.duration_ms.saturating_sub(elapsed);
+    Status {
+        state: if state.running {
+            "running"
+        } else if elapsed > 0 && left_ms > 0 {
+            "paused"
+        } else {
+            "idle"
+        },
+        kind: Some(state.timer_type),
+        left_secs: Some(left_ms.div_ceil(1000)),
+        duration_secs: Some(state.duration_ms / 1000),
+        completed_sessions: Some(state.completed_sessions),
+        task: state.task,
+    }
+}
+
+// Whether work is under way, with templates.away as the message. Today's
+// count and the goal aren't shared, so {today} and {goal} read 0
+fn away(state: Option<follow::SharedState>) -> Result<Away, Box<dyn std::error::Error>> {
+    let Some(state) = state else {
+        return Ok(Away::new(None));
+    };
+    let summary = status(Some(state.clone()));
+    let mut status = status::Status {
+        session: state.timer_type,
+        state: summary.state,
+        remaining_secs: summary.left_secs.unwrap_or(0),
+        duration_secs: state.duration_ms / 1000,
+        completed_sessions: state.completed_sessions,
+        today: 0,
+        daily_goal: 0,
+        task: state.task,
+        tags: state.tags,
+        text: String::new(),
+        away: String::new(),
+        clock: ClockDisplay::default(),
+    };
+    status.away = template::render(&cli::load_config()?.templates.away, &status);
+    Ok(Away::new(Some(&status)))
+}
+
+// `cyber-tomato remote COMMAND`
+pub fn run(command: &str, format: StatusFormat) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        "status" => match format {
+            StatusFormat::Json => say!("{}", serde_json::to_string(&status(follow::read()))?),
+            StatusFormat::Emacs => say!("{}", away(follow::read())?.emacs()),
+            StatusFormat::Xmpp => say!("{}", away(follow::read())?.xmpp()),
+        },
+        // Plain mm:ss for statuslines; empty when nothing is counting down
+        "left" => {
+            let status = status(follow::read());
+            match status.left_secs {
+                Some(secs) if matches!(status.state, "running" | "paused") => {
+                    say!("{:02}:{:02}", secs / 60, secs % 60)
+                }
+                _ => say!(),
+            }
+        }
+        _ if ACTIONS.contains(&command) => {
+            let line = send(command).map_err(|e| format!("No running timer to control: {e}"))?;
+            let reply: Reply = serde_json::from_str(&line)?;
+            if let Some(error) = reply.error {
+                return Err(error.into());
+            }
+            say!("{line}");
+        }
+        _ => return Err(format!("Unknown remote command: {command}").into()),
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn send(command: &str) -> io::Result<String> {
+    let mut stream = UnixStream::connect(socket_path())?;
+    writeln!(stream, "{command}")?;
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line)?;
+    Ok(line.trim_end().to_string())
+}
+
+#[cfg(not(unix))]
+fn send(_command: &str) -> io::Result<String> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "not supported on this platform"))
+}
+
+// The timer's end of the socket, polled once a frame
+#[cfg(unix)]
+pub struct Server {
+    listener: UnixListener,
+}
+
+#[cfg(unix)]
+impl Server {
+    pub fn bind() -> io::Result<Server> {
+        let path = socket_path();
+        if UnixStream::connect(&path).is_ok() {
+            return Err(io::Error::new(io::ErrorKind::AddrInUse, "another timer is listening"));
+        }
+        // Nobody answers, so the file is left over from a crash
+        let _ = std::fs::remove_file(&path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let listener = UnixListener::bind(&path)?;
+        listener.set_nonblocking(true)?;
+        Ok(Server { listener })
+    }
+
+    // Answers every waiting command with `handle`
+    pub fn poll(&self, mut handle: impl FnMut(&str) -> Result<(), String>) {
+        while let Ok((stream, _)) = self.listener.accept() {
+            // A client that connects and says nothing must not freeze the UI
+            let _ = stream.set_nonblocking(false);
+            let _ = stream.set_read_timeout(Some(Duration::from_millis(200)));
+            let mut line = String::new();
+            if BufReader::new(&stream).read_line(&mut line).is_err() {
+                continue;
+            }
+            let reply = match handle(line.trim()) {
+                Ok(()) => Reply { ok: true, error: None },
+                Err(e) => Reply { ok: false, error: Some(e) },
+            };
+            if let Ok(json) = serde_json::to_string(&reply) {
+                let _ = writeln!(&stream, "{json}");
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(socket_path());
+    }
+}
+
+#[cfg(not(unix))]
+pub struct Server;
+
+#[cfg(not(unix))]
+impl Server {
+    pub fn bind() -> io::Result<Server> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "not supported on this platform"))
+    }
+
+    pub fn poll(&self, _handle: impl FnMut(&str) -> Result<(), String>) {}
+}
@@ -0,0 +1,188 @@
+use std::fmt::Write;
+
+use chrono::{Datelike, NaiveDate, TimeDelta};
+
+use crate::history::SessionRecord;
+use crate::review;
+use crate::stats::{self, StatsView};
+
+// Height of the tallest bar in the column charts, in pixels
+const BAR_HEIGHT: u64 = 150;
+// Width of the longest bar in the tag chart
+const BAR_LENGTH: u64 = 360;
+
+const STYLE: &str = "body { background: #0d1117; color: #e6edf3; font-family: system-ui, sans-serif; max-width: 760px; margin: 2em auto; padding: 0 1em; }
+h1, h2 { color: #50fa7b; font-weight: 600; }
+.summary { font-size: 1.1em; }
+.bar { fill: #50fa7b; }
+.value, .label { fill: #e6edf3; font-size: 11px; }
+.label { fill: #8b949e; }
+table { border-collapse: collapse; width: 100%; }
+th, td { text-align: left; padding: 4px 8px; border-bottom: 1px solid #30363d; }
+td.time { text-align: right; }
+footer { color: #8b949e; font-size: 0.85em; margin-top: 3em; }";
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+// Vertical bars with their value above and their label below, scaled to the
+// largest
+fn columns(bars: &[(String, u64)], bar_width: u64, value: impl Fn(u64) -> String) -> String {
+    let max = bars.iter().map(|(_, v)| *v).max().unwrap_or(0).max(1);
+    let width = bars.len() as u64 * bar_width;
+    let height = BAR_HEIGHT + 40;
+    let mut svg = format!(r#"<svg viewBox="0 0 {width} {height}" width="{width}" height="{height}" role="img">"#);
+    for (i, (label, v)) in bars.iter().enumerate() {
+        let bar = v * BAR_HEIGHT / max;
+        let x = i as u64 * bar_width;
+        let middle = x + bar_width / 2;
+        let y = 20 + BAR_HEIGHT - bar;
+        let _ = write!(svg, r#"<rect class="bar" x="{}" y="{y}" width="{}" height="{bar}"/>"#, x + 2, bar_width - 4);
+        if *v > 0 {
+            let _ = write!(
+                svg,
+                r#"<text class="value" x="{middle}" y="{}" text-anchor="middle">{}</text>"#,
+                y - 4,
+                value(*v)
+            );
+        }
+        let _ = write!(
+            svg,
+            r#"<text class="label" x="{middle}" y="{}" text-anchor="middle">{}</text>"#,
+            BAR_HEIGHT + 36,
+            escape(label)
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+// Horizontal bars of focused time with each one's share of the total
+fn rows(groups: &[(String, u64)], total: u64) -> String {
+    let max = groups.iter().map(|(_, secs)| *secs).max().unwrap_or(0).max(1);
+    let height = groups.len() as u64 * 24;
+    let width = 160 + BAR_LENGTH + 120;
+    let mut svg = format!(r#"<svg viewBox="0 0 {width} {height}" width="{width}" height="{height}" role="img">"#);
+    for (i, (name, secs)) in groups.iter().enumerate() {
+        let y = i as u64 * 24;
+        let bar = (secs * BAR_LENGTH / max).max(1);
+        let share = secs * 100 / total.max(1);
+        let _ = write!(
+            svg,
+            r#"<text class="label" x="150" y="{}" text-anchor="end">{}</text><rect class="bar" x="160" y="{}" width="{bar}" height="16"/><text class="value" x="{}" y="{}">{} ({share}%)</text>"#,
+            y + 15,
+            escape(name),
+            y + 3,
+            166 + bar,
+            y + 15,
+            stats::format_hm(*secs)
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+// A standalone page for the week around `date`: totals, pomodoros per day,
+// start hours and the split by tag and task, with the charts inline as SVG
+pub fn html(records: &[SessionRecord], date: NaiveDate, goal: u32) -> String {
+    let (first, last) = review::week_of(date);
+    let week = review::summarize(records, date, goal);
+    let sessions = || stats::work_sessions_in(records, first, last);
+
+    let days: Vec<(String, u64)> = (0..7)
+        .map(|i| {
+            let day = first + TimeDelta::days(i);
+            let count = stats::work_sessions_in(records, day, day).map(|r| r.sessions as u64).sum();
+            (day.format("%a %-d").to_string(), count)
+        })
+        .collect();
+    let active_days = days.iter().filter(|(_, count)| *count > 0).count();
+    let hours: Vec<(String, u64)> = stats::hour_histogram(sessions())
+        .iter()
+        .enumerate()
+        .map(|(hour, &count)| (hour.to_string(), count))
+        .collect();
+    let tags = stats::breakdown(sessions(), StatsView::Tags);
+    let tasks = stats::breakdown(sessions(), StatsView::Tasks);
+
+    let goal_text = if goal > 0 { format!(" of {goal}") } else { String::new() };
+    let mut page = String::new();
+    let _ = write!(
+        page,
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Pomodoro report {id}</title>
+<style>
+{STYLE}
+</style>
+</head>
+<body>
+<h1>Pomodoro report, week {id}</h1>
+<p>{from} to {to}</p>
+<p class="summary"><strong>{pomodoros}</strong> pomodoros{goal_text}, <strong>{focus}</strong> focused, on {active_days} of 7 days</p>
+<h2>Pomodoros per day</h2>
+{days}
+<h2>Start hour</h2>
+{hours}
+"#,
+        id = week.week,
+        from = first.format("%A %-d %B"),
+        to = last.format("%A %-d %B %Y"),
+        pomodoros = week.pomodoros,
+        focus = stats::format_hm(week.focus_secs),
+        days = columns(&days, 64, |count| count.to_string()),
+        hours = columns(&hours, 26, |count| count.to_string()),
+    );
+
+    if week.focus_secs == 0 {
+        page.push_str("<p>No work sessions this week.</p>\n");
+    } else {
+        let _ = write!(page, "<h2>Tags</h2>\n{}\n<h2>Tasks</h2>\n<table>\n", rows(&tags, week.focus_secs));
+        page.push_str("<tr><th>Task</th><th>Focused</th></tr>\n");
+        for (task, secs) in &tasks {
+            let _ = writeln!(page, r#"<tr><td>{}</td><td class="time">{}</td></tr>"#, escape(task), stats::format_hm(*secs));
+        }
+        page.push_str("</table>\n");
+    }
+    page.push_str("<footer>Made with cyber-tomato</footer>\n</body>\n</html>\n");
+    page
+}
+
+// The Monday of the week `weeks_ago` weeks before the one `today` is in
+pub fn week_start(today: NaiveDate, weeks_ago: u32) -> NaiveDate {
+    today - TimeDelta::days(today.weekday().num_days_from_monday() as i64 + 7 * weeks_ago as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+
+    #[test]
+    fn test_html() {
+        // 2026-10-16 is a Friday in week 42
+        let date = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let started_at = Local.with_ymd_and_hms(2026, 10, 14, 9, 30, 0).unwrap().timestamp() as u64;
+        let record = SessionRecord {
+            id: "a".to_string(),
+            started_at,
+            duration_secs: 1500,
+            task: Some("Q&A <draft>".to_string()),
+            tags: vec!["writing".to_string()],
+            ..SessionRecord::default()
+        };
+        let page = html(&[record], date, 10);
+        assert!(page.contains("week 2026-W42"));
+        assert!(page.contains("<strong>1</strong> pomodoros of 10"));
+        assert!(page.contains("Q&amp;A &lt;draft&gt;"));
+        assert!(page.contains("writing"));
+        assert_eq!(page.matches("<svg").count(), 3);
+
+        let empty = html(&[], date, 0);
+        assert!(empty.contains("No work sessions this week."));
+        assert_eq!(week_start(date, 1), NaiveDate::from_ymd_opt(2026, 10, 5).unwrap());
+    }
+}
@@ -0,0 +1,204 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Gauge, Paragraph},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::history::{self, History, SessionRecord};
+use crate::stats::{self, StatsView};
+use crate::theme;
+
+// Biggest tasks and tags kept in a review
+const TOP: usize = 3;
+
+// How a week went against the weekly goal. The review shown at the end of a
+// week is appended to reviews/<machine>.jsonl in the history directory, so
+// it syncs along with the sessions and other machines don't ask again
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WeekReview {
+    pub week: String, // ISO week, "2026-W42"
+    pub goal: u32,    // Weekly pomodoro goal at the time, 0 for none
+    pub pomodoros: u32,
+    pub focus_secs: u64,
+    pub tasks: Vec<(String, u64)>, // Focused seconds, biggest first
+    pub tags: Vec<(String, u64)>,
+    pub written_at: u64,
+}
+
+// Monday to Sunday around `date`
+pub fn week_of(date: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let week = date.week(Weekday::Mon);
+    (week.first_day(), week.last_day())
+}
+
+pub fn week_id(date: NaiveDate) -> String {
+    let week = date.iso_week();
+    format!("{}-W{:02}", week.year(), week.week())
+}
+
+// Work sessions in the week around `date`; archive summaries count for all
+// the sessions they stand for
+pub fn pomodoros(records: &[SessionRecord], date: NaiveDate) -> u32 {
+    let (first, last) = week_of(date);
+    stats::work_sessions_in(records, first, last).map(|r| r.sessions).sum()
+}
+
+pub fn summarize(records: &[SessionRecord], date: NaiveDate, goal: u32) -> WeekReview {
+    let (first, last) = week_of(date);
+    let top = |view| {
+        let mut groups = stats::breakdown(stats::work_sessions_in(records, first, last), view);
+        groups.truncate(TOP);
+        groups
+    };
+    WeekReview {
+        week: week_id(date),
+        goal,
+        pomodoros: pomodoros(records, date),
+        focus_secs: stats::work_sessions_in(records, first, last).map(|r| r.duration_secs).sum(),
+        tasks: top(StatsView::Tasks),
+        tags: top(StatsView::Tags),
+        written_at: history::now_secs(),
+    }
+}
+
+pub struct Reviews {
+    dir: PathBuf,
+    machine: String,
+    weeks: Vec<WeekReview>,
+}
+
+impl Reviews {
+    pub fn load(history: &History) -> Self {
+        let dir = history.dir().join("reviews");
+        let weeks = fs::read_dir(&dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| fs::read_to_string(e.ok()?.path()).ok())
+                    .flat_map(|text| text.lines().filter_map(|line| serde_json::from_str(line).ok()).collect::<Vec<_>>())
+                    .collect()
+            })
+            .unwrap_or_default();
+        Reviews {
+            dir,
+            machine: history.machine().to_string(),
+            weeks,
+        }
+    }
+
+    pub fn has(&self, week: &str) -> bool {
+        self.weeks.iter().any(|review| review.week == week)
+    }
+
+    pub fn append(&mut self, review: WeekReview) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(format!("{}.jsonl", self.machine));
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", serde_json::to_string(&review)?)?;
+        self.weeks.push(review);
+        Ok(())
+    }
+}
+
+// `so_far` marks a review of the week still running
+pub fn render(f: &mut Frame, area: Rect, review: &WeekReview, so_far: bool) {
+    let title = if so_far {
+        format!("This week so far ({})", review.week)
+    } else {
+        format!("Week {} review", review.week)
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .title_alignment(Alignment::Center)
+        .border_style(Style::default().fg(theme::primary()));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(3), Constraint::Length(1)])
+        .split(inner);
+
+    let heading = Style::default().fg(theme::highlight()).add_modifier(Modifier::BOLD);
+    let (ratio, label) = if review.goal > 0 {
+        let ratio = (review.pomodoros as f64 / review.goal as f64).min(1.0);
+        (ratio, format!("{} of {} pomodoros", review.pomodoros, review.goal))
+    } else {
+        (0.0, format!("{} pomodoros (no weekly goal set)", review.pomodoros))
+    };
+    let met = review.goal > 0 && review.pomodoros >= review.goal;
+    let gauge = Gauge::default()
+        .block(Block::default().borders(Borders::ALL).title("Goal"))
+        .gauge_style(Style::default().fg(if met { Color::Green } else { theme::primary() }))
+        .ratio(ratio)
+        .label(label);
+    f.render_widget(gauge, chunks[0]);
+
+    let verdict = match review.goal {
+        0 => String::new(),
+        goal if review.pomodoros > goal => format!("Goal met with {} to spare", review.pomodoros - goal),
+        _ if met => "Goal met".to_string(),
+        goal if so_far => format!("{} to go", goal - review.pomodoros),
+        goal => format!("{} short of the goal", goal - review.pomodoros),
+    };
+    let mut lines = vec![
+        Line::from(vec![Span::styled("Focused: ", heading), Span::raw(stats::format_hm(review.focus_secs))]),
+        Line::from(verdict),
+    ];
+    for (title, groups) in [("Biggest tasks", &review.tasks), ("Biggest tags", &review.tags)] {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(title, heading)));
+        if groups.is_empty() {
+            lines.push(Line::from(Span::styled("  none", Style::default().fg(Color::DarkGray))));
+        }
+        for (name, secs) in groups {
+            lines.push(Line::from(format!("  {:>8}  {name}", stats::format_hm(*secs))));
+        }
+    }
+    f.render_widget(Paragraph::new(lines), chunks[1]);
+
+    let key = Style::default().fg(theme::primary()).add_modifier(Modifier::BOLD);
+    let help = Line::from(vec![Span::styled("Esc", key), Span::raw(" - Close")]);
+    f.render_widget(Paragraph::new(help).alignment(Alignment::Center), chunks[2]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+
+    #[test]
+    fn test_summarize() {
+        let at = |day: u32, task: &str, tags: &[&str]| SessionRecord {
+            id: format!("{day}{task}"),
+            started_at: Local.with_ymd_and_hms(2026, 10, day, 10, 0, 0).unwrap().timestamp() as u64,
+            duration_secs: 1500,
+            task: Some(task.to_string()),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..SessionRecord::default()
+        };
+        // Monday 12th to Sunday 18th October 2026; the 11th and 19th fall outside
+        let records = vec![
+            at(11, "early", &[]),
+            at(12, "report", &["writing"]),
+            at(14, "report", &["writing"]),
+            at(18, "email", &[]),
+            at(19, "late", &[]),
+        ];
+        let date = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
+        assert_eq!(week_id(date), "2026-W42");
+        let review = summarize(&records, date, 10);
+        assert_eq!(review.pomodoros, 3);
+        assert_eq!(review.focus_secs, 3 * 1500);
+        assert_eq!(review.tasks[0], ("report".to_string(), 3000));
+        assert_eq!(review.tags[0], ("writing".to_string(), 3000));
+    }
+}
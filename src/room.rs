@@ -0,0 +1,277 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::cli::say;
+use crate::config::RoomConfig;
+use crate::jobs;
+
+// Sent while nothing changes, so the server knows the timer is still there
+const HEARTBEAT: Duration = Duration::from_secs(20);
+// A presence not heard from in this long has gone: three missed heartbeats
+const EXPIRY: Duration = Duration::from_secs(60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const LEAVE_TIMEOUT: Duration = Duration::from_secs(2);
+const MAX_BODY: usize = 1024;
+const MAX_NAME: usize = 100;
+const MAX_PRESENT: usize = 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RoomState {
+    Focusing,
+    Break,
+    Idle,
+    Left,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Counts {
+    pub focusing: usize,
+    pub present: usize,
+}
+
+// What a timer tells the room: an id made up for the run and whether it's
+// working. Nothing else is sent, so no one in the room can be told apart
+#[derive(Serialize, Deserialize)]
+struct Presence {
+    id: String,
+    state: RoomState,
+}
+
+// A study-with-me room: everyone's timer runs on its own, and each posts its
+// state to the room's URL and gets back how many are there and focusing
+pub struct Room {
+    config: RoomConfig,
+    id: String,
+    counts: Option<Counts>,
+    sent: Option<(RoomState, Instant)>,
+    pending: Option<Receiver<Result<Counts, String>>>,
+    error: Option<String>, // The last error, until it has been reported
+    failing: bool,         // Reported, and nothing has got through since
+}
+
+impl Room {
+    pub fn new(config: RoomConfig) -> Self {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+        Room {
+            config,
+            id: format!("{nanos:x}{:x}", std::process::id()),
+            counts: None,
+            sent: None,
+            pending: None,
+            error: None,
+            failing: false,
+        }
+    }
+
+    pub fn set_config(&mut self, config: RoomConfig) {
+        if config.url != self.config.url {
+            self.counts = None;
+            self.sent = None;
+            self.failing = false;
+        }
+        self.config = config;
+    }
+
+    // Called every frame: picks up the last answer and posts `state` when it
+    // changes or the heartbeat is due
+    pub fn tick(&mut self, state: RoomState) {
+        if let Some(receiver) = &self.pending
+            && let Ok(result) = receiver.try_recv()
+        {
+            match result {
+                Ok(counts) => {
+                    self.counts = Some(counts);
+                    self.failing = false;
+                }
+                Err(e) => {
+                    self.counts = None;
+                    if !self.failing {
+                        self.error = Some(format!("Room: {e}"));
+                        self.failing = true;
+                    }
+                }
+            }
+            self.pending = None;
+        }
+        let Some(url) = self.config.url.clone() else {
+            return;
+        };
+        let due = self.sent.is_none_or(|(sent, at)| sent != state || at.elapsed() >= HEARTBEAT);
+        if self.pending.is_some() || !due {
+            return;
+        }
+        self.sent = Some((state, Instant::now()));
+        let presence = Presence { id: self.id.clone(), state };
+        self.pending = Some(jobs::run(move || post(&url, &presence, REQUEST_TIMEOUT)));
+    }
+
+    pub fn counts(&self) -> Option<Counts> {
+        self.counts
+    }
+
+    pub fn errors(&mut self) -> impl Iterator<Item = String> {
+        self.error.take().into_iter()
+    }
+
+    // On quit, so the room doesn't count the timer until it expires. Waits
+    // briefly and doesn't mind failing
+    pub fn leave(&mut self) {
+        if let (Some(url), Some(_)) = (&self.config.url, self.sent.take()) {
+            let presence = Presence {
+                id: self.id.clone(),
+                state: RoomState::Left,
+            };
+            let _ = post(url, &presence, LEAVE_TIMEOUT);
+        }
+    }
+}
+
+fn post(url: &str, presence: &Presence, timeout: Duration) -> Result<Counts, String> {
+    ureq::post(url)
+        .config()
+        .timeout_global(Some(timeout))
+        .build()
+        .send_json(presence)
+        .and_then(|mut response| response.body_mut().read_json::<Counts>())
+        .map_err(|e| e.to_string())
+}
+
+// The server's side: who is in each room and what they're doing
+#[derive(Default)]
+struct Rooms {
+    rooms: HashMap<String, HashMap<String, (RoomState, Instant)>>,
+}
+
+impl Rooms {
+    // Records `presence`, if any, and counts the room after dropping
+    // everyone who hasn't been heard from. None when the room is full
+    fn update(&mut self, name: &str, presence: Option<Presence>, now: Instant) -> Option<Counts> {
+        self.rooms.retain(|_, members| {
+            members.retain(|_, (_, seen)| now.duration_since(*seen) < EXPIRY);
+            !members.is_empty()
+        });
+        let members = self.rooms.entry(name.to_string()).or_default();
+        match presence {
+            Some(Presence { id, state: RoomState::Left }) => {
+                members.remove(&id);
+            }
+            Some(Presence { id, state }) => {
+                if members.len() >= MAX_PRESENT && !members.contains_key(&id) {
+                    return None;
+                }
+                members.insert(id, (state, now));
+            }
+            None => {}
+        }
+        let counts = Counts {
+            focusing: members.values().filter(|(state, _)| *state == RoomState::Focusing).count(),
+            present: members.len(),
+        };
+        if members.is_empty() {
+            self.rooms.remove(name);
+        }
+        Some(counts)
+    }
+}
+
+// `room serve`: a room server anyone can run. The path names the room, so
+// one server holds any number; POST a presence to join, GET to just look.
+// Plain http, meant to sit behind a TLS proxy when it's public
+pub fn serve(port: u16) -> io::Result<()> {
+    let listener = TcpListener::bind(("0.0.0.0", port))?;
+    say!("Serving rooms on port {port}: set [room] url to http://<this host>:{port}/<room name>");
+    let rooms = Arc::new(Mutex::new(Rooms::default()));
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let rooms = Arc::clone(&rooms);
+        thread::spawn(move || {
+            let _ = respond(stream, &rooms);
+        });
+    }
+    Ok(())
+}
+
+fn respond(stream: TcpStream, rooms: &Mutex<Rooms>) -> io::Result<()> {
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    let mut reader = BufReader::new(&stream);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut request = line.split_whitespace();
+    let method = request.next().unwrap_or_default().to_string();
+    let path = request.next().unwrap_or_default();
+    let name = path.split('?').next().unwrap_or_default().trim_matches('/').to_string();
+    let mut length = 0;
+    loop {
+        line.clear();
+        reader.read_line(&mut line)?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((key, value)) = header.split_once(':')
+            && key.eq_ignore_ascii_case("content-length")
+        {
+            length = value.trim().parse().unwrap_or(usize::MAX);
+        }
+    }
+
+    let (status, body) = if name.len() > MAX_NAME || length > MAX_BODY {
+        ("413 Content Too Large", None)
+    } else {
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body)?;
+        let presence = match method.as_str() {
+            "GET" => Ok(None),
+            "POST" => serde_json::from_slice::<Presence>(&body).map(Some).map_err(|_| "400 Bad Request"),
+            _ => Err("405 Method Not Allowed"),
+        };
+        match presence {
+            Ok(presence) => match rooms.lock().unwrap_or_else(|e| e.into_inner()).update(&name, presence, Instant::now()) {
+                Some(counts) => ("200 OK", Some(counts)),
+                None => ("503 Service Unavailable", None),
+            },
+            Err(status) => (status, None),
+        }
+    };
+    let body = body.map(|counts| serde_json::json!(counts).to_string()).unwrap_or_default();
+    write!(
+        &stream,
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_room_counts_and_expiry() {
+        let mut rooms = Rooms::default();
+        let join = |id: &str, state| Some(Presence { id: id.to_string(), state });
+        let start = Instant::now();
+        rooms.update("study", join("a", RoomState::Focusing), start);
+        rooms.update("study", join("b", RoomState::Break), start);
+        rooms.update("other", join("c", RoomState::Focusing), start);
+        let later = start + Duration::from_secs(30);
+        let counts = rooms.update("study", join("b", RoomState::Focusing), later);
+        assert_eq!(counts, Some(Counts { focusing: 2, present: 2 }));
+
+        // a stops posting and expires; b leaves
+        let counts = rooms.update("study", None, start + EXPIRY + Duration::from_secs(1));
+        assert_eq!(counts, Some(Counts { focusing: 1, present: 1 }));
+        rooms.update("study", join("b", RoomState::Left), later + Duration::from_secs(40));
+        assert!(rooms.rooms.is_empty());
+    }
+}
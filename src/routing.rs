@@ -0,0 +1,131 @@
+use std::io::{self, Write};
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::TimerType;
+use crate::auto_tags;
+use crate::config::NotifyRule;
+use crate::jobs::Jobs;
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Channel {
+    Sound,
+    Desktop,
+    Speech,
+    Webhook,
+    Bell, // The terminal bell, which most terminals turn into an urgency hint
+}
+
+// What is being announced
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyEvent {
+    Complete, // A session ended
+    Alarm,
+    Budget, // A task's time budget ran out
+}
+
+// What config check reports about a rule
+pub fn check(rule: &NotifyRule) -> Result<(), String> {
+    if rule.channels.contains(&Channel::Webhook) {
+        match &rule.webhook {
+            Some(url) if url.starts_with("http://") || url.starts_with("https://") => {}
+            Some(url) => return Err(format!("webhook '{url}' is not an http:// or https:// URL")),
+            None => return Err("the webhook channel needs a webhook URL".to_string()),
+        }
+    }
+    auto_tags::check_window(&rule.after, &rule.before, &rule.days)
+}
+
+// The first rule for `event` whose conditions all hold; None leaves the
+// notification to the built-in channels. `focused` is None when the
+// terminal doesn't report focus, which no `focused` condition matches
+pub fn route<'a>(rules: &'a [NotifyRule], event: NotifyEvent, session: &TimerType, focused: Option<bool>, at: NaiveDateTime) -> Option<&'a NotifyRule> {
+    rules.iter().find(|rule| {
+        rule.event == event
+            && rule.session.as_ref().is_none_or(|s| s == session)
+            && rule.focused.is_none_or(|f| Some(f) == focused)
+            && auto_tags::in_window(&rule.after, &rule.before, &rule.days, at)
+    })
+}
+
+pub fn bell() {
+    let mut stdout = io::stdout();
+    let _ = stdout.write_all(b"\x07");
+    let _ = stdout.flush();
+}
+
+// Posts notifications routed to a webhook as JSON
+pub struct Webhooks {
+    jobs: Jobs,
+}
+
+impl Webhooks {
+    pub fn new() -> Self {
+        Webhooks { jobs: Jobs::new() }
+    }
+
+    pub fn post(&self, url: &str, event: NotifyEvent, session: &TimerType, title: &str, body: &str) {
+        let url = url.to_string();
+        let payload = json!({
+            "event": event,
+            "session": session,
+            "title": title,
+            "body": body,
+        });
+        self.jobs
+            .spawn(move || ureq::post(&url).send_json(payload).map(drop).map_err(|e| format!("Webhook: {e}")));
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = String> + '_ {
+        self.jobs.errors()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn rule(event: NotifyEvent, channels: &[Channel]) -> NotifyRule {
+        NotifyRule {
+            event,
+            channels: channels.to_vec(),
+            session: None,
+            focused: None,
+            after: None,
+            before: None,
+            days: None,
+            webhook: None,
+        }
+    }
+
+    #[test]
+    fn test_route() {
+        let night = NotifyRule {
+            after: Some("22:00".to_string()),
+            before: Some("07:00".to_string()),
+            ..rule(NotifyEvent::Complete, &[Channel::Desktop])
+        };
+        let focused_work = NotifyRule {
+            session: Some(TimerType::Work),
+            focused: Some(true),
+            ..rule(NotifyEvent::Complete, &[Channel::Bell])
+        };
+        let rules = [night, focused_work, rule(NotifyEvent::Alarm, &[Channel::Speech])];
+        let at = |h| NaiveDate::from_ymd_opt(2026, 10, 16).unwrap().and_hms_opt(h, 0, 0).unwrap();
+        let channels = |event, session, focused, h| route(&rules, event, &session, focused, at(h)).map(|rule| rule.channels.clone());
+
+        assert_eq!(channels(NotifyEvent::Complete, TimerType::Work, Some(true), 23), Some(vec![Channel::Desktop]));
+        assert_eq!(channels(NotifyEvent::Complete, TimerType::Work, Some(true), 10), Some(vec![Channel::Bell]));
+        assert_eq!(channels(NotifyEvent::Complete, TimerType::Break, Some(true), 10), None);
+        assert_eq!(channels(NotifyEvent::Complete, TimerType::Work, None, 10), None);
+        assert_eq!(channels(NotifyEvent::Alarm, TimerType::Break, None, 10), Some(vec![Channel::Speech]));
+        assert_eq!(channels(NotifyEvent::Budget, TimerType::Work, Some(true), 10), None);
+
+        assert!(check(&rule(NotifyEvent::Complete, &[Channel::Webhook])).is_err());
+    }
+}
@@ -0,0 +1,121 @@
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::paths;
+
+// RTTTL, the old Nokia ringtone format, for custom alert melodies:
+//
+//     Beep:d=4,o=5,b=140:8e6,8d6,4f#,4g#,8c#6,8b,d,e
+//
+// A name, defaults for note duration, octave and tempo, then the notes. Each
+// note is [duration]letter[#][.][octave][.], where `p` is a rest and a dot
+// makes the note half as long again.
+pub type Melody = Vec<(f32, Duration)>;
+
+// A tune given inline, or the path of a file holding one
+pub fn load(tune: &str) -> Result<Melody, String> {
+    // Tunes have two colons; a Windows path like C:\tunes\beep.txt has one
+    if tune.matches(':').count() >= 2 {
+        return parse(tune);
+    }
+    let path = paths::expand_home(Path::new(tune.trim()));
+    let text = fs::read_to_string(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+    parse(&text).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+pub fn parse(tune: &str) -> Result<Melody, String> {
+    let mut sections = tune.trim().splitn(3, ':');
+    let (Some(_name), Some(defaults), Some(notes)) = (sections.next(), sections.next(), sections.next()) else {
+        return Err("expected name:defaults:notes".to_string());
+    };
+    let (mut duration, mut octave, mut bpm) = (4, 6, 63);
+    for setting in defaults.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let (key, value) = setting.split_once('=').ok_or_else(|| format!("bad default '{setting}'"))?;
+        let value: u32 = value.trim().parse().map_err(|_| format!("bad default '{setting}'"))?;
+        match key.trim() {
+            "d" => duration = value,
+            "o" => octave = value,
+            "b" => bpm = value,
+            _ => return Err(format!("unknown default '{setting}'")),
+        }
+    }
+    if bpm == 0 || duration == 0 {
+        return Err("duration and tempo must be at least 1".to_string());
+    }
+    // A whole note lasts four beats
+    let whole_ms = 4.0 * 60_000.0 / bpm as f32;
+
+    let mut melody = Vec::new();
+    for note in notes.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let bad = || format!("bad note '{note}'");
+        let lower = note.to_ascii_lowercase();
+        let letter_at = lower.find(|c: char| c.is_ascii_alphabetic()).ok_or_else(bad)?;
+        let length: u32 = match &lower[..letter_at] {
+            "" => duration,
+            digits => digits.parse().map_err(|_| bad())?,
+        };
+        if length == 0 {
+            return Err(bad());
+        }
+        let mut rest = lower[letter_at..].chars().peekable();
+        let semitone = match rest.next() {
+            Some('c') => Some(0),
+            Some('d') => Some(2),
+            Some('e') => Some(4),
+            Some('f') => Some(5),
+            Some('g') => Some(7),
+            Some('a') => Some(9),
+            Some('b' | 'h') => Some(11),
+            Some('p') => None,
+            _ => return Err(bad()),
+        };
+        let sharp = rest.next_if_eq(&'#').is_some();
+        let mut dotted = rest.next_if_eq(&'.').is_some();
+        let digits: String = std::iter::from_fn(|| rest.next_if(char::is_ascii_digit)).collect();
+        let note_octave: u32 = if digits.is_empty() { octave } else { digits.parse().map_err(|_| bad())? };
+        dotted |= rest.next_if_eq(&'.').is_some();
+        if rest.next().is_some() {
+            return Err(bad());
+        }
+
+        let mut ms = whole_ms / length as f32;
+        if dotted {
+            ms *= 1.5;
+        }
+        let freq = match semitone {
+            // Semitones away from A4 at 440 Hz
+            Some(semitone) => {
+                let steps = (note_octave as i32 - 4) * 12 + semitone + sharp as i32 - 9;
+                440.0 * 2f32.powf(steps as f32 / 12.0)
+            }
+            None => 0.0,
+        };
+        melody.push((freq, Duration::from_micros((ms * 1000.0).round() as u64)));
+    }
+    if melody.is_empty() {
+        return Err("no notes".to_string());
+    }
+    Ok(melody)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let melody = parse("Test:d=4,o=5,b=120:8a4,p,c#6.,2g4").unwrap();
+        let ms: Vec<u128> = melody.iter().map(|(_, length)| length.as_millis()).collect();
+        // A quarter note is one beat: 500ms at 120 bpm
+        assert_eq!(ms, [250, 500, 750, 1000]);
+        assert_eq!(melody[0].0, 440.0);
+        assert_eq!(melody[1].0, 0.0);
+        assert!((melody[2].0 - 1108.73).abs() < 0.1);
+        assert!((melody[3].0 - 392.0).abs() < 0.1);
+
+        assert!(parse("Test:d=4:x").is_err());
+        assert!(parse("Test:b=0:a").is_err());
+        assert!(parse("no notes here").is_err());
+    }
+}
@@ -0,0 +1,225 @@
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+use serde::Serialize;
+
+#[cfg(feature = "scripting")]
+use crate::paths;
+
+// Rhai scripts listed in [scripts] get the timer's events and a small API
+// back. Each script defines whichever of on_start, on_pause, on_resume and
+// on_complete it cares about; they get a map of the session (type,
+// duration_secs, remaining_secs, task, tags) and can call:
+//   notify(title, body)          desktop notification
+//   toast(text)                  message in the timer
+//   set_status(text)             text in the status bar, "" to clear it
+//   start_work(), start_work(minutes), start_break(), start_break(minutes)
+//   pause(), resume()
+// Calls are queued and carried out on the next tick, so a handler can't
+// re-enter the timer. Errors go through `errors` like the integrations'
+pub struct Scripts {
+    #[cfg(feature = "scripting")]
+    engine: rhai::Engine,
+    #[cfg(feature = "scripting")]
+    scripts: Vec<(PathBuf, rhai::AST, rhai::Scope<'static>)>,
+    #[cfg(feature = "scripting")]
+    sender: mpsc::Sender<String>,
+    errors: Receiver<String>,
+    commands: Receiver<ScriptCommand>,
+}
+
+// Builds without the feature never run a handler
+#[cfg_attr(not(feature = "scripting"), allow(dead_code))]
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScriptCommand {
+    Notify(String, String),
+    Toast(String),
+    Status(String),
+    Work(Option<Duration>),
+    Break(Option<Duration>),
+    Pause,
+    Resume,
+}
+
+// What a handler is told about the session; plugins get the same as JSON
+#[derive(Serialize)]
+pub struct Event {
+    #[serde(rename = "type")]
+    pub timer_type: &'static str, // "work" or "break"
+    pub duration_secs: u64,
+    pub remaining_secs: u64,
+    pub task: Option<String>,
+    pub tags: Vec<String>,
+}
+
+pub const NO_SCRIPTING: &str = "this build has no scripting support (build with --features scripting)";
+
+// Enough for any sensible handler; a runaway loop is stopped rather than
+// freezing the UI
+#[cfg(feature = "scripting")]
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+impl Scripts {
+    pub fn load(files: &[PathBuf]) -> Self {
+        let (sender, errors) = mpsc::channel();
+        let (commands_sender, commands) = mpsc::channel();
+        #[cfg(not(feature = "scripting"))]
+        {
+            let _ = commands_sender;
+            if !files.is_empty() {
+                let _ = sender.send(format!("Scripts: {NO_SCRIPTING}"));
+            }
+            Scripts { errors, commands }
+        }
+        #[cfg(feature = "scripting")]
+        {
+            let engine = engine(commands_sender);
+            let mut scripts = Vec::new();
+            for file in files {
+                let mut scope = rhai::Scope::new();
+                // The top level runs once, for any setup
+                let loaded = engine
+                    .compile_file(paths::expand_home(file))
+                    .and_then(|ast| engine.run_ast_with_scope(&mut scope, &ast).map(|_| ast));
+                match loaded {
+                    Ok(ast) => scripts.push((file.clone(), ast, scope)),
+                    Err(e) => {
+                        let _ = sender.send(format!("Script {}: {e}", file.display()));
+                    }
+                }
+            }
+            Scripts {
+                engine,
+                scripts,
+                sender,
+                errors,
+                commands,
+            }
+        }
+    }
+
+    // Calls `on_<name>` in each script that has it
+    #[cfg(feature = "scripting")]
+    pub fn emit(&mut self, name: &str, event: &Event) {
+        let handler = format!("on_{name}");
+        for (file, ast, scope) in &mut self.scripts {
+            if !ast.iter_functions().any(|f| f.name == handler && f.params.len() == 1) {
+                continue;
+            }
+            let options = rhai::CallFnOptions::new().eval_ast(false);
+            if let Err(e) = self
+                .engine
+                .call_fn_with_options::<rhai::Dynamic>(options, scope, ast, &handler, (event.to_map(),))
+            {
+                let _ = self.sender.send(format!("Script {}: {e}", file.display()));
+            }
+        }
+    }
+
+    #[cfg(not(feature = "scripting"))]
+    pub fn emit(&mut self, _name: &str, _event: &Event) {}
+
+    // Calls made by handlers since the last call
+    pub fn commands(&self) -> impl Iterator<Item = ScriptCommand> + '_ {
+        self.commands.try_iter()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = String> + '_ {
+        self.errors.try_iter()
+    }
+}
+
+#[cfg(feature = "scripting")]
+impl Event {
+    fn to_map(&self) -> rhai::Map {
+        let mut map = rhai::Map::new();
+        map.insert("type".into(), self.timer_type.into());
+        map.insert("duration_secs".into(), (self.duration_secs as rhai::INT).into());
+        map.insert("remaining_secs".into(), (self.remaining_secs as rhai::INT).into());
+        map.insert("task".into(), self.task.clone().unwrap_or_default().into());
+        let tags: rhai::Array = self.tags.iter().map(|tag| tag.clone().into()).collect();
+        map.insert("tags".into(), tags.into());
+        map
+    }
+}
+
+// The API above; every function just queues its command
+#[cfg(feature = "scripting")]
+fn engine(commands: mpsc::Sender<ScriptCommand>) -> rhai::Engine {
+    let mut engine = rhai::Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    let queue = |command: ScriptCommand| {
+        let commands = commands.clone();
+        move || {
+            let _ = commands.send(command.clone());
+        }
+    };
+    let minutes = |minutes: rhai::INT| Duration::from_secs(minutes.max(1) as u64 * 60);
+    let sender = commands.clone();
+    engine.register_fn("notify", move |title: &str, body: &str| {
+        let _ = sender.send(ScriptCommand::Notify(title.to_string(), body.to_string()));
+    });
+    let sender = commands.clone();
+    engine.register_fn("toast", move |text: &str| {
+        let _ = sender.send(ScriptCommand::Toast(text.to_string()));
+    });
+    let sender = commands.clone();
+    engine.register_fn("set_status", move |text: &str| {
+        let _ = sender.send(ScriptCommand::Status(text.to_string()));
+    });
+    engine.register_fn("start_work", queue(ScriptCommand::Work(None)));
+    let sender = commands.clone();
+    engine.register_fn("start_work", move |length: rhai::INT| {
+        let _ = sender.send(ScriptCommand::Work(Some(minutes(length))));
+    });
+    engine.register_fn("start_break", queue(ScriptCommand::Break(None)));
+    let sender = commands.clone();
+    engine.register_fn("start_break", move |length: rhai::INT| {
+        let _ = sender.send(ScriptCommand::Break(Some(minutes(length))));
+    });
+    engine.register_fn("pause", queue(ScriptCommand::Pause));
+    engine.register_fn("resume", queue(ScriptCommand::Resume));
+    engine
+}
+
+#[cfg(all(test, feature = "scripting"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handlers_queue_commands() {
+        let dir = std::env::temp_dir().join(format!("cyber-tomato-scripts-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("test.rhai");
+        let script = r#"
+            fn on_complete(event) {
+                if event.type == "work" {
+                    set_status(`${event.task} done`);
+                    start_break(10);
+                }
+            }
+        "#;
+        std::fs::write(&file, script).unwrap();
+        let mut scripts = Scripts::load(&[file]);
+        let event = |timer_type| Event {
+            timer_type,
+            duration_secs: 1500,
+            remaining_secs: 0,
+            task: Some("Draft".to_string()),
+            tags: Vec::new(),
+        };
+        scripts.emit("complete", &event("break"));
+        scripts.emit("start", &event("work"));
+        scripts.emit("complete", &event("work"));
+        assert_eq!(
+            scripts.commands().collect::<Vec<_>>(),
+            vec![
+                ScriptCommand::Status("Draft done".to_string()),
+                ScriptCommand::Break(Some(Duration::from_secs(600)))
+            ]
+        );
+        assert_eq!(scripts.errors().count(), 0);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
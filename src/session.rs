@@ -0,0 +1,230 @@
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::TimerType;
+use crate::history::{self, PauseTotal};
+
+// Where a session is in its life. Only Running moves the clock, and the
+// methods on PomodoroSession are the only way from one state to another:
+//
+//   Idle --resume--> Running <--resume-- Paused
+//                    |   `------pause------^
+//                    |--finish--> Completed
+//                    `--finish--> Overtime --acknowledge--> Completed
+//
+// A finished session stays finished; the next one is a new PomodoroSession
+#[derive(Clone, Debug, PartialEq)]
+pub enum SessionState {
+    // Set up but never started
+    Idle,
+    // `banked` is the time run before the last resume
+    Running { since: Instant, banked: Duration },
+    Paused { elapsed: Duration },
+    // Finished, waiting for the next session
+    Completed,
+    // Finished and ringing until a key acknowledges it (nag mode)
+    Overtime { since: Instant },
+}
+
+#[derive(Clone)]
+pub struct PomodoroSession {
+    pub timer_type: TimerType,
+    pub duration: Duration,
+    pub state: SessionState,
+    pub started_at: Option<SystemTime>, // Wall clock time the session first started running
+    pub away: Duration,                 // Paused while the terminal was in the background
+    pub pauses: Vec<PauseTotal>,        // Pauses resumed from, by reason
+    pause: Option<(Instant, String)>,   // When the current pause began, and why
+}
+
+impl PomodoroSession {
+    pub fn new(timer_type: TimerType, duration: Duration) -> Self {
+        PomodoroSession {
+            timer_type,
+            duration,
+            state: SessionState::Idle,
+            started_at: None,
+            away: Duration::ZERO,
+            pauses: Vec::new(),
+            pause: None,
+        }
+    }
+
+    pub fn started(timer_type: TimerType, duration: Duration) -> Self {
+        let mut session = PomodoroSession::new(timer_type, duration);
+        session.resume();
+        session
+    }
+
+    // A copy of another instance's session for watch mode, from what it shares
+    pub fn mirror(timer_type: TimerType, duration: Duration, elapsed: Duration, running: bool) -> Self {
+        let state = if running {
+            SessionState::Running {
+                since: Instant::now(),
+                banked: elapsed,
+            }
+        } else if elapsed.is_zero() {
+            SessionState::Idle
+        } else if elapsed >= duration {
+            SessionState::Completed
+        } else {
+            SessionState::Paused { elapsed }
+        };
+        PomodoroSession {
+            state,
+            ..PomodoroSession::new(timer_type, duration)
+        }
+    }
+
+    // Time on the clock; `scale` is the --time-scale speed-up
+    pub fn elapsed(&self, scale: f64) -> Duration {
+        match self.state {
+            SessionState::Idle => Duration::ZERO,
+            SessionState::Running { since, banked } => banked + since.elapsed().mul_f64(scale),
+            SessionState::Paused { elapsed } => elapsed,
+            SessionState::Completed | SessionState::Overtime { .. } => self.duration,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        matches!(self.state, SessionState::Running { .. })
+    }
+
+    // Started and stopped part-way, as opposed to not yet started or finished
+    pub fn is_paused(&self) -> bool {
+        matches!(self.state, SessionState::Paused { .. })
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(self.state, SessionState::Completed | SessionState::Overtime { .. })
+    }
+
+    // Running -> Paused
+    pub fn pause(&mut self, scale: f64) -> bool {
+        if !self.is_running() {
+            return false;
+        }
+        self.state = SessionState::Paused { elapsed: self.elapsed(scale) };
+        self.pause = Some((Instant::now(), String::new()));
+        true
+    }
+
+    // Why the session is paused, counted against its pause once it resumes
+    pub fn set_pause_reason(&mut self, reason: &str) {
+        if let Some((_, current)) = &mut self.pause {
+            *current = reason.to_string();
+        }
+    }
+
+    // Idle or Paused -> Running
+    pub fn resume(&mut self) -> bool {
+        let banked = match self.state {
+            SessionState::Idle => Duration::ZERO,
+            SessionState::Paused { elapsed } => elapsed,
+            _ => return false,
+        };
+        self.state = SessionState::Running { since: Instant::now(), banked };
+        self.started_at.get_or_insert_with(SystemTime::now);
+        if let Some((since, reason)) = self.pause.take() {
+            history::add_pauses(&mut self.pauses, &reason, 1, since.elapsed().as_secs());
+        }
+        true
+    }
+
+    // Running -> Completed, or Overtime when it should ring until acknowledged
+    pub fn finish(&mut self, ring: bool) -> bool {
+        if !self.is_running() {
+            return false;
+        }
+        self.state = if ring {
+            SessionState::Overtime { since: Instant::now() }
+        } else {
+            SessionState::Completed
+        };
+        true
+    }
+
+    // Overtime -> Completed, with how long it rang
+    pub fn acknowledge(&mut self) -> Option<Duration> {
+        let SessionState::Overtime { since } = self.state else {
+            return None;
+        };
+        self.state = SessionState::Completed;
+        Some(since.elapsed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transitions() {
+        let mut session = PomodoroSession::new(TimerType::Work, Duration::from_secs(60));
+        // Nothing to pause, finish or acknowledge before it starts
+        assert!(!session.pause(1.0));
+        assert!(!session.finish(false));
+        assert!(session.acknowledge().is_none());
+        assert_eq!(session.state, SessionState::Idle);
+
+        assert!(session.resume());
+        assert!(!session.resume());
+        assert!(session.pause(1.0));
+        assert!(session.is_paused());
+        // Finishing needs the clock running
+        assert!(!session.finish(false));
+        assert!(session.resume());
+
+        assert!(session.finish(true));
+        assert!(session.is_finished());
+        // Pausing or resuming a finished session doesn't bring it back
+        assert!(!session.pause(1.0));
+        assert!(!session.resume());
+        assert!(session.acknowledge().is_some());
+        assert_eq!(session.state, SessionState::Completed);
+        assert!(session.acknowledge().is_none());
+        assert!(!session.resume());
+    }
+
+    #[test]
+    fn test_elapsed() {
+        let duration = Duration::from_secs(1500);
+        let mut session = PomodoroSession::new(TimerType::Work, duration);
+        session.state = SessionState::Running {
+            since: Instant::now() - Duration::from_secs(10),
+            banked: Duration::from_secs(5),
+        };
+        let secs = |elapsed: Duration| elapsed.as_secs();
+        assert_eq!(secs(session.elapsed(1.0)), 15);
+        assert_eq!(secs(session.elapsed(60.0)), 605);
+
+        // Pausing keeps the time run so far, scaled as it ran
+        session.pause(60.0);
+        assert_eq!(secs(session.elapsed(1.0)), 605);
+        session.resume();
+        assert_eq!(secs(session.elapsed(1.0)), 605);
+
+        session.finish(false);
+        assert_eq!(session.elapsed(1.0), duration);
+        let mirrored = PomodoroSession::mirror(TimerType::Break, duration, Duration::from_secs(30), false);
+        assert_eq!(
+            mirrored.state,
+            SessionState::Paused {
+                elapsed: Duration::from_secs(30)
+            }
+        );
+    }
+
+    #[test]
+    fn test_pause_reasons() {
+        let mut session = PomodoroSession::started(TimerType::Work, Duration::from_secs(1500));
+        for reason in ["phone", "", "phone"] {
+            session.pause(1.0);
+            session.set_pause_reason(reason);
+            session.resume();
+        }
+        // Only pauses that have ended count
+        session.pause(1.0);
+        let counts: Vec<(&str, u32)> = session.pauses.iter().map(|p| (p.reason.as_str(), p.count)).collect();
+        assert_eq!(counts, [("phone", 2), ("", 1)]);
+    }
+}
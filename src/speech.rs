@@ -0,0 +1,103 @@
+use std::io;
+use std::process::{Command, Stdio};
+
+use crate::config::SpeechConfig;
+use crate::jobs::Jobs;
+
+// Spoken announcements at session boundaries, for working away from the
+// screen. Uses the platform's own speech command, and says which to install
+// when there is none
+pub struct Speaker {
+    config: SpeechConfig,
+    jobs: Jobs,
+}
+
+impl Speaker {
+    pub fn new(config: SpeechConfig) -> Self {
+        Speaker { config, jobs: Jobs::new() }
+    }
+
+    pub fn set_config(&mut self, config: SpeechConfig) {
+        self.config = config;
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    // Speaks whether announcements are on or not: [[notify]] rules can ask
+    // for speech on their own
+    pub fn say(&self, text: &str) {
+        let commands = commands(text, self.config.voice.as_deref());
+        self.jobs.spawn(move || {
+            // The first command that exists does the talking
+            for mut command in commands {
+                match command.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null()).status() {
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                    Err(e) => return Err(format!("Speech: {e}")),
+                    Ok(_) => return Ok(()),
+                }
+            }
+            Err(format!("Speech: {MISSING}"))
+        });
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = String> + '_ {
+        self.jobs.errors()
+    }
+}
+
+// speech-dispatcher is what desktops set up; espeak is the usual fallback
+#[cfg(not(any(target_os = "macos", windows)))]
+const MISSING: &str = "install speech-dispatcher (spd-say) or espeak-ng";
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn commands(text: &str, voice: Option<&str>) -> Vec<Command> {
+    let mut spd_say = Command::new("spd-say");
+    if let Some(voice) = voice {
+        spd_say.args(["--synthesis-voice", voice]);
+    }
+    // Wait, so two announcements don't cut each other off
+    spd_say.args(["--wait", "--", text]);
+    let espeak = |program: &str| {
+        let mut command = Command::new(program);
+        if let Some(voice) = voice {
+            command.args(["-v", voice]);
+        }
+        command.args(["--", text]);
+        command
+    };
+    vec![spd_say, espeak("espeak-ng"), espeak("espeak")]
+}
+
+#[cfg(target_os = "macos")]
+const MISSING: &str = "the say command is missing";
+
+#[cfg(target_os = "macos")]
+fn commands(text: &str, voice: Option<&str>) -> Vec<Command> {
+    let mut command = Command::new("say");
+    if let Some(voice) = voice {
+        command.args(["-v", voice]);
+    }
+    command.arg(text);
+    vec![command]
+}
+
+#[cfg(windows)]
+const MISSING: &str = "PowerShell is missing";
+
+// System.Speech ships with Windows; the text travels in the environment to
+// stay clear of quoting
+#[cfg(windows)]
+fn commands(text: &str, voice: Option<&str>) -> Vec<Command> {
+    let script = "Add-Type -AssemblyName System.Speech; \
+         $speech = New-Object System.Speech.Synthesis.SpeechSynthesizer; \
+         if ($env:CYBER_TOMATO_VOICE) { $speech.SelectVoice($env:CYBER_TOMATO_VOICE) }; \
+         $speech.Speak($env:CYBER_TOMATO_SAY)";
+    let mut command = Command::new("powershell");
+    command
+        .args(["-NoProfile", "-WindowStyle", "Hidden", "-Command", script])
+        .env("CYBER_TOMATO_SAY", text)
+        .env("CYBER_TOMATO_VOICE", voice.unwrap_or(""));
+    vec![command]
+}
@@ -0,0 +1,171 @@
+use std::collections::HashSet;
+use std::io;
+use std::path::Path;
+
+use rusqlite::{Connection, params};
+use serde_json::Value;
+
+use crate::history::{self, HistoryStore, SessionRecord};
+use crate::migrate;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS sessions (
+    seq INTEGER PRIMARY KEY,
+    id TEXT NOT NULL,
+    started_at INTEGER NOT NULL,
+    record TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS sessions_started_at ON sessions (started_at);
+CREATE INDEX IF NOT EXISTS sessions_id ON sessions (id);
+CREATE TABLE IF NOT EXISTS archive (
+    seq INTEGER PRIMARY KEY,
+    id TEXT NOT NULL,
+    record TEXT NOT NULL
+);
+";
+
+// Session versions as rows in one database: the same JSON as a JSONL line,
+// plus the id and start time as columns to look them up by. Rows are never
+// updated, only added, so the order they were written in is kept like in a
+// file
+pub struct SqliteStore {
+    db: Connection,
+}
+
+fn other(e: rusqlite::Error) -> io::Error {
+    io::Error::other(e)
+}
+
+impl SqliteStore {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut db = Connection::open(path).map_err(other)?;
+        db.execute_batch(SCHEMA).map_err(other)?;
+        upgrade(&mut db, path)?;
+        Ok(SqliteStore { db })
+    }
+}
+
+// Brings the stored records up to the current version, keeping a copy of
+// the database first. Databases from before versions were recorded are at 1
+fn upgrade(db: &mut Connection, path: &Path) -> io::Result<()> {
+    let version = history::SCHEMA.version;
+    let stored: u32 = db.query_row("PRAGMA user_version", [], |row| row.get(0)).map_err(other)?;
+    if stored == version {
+        return Ok(());
+    }
+    let found = stored.max(1);
+    history::SCHEMA.check(found).map_err(io::Error::other)?;
+    if found < version {
+        migrate::backup(path, found)?;
+    }
+    let tx = db.transaction().map_err(other)?;
+    if found < version {
+        for table in ["sessions", "archive"] {
+            let rows: Vec<(i64, String)> = tx
+                .prepare(&format!("SELECT seq, record FROM {table}"))
+                .and_then(|mut query| query.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?.collect())
+                .map_err(other)?;
+            for (seq, record) in rows {
+                let Ok(mut value) = serde_json::from_str::<Value>(&record) else {
+                    continue;
+                };
+                history::SCHEMA.upgrade(found, &mut value).map_err(io::Error::other)?;
+                let update = format!("UPDATE {table} SET record = ?1 WHERE seq = ?2");
+                tx.execute(&update, params![value.to_string(), seq]).map_err(other)?;
+            }
+        }
+    }
+    tx.pragma_update(None, "user_version", version).map_err(other)?;
+    tx.commit().map_err(other)
+}
+
+// A row that no longer parses is skipped like a bad JSONL line
+fn read_rows(rows: impl Iterator<Item = rusqlite::Result<String>>) -> io::Result<Vec<SessionRecord>> {
+    let mut records = Vec::new();
+    for row in rows {
+        if let Ok(record) = serde_json::from_str(&row.map_err(other)?) {
+            records.push(record);
+        }
+    }
+    Ok(records)
+}
+
+fn insert(db: &Connection, record: &SessionRecord) -> io::Result<()> {
+    let json = serde_json::to_string(record)?;
+    db.prepare_cached("INSERT INTO sessions (id, started_at, record) VALUES (?1, ?2, ?3)")
+        .and_then(|mut insert| insert.execute(params![record.id, record.started_at as i64, json]))
+        .map_err(other)?;
+    Ok(())
+}
+
+impl HistoryStore for SqliteStore {
+    fn load(&self) -> io::Result<Vec<SessionRecord>> {
+        let mut query = self.db.prepare_cached("SELECT record FROM sessions ORDER BY seq").map_err(other)?;
+        let rows = query.query_map([], |row| row.get::<_, String>(0)).map_err(other)?;
+        read_rows(rows)
+    }
+
+    fn append(&mut self, record: &SessionRecord) -> io::Result<()> {
+        insert(&self.db, record)
+    }
+
+    // One transaction, or a migration of years of sessions takes minutes
+    fn append_all(&mut self, records: &[SessionRecord]) -> io::Result<()> {
+        let tx = self.db.transaction().map_err(other)?;
+        for record in records {
+            insert(&tx, record)?;
+        }
+        tx.commit().map_err(other)
+    }
+
+    fn archive(&mut self, ids: &HashSet<&str>) -> io::Result<()> {
+        let tx = self.db.transaction().map_err(other)?;
+        for id in ids {
+            tx.execute(
+                "INSERT INTO archive (id, record) SELECT id, record FROM sessions WHERE id = ?1 ORDER BY seq",
+                [id],
+            )
+            .map_err(other)?;
+            tx.execute("DELETE FROM sessions WHERE id = ?1", [id]).map_err(other)?;
+        }
+        tx.commit().map_err(other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TimerType;
+    use crate::config::HistoryBackend;
+    use crate::history::{self, History, SessionStore};
+
+    #[test]
+    fn test_round_trip() {
+        let dir = std::env::temp_dir().join(format!("cyber-tomato-sqlite-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut history = History::open(dir.clone(), HistoryBackend::Sqlite).unwrap();
+        let old = history.new_record(TimerType::Work, 1_000_000, 1500);
+        let recent = history.new_record(TimerType::Work, history::now_secs(), 1500);
+        history.append(old.clone()).unwrap();
+        history.append(recent.clone()).unwrap();
+        let mut edited = recent.clone();
+        edited.task = Some("Edited".to_string());
+        edited.updated_at += 1;
+        history.append(edited).unwrap();
+
+        let store = SqliteStore::open(&dir.join("history.sqlite3")).unwrap();
+        assert_eq!(store.load().unwrap().len(), 3);
+        let history = History::open(dir.clone(), HistoryBackend::Sqlite).unwrap();
+        assert_eq!(history.get(&recent.id).unwrap().task.as_deref(), Some("Edited"));
+
+        let mut history = history;
+        assert_eq!(history.archive(1_000_000 + 86400).unwrap(), 1);
+        assert!(history.get(&old.id).is_none());
+        let archived: i64 = store.db.query_row("SELECT COUNT(*) FROM archive", [], |row| row.get(0)).unwrap();
+        assert_eq!(archived, 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use crate::cli;
+use crate::remote;
+
+// One entry of a [startup] or profile action list
+#[derive(Clone, Debug, PartialEq)]
+pub enum StartupAction {
+    Theme(String), // A [themes.NAME] table in place of [theme]
+    Mute(bool),
+    Work(Option<Duration>), // The configured length, or a custom one like the c dialog's
+    Break(Option<Duration>),
+    Label(String),   // "Task #tag", as typed into the label dialog
+    Manual(bool),    // Manual rather than auto mode
+    Command(String), // Any action `cyber-tomato remote` takes, e.g. "pause"
+}
+
+// "work 50", "theme nord", "mute": a name, then its argument if it takes one
+pub fn parse(text: &str) -> Result<StartupAction, String> {
+    let text = text.trim();
+    let (name, arg) = text.split_once(char::is_whitespace).map_or((text, ""), |(name, arg)| (name, arg.trim()));
+    let length = |arg: &str| (!arg.is_empty()).then(|| cli::parse_duration(arg)).transpose();
+    let action = match (name, arg) {
+        ("theme", "") => return Err("theme needs a NAME".to_string()),
+        ("theme", name) => StartupAction::Theme(name.to_string()),
+        ("mute", "") => StartupAction::Mute(true),
+        ("unmute", "") => StartupAction::Mute(false),
+        ("work", arg) => StartupAction::Work(length(arg)?),
+        ("break", arg) => StartupAction::Break(length(arg)?),
+        ("label", "") => return Err("label needs a task or #tags".to_string()),
+        ("label", label) => StartupAction::Label(label.to_string()),
+        ("mode", "manual") => StartupAction::Manual(true),
+        ("mode", "auto") => StartupAction::Manual(false),
+        ("mode", _) => return Err("mode is manual or auto".to_string()),
+        (name, "") if remote::ACTIONS.contains(&name) => StartupAction::Command(name.to_string()),
+        ("mute" | "unmute", _) => return Err(format!("{name} takes no argument")),
+        _ => return Err(format!("unknown action '{name}'")),
+    };
+    Ok(action)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("theme nord"), Ok(StartupAction::Theme("nord".to_string())));
+        assert_eq!(parse("  work 50 "), Ok(StartupAction::Work(Some(Duration::from_secs(50 * 60)))));
+        assert_eq!(parse("break"), Ok(StartupAction::Break(None)));
+        assert_eq!(parse("mute"), Ok(StartupAction::Mute(true)));
+        assert_eq!(parse("label Draft  #writing"), Ok(StartupAction::Label("Draft  #writing".to_string())));
+        assert_eq!(parse("mode manual"), Ok(StartupAction::Manual(true)));
+        assert_eq!(parse("pause"), Ok(StartupAction::Command("pause".to_string())));
+        assert!(parse("work soon").is_err());
+        assert!(parse("theme").is_err());
+        assert!(parse("mute loudly").is_err());
+        assert!(parse("dance").is_err());
+    }
+}
@@ -0,0 +1,371 @@
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Timelike};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Bar, BarChart, BarGroup, Block, Borders, Paragraph},
+};
+
+use crate::eye_rest::EyeRestRecord;
+use crate::history::{SessionRecord, SessionStore, Totals};
+use crate::tasks::{self, TaskList};
+use crate::{TimerType, theme};
+
+pub const MIN_DAYS: u32 = 1;
+pub const MAX_DAYS: u32 = 365;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StatsView {
+    Hours,
+    Tasks,
+    Tags,
+    Pauses,
+}
+
+impl StatsView {
+    fn title(self) -> &'static str {
+        match self {
+            StatsView::Hours => "Hours",
+            StatsView::Tasks => "Tasks",
+            StatsView::Tags => "Tags",
+            StatsView::Pauses => "Pauses",
+        }
+    }
+}
+
+// Which slice of history the stats screen shows: `days` whole local days
+// ending `offset` days before today
+pub struct StatsScreen {
+    pub view: StatsView,
+    pub days: u32,
+    pub offset: u32,
+}
+
+impl StatsScreen {
+    pub fn new(days: u32) -> Self {
+        StatsScreen {
+            view: StatsView::Hours,
+            days: days.clamp(MIN_DAYS, MAX_DAYS),
+            offset: 0,
+        }
+    }
+
+    pub fn next_view(&mut self) {
+        self.view = match self.view {
+            StatsView::Hours => StatsView::Tasks,
+            StatsView::Tasks => StatsView::Tags,
+            StatsView::Tags => StatsView::Pauses,
+            StatsView::Pauses => StatsView::Hours,
+        };
+    }
+
+    pub fn adjust_days(&mut self, delta: i64) {
+        self.days = (self.days as i64 + delta).clamp(MIN_DAYS as i64, MAX_DAYS as i64) as u32;
+    }
+
+    // Move the whole range back (positive) or forward (negative) in time
+    pub fn shift(&mut self, delta: i64) {
+        self.offset = (self.offset as i64 + delta).clamp(0, MAX_DAYS as i64) as u32;
+    }
+
+    fn range(&self, now: DateTime<Local>) -> (NaiveDate, NaiveDate) {
+        let last = now.date_naive() - chrono::Duration::days(self.offset as i64);
+        let first = last - chrono::Duration::days(self.days.saturating_sub(1) as i64);
+        (first, last)
+    }
+}
+
+fn local_time(secs: u64) -> Option<DateTime<Local>> {
+    Local.timestamp_opt(secs as i64, 0).single()
+}
+
+// Unix seconds at the local midnight starting `date`
+fn day_start(date: NaiveDate) -> u64 {
+    date.and_hms_opt(0, 0, 0)
+        .and_then(|d| Local.from_local_datetime(&d).earliest())
+        .map_or(0, |t| t.timestamp().max(0) as u64)
+}
+
+// Completed work sessions whose start falls inside [first, last] local days
+pub fn work_sessions_in(records: &[SessionRecord], first: NaiveDate, last: NaiveDate) -> impl Iterator<Item = &SessionRecord> {
+    records
+        .iter()
+        .filter(move |r| r.kind == TimerType::Work && local_time(r.started_at).is_some_and(|t| (first..=last).contains(&t.date_naive())))
+}
+
+// Count completed work sessions by the local hour they started in
+pub fn hour_histogram<'a>(records: impl Iterator<Item = &'a SessionRecord>) -> [u64; 24] {
+    let mut buckets = [0; 24];
+    for record in records {
+        if let Some(start) = local_time(record.started_at) {
+            buckets[start.hour() as usize] += record.sessions as u64;
+        }
+    }
+    buckets
+}
+
+// Sum focused seconds per group, largest first. A session with several tags
+// counts towards each of them; the Pauses view sums time paused by reason
+pub fn breakdown<'a>(records: impl Iterator<Item = &'a SessionRecord>, view: StatsView) -> Vec<(String, u64)> {
+    let mut totals: Vec<(String, u64)> = Vec::new();
+    let mut add = |name: &str, secs: u64| match totals.iter_mut().find(|(n, _)| n == name) {
+        Some((_, total)) => *total += secs,
+        None => totals.push((name.to_string(), secs)),
+    };
+
+    for record in records {
+        match view {
+            StatsView::Tasks => add(record.task.as_deref().unwrap_or("(no task)"), record.duration_secs),
+            StatsView::Tags if record.tags.is_empty() => add("(untagged)", record.duration_secs),
+            StatsView::Tags => record.tags.iter().for_each(|tag| add(tag, record.duration_secs)),
+            StatsView::Pauses => record.pauses.iter().for_each(|pause| add(pause_reason(&pause.reason), pause.secs)),
+            StatsView::Hours => {}
+        }
+    }
+
+    totals.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    totals
+}
+
+fn pause_reason(reason: &str) -> &str {
+    if reason.is_empty() { "(no reason)" } else { reason }
+}
+
+// How many times work was paused for `reason`, as the breakdown names it
+pub fn pause_count<'a>(records: impl Iterator<Item = &'a SessionRecord>, reason: &str) -> u32 {
+    records
+        .flat_map(|record| &record.pauses)
+        .filter(|pause| pause_reason(&pause.reason) == reason)
+        .map(|pause| pause.count)
+        .sum()
+}
+
+pub fn format_hm(secs: u64) -> String {
+    let mins = secs / 60;
+    format!("{}h {:02}m", mins / 60, mins % 60)
+}
+
+pub fn render(f: &mut Frame, area: Rect, store: &dyn SessionStore, screen: &StatsScreen, tasks: &TaskList, eye_rests: &[EyeRestRecord]) {
+    let now = Local::now();
+    let (first, last) = screen.range(now);
+    let (from, to) = (day_start(first), day_start(last + chrono::Duration::days(1)));
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Stats")
+        .title_alignment(Alignment::Center)
+        .border_style(Style::default().fg(theme::primary()));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(4), Constraint::Min(5), Constraint::Length(1)])
+        .split(inner);
+
+    let sessions: Vec<&SessionRecord> = store.range(from, to).iter().filter(|r| r.kind == TimerType::Work).collect();
+    let Totals { pomodoros, focus_secs } = store.aggregate(from, to);
+    let rests_taken = eye_rests
+        .iter()
+        .filter(|rest| rest.taken && local_time(rest.at).is_some_and(|t| (first..=last).contains(&t.date_naive())))
+        .count();
+
+    let mut tabs = vec![Span::raw("  ")];
+    for view in [StatsView::Hours, StatsView::Tasks, StatsView::Tags, StatsView::Pauses] {
+        let style = if view == screen.view {
+            Style::default().fg(Color::Black).bg(theme::primary()).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme::primary())
+        };
+        tabs.push(Span::styled(format!(" {} ", view.title()), style));
+        tabs.push(Span::raw(" "));
+    }
+
+    let mut totals = vec![
+        Span::raw(format!("  {} – {}: ", first.format("%b %d"), last.format("%b %d"))),
+        Span::styled(pomodoros.to_string(), Style::default().fg(theme::primary()).add_modifier(Modifier::BOLD)),
+        Span::raw(format!(" pomodoros, {} focused", format_hm(focus_secs))),
+    ];
+    if rests_taken > 0 {
+        totals.push(Span::raw(format!(", {rests_taken} eye rests")));
+    }
+
+    let summary = Paragraph::new(vec![Line::from(totals), Line::from(""), Line::from(tabs)]);
+    f.render_widget(summary, chunks[0]);
+
+    match screen.view {
+        StatsView::Hours => render_hours(f, chunks[1], &sessions),
+        StatsView::Tasks | StatsView::Tags => {
+            // Planned tasks show their all-time pomodoros against the estimate
+            let estimate = |name: &str| {
+                let task = tasks.get(name).filter(|_| screen.view == StatsView::Tasks)?;
+                let done = tasks::actual(store.records(), name);
+                Some((tasks::progress_text(Some(task), done), tasks::is_over_estimate(task, done)))
+            };
+            render_breakdown(f, chunks[1], breakdown(sessions.into_iter(), screen.view), focus_secs, estimate, "No sessions")
+        }
+        StatsView::Pauses => {
+            // Shares of all the time paused, with how often
+            let groups = breakdown(sessions.iter().copied(), screen.view);
+            let paused_secs = groups.iter().map(|(_, secs)| secs).sum();
+            let count = |reason: &str| Some((format!("{}×", pause_count(sessions.iter().copied(), reason)), false));
+            render_breakdown(f, chunks[1], groups, paused_secs, count, "No pauses")
+        }
+    }
+
+    let help = Paragraph::new(Line::from(vec![
+        Span::styled("Tab", Style::default().fg(theme::primary()).add_modifier(Modifier::BOLD)),
+        Span::raw(" - View | "),
+        Span::styled("←/→", Style::default().fg(theme::primary()).add_modifier(Modifier::BOLD)),
+        Span::raw(" - Earlier/Later | "),
+        Span::styled("+/-", Style::default().fg(theme::primary()).add_modifier(Modifier::BOLD)),
+        Span::raw(" - Days | "),
+        Span::styled("s/Esc", Style::default().fg(theme::primary()).add_modifier(Modifier::BOLD)),
+        Span::raw(" - Close"),
+    ]))
+    .alignment(Alignment::Center);
+    f.render_widget(help, chunks[2]);
+}
+
+fn render_hours(f: &mut Frame, area: Rect, sessions: &[&SessionRecord]) {
+    let histogram = hour_histogram(sessions.iter().copied());
+    // Fit 24 bars into the available width: bar + gap per hour
+    let slot = (area.width / 24).max(1);
+    let bar_width = if slot > 1 { slot - 1 } else { 1 };
+    let bars: Vec<Bar> = histogram
+        .iter()
+        .enumerate()
+        .map(|(hour, &count)| {
+            let label = if hour % 3 == 0 || slot >= 3 { format!("{hour:02}") } else { String::new() };
+            Bar::default()
+                .value(count)
+                .label(Line::from(label))
+                .text_value(if count > 0 { count.to_string() } else { String::new() })
+        })
+        .collect();
+
+    let chart = BarChart::default()
+        .data(BarGroup::default().bars(&bars))
+        .bar_width(bar_width)
+        .bar_gap(if slot > 1 { 1 } else { 0 })
+        .bar_style(Style::default().fg(theme::primary()))
+        .value_style(Style::default().fg(Color::Black).bg(theme::primary()));
+    f.render_widget(chart, area);
+}
+
+fn render_breakdown(f: &mut Frame, area: Rect, groups: Vec<(String, u64)>, total_secs: u64, estimate: impl Fn(&str) -> Option<(String, bool)>, empty: &str) {
+    if groups.is_empty() {
+        f.render_widget(Paragraph::new(format!("  {empty} in this range")).alignment(Alignment::Left), area);
+        return;
+    }
+
+    let notes: Vec<Option<(String, bool)>> = groups.iter().map(|(name, _)| estimate(name)).collect();
+    let note_width = notes.iter().flatten().map(|(text, _)| text.len() + 4).max().unwrap_or(0);
+    let name_width = groups.iter().map(|(name, _)| name.chars().count()).max().unwrap_or(0).clamp(8, 24);
+    // name + time + percentage (+ estimate) columns, the rest is bar
+    let bar_space = (area.width as usize).saturating_sub(name_width + 22 + note_width).max(1);
+    let max_secs = groups.first().map(|(_, secs)| *secs).unwrap_or(1).max(1);
+
+    let lines: Vec<Line> = groups
+        .iter()
+        .take(area.height as usize)
+        .zip(&notes)
+        .map(|((name, secs), note)| {
+            let name: String = name.chars().take(name_width).collect();
+            let bar_len = ((*secs as f64 / max_secs as f64) * bar_space as f64).round().max(1.0) as usize;
+            let percent = *secs as f64 / total_secs.max(1) as f64 * 100.0;
+            let over = note.as_ref().is_some_and(|(_, over)| *over);
+            let bar_color = if over { tasks::OVER_ESTIMATE_COLOR } else { theme::primary() };
+            let mut spans = vec![
+                Span::styled(format!("  {name:<name_width$} "), Style::default().fg(theme::highlight())),
+                Span::styled("█".repeat(bar_len), Style::default().fg(bar_color)),
+                Span::raw(" ".repeat(bar_space - bar_len)),
+                Span::raw(format!(" {:>8} {:>5.1}%", format_hm(*secs), percent)),
+            ];
+            if let Some((text, _)) = note {
+                let flag = if over { "!" } else { " " };
+                spans.push(Span::styled(format!(" {text:>w$}{flag}", w = note_width - 2), Style::default().fg(bar_color)));
+            }
+            Line::from(spans)
+        })
+        .collect();
+    f.render_widget(Paragraph::new(lines), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn work_at(id: &str, time: DateTime<Local>, task: Option<&str>, tags: &[&str]) -> SessionRecord {
+        SessionRecord {
+            id: id.to_string(),
+            started_at: time.timestamp() as u64,
+            duration_secs: 1500,
+            task: task.map(str::to_string),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..SessionRecord::default()
+        }
+    }
+
+    #[test]
+    fn test_hour_histogram_respects_range() {
+        let now = Local.with_ymd_and_hms(2025, 3, 12, 18, 0, 0).unwrap();
+        let records = vec![
+            work_at("a", Local.with_ymd_and_hms(2025, 3, 12, 9, 15, 0).unwrap(), None, &[]),
+            work_at("b", Local.with_ymd_and_hms(2025, 3, 11, 9, 45, 0).unwrap(), None, &[]),
+            work_at("c", Local.with_ymd_and_hms(2025, 3, 11, 14, 0, 0).unwrap(), None, &[]),
+            work_at("d", Local.with_ymd_and_hms(2025, 3, 1, 9, 0, 0).unwrap(), None, &[]),
+        ];
+
+        let mut screen = StatsScreen::new(1);
+        let (first, last) = screen.range(now);
+        let today = hour_histogram(work_sessions_in(&records, first, last));
+        assert_eq!(today[9], 1);
+        assert_eq!(today.iter().sum::<u64>(), 1);
+
+        screen.adjust_days(1);
+        let (first, last) = screen.range(now);
+        let two_days = hour_histogram(work_sessions_in(&records, first, last));
+        assert_eq!(two_days[9], 2);
+        assert_eq!(two_days[14], 1);
+        assert_eq!(two_days.iter().sum::<u64>(), 3);
+
+        screen.shift(10);
+        let (first, last) = screen.range(now);
+        assert_eq!(hour_histogram(work_sessions_in(&records, first, last)).iter().sum::<u64>(), 1);
+    }
+
+    #[test]
+    fn test_breakdown_groups_by_task_and_tag() {
+        let t = Local.with_ymd_and_hms(2025, 3, 12, 9, 0, 0).unwrap();
+        let records = [
+            work_at("a", t, Some("report"), &["work", "writing"]),
+            work_at("b", t, Some("report"), &["work"]),
+            work_at("c", t, None, &[]),
+        ];
+
+        let tasks = breakdown(records.iter(), StatsView::Tasks);
+        assert_eq!(tasks, vec![("report".to_string(), 3000), ("(no task)".to_string(), 1500)]);
+
+        let tags = breakdown(records.iter(), StatsView::Tags);
+        assert_eq!(
+            tags,
+            vec![("work".to_string(), 3000), ("(untagged)".to_string(), 1500), ("writing".to_string(), 1500)]
+        );
+    }
+
+    #[test]
+    fn test_pauses_by_reason() {
+        let t = Local.with_ymd_and_hms(2025, 3, 12, 9, 0, 0).unwrap();
+        let mut records = [work_at("a", t, None, &[]), work_at("b", t, None, &[])];
+        crate::history::add_pauses(&mut records[0].pauses, "phone", 2, 300);
+        crate::history::add_pauses(&mut records[0].pauses, "", 1, 60);
+        crate::history::add_pauses(&mut records[1].pauses, "phone", 1, 120);
+
+        let pauses = breakdown(records.iter(), StatsView::Pauses);
+        assert_eq!(pauses, vec![("phone".to_string(), 420), ("(no reason)".to_string(), 60)]);
+        assert_eq!(pause_count(records.iter(), "phone"), 3);
+        assert_eq!(pause_count(records.iter(), "(no reason)"), 1);
+    }
+}
@@ -0,0 +1,103 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::away::Away;
+use crate::clock::ClockDisplay;
+use crate::config::StatusFileConfig;
+use crate::{TimerType, paths};
+
+// What status bars, scripts and overlays get: the running timer in whole
+// seconds, rewritten whenever any of it changes
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Status {
+    pub session: TimerType,
+    pub state: &'static str, // "idle", "running", "paused" or "finished"
+    pub remaining_secs: u64,
+    pub duration_secs: u64,
+    pub completed_sessions: u32, // Since the timer started
+    pub today: u32,              // Work sessions in today's history
+    pub daily_goal: u32,         // 0 for none
+    pub task: Option<String>,
+    pub tags: Vec<String>,
+    // templates.status filled in from the rest, for bars that show it as is
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub text: String,
+    // templates.away filled in, for the away file
+    #[serde(skip)]
+    pub away: String,
+    // What the digits show, for {clock}
+    #[serde(skip)]
+    pub clock: ClockDisplay,
+}
+
+pub fn default_path() -> PathBuf {
+    paths::cache_dir().join("status.json")
+}
+
+// The status file, and the away file for chat bots, removed again when the
+// timer quits so a leftover one doesn't look like a running timer
+pub struct StatusFile {
+    path: Option<PathBuf>, // None when turned off
+    away_path: Option<PathBuf>,
+    last: Option<Status>,
+}
+
+impl StatusFile {
+    pub fn new() -> Self {
+        StatusFile {
+            path: None,
+            away_path: None,
+            last: None,
+        }
+    }
+
+    pub fn set_config(&mut self, config: &StatusFileConfig) {
+        let path = config
+            .enabled
+            .then(|| config.path.as_deref().map(paths::expand_home).unwrap_or_else(default_path));
+        let away_path = config.away_path.as_deref().map(paths::expand_home);
+        if path != self.path || away_path != self.away_path {
+            self.remove();
+            self.path = path;
+            self.away_path = away_path;
+        }
+    }
+
+    pub fn update(&mut self, status: Status) {
+        if (self.path.is_none() && self.away_path.is_none()) || self.last.as_ref() == Some(&status) {
+            return;
+        }
+        // Readers are a nicety; a failed write must not disturb the timer
+        if let Some(path) = &self.path
+            && let Ok(json) = serde_json::to_string(&status)
+        {
+            let _ = write(path, &json);
+        }
+        if let Some(path) = &self.away_path {
+            let _ = write(path, &Away::new(Some(&status)).key_value());
+        }
+        self.last = Some(status);
+    }
+
+    pub fn remove(&mut self) {
+        for path in self.path.iter().chain(&self.away_path) {
+            let _ = fs::remove_file(path);
+        }
+        self.last = None;
+    }
+}
+
+fn write(path: &Path, contents: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Replace atomically so a reader never sees half a file
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    let tmp = path.with_file_name(name);
+    fs::write(&tmp, contents)?;
+    fs::rename(tmp, path)
+}
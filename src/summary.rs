@@ -0,0 +1,89 @@
+use chrono::{Local, TimeZone};
+
+use crate::TimerType;
+use crate::history::SessionRecord;
+use crate::stats::format_hm;
+
+// Pomodoros at most this far apart count toward the same streak, which a
+// long break doesn't end
+const STREAK_GAP_SECS: u64 = 30 * 60;
+
+// Printed to the normal screen on quit, so the run leaves a record in the
+// scrollback once the alternate screen is gone. `run` is this machine's
+// sessions since `since`, `today` every work session of the day
+pub fn lines(run: &[&SessionRecord], today: &[&SessionRecord], since: u64, now: u64) -> Vec<String> {
+    let run: Vec<&SessionRecord> = run.iter().copied().filter(|r| r.kind == TimerType::Work).collect();
+    let span = format!("{}-{}", clock(since), clock(now));
+    let mut lines = vec![match run.len() {
+        0 => format!("Cyber Tomato {span}: no pomodoros completed"),
+        n => format!("Cyber Tomato {span}: {n} {}, {} of focus", pomodoros(n), format_hm(focus_secs(&run))),
+    }];
+    if !today.is_empty() {
+        lines.push(format!(
+            "Today: {} {}, {} of focus, longest streak {}",
+            today.len(),
+            pomodoros(today.len()),
+            format_hm(focus_secs(today)),
+            longest_streak(today)
+        ));
+    }
+    lines
+}
+
+fn pomodoros(n: usize) -> &'static str {
+    if n == 1 { "pomodoro" } else { "pomodoros" }
+}
+
+fn focus_secs(records: &[&SessionRecord]) -> u64 {
+    records.iter().map(|r| r.duration_secs).sum()
+}
+
+fn clock(secs: u64) -> String {
+    Local
+        .timestamp_opt(secs as i64, 0)
+        .single()
+        .map(|t| t.format("%H:%M").to_string())
+        .unwrap_or_default()
+}
+
+// Most pomodoros in a row, each starting within STREAK_GAP_SECS of the end
+// of the one before
+fn longest_streak(records: &[&SessionRecord]) -> usize {
+    let mut sorted: Vec<&SessionRecord> = records.to_vec();
+    sorted.sort_by_key(|r| r.started_at);
+    let (mut longest, mut streak) = (0, 0);
+    let mut last_end: Option<u64> = None;
+    for record in sorted {
+        streak = match last_end {
+            Some(end) if record.started_at <= end + STREAK_GAP_SECS => streak + 1,
+            _ => 1,
+        };
+        longest = longest.max(streak);
+        last_end = Some(record.started_at + record.duration_secs);
+    }
+    longest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_longest_streak() {
+        let at = |started_at: u64| SessionRecord {
+            id: started_at.to_string(),
+            started_at,
+            duration_secs: 1500,
+            ..SessionRecord::default()
+        };
+        // Three pomodoros with short breaks, lunch, then one more
+        let records = [at(0), at(1800), at(3600), at(3600 + 4 * 3600)];
+        let refs: Vec<&SessionRecord> = records.iter().collect();
+        assert_eq!(longest_streak(&refs), 3);
+        assert_eq!(longest_streak(&[]), 0);
+
+        let lines = lines(&refs[..2], &refs, 0, 3600);
+        assert!(lines[0].ends_with("2 pomodoros, 0h 50m of focus"));
+        assert!(lines[1].starts_with("Today: 4 pomodoros, 1h 40m of focus, longest streak 3"));
+    }
+}
@@ -0,0 +1,477 @@
+use std::io;
+use std::path::PathBuf;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Row, Table},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::cli::parse_duration;
+use crate::history::{self, SessionRecord};
+use crate::migrate::{self, Schema};
+use crate::{TimerType, parse_label, theme};
+
+pub const OVER_ESTIMATE_COLOR: Color = Color::Red;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Task {
+    pub name: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub estimate: Option<u32>, // Planned number of pomodoros
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_minutes: Option<u32>, // Focused time to alert at, for timeboxes longer than a pomodoro
+    #[serde(default)]
+    pub done: bool,
+    pub created_at: u64,
+}
+
+// tasks.json's format; see migrate
+const SCHEMA: Schema = Schema {
+    file: "tasks.json",
+    version: 1,
+    steps: &[|value| migrate::wrap_list(value, "tasks")],
+};
+
+pub struct TaskList {
+    path: PathBuf,
+    pub tasks: Vec<Task>,
+    locked: Option<String>, // Why saving would lose data
+}
+
+impl TaskList {
+    pub fn load(path: PathBuf) -> Self {
+        let list = migrate::read_list(&path, &SCHEMA, "tasks");
+        TaskList {
+            path,
+            tasks: list.items,
+            locked: list.locked,
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        if let Some(reason) = &self.locked {
+            return Err(io::Error::other(reason.clone()));
+        }
+        migrate::write_list(&self.path, &SCHEMA, "tasks", &self.tasks)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Task> {
+        self.tasks.iter().find(|t| t.name == name)
+    }
+}
+
+// Completed pomodoros recorded against a task name
+pub fn actual(records: &[SessionRecord], name: &str) -> u32 {
+    records
+        .iter()
+        .filter(|r| r.kind == TimerType::Work && r.task.as_deref() == Some(name))
+        .map(|r| r.sessions)
+        .sum()
+}
+
+// Work time recorded against a task name, snoozes included
+pub fn focused_secs(records: &[SessionRecord], name: &str) -> u64 {
+    records
+        .iter()
+        .filter(|r| r.kind == TimerType::Work && r.task.as_deref() == Some(name))
+        .map(|r| r.duration_secs)
+        .sum()
+}
+
+pub fn is_over_budget(task: &Task, secs: u64) -> bool {
+    task.budget_minutes.is_some_and(|budget| secs >= budget as u64 * 60)
+}
+
+// "1h10m/2h", or just the time when there is no budget
+pub fn budget_text(task: &Task, secs: u64) -> String {
+    match task.budget_minutes {
+        Some(budget) => format!("{}/{}", hours_minutes(secs / 60), hours_minutes(budget as u64)),
+        None => hours_minutes(secs / 60),
+    }
+}
+
+// "45m", "2h", "1h10m"
+fn hours_minutes(minutes: u64) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{m}m"),
+        (h, 0) => format!("{h}h"),
+        (h, m) => format!("{h}h{m:02}m"),
+    }
+}
+
+pub fn is_over_estimate(task: &Task, actual: u32) -> bool {
+    task.estimate.is_some_and(|estimate| actual > estimate)
+}
+
+// "3/5" or just "3" when no estimate was made
+pub fn progress_text(task: Option<&Task>, actual: u32) -> String {
+    match task.and_then(|t| t.estimate) {
+        Some(estimate) => format!("{actual}/{estimate}"),
+        None => actual.to_string(),
+    }
+}
+
+pub enum PaneAction {
+    None,
+    Close,
+    Select(Task),
+}
+
+enum PaneInput {
+    Add,
+    Budget,
+}
+
+pub struct TaskPane {
+    selected: usize,
+    editing: Option<PaneInput>,
+    input: String,
+    message: Option<String>,
+}
+
+impl TaskPane {
+    pub fn new() -> Self {
+        TaskPane {
+            selected: 0,
+            editing: None,
+            input: String::new(),
+            message: None,
+        }
+    }
+
+    pub fn open(&mut self) {
+        self.editing = None;
+        self.message = None;
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent, list: &mut TaskList) -> PaneAction {
+        if let Some(editing) = &self.editing {
+            self.message = None;
+            match key.code {
+                KeyCode::Esc => self.editing = None,
+                KeyCode::Enter if matches!(editing, PaneInput::Add) => {
+                    self.editing = None;
+                    if let (Some(name), tags) = parse_label(&self.input) {
+                        if list.get(&name).is_some() {
+                            self.message = Some(format!("'{name}' already exists"));
+                            return PaneAction::None;
+                        }
+                        list.tasks.push(Task {
+                            name,
+                            tags,
+                            estimate: Some(1),
+                            budget_minutes: None,
+                            done: false,
+                            created_at: history::now_secs(),
+                        });
+                        self.selected = list.tasks.len() - 1;
+                        self.save(list);
+                    }
+                }
+                KeyCode::Enter => {
+                    // Empty clears the budget
+                    let budget = match self.input.trim() {
+                        "" => None,
+                        text => match parse_duration(text) {
+                            Ok(duration) => Some((duration.as_secs() / 60).max(1) as u32),
+                            Err(e) => {
+                                self.message = Some(e);
+                                return PaneAction::None;
+                            }
+                        },
+                    };
+                    self.editing = None;
+                    if let Some(task) = list.tasks.get_mut(self.selected) {
+                        task.budget_minutes = budget;
+                        self.save(list);
+                    }
+                }
+                KeyCode::Backspace => {
+                    self.input.pop();
+                }
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => self.input.push(c),
+                _ => {}
+            }
+            return PaneAction::None;
+        }
+
+        self.message = None;
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('p') => return PaneAction::Close,
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.selected += 1,
+            KeyCode::Char('a') => {
+                self.editing = Some(PaneInput::Add);
+                self.input.clear();
+            }
+            KeyCode::Char('t') => {
+                if let Some(task) = list.tasks.get(self.selected) {
+                    self.input = task.budget_minutes.map(|m| format!("{m}m")).unwrap_or_default();
+                    self.editing = Some(PaneInput::Budget);
+                }
+            }
+            KeyCode::Char('+' | '=') => self.adjust_estimate(list, 1),
+            KeyCode::Char('-') => self.adjust_estimate(list, -1),
+            KeyCode::Char('c') => {
+                if let Some(task) = list.tasks.get_mut(self.selected) {
+                    task.done = !task.done;
+                    self.save(list);
+                }
+            }
+            KeyCode::Delete | KeyCode::Char('D') if self.selected < list.tasks.len() => {
+                list.tasks.remove(self.selected);
+                self.save(list);
+            }
+            KeyCode::Enter => {
+                if let Some(task) = list.tasks.get(self.selected) {
+                    return PaneAction::Select(task.clone());
+                }
+            }
+            _ => {}
+        }
+        self.selected = self.selected.min(list.tasks.len().saturating_sub(1));
+        PaneAction::None
+    }
+
+    fn adjust_estimate(&mut self, list: &mut TaskList, delta: i32) {
+        if let Some(task) = list.tasks.get_mut(self.selected) {
+            let estimate = task.estimate.unwrap_or(0) as i32 + delta;
+            task.estimate = if estimate > 0 { Some(estimate as u32) } else { None };
+            self.save(list);
+        }
+    }
+
+    fn save(&mut self, list: &TaskList) {
+        if let Err(e) = list.save() {
+            self.message = Some(format!("Could not save tasks: {e}"));
+        }
+    }
+}
+
+pub fn render(f: &mut Frame, area: Rect, list: &TaskList, pane: &TaskPane, records: &[SessionRecord], current: Option<&str>) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Tasks")
+        .title_alignment(Alignment::Center)
+        .border_style(Style::default().fg(theme::primary()));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(1), Constraint::Length(1)])
+        .split(inner);
+
+    if list.tasks.is_empty() {
+        f.render_widget(Paragraph::new("  No tasks yet - press a to plan one"), chunks[0]);
+    } else {
+        let visible = chunks[0].height.saturating_sub(1) as usize;
+        let offset = (pane.selected + 1).saturating_sub(visible);
+        let rows: Vec<Row> = list
+            .tasks
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(visible)
+            .map(|(i, task)| {
+                let done = actual(records, &task.name);
+                let secs = focused_secs(records, &task.name);
+                let over = is_over_estimate(task, done) || is_over_budget(task, secs);
+                let marker = if current == Some(task.name.as_str()) { "▶" } else { " " };
+                let status = if task.done {
+                    "done"
+                } else if is_over_budget(task, secs) {
+                    "over budget"
+                } else if over {
+                    "over estimate"
+                } else {
+                    ""
+                };
+                let tags = task.tags.iter().map(|t| format!("#{t}")).collect::<Vec<_>>().join(" ");
+                let mut style = Style::default();
+                if over {
+                    style = style.fg(OVER_ESTIMATE_COLOR);
+                }
+                if task.done {
+                    style = style.add_modifier(Modifier::CROSSED_OUT | Modifier::DIM);
+                }
+                if i == pane.selected {
+                    style = style.fg(Color::Black).bg(if over { OVER_ESTIMATE_COLOR } else { theme::primary() });
+                }
+                Row::new(vec![
+                    marker.to_string(),
+                    task.name.clone(),
+                    tags,
+                    progress_text(Some(task), done),
+                    budget_text(task, secs),
+                    status.to_string(),
+                ])
+                .style(style)
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(1),
+                Constraint::Percentage(45),
+                Constraint::Percentage(30),
+                Constraint::Length(10),
+                Constraint::Length(13),
+                Constraint::Length(13),
+            ],
+        )
+        .header(
+            Row::new(vec!["", "Task", "Tags", "Actual/Est", "Time/Budget", ""]).style(Style::default().fg(theme::highlight()).add_modifier(Modifier::BOLD)),
+        );
+        f.render_widget(table, chunks[0]);
+    }
+
+    let prompt = match pane.editing {
+        Some(PaneInput::Add) => Some("New task (name #tag)"),
+        Some(PaneInput::Budget) => Some("Time budget (90m, 2h; empty for none)"),
+        None => None,
+    };
+    let status_line = if let Some(prompt) = prompt {
+        let mut spans = vec![
+            Span::raw(format!("  {prompt}: ")),
+            Span::styled(pane.input.as_str(), Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+            Span::styled("█", Style::default().fg(theme::primary())), // Cursor
+        ];
+        if let Some(message) = &pane.message {
+            spans.push(Span::styled(format!("  {message}"), Style::default().fg(Color::Yellow)));
+        }
+        Line::from(spans)
+    } else {
+        Line::from(Span::styled(
+            format!("  {}", pane.message.as_deref().unwrap_or("")),
+            Style::default().fg(Color::Yellow),
+        ))
+    };
+    f.render_widget(Paragraph::new(status_line), chunks[1]);
+
+    let key = |k: &'static str| Span::styled(k, Style::default().fg(theme::primary()).add_modifier(Modifier::BOLD));
+    let help = if pane.editing.is_some() {
+        Line::from(vec![key("↵"), Span::raw(" - Save | "), key("Esc"), Span::raw(" - Cancel")])
+    } else {
+        Line::from(vec![
+            key("a"),
+            Span::raw(" - Add | "),
+            key("+/-"),
+            Span::raw(" - Estimate | "),
+            key("t"),
+            Span::raw(" - Budget | "),
+            key("↵"),
+            Span::raw(" - Work on | "),
+            key("c"),
+            Span::raw(" - Done | "),
+            key("D"),
+            Span::raw(" - Delete | "),
+            key("p/Esc"),
+            Span::raw(" - Close"),
+        ])
+    };
+    f.render_widget(Paragraph::new(help).alignment(Alignment::Center), chunks[2]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_budget_text() {
+        let mut task = Task {
+            name: "Code review".to_string(),
+            tags: Vec::new(),
+            estimate: None,
+            budget_minutes: Some(120),
+            done: false,
+            created_at: 0,
+        };
+        assert_eq!(budget_text(&task, 70 * 60), "1h10m/2h");
+        assert!(!is_over_budget(&task, 119 * 60 + 59));
+        assert!(is_over_budget(&task, 120 * 60));
+        task.budget_minutes = None;
+        assert_eq!(budget_text(&task, 45 * 60), "45m");
+        assert!(!is_over_budget(&task, u64::MAX));
+    }
+
+    fn press(pane: &mut TaskPane, list: &mut TaskList, keys: &str) {
+        for c in keys.chars() {
+            let code = match c {
+                '\n' => KeyCode::Enter,
+                '\x08' => KeyCode::Backspace,
+                c => KeyCode::Char(c),
+            };
+            pane.handle_key(KeyEvent::new(code, KeyModifiers::NONE), list);
+        }
+    }
+
+    #[test]
+    fn test_pane_edits_and_saves() {
+        let path = std::env::temp_dir().join(format!("cyber-tomato-tasks-{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let mut list = TaskList::load(path.clone());
+        let mut pane = TaskPane::new();
+
+        press(&mut pane, &mut list, "aWrite report #work\naReview\n");
+        assert_eq!(list.tasks.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(), ["Write report", "Review"]);
+        assert_eq!(list.tasks[0].tags, ["work"]);
+        assert_eq!(list.tasks[1].estimate, Some(1));
+        // A name already on the list isn't added twice
+        press(&mut pane, &mut list, "aReview\n");
+        assert_eq!(list.tasks.len(), 2);
+        assert_eq!(pane.message.as_deref(), Some("'Review' already exists"));
+
+        // Estimates go down to none and back up
+        press(&mut pane, &mut list, "k++");
+        assert_eq!(list.tasks[0].estimate, Some(3));
+        press(&mut pane, &mut list, "---");
+        assert_eq!(list.tasks[0].estimate, None);
+        press(&mut pane, &mut list, "+");
+        assert_eq!(list.tasks[0].estimate, Some(1));
+
+        // A budget that doesn't parse keeps the input open
+        press(&mut pane, &mut list, "tsoon\n");
+        assert!(pane.editing.is_some() && pane.message.is_some());
+        press(&mut pane, &mut list, "\x08\x08\x08\x082h\n");
+        assert_eq!(list.tasks[0].budget_minutes, Some(120));
+
+        press(&mut pane, &mut list, "c");
+        assert!(list.tasks[0].done);
+
+        let reloaded = TaskList::load(path.clone());
+        let task = reloaded.get("Write report").unwrap();
+        assert_eq!((task.estimate, task.budget_minutes, task.done), (Some(1), Some(120), true));
+        assert_eq!(task.tags, ["work"]);
+
+        // Deleting the last task keeps the selection on the list
+        press(&mut pane, &mut list, "jjD");
+        assert_eq!(list.tasks.len(), 1);
+        assert_eq!(pane.selected, 0);
+        assert_eq!(TaskList::load(path.clone()).tasks.len(), 1);
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_progress_text() {
+        let task = Task {
+            name: "Review".to_string(),
+            tags: Vec::new(),
+            estimate: Some(2),
+            budget_minutes: None,
+            done: false,
+            created_at: 0,
+        };
+        assert_eq!(progress_text(Some(&task), 3), "3/2");
+        assert_eq!(progress_text(None, 3), "3");
+        assert!(is_over_estimate(&task, 3) && !is_over_estimate(&task, 2));
+    }
+}
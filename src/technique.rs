@@ -0,0 +1,170 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::theme;
+
+// Well-known work/break rhythms, picked with --technique or the techniques
+// popup in place of the lengths in [timer]
+pub struct Technique {
+    pub id: &'static str, // For --technique
+    pub name: &'static str,
+    pub work_minutes: u32,
+    pub break_minutes: u32,
+    pub long_break_minutes: u32,
+    pub long_break_every: u32, // Pomodoros per long break; 0 for none
+    description: &'static str,
+}
+
+pub const TECHNIQUES: [Technique; 4] = [
+    Technique {
+        id: "classic",
+        name: "Classic",
+        work_minutes: 25,
+        break_minutes: 5,
+        long_break_minutes: 15,
+        long_break_every: 4,
+        description: "25 minutes of work, 5 off, and 15 after every fourth",
+    },
+    Technique {
+        id: "desktime",
+        name: "DeskTime",
+        work_minutes: 52,
+        break_minutes: 17,
+        long_break_minutes: 0,
+        long_break_every: 0,
+        description: "52/17, the rhythm of DeskTime's most productive users",
+    },
+    Technique {
+        id: "ultradian",
+        name: "Ultradian",
+        work_minutes: 90,
+        break_minutes: 20,
+        long_break_minutes: 0,
+        long_break_every: 0,
+        description: "90/20, one of the body's natural cycles of alertness",
+    },
+    Technique {
+        id: "45-15",
+        name: "45/15",
+        work_minutes: 45,
+        break_minutes: 15,
+        long_break_minutes: 0,
+        long_break_every: 0,
+        description: "Three quarters of every hour on, one off",
+    },
+];
+
+pub fn find(id: &str) -> Option<&'static Technique> {
+    TECHNIQUES.iter().find(|technique| technique.id.eq_ignore_ascii_case(id))
+}
+
+pub fn ids() -> String {
+    TECHNIQUES.iter().map(|technique| technique.id).collect::<Vec<_>>().join(", ")
+}
+
+impl Technique {
+    pub fn summary(&self) -> String {
+        let mut summary = format!("{}/{}", self.work_minutes, self.break_minutes);
+        if self.long_break_every > 0 {
+            summary.push_str(&format!("×{}+{}", self.long_break_every, self.long_break_minutes));
+        }
+        summary
+    }
+}
+
+pub enum Pick {
+    Stay,
+    Close,
+    // None goes back to the lengths in the config
+    Use(Option<&'static Technique>),
+}
+
+// The popup list: the config's own lengths, then every technique
+pub struct TechniquePicker {
+    selected: usize,
+}
+
+impl TechniquePicker {
+    pub fn new(current: Option<&'static Technique>) -> Self {
+        let selected = current
+            .and_then(|current| TECHNIQUES.iter().position(|t| t.id == current.id))
+            .map_or(0, |i| i + 1);
+        TechniquePicker { selected }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Pick {
+        match key.code {
+            KeyCode::Esc => return Pick::Close,
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.selected = (self.selected + 1).min(TECHNIQUES.len()),
+            KeyCode::Enter | KeyCode::Char(' ') => return Pick::Use(self.selected.checked_sub(1).map(|i| &TECHNIQUES[i])),
+            _ => {}
+        }
+        Pick::Stay
+    }
+}
+
+pub fn render(f: &mut Frame, area: Rect, picker: &TechniquePicker, current: Option<&'static Technique>) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Techniques")
+        .title_alignment(Alignment::Center)
+        .border_style(Style::default().fg(theme::primary()));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    let dim = Style::default().fg(Color::DarkGray);
+    let entries = std::iter::once(("Config".to_string(), "The lengths set in [timer]", current.is_none())).chain(
+        TECHNIQUES
+            .iter()
+            .map(|t| (format!("{} {}", t.name, t.summary()), t.description, current.is_some_and(|c| c.id == t.id))),
+    );
+    let mut lines = Vec::new();
+    for (i, (name, description, in_use)) in entries.enumerate() {
+        let marker = if i == picker.selected { "▶" } else { " " };
+        let style = if i == picker.selected {
+            Style::default().fg(theme::highlight()).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        let in_use = if in_use { " (in use)" } else { "" };
+        lines.push(Line::from(Span::styled(format!(" {marker} {name}{in_use}"), style)));
+        lines.push(Line::from(Span::styled(format!("   {description}"), dim)));
+        lines.push(Line::from(""));
+    }
+    f.render_widget(Paragraph::new(lines), chunks[0]);
+
+    let key = Style::default().fg(theme::primary()).add_modifier(Modifier::BOLD);
+    let help = Line::from(vec![
+        Span::styled("↑/↓", key),
+        Span::raw(" - Select | "),
+        Span::styled("Enter", key),
+        Span::raw(" - Use | "),
+        Span::styled("Esc", key),
+        Span::raw(" - Close"),
+    ]);
+    f.render_widget(Paragraph::new(help).alignment(Alignment::Center), chunks[1]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find() {
+        assert_eq!(find("DeskTime").map(|t| t.work_minutes), Some(52));
+        assert_eq!(find("classic").map(Technique::summary).as_deref(), Some("25/5×4+15"));
+        assert!(find("tomato").is_none());
+    }
+}
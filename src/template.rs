@@ -0,0 +1,72 @@
+use crate::TimerType;
+use crate::clock;
+use crate::icons;
+use crate::status::Status;
+
+// Text the config can reword: the terminal title, notification titles, the
+// OBS overlay and the status file's `text`. Placeholders are {session} (Work
+// or Break), {type} (work or break), {icon} (or {emoji}, from the icon set),
+// {state}, {remaining}, {elapsed} and {duration} (mm:ss), {ends} (14:37),
+// {clock} (whichever of those the digits show), {task}, {tags} (as #a #b),
+// {today}, {goal}, {dots} (today against the goal) and {done} (sessions
+// finished since the timer started); anything else in braces is left as it is
+pub fn render(template: &str, status: &Status) -> String {
+    let (session, kind) = match status.session {
+        TimerType::Work => ("Work", "work"),
+        TimerType::Break => ("Break", "break"),
+    };
+    let icons = icons::current();
+    let icon = icons.session(&status.session);
+    let tags: Vec<String> = status.tags.iter().map(|tag| format!("#{tag}")).collect();
+    let elapsed = status.duration_secs.saturating_sub(status.remaining_secs);
+    template
+        .replace("{session}", session)
+        .replace("{type}", kind)
+        .replace("{icon}", icon)
+        .replace("{emoji}", icon)
+        .replace("{dots}", &icons.dots(status.today, status.daily_goal))
+        .replace("{state}", status.state)
+        .replace("{clock}", &status.clock.text(elapsed, status.remaining_secs))
+        .replace("{remaining}", &clock::minutes(status.remaining_secs, ':'))
+        .replace("{elapsed}", &clock::minutes(elapsed, ':'))
+        .replace("{ends}", &clock::ends_at(status.remaining_secs))
+        .replace("{duration}", &clock::minutes(status.duration_secs, ':'))
+        .replace("{task}", status.task.as_deref().unwrap_or(""))
+        .replace("{tags}", &tags.join(" "))
+        .replace("{today}", &status.today.to_string())
+        .replace("{goal}", &status.daily_goal.to_string())
+        .replace("{done}", &status.completed_sessions.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ClockDisplay;
+
+    #[test]
+    fn test_render() {
+        let status = Status {
+            session: TimerType::Break,
+            state: "paused",
+            remaining_secs: 4 * 60 + 5,
+            duration_secs: 5 * 60,
+            completed_sessions: 2,
+            today: 3,
+            daily_goal: 8,
+            task: Some("Report".to_string()),
+            tags: vec!["deep".to_string(), "q3".to_string()],
+            text: String::new(),
+            away: String::new(),
+            clock: ClockDisplay::Elapsed,
+        };
+        assert_eq!(render("{session} {remaining}", &status), "Break 04:05");
+        assert_eq!(render("{task}: {today}/{goal} ({state})", &status), "Report: 3/8 (paused)");
+        assert_eq!(
+            render("{emoji} {type} {remaining} — {task} {tags}", &status),
+            "☕ break 04:05 — Report #deep #q3"
+        );
+        assert_eq!(render("{duration}, {done} done", &status), "05:00, 2 done");
+        assert_eq!(render("{session} {clock}", &status), "Break 00:55 elapsed");
+        assert_eq!(render("{unknown}", &status), "{unknown}");
+    }
+}
@@ -0,0 +1,196 @@
+use std::sync::RwLock;
+
+use ratatui::style::Color;
+use serde::Deserialize;
+
+use crate::TimerType;
+use crate::audio::TuneList;
+use crate::config::{SoundsConfig, Waveform};
+use crate::progress_bar::ProgressBarStyle;
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    pub primary: Color,   // Borders, key hints, work digits
+    pub highlight: Color, // Labels, values and examples
+    // Colors the digits and gauge blend through as a session runs, e.g.
+    // green -> yellow -> red. Empty keeps the session color throughout
+    pub progress: Vec<Color>,
+    pub progress_bar: ProgressBarStyle,
+    // Per session type, so work and breaks tell apart at a glance
+    pub work: SessionTheme,
+    #[serde(rename = "break")]
+    pub breaks: SessionTheme,
+}
+
+// Colors and completion sound of one session type. Anything unset falls back
+// to the theme's colors and the [sounds] settings
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct SessionTheme {
+    pub digits: Option<Color>,
+    pub gauge: Option<Color>,  // Progress bar and percentage; the digits color otherwise
+    pub border: Option<Color>, // Digits and progress panels
+    pub progress: Option<Vec<Color>>,
+    // Sound when a session of this type ends
+    pub waveform: Option<Waveform>,
+    pub tune: Option<TuneList>,
+}
+
+const PLAIN: SessionTheme = SessionTheme {
+    digits: None,
+    gauge: None,
+    border: None,
+    progress: None,
+    waveform: None,
+    tune: None,
+};
+
+const DEFAULT: Theme = Theme {
+    primary: Color::LightGreen, // Color::Rgb(144, 255, 161); //Color::Rgb(80,250,123);
+    highlight: Color::Rgb(0, 255, 150),
+    progress: Vec::new(),
+    progress_bar: ProgressBarStyle::Solid,
+    work: PLAIN,
+    breaks: PLAIN,
+};
+
+impl Default for Theme {
+    fn default() -> Self {
+        DEFAULT
+    }
+}
+
+impl Theme {
+    fn session(&self, timer_type: &TimerType) -> &SessionTheme {
+        match timer_type {
+            TimerType::Work => &self.work,
+            TimerType::Break => &self.breaks,
+        }
+    }
+
+    fn progress(&self, timer_type: &TimerType) -> &[Color] {
+        self.session(timer_type).progress.as_deref().unwrap_or(&self.progress)
+    }
+
+    // The [sounds] settings with this theme's completion sounds put in
+    pub fn sounds(&self, sounds: &SoundsConfig) -> SoundsConfig {
+        let mut sounds = sounds.clone();
+        if let Some(waveform) = self.work.waveform {
+            sounds.work_complete = waveform;
+        }
+        if let Some(waveform) = self.breaks.waveform {
+            sounds.break_complete = waveform;
+        }
+        if let Some(tune) = &self.work.tune {
+            sounds.work_complete_tune = Some(tune.clone());
+        }
+        if let Some(tune) = &self.breaks.tune {
+            sounds.break_complete_tune = Some(tune.clone());
+        }
+        sounds
+    }
+}
+
+// The active theme is global so every screen picks up a config reload on the
+// next frame without threading colors through each render function
+static CURRENT: RwLock<Theme> = RwLock::new(DEFAULT);
+
+pub fn set(theme: Theme) {
+    if let Ok(mut current) = CURRENT.write() {
+        *current = theme;
+    }
+}
+
+fn with<R>(f: impl FnOnce(&Theme) -> R) -> R {
+    match CURRENT.read() {
+        Ok(theme) => f(&theme),
+        Err(_) => f(&DEFAULT),
+    }
+}
+
+pub fn primary() -> Color {
+    with(|theme| theme.primary)
+}
+
+pub fn highlight() -> Color {
+    with(|theme| theme.highlight)
+}
+
+// Digits color of a session type, before any progress blend
+pub fn digits(timer_type: &TimerType) -> Color {
+    with(|theme| {
+        theme.session(timer_type).digits.unwrap_or(match timer_type {
+            TimerType::Work => theme.primary,
+            TimerType::Break => Color::default(),
+        })
+    })
+}
+
+// Digits color at `ratio` (0.0 start, 1.0 end) of the session: along the
+// progress colors if there are any
+pub fn session_color(timer_type: &TimerType, ratio: f64) -> Color {
+    with(|theme| blend_stops(theme.progress(timer_type), ratio)).unwrap_or_else(|| digits(timer_type))
+}
+
+pub fn gauge_color(timer_type: &TimerType, ratio: f64) -> Color {
+    with(|theme| theme.session(timer_type).gauge).unwrap_or_else(|| session_color(timer_type, ratio))
+}
+
+pub fn border(timer_type: &TimerType) -> Color {
+    with(|theme| theme.session(timer_type).border.unwrap_or(theme.primary))
+}
+
+pub fn progress_bar() -> ProgressBarStyle {
+    with(|theme| theme.progress_bar)
+}
+
+// Color at `position` along the gradient progress bar: the progress colors,
+// or primary to highlight when none are set
+pub fn gradient_color(timer_type: &TimerType, position: f64) -> Color {
+    with(|theme| {
+        blend_stops(theme.progress(timer_type), position)
+            .or_else(|| blend_stops(&[theme.primary, theme.highlight], position))
+            .unwrap_or(theme.primary)
+    })
+}
+
+fn blend_stops(stops: &[Color], ratio: f64) -> Option<Color> {
+    let last = stops.len().checked_sub(1)?;
+    let position = ratio.clamp(0.0, 1.0) * last as f64;
+    let index = (position.floor() as usize).min(last);
+    let (from, to) = (stops[index], stops[(index + 1).min(last)]);
+    // Palette colors without a known RGB value switch at the stop instead of blending
+    match (rgb(from), rgb(to)) {
+        (Some(a), Some(b)) => {
+            let t = position - index as f64;
+            let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+            Some(Color::Rgb(mix(a.0, b.0), mix(a.1, b.1), mix(a.2, b.2)))
+        }
+        _ => Some(from),
+    }
+}
+
+// Typical xterm values for the named colors
+fn rgb(color: Color) -> Option<(u8, u8, u8)> {
+    Some(match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Black => (0, 0, 0),
+        Color::Red => (205, 0, 0),
+        Color::Green => (0, 205, 0),
+        Color::Yellow => (205, 205, 0),
+        Color::Blue => (0, 0, 238),
+        Color::Magenta => (205, 0, 205),
+        Color::Cyan => (0, 205, 205),
+        Color::Gray => (229, 229, 229),
+        Color::DarkGray => (127, 127, 127),
+        Color::LightRed => (255, 0, 0),
+        Color::LightGreen => (0, 255, 0),
+        Color::LightYellow => (255, 255, 0),
+        Color::LightBlue => (92, 92, 255),
+        Color::LightMagenta => (255, 0, 255),
+        Color::LightCyan => (0, 255, 255),
+        Color::White => (255, 255, 255),
+        _ => return None,
+    })
+}
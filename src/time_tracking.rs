@@ -0,0 +1,80 @@
+use std::process::{Command, Stdio};
+
+use chrono::{Local, TimeZone, Utc};
+
+use crate::TimerType;
+use crate::config::{TimeTool, TimeTrackingConfig};
+use crate::history::SessionRecord;
+use crate::jobs::Jobs;
+
+// Finished work sessions are also logged with timewarrior or Watson, for
+// people whose reports come from there. A tool that fails says why on stderr
+pub struct TimeTracker {
+    config: TimeTrackingConfig,
+    jobs: Jobs,
+}
+
+impl TimeTracker {
+    pub fn new(config: TimeTrackingConfig) -> Self {
+        TimeTracker { config, jobs: Jobs::new() }
+    }
+
+    pub fn set_config(&mut self, config: TimeTrackingConfig) {
+        self.config = config;
+    }
+
+    pub fn push(&self, record: &SessionRecord) {
+        let Some(tool) = self.config.tool else {
+            return;
+        };
+        if record.kind != TimerType::Work {
+            return;
+        }
+        let mut command = command(tool, &self.config.project, record);
+        self.jobs.spawn(move || {
+            let program = command.get_program().to_string_lossy().into_owned();
+            let result = command.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::piped()).output();
+            let error = match result {
+                Ok(output) if output.status.success() => return Ok(()),
+                Ok(output) => String::from_utf8_lossy(&output.stderr).trim().to_string(),
+                Err(e) => e.to_string(),
+            };
+            Err(format!("{program}: {error}"))
+        });
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = String> + '_ {
+        self.jobs.errors()
+    }
+}
+
+fn command(tool: TimeTool, project: &str, record: &SessionRecord) -> Command {
+    let start = record.started_at as i64;
+    let end = start + record.duration_secs as i64;
+    match tool {
+        // timew track 20250101T090000Z - 20250101T092500Z "Report" work writing
+        TimeTool::Timewarrior => {
+            let utc = |secs: i64| Utc.timestamp_opt(secs, 0).single().unwrap_or_default().format("%Y%m%dT%H%M%SZ").to_string();
+            let mut command = Command::new("timew");
+            command.arg("track").arg(utc(start)).arg("-").arg(utc(end));
+            command.args(record.task.iter()).args(&record.tags).arg(":quiet");
+            command
+        }
+        // watson add --from "2025-01-01 10:00:00" --to "2025-01-01 10:25:00" Report +work
+        TimeTool::Watson => {
+            let local = |secs: i64| {
+                Local
+                    .timestamp_opt(secs, 0)
+                    .single()
+                    .unwrap_or_default()
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string()
+            };
+            let mut command = Command::new("watson");
+            command.arg("add").arg("--from").arg(local(start)).arg("--to").arg(local(end));
+            command.arg(record.task.as_deref().unwrap_or(project));
+            command.args(record.tags.iter().map(|tag| format!("+{tag}")));
+            command
+        }
+    }
+}
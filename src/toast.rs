@@ -0,0 +1,94 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    Frame,
+    layout::{Alignment, Rect},
+    style::{Color, Style},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::theme;
+
+const INFO_DURATION: Duration = Duration::from_secs(2);
+const ERROR_DURATION: Duration = Duration::from_secs(5); // Errors need time to be read
+const MAX_QUEUED: usize = 5;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Level {
+    Info,
+    Error,
+}
+
+struct Toast {
+    text: String,
+    level: Level,
+    shown_at: Option<Instant>, // Set when the toast reaches the front of the queue
+}
+
+// Transient messages shown one at a time in the top-right corner
+pub struct Toasts {
+    queue: VecDeque<Toast>,
+}
+
+impl Toasts {
+    pub fn new() -> Self {
+        Toasts { queue: VecDeque::new() }
+    }
+
+    pub fn info(&mut self, text: impl Into<String>) {
+        self.push(text.into(), Level::Info);
+    }
+
+    pub fn error(&mut self, text: impl Into<String>) {
+        self.push(text.into(), Level::Error);
+    }
+
+    fn push(&mut self, text: String, level: Level) {
+        // Repeating the message that is already last in line adds nothing
+        if self.queue.back().is_some_and(|last| last.text == text) {
+            return;
+        }
+        // Quick toggles (pause, resume, pause) would otherwise build a backlog;
+        // drop the oldest waiting toast but never the one on screen
+        if self.queue.len() >= MAX_QUEUED {
+            self.queue.remove(1);
+        }
+        self.queue.push_back(Toast { text, level, shown_at: None });
+    }
+
+    // Called every frame: expires the visible toast and starts the next one's timer
+    pub fn tick(&mut self) {
+        if let Some(front) = self.queue.front() {
+            let duration = match front.level {
+                Level::Info => INFO_DURATION,
+                Level::Error => ERROR_DURATION,
+            };
+            if front.shown_at.is_some_and(|shown| shown.elapsed() >= duration) {
+                self.queue.pop_front();
+            }
+        }
+        if let Some(front) = self.queue.front_mut() {
+            front.shown_at.get_or_insert_with(Instant::now);
+        }
+    }
+}
+
+pub fn render(f: &mut Frame, toasts: &Toasts) {
+    let Some(toast) = toasts.queue.front() else {
+        return;
+    };
+    let color = match toast.level {
+        Level::Info => theme::primary(),
+        Level::Error => Color::Red,
+    };
+    let area = f.area();
+    let width = (toast.text.chars().count() as u16 + 4).min(area.width);
+    let toast_area = Rect::new(area.right().saturating_sub(width), area.y, width, 3.min(area.height));
+    f.render_widget(Clear, toast_area);
+    let widget = Paragraph::new(toast.text.as_str())
+        .style(Style::default().fg(color))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(color)));
+    f.render_widget(widget, toast_area);
+}